@@ -1,27 +1,38 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use aoc_common::{ParseResult, Solution};
 
 fn main() {
-    if let Ok(lines) = read_lines("src/1/input.txt") {
-        let mut list = Vec::new();
-        for line in lines {
-            if let Ok(ip) = line {
-                let my_int = ip.parse::<u32>().unwrap();
-                list.push(my_int);
-            }
-        }
-        let pair = find_pair_that_sums_to(&mut list, 2020);
-        println!("{} * {} = {}", pair.0, pair.1, pair.0*pair.1);
-        let triple = find_triple_that_sums_to(&mut list, 2020);
-        println!("{} * {} * {} = {}", triple.0, triple.1, triple.2, triple.0*triple.1*triple.2);
+    aoc_common::run_day::<Day>(2020, 1);
+}
+
+pub struct Day;
+
+impl Solution for Day
+{
+    type Parsed = Vec<u32>;
+    type PartOne = u32;
+    type PartTwo = u32;
+
+    fn parse(input: &str) -> ParseResult<Vec<u32>>
+    {
+        return parse(input);
+    }
+
+    fn part_one(input: &Vec<u32>) -> u32
+    {
+        let pair = find_pair_that_sums_to(&mut input.clone(), 2020);
+        return pair.0 * pair.1;
+    }
+
+    fn part_two(input: &Vec<u32>) -> u32
+    {
+        let triple = find_triple_that_sums_to(&mut input.clone(), 2020);
+        return triple.0 * triple.1 * triple.2;
     }
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+fn parse(input: &str) -> ParseResult<Vec<u32>>
+{
+    return aoc_common::numbers(input);
 }
 
 pub fn find_pair_that_sums_to(numbers: &mut Vec<u32>, target: u32) -> (u32, u32)
@@ -1,23 +1,41 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use aoc_common::{ParseResult, Solution};
 
 fn main() {
-    if let Ok(lines) = read_lines("src/10/input.txt") {
-        let mut input:Vec<i64> = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap())
-            .map(|s| s.parse::<i64>().unwrap())
-            .collect();    
-        input.sort();
+    aoc_common::run_day::<Day>(2020, 10);
+}
+
+pub struct Day;
+
+impl Solution for Day
+{
+    type Parsed = Vec<i64>;
+    type PartOne = i64;
+    type PartTwo = i64;
+
+    fn parse(input: &str) -> ParseResult<Vec<i64>>
+    {
+        return parse(input);
+    }
+
+    fn part_one(input: &Vec<i64>) -> i64
+    {
+        let diffs = calc_diffs(input);
+        return diffs[0] * diffs[2];
+    }
 
-        let diffs = calc_diffs(&input);
-        println!("Result: {} * {} = {:?}", diffs[0], diffs[2], diffs[0] * diffs[2]);
-        let perms = permutations(&input);
-        println!("Permuations: = {:?}", perms);
+    fn part_two(input: &Vec<i64>) -> i64
+    {
+        return permutations(input);
     }
 }
 
+fn parse(input: &str) -> ParseResult<Vec<i64>>
+{
+    let mut adapters:Vec<i64> = aoc_common::numbers(input)?;
+    adapters.sort();
+    return Ok(adapters);
+}
+
 pub fn calc_diffs(input: &Vec<i64>) -> [i64; 3]
 {
     let mut diffs = [0, 0, 0];
@@ -64,12 +82,6 @@ pub fn permutations(input: &Vec<i64>) -> i64
     return perms[perms.len() - 1];
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
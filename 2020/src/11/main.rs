@@ -1,216 +1,113 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
-use std::iter::FromIterator;
+use aoc_common::{Automaton, Grid, LineOfSight, Moore, Neighbourhood, ParseResult, Pos, Rule, Solution};
 
 fn main() {
-    if let Ok(lines) = read_lines("src/11/input.txt") {
-        let initial_state:Vec<Vec<char>> = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap())
-            .map(|s| s.chars().collect())
-            .collect();
-
-        let count1 = count_final_state(&initial_state);
-        println!("Count Part 1: {}", count1);
-        let count2 = count_final_state2(&initial_state);
-        println!("Count Part 2: {}", count2);
-    }
+    aoc_common::run_day::<Day>(2020, 11);
 }
 
-#[allow(dead_code)]
-fn print_state(state: &Vec<Vec<char>>)
+pub struct Day;
+
+impl Solution for Day
 {
-    for line in state
+    type Parsed = Grid<char>;
+    type PartOne = i64;
+    type PartTwo = i64;
+
+    fn parse(input: &str) -> ParseResult<Grid<char>>
     {
-        println!("{}", String::from_iter(line.iter()));
+        return Grid::parse(input, |c| c);
     }
-}
 
-pub fn count_final_state2(input: &Vec<Vec<char>>) -> i64
-{
-    let final_state = iterate_until_stable2(input);
-    return final_state.iter()
-        .map(|l| l.iter().filter(|c| **c == '#').count() as i64)
-        .sum();
-}
-
-fn iterate_until_stable2(layout: &Vec<Vec<char>>) -> Vec<Vec<char>>
-{
-    let mut input = &mut layout.clone();
-    let mut output = &mut layout.clone();
-    let mut changes = 1;
+    fn part_one(input: &Grid<char>) -> i64
+    {
+        return count_final_state(input);
+    }
 
-    while changes != 0
+    fn part_two(input: &Grid<char>) -> i64
     {
-        let tmp = output;
-        output = input;
-        input = tmp;
-        changes = iterate_state2(input, output);
+        return count_final_state2(input);
     }
-    return output.to_vec();
 }
 
-pub fn iterate_state2(input: &Vec<Vec<char>>, output: &mut Vec<Vec<char>>) -> i32
-{
-    let mut changes = 0;
-    for y in 0..input.len()
-    {
-        let line = &input[y];
+type Seating<N> = Automaton<[i64; 2], N>;
 
-        for x in 0..line.len()
-        {
-            let ch = line[x];
-            let count = count_occupied2(x as i32, y as i32, input);
-            if ch == 'L' && count == 0
-            {
-                output[y][x] = '#';
-                changes = changes + 1;
-            }
-            else if ch == '#' && count >= 5
-            {
-                output[y][x] = 'L';
-                changes = changes + 1;
-            }
-            else
-            {
-                output[y][x] = ch;
-            }
-        }
-    }
-    return changes;
+fn cell(pos: Pos) -> [i64; 2]
+{
+    return [pos.0 as i64, pos.1 as i64];
 }
 
-pub fn count_occupied2(x: i32, y: i32, input: &Vec<Vec<char>>) -> i32
+/// Empty seats with no occupied neighbours fill up, and occupied seats with
+/// `tolerance` or more occupied neighbours empty. Floor never changes.
+fn seating<N: Neighbourhood<[i64; 2]>>(layout: &Grid<char>, neighbourhood: N, tolerance: usize) -> Seating<N>
 {
-    let mut count: i32 = 0;
-    count = count + count_in_direction((x, y), (1, 0), input);
-    count = count + count_in_direction((x, y), (1, -1), input);
-    count = count + count_in_direction((x, y), (0, -1), input);
-    count = count + count_in_direction((x, y), (-1, -1), input);
-    count = count + count_in_direction((x, y), (-1, 0), input);
-    count = count + count_in_direction((x, y), (-1, 1), input);
-    count = count + count_in_direction((x, y), (0, 1), input);
-    count = count + count_in_direction((x, y), (1, 1), input);
-    return count;
+    let occupied = layout.iter().filter(|(_, &c)| c == '#').map(|(p, _)| cell(p));
+    let seats = layout.iter().filter(|(_, &c)| c != '.').map(|(p, _)| cell(p));
+    let survival:Vec<usize> = (0..tolerance).collect();
+    return Automaton::new(occupied, neighbourhood, Rule::new(&[0], &survival)).within(seats);
 }
 
-pub fn count_in_direction(start: (i32, i32), direction: (i32, i32), input: &Vec<Vec<char>>) -> i32
+fn adjacent(layout: &Grid<char>) -> Seating<Moore>
 {
-    let mut pos = (start.0 + direction.0, start.1 + direction.1);
-    loop
-    {
-        if pos.0 < 0 || pos.1 < 0
-        {
-            return 0;
-        }
+    return seating(layout, Moore, 4);
+}
 
-        let ch = input.get(pos.1 as usize).map_or(None, |l| l.get(pos.0 as usize));
-        if ch.is_none()
-        {
-            return 0;
-        }
+fn line_of_sight(layout: &Grid<char>) -> Seating<LineOfSight>
+{
+    return seating(layout, LineOfSight::new(&layout.map(|&c| c != '.')), 5);
+}
 
-        let c = *ch.unwrap();
-        if c == 'L'
-        {
-            return 0;
-        }
-        else if c == '#'
+fn render<N: Neighbourhood<[i64; 2]>>(layout: &Grid<char>, seating: &Seating<N>) -> Grid<char>
+{
+    return Grid::from_fn(layout.width(), layout.height(), |pos| {
+        if layout[pos] == '.'
         {
-            return 1;
+            return '.';
         }
-
-        pos.0 = pos.0 + direction.0;
-        pos.1 = pos.1 + direction.1;
-    }
+        return if seating.is_live(&cell(pos)) { '#' } else { 'L' };
+    });
 }
 
-pub fn count_final_state(input: &Vec<Vec<char>>) -> i64
+#[allow(dead_code)]
+fn print_state(state: &Grid<char>)
 {
-    let final_state = iterate_until_stable(input);
-    return final_state.iter()
-        .map(|l| l.iter().filter(|c| **c == '#').count() as i64)
-        .sum();
+    println!("{}", state);
 }
 
-fn iterate_until_stable(layout: &Vec<Vec<char>>) -> Vec<Vec<char>>
+pub fn count_final_state2(input: &Grid<char>) -> i64
 {
-    let mut input = &mut layout.clone();
-    let mut output = &mut layout.clone();
-    let mut changes = 1;
-
-    while changes != 0
-    {
-        let tmp = output;
-        output = input;
-        input = tmp;
-        changes = iterate_state(input, output);
-    }
-    return output.to_vec();
+    let mut seating = line_of_sight(input);
+    seating.run_until_stable();
+    return seating.count() as i64;
 }
 
-pub fn iterate_state(input: &Vec<Vec<char>>, output: &mut Vec<Vec<char>>) -> i32
+pub fn iterate_state2(input: &Grid<char>) -> Grid<char>
 {
-    let mut changes = 0;
-    for y in 0..input.len()
-    {
-        let prev = if y > 0 { input.get(y - 1) } else { None };
-        let line = &input[y];
-        let next = input.get(y + 1);
+    let mut seating = line_of_sight(input);
+    seating.step();
+    return render(input, &seating);
+}
 
-        for x in 0..line.len()
-        {
-            let ch = line[x];
-            let count = count_occupied(x, prev, line, next);
-            if ch == 'L' && count == 0
-            {
-                output[y][x] = '#';
-                changes = changes + 1;
-            }
-            else if ch == '#' && count >= 4
-            {
-                output[y][x] = 'L';
-                changes = changes + 1;
-            }
-            else
-            {
-                output[y][x] = ch;
-            }
-        }
-    }
-    return changes;
+pub fn count_occupied2(pos: Pos, input: &Grid<char>) -> i32
+{
+    return line_of_sight(input).live_neighbours(&cell(pos)) as i32;
 }
 
-pub fn count_occupied(x: usize, line_above: Option<&Vec<char>>, line: &Vec<char>, line_below: Option<&Vec<char>>) -> i32
+pub fn count_final_state(input: &Grid<char>) -> i64
 {
-    let mut count: i32 = 0;
-    count = count + line_above.map_or(0, |l| count_line(x, l));
-    count = count + line_below.map_or(0, |l| count_line(x, l));
-    count = count + count_line(x, line);
-    if line[x] == '#'
-    {
-        count = count - 1;
-    }
-    return count;
+    let mut seating = adjacent(input);
+    seating.run_until_stable();
+    return seating.count() as i64;
 }
 
-fn count_line(x: usize, line: &Vec<char>) -> i32
+pub fn iterate_state(input: &Grid<char>) -> Grid<char>
 {
-    let mut count: i32 = 0;
-    if x > 0 
-    {
-        count = count + line.get(x - 1).map_or(0, |c| if *c == '#' { 1 } else { 0 });
-    }
-    count = count + (if line[x] == '#' { 1 } else { 0 });
-    count = count + line.get(x + 1).map_or(0, |c| if *c == '#' { 1 } else { 0 });
-    return count;
+    let mut seating = adjacent(input);
+    seating.step();
+    return render(input, &seating);
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+pub fn count_occupied(pos: Pos, input: &Grid<char>) -> i32
+{
+    return adjacent(input).live_neighbours(&cell(pos)) as i32;
 }
 
 #[cfg(test)]
@@ -220,7 +117,7 @@ mod tests {
     #[test]
     fn test_initial_iteration() 
     {
-        let input:Vec<Vec<char>> = vec![
+        let input = Grid::from_rows(vec![
             "L.LL.LL.LL".chars().collect(),
             "LLLLLLL.LL".chars().collect(),
             "L.L.L..L..".chars().collect(),
@@ -231,11 +128,10 @@ mod tests {
             "LLLLLLLLLL".chars().collect(),
             "L.LLLLLL.L".chars().collect(),
             "L.LLLLL.LL".chars().collect()
-        ];
-        let mut output = input.clone();
-        iterate_state(&input, &mut output);
+        ]);
+        let output = iterate_state(&input);
 
-        let expected:Vec<Vec<char>> = vec![
+        let expected = Grid::from_rows(vec![
             "#.##.##.##".chars().collect(),
             "#######.##".chars().collect(),
             "#.#.#..#..".chars().collect(),
@@ -246,7 +142,7 @@ mod tests {
             "##########".chars().collect(),
             "#.######.#".chars().collect(),
             "#.#####.##".chars().collect()
-        ];
+        ]);
         assert_eq!(expected, output);
     }
 
@@ -255,7 +151,7 @@ mod tests {
     #[test]
     fn test_second_iteration() 
     {
-        let input:Vec<Vec<char>> = vec![
+        let input = Grid::from_rows(vec![
             "#.##.##.##".chars().collect(),
             "#######.##".chars().collect(),
             "#.#.#..#..".chars().collect(),
@@ -266,11 +162,10 @@ mod tests {
             "##########".chars().collect(),
             "#.######.#".chars().collect(),
             "#.#####.##".chars().collect()
-        ];
-        let mut output = input.clone();
-        iterate_state(&input, &mut output);
+        ]);
+        let output = iterate_state(&input);
 
-        let expected:Vec<Vec<char>> = vec![
+        let expected = Grid::from_rows(vec![
             "#.LL.L#.##".chars().collect(),
             "#LLLLLL.L#".chars().collect(),
             "L.L.L..L..".chars().collect(),
@@ -281,7 +176,7 @@ mod tests {
             "#LLLLLLLL#".chars().collect(),
             "#.LLLLLL.L".chars().collect(),
             "#.#LLLL.##".chars().collect()
-        ];
+        ]);
         assert_eq!(expected, output);
     }
 
@@ -291,17 +186,18 @@ mod tests {
         let line_one:Vec<char> = "L.LL.LL.LL".chars().collect();
         let line_two:Vec<char> = "..L.L.....".chars().collect();
         let line_three:Vec<char> = "#.#####.##".chars().collect();
-        assert_eq!(0, count_occupied(0, None, &line_one, Some(&line_two)));
-        assert_eq!(0, count_occupied(0, Some(&line_two), &line_three, Some(&line_two)));
-        assert_eq!(8, count_occupied(3, Some(&line_three), &line_three, Some(&line_three)));
-        assert_eq!(2, count_occupied(1, Some(&line_one), &line_three, Some(&line_one)));
-        assert_eq!(5, count_occupied(9, Some(&line_three), &line_three, Some(&line_three)));
+        let layout = |lines: &[&Vec<char>]| Grid::from_rows(lines.iter().map(|l| l.to_vec()).collect());
+        assert_eq!(0, count_occupied((0, 0), &layout(&[&line_one, &line_two])));
+        assert_eq!(0, count_occupied((0, 1), &layout(&[&line_two, &line_three, &line_two])));
+        assert_eq!(8, count_occupied((3, 1), &layout(&[&line_three, &line_three, &line_three])));
+        assert_eq!(2, count_occupied((1, 1), &layout(&[&line_one, &line_three, &line_one])));
+        assert_eq!(5, count_occupied((9, 1), &layout(&[&line_three, &line_three, &line_three])));
     }
 
     #[test]
     fn test_count_final_state() 
     {
-        let input:Vec<Vec<char>> = vec![
+        let input = Grid::from_rows(vec![
             "L.LL.LL.LL".chars().collect(),
             "LLLLLLL.LL".chars().collect(),
             "L.L.L..L..".chars().collect(),
@@ -312,14 +208,30 @@ mod tests {
             "LLLLLLLLLL".chars().collect(),
             "L.LLLLLL.L".chars().collect(),
             "L.LLLLL.LL".chars().collect()
-        ];
+        ]);
         assert_eq!(37, count_final_state(&input));
     }
 
+    #[test]
+    fn test_count_final_state2() 
+    {
+        let input = Grid::parse("L.LL.LL.LL
+LLLLLLL.LL
+L.L.L..L..
+LLLL.LL.LL
+L.LL.LL.LL
+L.LLLLL.LL
+..L.L.....
+LLLLLLLLLL
+L.LLLLLL.L
+L.LLLLL.LL", |c| c).unwrap();
+        assert_eq!(26, count_final_state2(&input));
+    }
+
     #[test]
     fn test_count_occupied2_full() 
     {
-        let input:Vec<Vec<char>> = vec![
+        let input = Grid::from_rows(vec![
             ".......#.".chars().collect(),
             "...#.....".chars().collect(),
             ".#.......".chars().collect(),
@@ -329,25 +241,25 @@ mod tests {
             ".........".chars().collect(),
             "#........".chars().collect(),
             "...#.....".chars().collect()
-        ];
-        assert_eq!(8, count_occupied2(3, 4, &input));
+        ]);
+        assert_eq!(8, count_occupied2((3, 4), &input));
     }
 
     #[test]
     fn test_count_occupied2_empty1() 
     {
-        let input:Vec<Vec<char>> = vec![
+        let input = Grid::from_rows(vec![
             ".............".chars().collect(),
             ".L.L.#.#.#.#.".chars().collect(),
             ".............".chars().collect()
-        ];
-        assert_eq!(0, count_occupied2(1, 1, &input));
+        ]);
+        assert_eq!(0, count_occupied2((1, 1), &input));
     }
 
     #[test]
     fn test_count_occupied2_empty2() 
     {
-        let input:Vec<Vec<char>> = vec![
+        let input = Grid::from_rows(vec![
             ".##.##.".chars().collect(),
             "#.#.#.#".chars().collect(),
             "##...##".chars().collect(),
@@ -355,7 +267,7 @@ mod tests {
             "##...##".chars().collect(),
             "#.#.#.#".chars().collect(),
             ".##.##.".chars().collect()
-        ];
-        assert_eq!(0, count_occupied2(3, 3, &input));
+        ]);
+        assert_eq!(0, count_occupied2((3, 3), &input));
     }
 }
\ No newline at end of file
@@ -1,93 +1,103 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use aoc_common::{ParseError, ParseResult, Point, Solution};
 
 fn main() {
-    if let Ok(lines) = read_lines("src/12/input.txt") {
-        let commands:Vec<(char, i32)> = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap())
-            .map(|r| parse_command(&r))
-            .collect();
-
-        let pos1 = follow_commands(&commands);
-        println!("[{:?}, {:?}]", pos1.0, pos1.1);
-        println!("Distance: {:?}", pos1.0.abs() + pos1.1.abs());
-        let pos2 = follow_waypoint(&commands);
-        println!("[{:?}, {:?}]", pos2.0, pos2.1);
-        println!("Distance: {:?}", pos2.0.abs() + pos2.1.abs());
+    aoc_common::run_day::<Day>(2020, 12);
+}
+
+pub struct Day;
+
+impl Solution for Day
+{
+    type Parsed = Vec<(char, i32)>;
+    type PartOne = i32;
+    type PartTwo = i32;
+
+    fn parse(input: &str) -> ParseResult<Vec<(char, i32)>>
+    {
+        return parse(input);
+    }
+
+    fn part_one(input: &Vec<(char, i32)>) -> i32
+    {
+        let pos = follow_commands(input);
+        return pos.0.abs() + pos.1.abs();
+    }
+
+    fn part_two(input: &Vec<(char, i32)>) -> i32
+    {
+        let pos = follow_waypoint(input);
+        return pos.0.abs() + pos.1.abs();
     }
 }
 
-pub fn parse_command(input: &String) -> (char, i32)
+fn parse(input: &str) -> ParseResult<Vec<(char, i32)>>
 {
-    return (input.chars().next().unwrap(), input[1..].parse::<i32>().unwrap());
+    return aoc_common::lines(input)
+        .enumerate()
+        .map(|(y, r)| parse_command(r).map_err(|e| ParseError::at_line(input, y, 0, e)))
+        .collect();
+}
+
+/// An action letter followed by its value, such as `F10`.
+pub fn parse_command(input: &str) -> Result<(char, i32), String>
+{
+    let action = match input.chars().next()
+    {
+        Some(c) if "NSEWFLR".contains(c) => c,
+        _ => return Err(format!("expected an action from NSEWLRF, got '{}'", input))
+    };
+    let value = input[1..].parse::<i32>().map_err(|_| format!("invalid value in '{}'", input))?;
+    return Ok((action, value));
 }
 
 pub fn follow_commands(commands: &Vec<(char, i32)>) -> (i32, i32)
 {
-    let mut pos = (0, 0);
-    let mut dir = (1, 0);
+    let mut pos = Point::new(0, 0);
+    let mut dir = Point::new(1, 0);
     for cmd in commands
     {
         match cmd.0
         {
-            'N' => pos.1 = pos.1 + cmd.1,
-            'S' => pos.1 = pos.1 - cmd.1,
-            'E' => pos.0 = pos.0 + cmd.1,
-            'W' => pos.0 = pos.0 - cmd.1,
-            'F' => {
-                pos.0 = pos.0 + (dir.0*cmd.1);
-                pos.1 = pos.1 + (dir.1*cmd.1);
-            },
-            'L' => dir = rotate_vec(&dir, -cmd.1),
-            'R' => dir = rotate_vec(&dir, cmd.1),
+            'N' => pos.y = pos.y + cmd.1,
+            'S' => pos.y = pos.y - cmd.1,
+            'E' => pos.x = pos.x + cmd.1,
+            'W' => pos.x = pos.x - cmd.1,
+            'F' => pos = pos + dir * cmd.1,
+            'L' => dir = rotate_vec(dir, -cmd.1),
+            'R' => dir = rotate_vec(dir, cmd.1),
             _ => panic!("Unknown command: {}{}", cmd.0, cmd.1)
         }
     }
-    return pos;
+    return (pos.x, pos.y);
 }
 
 
 pub fn follow_waypoint(commands: &Vec<(char, i32)>) -> (i32, i32)
 {
-    let mut pos = (0, 0);
-    let mut dir = (10, 1);
+    let mut pos = Point::new(0, 0);
+    let mut dir = Point::new(10, 1);
     for cmd in commands
     {
         match cmd.0
         {
-            'N' => dir.1 = dir.1 + cmd.1,
-            'S' => dir.1 = dir.1 - cmd.1,
-            'E' => dir.0 = dir.0 + cmd.1,
-            'W' => dir.0 = dir.0 - cmd.1,
-            'F' => {
-                pos.0 = pos.0 + (dir.0*cmd.1);
-                pos.1 = pos.1 + (dir.1*cmd.1);
-            },
-            'L' => dir = rotate_vec(&dir, -cmd.1),
-            'R' => dir = rotate_vec(&dir, cmd.1),
+            'N' => dir.y = dir.y + cmd.1,
+            'S' => dir.y = dir.y - cmd.1,
+            'E' => dir.x = dir.x + cmd.1,
+            'W' => dir.x = dir.x - cmd.1,
+            'F' => pos = pos + dir * cmd.1,
+            'L' => dir = rotate_vec(dir, -cmd.1),
+            'R' => dir = rotate_vec(dir, cmd.1),
             _ => panic!("Unknown command: {}{}", cmd.0, cmd.1)
         }
     }
-    return pos;
+    return (pos.x, pos.y);
 }
 
-// rotates right
-pub fn rotate_vec(vec: &(i32, i32), angle_deg: i32) -> (i32, i32)
+// rotates right, in whole quarter turns. North is +y here rather than -y as
+// on screen, so turning right is an anticlockwise rotation of the point.
+pub fn rotate_vec(vec: Point<i32>, angle_deg: i32) -> Point<i32>
 {
-    let sc = (-angle_deg as f32).to_radians().sin_cos();
-    let fvec = (vec.0 as f32, vec.1 as f32);
-    return (
-        ((fvec.0 * sc.1) - (fvec.1 * sc.0)).round() as i32,
-        ((fvec.0 * sc.0) + (fvec.1 * sc.1)).round() as i32
-    );
-}
-
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+    return vec.rotate(-angle_deg / 90);
 }
 
 #[cfg(test)]
@@ -97,15 +107,15 @@ mod tests {
     #[test]
     fn test_parse_command() 
     {
-        assert_eq!(('F', 10), parse_command(&String::from("F10")));
-        assert_eq!(('N', 3), parse_command(&String::from("N3")));
-        assert_eq!(('S', 15), parse_command(&String::from("S15")));
-        assert_eq!(('E', 30), parse_command(&String::from("E30")));
-        assert_eq!(('W', 50), parse_command(&String::from("W50")));
-        assert_eq!(('F', 7), parse_command(&String::from("F7")));
-        assert_eq!(('R', 90), parse_command(&String::from("R90")));
-        assert_eq!(('F', 11), parse_command(&String::from("F11")));
-        assert_eq!(('L', 45), parse_command(&String::from("L45")));
+        assert_eq!(Ok(('F', 10)), parse_command("F10"));
+        assert_eq!(Ok(('N', 3)), parse_command("N3"));
+        assert_eq!(Ok(('S', 15)), parse_command("S15"));
+        assert_eq!(Ok(('E', 30)), parse_command("E30"));
+        assert_eq!(Ok(('W', 50)), parse_command("W50"));
+        assert_eq!(Ok(('F', 7)), parse_command("F7"));
+        assert_eq!(Ok(('R', 90)), parse_command("R90"));
+        assert_eq!(Ok(('F', 11)), parse_command("F11"));
+        assert_eq!(Ok(('L', 45)), parse_command("L45"));
     }
 
     #[test]
@@ -189,11 +199,20 @@ mod tests {
     #[test]
     fn test_rotate_vec() 
     {
-        assert_eq!((0, -1), rotate_vec(&(1, 0), 90));
-        assert_eq!((0, -1), rotate_vec(&(1, 0), -270));
-        assert_eq!((0, 1), rotate_vec(&(1, 0), -90));
-        assert_eq!((0, 1), rotate_vec(&(1, 0), 270));
-        assert_eq!((-1, 0), rotate_vec(&(1, 0), 180));
-        assert_eq!((1, -10), rotate_vec(&(10, 1), 90));
+        assert_eq!(Point::new(0, -1), rotate_vec(Point::new(1, 0), 90));
+        assert_eq!(Point::new(0, -1), rotate_vec(Point::new(1, 0), -270));
+        assert_eq!(Point::new(0, 1), rotate_vec(Point::new(1, 0), -90));
+        assert_eq!(Point::new(0, 1), rotate_vec(Point::new(1, 0), 270));
+        assert_eq!(Point::new(-1, 0), rotate_vec(Point::new(1, 0), 180));
+        assert_eq!(Point::new(1, -10), rotate_vec(Point::new(10, 1), 90));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_errors()
+    {
+        let err = Day::parse("F10\nX3\n").unwrap_err();
+        assert_eq!((2, 1), (err.line, err.column));
+        assert!(Day::parse("F10\nN\n").is_err());
+        assert!(Day::parse("F10\n\u{e9}3\n").is_err());
+    }
+}
@@ -1,27 +1,50 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
-use std::time::{Instant};
+use aoc_common::{ParseError, ParseResult, Solution};
 
-fn main() 
+fn main() {
+    aoc_common::run_day::<Day>(2020, 13);
+}
+
+pub struct Day;
+
+impl Solution for Day
 {
-    if let Ok(lines) = read_lines("src/13/input.txt") {
-        let mut it = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap());
-        let timestamp = it.next().unwrap().parse::<i64>().unwrap();
-        let buses_with_zeros = get_buses(&it.next().unwrap());
+    type Parsed = (i64, Vec<(i64, i64)>);
+    type PartOne = i64;
+    type PartTwo = i64;
+
+    fn parse(input: &str) -> ParseResult<(i64, Vec<(i64, i64)>)>
+    {
+        return parse(input);
+    }
+
+    fn part_one(input: &(i64, Vec<(i64, i64)>)) -> i64
+    {
+        let (timestamp, buses_with_zeros) = input;
         let buses = remove_indexes(&buses_with_zeros);
 
-        let t = first_time(timestamp, &buses);
+        let t = first_time(*timestamp, &buses);
         let diff = t.1 - timestamp;
-        println!("Found: {:?} in {:?} ({:?})", t.0, diff, t.1);
-        println!("Result: {:?}", diff*t.0);
-        let best = find_perfect_time(&buses_with_zeros);
-        println!("Result 2: {:?}", best);
+        return diff*t.0;
+    }
+
+    fn part_two(input: &(i64, Vec<(i64, i64)>)) -> i64
+    {
+        let (_, buses_with_zeros) = input;
+        return find_perfect_time(&buses_with_zeros);
     }
 }
 
+fn parse(input: &str) -> ParseResult<(i64, Vec<(i64, i64)>)>
+{
+    let mut it = aoc_common::lines(input);
+    let line = it.next().unwrap_or("");
+    let timestamp = line.parse::<u32>()
+        .map_err(|_| ParseError::at_slice(input, line, "expected the earliest departure time"))?;
+    let line = it.next().unwrap_or(&input[input.len()..]);
+    let buses_with_zeros = get_buses(input, line)?;
+    return Ok((timestamp as i64, buses_with_zeros));
+}
+
 fn first_time(start: i64, buses: &Vec<i64>) -> (i64, i64)
 {
     let mut time = start;
@@ -37,31 +60,39 @@ fn first_time(start: i64, buses: &Vec<i64>) -> (i64, i64)
     }
 }
 
+/// Each bus leaving `offset` minutes after the time means the time is
+/// congruent to `-offset` modulo the bus's id.
 fn find_perfect_time(buses: &Vec<(i64, i64)>) -> i64
 {
-    let now = Instant::now();
-    let mut time = 0;
-    let mut step = 1;
-    for bus in buses
+    let congruences:Vec<(i128, i128)> = buses.iter()
+        .map(|(offset, bus)| (-*offset as i128, *bus as i128))
+        .collect();
+    let (time, _) = aoc_common::crt(&congruences).expect("the buses never line up");
+    return time as i64;
+}
+
+/// The buses in service from `line`, part of `input`, with their positions
+/// in the list.
+fn get_buses(input: &str, line: &str) -> ParseResult<Vec<(i64, i64)>>
+{
+    let mut buses = Vec::new();
+    for (i, bus) in line.split(',').enumerate()
     {
-        while (time + bus.0) % bus.1 != 0
+        if bus == "x"
         {
-            time = time + step;
+            continue;
+        }
+        match bus.parse::<u32>()
+        {
+            Ok(id) if id > 0 => buses.push((i as i64, id as i64)),
+            _ => return Err(ParseError::at_slice(input, bus, "expected a bus id or 'x'"))
         }
-        step = step * bus.1;
     }
-    let elapsed = now.elapsed();
-    println!("Found answer in {}.{}s", elapsed.as_secs(), elapsed.subsec_millis());
-    return time;
-}
-
-fn get_buses(input: &String) -> Vec<(i64, i64)>
-{
-    return input.split(',')
-        .enumerate()
-        .filter(|x| x.1 != "x")
-        .map(|x| (x.0 as i64, x.1.parse::<i64>().unwrap()))
-        .collect();
+    if buses.is_empty()
+    {
+        return Err(ParseError::at_slice(input, line, "expected at least one bus"));
+    }
+    return Ok(buses);
 }
 
 fn remove_indexes(input: &Vec<(i64, i64)>) -> Vec<i64>
@@ -71,25 +102,34 @@ fn remove_indexes(input: &Vec<(i64, i64)>) -> Vec<i64>
         .collect();
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
-
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn buses(line: &str) -> Vec<(i64, i64)>
+    {
+        return get_buses(line, line).unwrap();
+    }
+
     #[test]
     fn test_find_perfect_time() 
     {
-        assert_eq!(1068781, find_perfect_time(&get_buses(&String::from("7,13,x,x,59,x,31,19"))));
-        assert_eq!(3417, find_perfect_time(&get_buses(&String::from("17,x,13,19"))));
-        assert_eq!(754018, find_perfect_time(&get_buses(&String::from("67,7,59,61"))));
-        assert_eq!(779210, find_perfect_time(&get_buses(&String::from("67,x,7,59,61"))));
-        assert_eq!(1261476, find_perfect_time(&get_buses(&String::from("67,7,x,59,61"))));
-        assert_eq!(1202161486, find_perfect_time(&get_buses(&String::from("1789,37,47,1889"))));
+        assert_eq!(1068781, find_perfect_time(&buses(("7,13,x,x,59,x,31,19"))));
+        assert_eq!(3417, find_perfect_time(&buses(("17,x,13,19"))));
+        assert_eq!(754018, find_perfect_time(&buses(("67,7,59,61"))));
+        assert_eq!(779210, find_perfect_time(&buses(("67,x,7,59,61"))));
+        assert_eq!(1261476, find_perfect_time(&buses(("67,7,x,59,61"))));
+        assert_eq!(1202161486, find_perfect_time(&buses(("1789,37,47,1889"))));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_errors()
+    {
+        let err = Day::parse("939\n7,13,x,0,59\n").unwrap_err();
+        assert_eq!((2, 8), (err.line, err.column));
+        assert!(Day::parse("939\nx,x\n").is_err());
+        assert!(Day::parse("soon\n7,13\n").is_err());
+        assert!(Day::parse("939\n").is_err());
+    }
+}
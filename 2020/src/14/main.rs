@@ -1,168 +1,328 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use std::collections::HashMap;
 
-#[derive(Debug)]
+use aoc_common::{BitSet, Debuggable, Flow, InstructionSet, Machine, ParseError, ParseResult, Solution};
+
+#[derive(Debug, Clone)]
 pub struct VMState {
-    mask_bits: u64,
-    mask_data: u64,
-    memory: Vec<(u64, u64)>
+    mask_bits: BitSet,
+    mask_data: BitSet,
+    memory: HashMap<u64, u64>
 }
 
 impl VMState {
     fn new() -> VMState {
         return VMState {
-            mask_bits: u64::MAX,
-            mask_data: 0,
-            memory: Vec::new()
+            mask_bits: BitSet::full(),
+            mask_data: BitSet::new(),
+            memory: HashMap::new()
         };
     }
 
+    /// The value at each address written to, in address order.
+    fn contents(&self) -> Vec<(u64, u64)>
+    {
+        let mut contents: Vec<(u64, u64)> = self.memory.iter()
+            .map(|(&addr, &val)| (addr, val))
+            .collect();
+        contents.sort();
+        return contents;
+    }
+
     #[allow(dead_code)]
     fn get_mem(&self, addr: u64) -> u64
     {
-        return self.memory.iter()
-            .find(|x| x.0 == addr)
-            .map(|x| x.1)
-            .unwrap_or(0);
+        return *self.memory.get(&addr).unwrap_or(&0);
     }
 
     fn sum_mem(&self) -> u64
     {
-        return self.memory.iter()
-            .map(|x| x.1)
-            .sum();
+        return self.memory.values().sum();
     }
 
     fn set_mem(&mut self, addr: u64, val: u64)
     {
-        let mem = self.memory.iter_mut()
-            .find(|x| x.0 == addr);
-        if mem.is_some()
-        {
-            mem.unwrap().1 = val;
-        }
-        else
-        {
-            self.memory.push((addr, val));
-        }
+        self.memory.insert(addr, val);
     }
 
     fn mask_value(&self, val: u64) -> u64
     {
-        return (val & self.mask_bits) | self.mask_data;
+        return BitSet::from_bits(val)
+            .intersection(&self.mask_bits)
+            .union(&self.mask_data)
+            .bits();
     }
 
     fn mask_addr(&self, addr: u64) -> Vec<u64>
     {
-        let base_addr = (addr | self.mask_data) & !self.mask_bits;
-        let mut results = Vec::new();
-        results.push(base_addr);
-
-        for i in 0..36
-        {
-            let m = 1 << i;
-            if self.mask_bits & m > 0
-            {
-                let len = results.len();
-                for j in 0..len
-                {
-                    results.push(results[j] | m);
-                }
-            }
-        }
+        let base_addr = BitSet::from_bits(addr)
+            .union(&self.mask_data)
+            .difference(&self.mask_bits);
 
-        return results;
+        return self.mask_bits.subsets()
+            .map(|floating| base_addr.union(&floating).bits())
+            .collect();
     }
 
     fn set_mask(&mut self, mask: &str)
     {
         self.mask_bits = mask.chars()
+            .rev()
             .enumerate()
             .filter(|x| x.1 == 'X')
-            .map(|x| 1 << (35 - x.0))
-            .sum();
+            .map(|x| x.0)
+            .collect();
         self.mask_data = mask.chars()
+            .rev()
             .enumerate()
             .filter(|x| x.1 == '1')
-            .map(|x| 1 << (35 - x.0))
-            .sum();
+            .map(|x| x.0)
+            .collect();
+    }
+
+    /// The mask as written in the program.
+    fn mask_string(&self) -> String
+    {
+        return (0..36).rev()
+            .map(|i| if self.mask_bits.contains(i) { 'X' } else if self.mask_data.contains(i) { '1' } else { '0' })
+            .collect();
     }
 
     #[allow(dead_code)]
     fn debug_print(&self)
     {
         println!("=== VM STATE ===");
-        println!("Mask bits: {}", format!("{:036b}", self.mask_bits));
-        println!("Mask data: {}", format!("{:036b}", self.mask_data));
+        println!("Mask bits: {}", format!("{:036b}", self.mask_bits.bits()));
+        println!("Mask data: {}", format!("{:036b}", self.mask_data.bits()));
         println!("Memory [");
-        for m in self.memory.iter()
+        for (addr, val) in self.contents()
         {
-            println!(" {}: {}", m.0, m.1);
+            println!(" {}: {}", addr, val);
         }
         println!("]");
     }
 }
 
-fn main() 
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Mask(String),
+    Mem(u64, u64)
+}
+
+/// The ferry's docking program, a command at a time. Version 1 masks the
+/// values written and version 2 the addresses.
+#[derive(Debug, Clone)]
+pub struct Docking {
+    version: i32
+}
+
+/// What a command overwrote, so the debugger can step back without copying
+/// the whole memory.
+#[derive(Debug, Clone)]
+pub enum Overwritten {
+    Mask(BitSet, BitSet),
+    /// Each address written to and what it held before, if anything.
+    Mem(Vec<(u64, Option<u64>)>)
+}
+
+impl Docking
 {
-    if let Ok(lines) = read_lines("src/14/input.txt") {
-        let program = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap())
+    /// The address and value of each write `mem[addr] = val` makes.
+    fn writes(&self, state: &VMState, addr: u64, val: u64) -> Vec<(u64, u64)>
+    {
+        if self.version == 1
+        {
+            return vec![(addr, state.mask_value(val))];
+        }
+        return state.mask_addr(addr)
+            .into_iter()
+            .map(|a| (a, val))
             .collect();
-        let state1 = run_program(&program, 1);
-        println!("Memory Sum 1: {}", state1.sum_mem());
-        let state2 = run_program(&program, 2);
-        println!("Memory Sum 2: {}", state2.sum_mem());
     }
 }
 
-pub fn run_program(input: &Vec<String>, version: i32) -> VMState
+impl InstructionSet for Docking
 {
-    let mut state = VMState::new();
-    for cmd in input
+    type Code = Command;
+    type Instruction = Command;
+    type State = VMState;
+    type Undo = Overwritten;
+
+    fn decode(&self, code: &[Command], ip: usize) -> Result<(Command, usize), String>
+    {
+        return Ok((code[ip].clone(), 1));
+    }
+
+    fn execute(&self, cmd: &Command, state: &mut VMState) -> Result<Flow, String>
     {
-        if &cmd[0..4] == "mask"
+        match cmd
         {
-            let val = &cmd[7..];
-            state.set_mask(val);
+            Command::Mask(mask) => state.set_mask(mask),
+            Command::Mem(addr, val) =>
+            {
+                for (a, v) in self.writes(state, *addr, *val)
+                {
+                    state.set_mem(a, v);
+                }
+            }
         }
-        else if &cmd[0..3] == "mem"
+        return Ok(Flow::Next);
+    }
+
+    fn save(&self, cmd: &Command, state: &VMState) -> Overwritten
+    {
+        return match cmd {
+            Command::Mask(_) => Overwritten::Mask(state.mask_bits, state.mask_data),
+            Command::Mem(addr, val) => Overwritten::Mem(
+                self.writes(state, *addr, *val)
+                    .iter()
+                    .map(|&(a, _)| (a, state.memory.get(&a).copied()))
+                    .collect()
+            )
+        };
+    }
+
+    fn restore(&self, undo: Overwritten, state: &mut VMState)
+    {
+        match undo
         {
-            let addr = cmd.split(']').next().unwrap()[4..].parse::<u64>().unwrap();
-            let val = cmd.split('=').skip(1).next().unwrap()[1..].parse::<u64>().unwrap();
-            if version == 1
+            Overwritten::Mask(bits, data) =>
             {
-                state.set_mem(addr, state.mask_value(val));
+                state.mask_bits = bits;
+                state.mask_data = data;
             }
-            else
+            Overwritten::Mem(old) =>
             {
-                let addrs = state.mask_addr(addr);
-                for a in addrs
+                for (addr, val) in old.into_iter().rev()
                 {
-                    state.set_mem(a, val);
+                    match val
+                    {
+                        Some(val) => state.set_mem(addr, val),
+                        None => { state.memory.remove(&addr); }
+                    }
                 }
             }
         }
-        else
+    }
+}
+
+impl Debuggable for Docking
+{
+    fn mnemonic(&self, cmd: &Command) -> String
+    {
+        return match cmd {
+            Command::Mask(_) => String::from("mask"),
+            Command::Mem(..) => String::from("mem")
+        };
+    }
+
+    fn describe(&self, cmd: &Command) -> String
+    {
+        return match cmd {
+            Command::Mask(mask) => format!("mask = {}", mask),
+            Command::Mem(addr, val) => format!("mem[{}] = {}", addr, val)
+        };
+    }
+
+    fn registers(&self, state: &VMState) -> Vec<(String, String)>
+    {
+        return vec![(String::from("mask"), state.mask_string())];
+    }
+
+    fn memory(&self, state: &VMState) -> Vec<(String, String)>
+    {
+        return state.contents()
+            .iter()
+            .map(|(addr, val)| (format!("mem[{}]", addr), val.to_string()))
+            .collect();
+    }
+}
+
+fn main() {
+    aoc_common::run_debuggable::<Day, _>(2020, 14, debug_machine);
+}
+
+fn debug_machine(input: &Vec<Command>, part: u32) -> Machine<Docking>
+{
+    return Machine::new(Docking { version: part as i32 }, input.to_vec(), VMState::new());
+}
+
+pub struct Day;
+
+impl Solution for Day
+{
+    type Parsed = Vec<Command>;
+    type PartOne = u64;
+    type PartTwo = u64;
+
+    fn parse(input: &str) -> ParseResult<Vec<Command>>
+    {
+        return parse(input);
+    }
+
+    fn part_one(input: &Vec<Command>) -> u64
+    {
+        return run_program(input, 1).sum_mem();
+    }
+
+    fn part_two(input: &Vec<Command>) -> u64
+    {
+        return run_program(input, 2).sum_mem();
+    }
+}
+
+/// The program, which must set a mask before writing to memory.
+fn parse(input: &str) -> ParseResult<Vec<Command>>
+{
+    let mut program = Vec::new();
+    for (y, line) in aoc_common::lines(input).enumerate()
+    {
+        let cmd = parse_command(line).map_err(|e| ParseError::at_line(input, y, 0, e))?;
+        if program.is_empty() && matches!(cmd, Command::Mem(..))
+        {
+            return Err(ParseError::at_line(input, y, 0, "mem comes before any mask"));
+        }
+        program.push(cmd);
+    }
+    return Ok(program);
+}
+
+fn parse_command(cmd: &str) -> Result<Command, String>
+{
+    if let Some(mask) = cmd.strip_prefix("mask = ")
+    {
+        if mask.len() != 36 || !mask.chars().all(|c| c == 'X' || c == '0' || c == '1')
         {
-            panic!("Unrecognised command {:?}", cmd);
+            return Err(format!("expected a mask of 36 Xs, 0s and 1s, got '{}'", mask));
         }
+        return Ok(Command::Mask(mask.to_string()));
     }
-    return state;
+    let (addr, val) = cmd.strip_prefix("mem[")
+        .and_then(|rest| rest.split_once("] = "))
+        .ok_or_else(|| format!("Unrecognised command {:?}", cmd))?;
+    let addr = addr.parse::<u64>().map_err(|_| format!("invalid address '{}'", addr))?;
+    let val = val.parse::<u64>().map_err(|_| format!("invalid value '{}'", val))?;
+    return Ok(Command::Mem(addr, val));
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+pub fn run_program(input: &Vec<Command>, version: i32) -> VMState
+{
+    let mut machine = Machine::new(Docking { version: version }, input.to_vec(), VMState::new());
+    machine.run().unwrap_or_else(|e| panic!("{}", e));
+    return machine.into_state();
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_mask_string()
+    {
+        let mut state = VMState::new();
+        state.set_mask("XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X");
+        assert_eq!("XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X", state.mask_string());
+    }
+
     #[test]
     fn test_get_set_mem() 
     {
@@ -174,16 +334,55 @@ mod tests {
         assert_eq!(1024, state.get_mem(100));
     }
 
+    #[test]
+    fn test_step_back()
+    {
+        let input = "mask = 000000000000000000000000000000X1001X
+mem[42] = 100
+mask = 00000000000000000000000000000000X0XX
+mem[26] = 1
+mem[58] = 7";
+        let mut machine = debug_machine(&parse(input).unwrap(), 2).with_trace();
+        machine.run().unwrap();
+        assert_eq!(7, machine.state().get_mem(58));
+
+        assert!(machine.step_back());
+        assert_eq!(100, machine.state().get_mem(58));
+        assert!(machine.step_back());
+        assert_eq!(0, machine.state().get_mem(16));
+        assert_eq!(400, machine.state().sum_mem());
+        assert!(machine.step_back());
+        assert_eq!("000000000000000000000000000000X1001X", machine.state().mask_string());
+        while machine.step_back() {}
+        assert!(machine.state().memory.is_empty());
+    }
+
+    #[test]
+    fn test_parse_error()
+    {
+        let mask = "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X";
+        let err = Day::parse(&format!("{}\nmem[8 = 11\n", mask)).unwrap_err();
+        assert_eq!((2, 1), (err.line, err.column));
+        assert_eq!("Unrecognised command \"mem[8 = 11\"", err.message);
+
+        let err = Day::parse(&format!("mem[8] = 11\n{}\n", mask)).unwrap_err();
+        assert_eq!((1, 1), (err.line, err.column));
+        assert_eq!("mem comes before any mask", err.message);
+
+        let long = format!("mask = {}\nmem[8] = 11\n", "X".repeat(65));
+        assert!(Day::parse(&long).is_err());
+        assert!(Day::parse("mask = X1\nmem[8] = 11\n").is_err());
+        assert!(Day::parse("mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX2X\n").is_err());
+    }
+
     #[test]
     fn test_run_program() 
     {
-        let input = vec![
-            String::from("mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X"),
-            String::from("mem[8] = 11"),
-            String::from("mem[7] = 101"),
-            String::from("mem[8] = 0")
-        ];
-        let state = run_program(&input, 1);
+        let input = "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X
+mem[8] = 11
+mem[7] = 101
+mem[8] = 0";
+        let state = run_program(&parse(input).unwrap(), 1);
         assert_eq!(101, state.get_mem(7));
         assert_eq!(64, state.get_mem(8));
         assert_eq!(165, state.sum_mem());
@@ -192,13 +391,11 @@ mod tests {
     #[test]
     fn test_run_program_v2() 
     {
-        let input = vec![
-            String::from("mask = 000000000000000000000000000000X1001X"),
-            String::from("mem[42] = 100"),
-            String::from("mask = 00000000000000000000000000000000X0XX"),
-            String::from("mem[26] = 1")
-        ];
-        let state = run_program(&input, 2);
+        let input = "mask = 000000000000000000000000000000X1001X
+mem[42] = 100
+mask = 00000000000000000000000000000000X0XX
+mem[26] = 1";
+        let state = run_program(&parse(input).unwrap(), 2);
         assert_eq!(100, state.get_mem(58));
         assert_eq!(100, state.get_mem(59));
         assert_eq!(1, state.get_mem(16));
@@ -1,9 +1,36 @@
+use aoc_common::{ParseResult, Solution};
 
-fn main() 
+fn main() {
+    aoc_common::run_day::<Day>(2020, 15);
+}
+
+pub struct Day;
+
+impl Solution for Day
+{
+    type Parsed = Vec<usize>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> ParseResult<Vec<usize>>
+    {
+        return parse(input);
+    }
+
+    fn part_one(input: &Vec<usize>) -> usize
+    {
+        return get_nth_number(input, 2020);
+    }
+
+    fn part_two(input: &Vec<usize>) -> usize
+    {
+        return get_nth_number(input, 30000000);
+    }
+}
+
+fn parse(input: &str) -> ParseResult<Vec<usize>>
 {
-    let input = vec![17,1,3,16,19,0];
-    println!("2020th #: {}", get_nth_number(&input, 2020));
-    println!("30000000th #: {}", get_nth_number(&input, 30000000));
+    return aoc_common::numbers(input);
 }
 
 pub fn get_nth_number(input: &Vec<usize>, n: usize) -> usize
@@ -1,6 +1,4 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use aoc_common::{ParseError, ParseResult, Solution};
 
 #[derive(Debug)]
 pub struct Rule {
@@ -15,75 +13,116 @@ pub struct Field {
 }
 
 
-fn main() 
+fn main() {
+    aoc_common::run_day::<Day>(2020, 16);
+}
+
+pub struct Day;
+
+impl Solution for Day
 {
-    if let Ok(lines) = read_lines("src/16/input.txt") {
-        let input = &mut lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap());
-        
-        let fields = get_fields(input);
-        let my_ticket = parse_ticket(&input.skip(1).next().unwrap());
-        let tickets:Vec<_> = input.skip(2).map(|s| parse_ticket(&s)).collect();
-
-        let error_rate = calc_error_rate(&tickets, &fields);
-        println!("Error Rate: {}", error_rate);
+    type Parsed = (Vec<Field>, Vec<i32>, Vec<Vec<i32>>);
+    type PartOne = i32;
+    type PartTwo = i64;
+
+    fn parse(input: &str) -> ParseResult<(Vec<Field>, Vec<i32>, Vec<Vec<i32>>)>
+    {
+        return parse(input);
+    }
+
+    fn part_one(input: &(Vec<Field>, Vec<i32>, Vec<Vec<i32>>)) -> i32
+    {
+        let (fields, _, tickets) = input;
+        return calc_error_rate(&tickets, &fields);
+    }
 
+    fn part_two(input: &(Vec<Field>, Vec<i32>, Vec<Vec<i32>>)) -> i64
+    {
+        let (fields, my_ticket, tickets) = input;
         let valid_tickets = tickets.iter()
             .filter(|t| find_invalid_value(t, &fields).is_none())
             .cloned()
             .collect();
         let field_order = find_field_order(&valid_tickets, &fields);
-        println!("My Ticket:");
-        for i in 0..field_order.len()
-        {
-            println!("{}: {}", field_order[i], my_ticket[i]);
-        }
-        let dep_result:i64 = field_order.iter()
+        return field_order.iter()
             .enumerate()
             .filter(|f| f.1.len() > 8)
             .filter(|f| &f.1[..9] == "departure")
             .map(|f| my_ticket[f.0] as i64)
             .product();
-        println!("Departure Product: {}", dep_result);
     }
 }
 
-fn get_fields(input: &mut dyn Iterator<Item = String>) -> Vec<Field>
+fn parse(input: &str) -> ParseResult<(Vec<Field>, Vec<i32>, Vec<Vec<i32>>)>
 {
-    let mut fields = Vec::new();
-    for line in input
+    let lines: Vec<&str> = aoc_common::lines(input).collect();
+    let blank = lines.iter().position(|l| l.is_empty()).unwrap_or(lines.len());
+    let fields = lines[..blank].iter()
+        .map(|line| parse_field(input, line))
+        .collect::<ParseResult<Vec<Field>>>()?;
+
+    let expect = |y: usize, text: &str| -> ParseResult<()>
     {
-        if line == ""
+        if lines.get(y) == Some(&text)
         {
-            break;
+            return Ok(());
         }
-        fields.push(parse_field(&line));
+        return Err(ParseError::at_line(input, y, 0, format!("expected '{}'", text)));
+    };
+    expect(blank + 1, "your ticket:")?;
+    let my_ticket = parse_ticket(input, blank + 2, fields.len())?;
+    expect(blank + 3, "")?;
+    expect(blank + 4, "nearby tickets:")?;
+    let tickets = (blank + 5..lines.len())
+        .map(|y| parse_ticket(input, y, fields.len()))
+        .collect::<ParseResult<Vec<Vec<i32>>>>()?;
+    if tickets.is_empty()
+    {
+        return Err(ParseError::at(input, input.len(), "expected some nearby tickets"));
     }
-    return fields;
+    return Ok((fields, my_ticket, tickets));
 }
 
-fn parse_field(input: &String) -> Field
+/// A field such as `class: 1-3 or 5-7`, from a line of `input`.
+fn parse_field(input: &str, line: &str) -> ParseResult<Field>
 {
-    let mut split = input.split(": ");
-    return Field{
-        name: split.next().unwrap().to_string(),
-        rules: split.next().unwrap().split(" or ").map(|s| parse_rule(s)).collect()
-    };
+    let (name, rules) = line.split_once(": ")
+        .ok_or_else(|| ParseError::at_slice(input, line, "expected '<field>: <rules>'"))?;
+    return Ok(Field{
+        name: name.to_string(),
+        rules: rules.split(" or ").map(|s| parse_rule(input, s)).collect::<ParseResult<_>>()?
+    });
 }
 
-fn parse_rule(input: &str) -> Rule
+fn parse_rule(input: &str, text: &str) -> ParseResult<Rule>
 {
-    let mut split = input.split('-');
-    return Rule{
-        min: split.next().map_or(0, |s| s.parse::<i32>().unwrap()),
-        max: split.next().map_or(0, |s| s.parse::<i32>().unwrap()),
+    let range = text.split_once('-')
+        .and_then(|(min, max)| Some((min.parse::<i32>().ok()?, max.parse::<i32>().ok()?)));
+    return match range
+    {
+        Some((min, max)) => Ok(Rule{ min: min, max: max }),
+        None => Err(ParseError::at_slice(input, text, "expected a range such as '1-3'"))
     };
 }
 
-fn parse_ticket(input: &String) -> Vec<i32>
+/// The ticket on line `y` of `input`, which must have a value for each of the
+/// `len` fields.
+fn parse_ticket(input: &str, y: usize, len: usize) -> ParseResult<Vec<i32>>
 {
-    return input.split(',').map(|s| s.parse::<i32>().unwrap()).collect();
+    let line = aoc_common::lines(input).nth(y).unwrap_or("");
+    let mut ticket = Vec::new();
+    for value in line.split(',')
+    {
+        let value = value.parse::<i32>()
+            .map_err(|_| ParseError::at_slice(input, value, "expected a number"))?;
+        ticket.push(value);
+    }
+    if ticket.len() != len
+    {
+        let message = format!("expected {} values, found {}", len, ticket.len());
+        return Err(ParseError::at_line(input, y, 0, message));
+    }
+    return Ok(ticket);
 }
 
 pub fn find_invalid_value(ticket: &Vec<i32>, fields: &Vec<Field>) -> Option<i32>
@@ -171,12 +210,6 @@ fn matches_all_at(i : usize, tickets: &Vec<Vec<i32>>, field: &Field) -> bool
         .all(|t| matches_field(t[i], field));
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -188,7 +221,7 @@ mod tests {
             "class: 1-3 or 5-7",
             "row: 6-11 or 33-44",
             "seat: 13-40 or 45-50"
-        ].iter().map(|s| parse_field(&s.to_string())).collect();
+        ].iter().map(|s| parse_field(s, s).unwrap()).collect();
 
         assert_eq!(None, find_invalid_value(&vec![7,3,47], &rules));
         assert_eq!(Some(4), find_invalid_value(&vec![40,4,50], &rules));
@@ -203,7 +236,7 @@ mod tests {
             "class: 1-3 or 5-7",
             "row: 6-11 or 33-44",
             "seat: 13-40 or 45-50"
-        ].iter().map(|s| parse_field(&s.to_string())).collect();
+        ].iter().map(|s| parse_field(s, s).unwrap()).collect();
         let tickets = vec![
             vec![7,3,47],
             vec![40,4,50],
@@ -221,7 +254,7 @@ mod tests {
             "class: 0-1 or 4-19",
             "row: 0-5 or 8-19",
             "seat: 0-13 or 16-19"
-        ].iter().map(|s| parse_field(&s.to_string())).collect();
+        ].iter().map(|s| parse_field(s, s).unwrap()).collect();
         let tickets = vec![
             vec![3,9,18],
             vec![15,1,5],
@@ -235,4 +268,19 @@ mod tests {
         ];
         assert_eq!(expected, find_field_order(&tickets, &rules));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_errors()
+    {
+        let input = "class: 1-3 or 5-7\nrow: 6-11 or 33-44\n\nyour ticket:\n7,1\n\nnearby tickets:\n7,3\n40,x\n";
+        assert!(Day::parse(&input.replace("x", "4")).is_ok());
+        let err = Day::parse(input).unwrap_err();
+        assert_eq!((9, 4), (err.line, err.column));
+        let err = Day::parse(&input.replace("40,x", "40,4,50")).unwrap_err();
+        assert_eq!("expected 2 values, found 3", err.message);
+        let err = Day::parse(&input.replace("row: 6-11", "row: 6")).unwrap_err();
+        assert_eq!((2, 6), (err.line, err.column));
+        let err = Day::parse(&input.replace("your ticket:\n", "")).unwrap_err();
+        assert_eq!((4, 1), (err.line, err.column));
+    }
+}
@@ -1,176 +1,239 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use aoc_common::{ParseError, ParseResult, Solution};
 
-fn main() 
+fn main() {
+    aoc_common::run_day::<Day>(2020, 18);
+}
+
+pub struct Day;
+
+impl Solution for Day
 {
-    if let Ok(lines) = read_lines("src/18/input.txt") {
-        let list:Vec<String> = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap())
+    type Parsed = Vec<Expr>;
+    type PartOne = i64;
+    type PartTwo = i64;
+
+    fn parse(input: &str) -> ParseResult<Vec<Expr>>
+    {
+        return aoc_common::lines(input)
+            .enumerate()
+            .map(|(y, line)| parse_exp(line).map_err(|(x, e)| ParseError::at_line(input, y, x, e)))
             .collect();
+    }
 
-        let result1:i64 = list.iter()
-            .map(|s| evaluate_exp(&s[..]))
+    fn part_one(input: &Vec<Expr>) -> i64
+    {
+        return input.iter()
+            .map(|e| evaluate_exp(e))
             .sum();
-        println!("Total of answers 1: {}", result1);
+    }
 
-        let result2:i64 = list.iter()
-            .map(|s| evaluate_exp2(&s[..]))
+    fn part_two(input: &Vec<Expr>) -> i64
+    {
+        return input.iter()
+            .map(|e| evaluate_exp2(e))
             .sum();
-        println!("Total of answers 2: {}", result2);
     }
 }
 
-pub fn evaluate_exp(exp: &str) -> i64
+/// A value followed by any number of operators and values, in order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expr {
+    first: Operand,
+    rest: Vec<(char, Operand)>
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operand {
+    Number(i64),
+    Brackets(Box<Expr>)
+}
+
+pub fn evaluate_exp(exp: &Expr) -> i64
 {
-    let mut idx = find_exp_part_end(0, exp);
-    let mut lhs = get_value(&exp[0..idx]);
-    
-    while idx < exp.len()
+    let mut lhs = get_value(&exp.first);
+    for (cmd, operand) in &exp.rest
     {
-        let cmd = &exp[idx+1..idx+2];
-        let second_exp_end = find_exp_part_end(idx+3, exp);
-        let second_exp = &exp[idx+3..second_exp_end];
-
-        let second_value = get_value(second_exp);
-        
+        let second_value = get_value(operand);
         match cmd
         {
-            "*" => lhs = lhs * second_value,
-            "+" => lhs = lhs + second_value,
-            _ => panic!("invalid command '{}' in '{}'", cmd, exp)
+            '*' => lhs = lhs * second_value,
+            _ => lhs = lhs + second_value
         }
-        idx = second_exp_end;
     }
     return lhs;
 }
 
-fn get_value(exp: &str) -> i64
+fn get_value(operand: &Operand) -> i64
 {
-    if &exp[0..1] == "("
-    {
-        return evaluate_exp(&exp[1..exp.len()-1]);
-    }
-    else
-    {
-        return exp.parse::<i64>().unwrap();
-    }
+    return match operand {
+        Operand::Number(n) => *n,
+        Operand::Brackets(exp) => evaluate_exp(exp)
+    };
 }
 
-pub fn evaluate_exp2(exp: &str) -> i64
+pub fn evaluate_exp2(exp: &Expr) -> i64
 {
-    let mut idx = find_exp_part_end(0, exp);
     let mut stack = Vec::new();
-    stack.push(get_value2(&exp[0..idx]));
-    
-    while idx < exp.len()
-    {
-        let cmd = &exp[idx+1..idx+2];
-        let second_exp_end = find_exp_part_end(idx+3, exp);
-        let second_exp = &exp[idx+3..second_exp_end];
+    stack.push(get_value2(&exp.first));
 
-        let second_value = get_value2(second_exp);
-        
+    for (cmd, operand) in &exp.rest
+    {
+        let second_value = get_value2(operand);
         match cmd
         {
-            "*" => stack.push(second_value),
-            "+" => {
+            '*' => stack.push(second_value),
+            _ => {
                 let lhs = stack.pop().unwrap();
                 stack.push(lhs + second_value)
-            },
-            _ => panic!("invalid command '{}' in '{}'", cmd, exp)
+            }
         }
-        idx = second_exp_end;
     }
-    
+
     return stack.iter().product();
 }
 
-fn get_value2(exp: &str) -> i64
+fn get_value2(operand: &Operand) -> i64
 {
-    if &exp[0..1] == "("
-    {
-        return evaluate_exp2(&exp[1..exp.len()-1]);
-    }
-    else
+    return match operand {
+        Operand::Number(n) => *n,
+        Operand::Brackets(exp) => evaluate_exp2(exp)
+    };
+}
+
+/// Parses a line, giving the column and reason if it isn't an expression.
+pub fn parse_exp(line: &str) -> Result<Expr, (usize, String)>
+{
+    let chars: Vec<char> = line.chars().collect();
+    let mut pos = 0;
+    let exp = parse_sequence(&chars, &mut pos)?;
+    if pos < chars.len()
     {
-        return exp.parse::<i64>().unwrap();
+        return Err((pos, "unmatched ')'".to_string()));
     }
+    return Ok(exp);
 }
 
-pub fn find_exp_part_end(start: usize, exp: &str) -> usize
+fn parse_sequence(chars: &[char], pos: &mut usize) -> Result<Expr, (usize, String)>
 {
-    let mut layers = 0;
-    let mut i = start;
-    for c in exp.chars().skip(start)
+    let first = parse_operand(chars, pos)?;
+    let mut rest = Vec::new();
+    loop
     {
-        if c == '('
+        skip_spaces(chars, pos);
+        match chars.get(*pos)
         {
-            layers = layers + 1;
+            None | Some(')') => break,
+            Some('+') | Some('*') => {
+                let cmd = chars[*pos];
+                *pos = *pos + 1;
+                rest.push((cmd, parse_operand(chars, pos)?));
+            }
+            Some(c) => return Err((*pos, format!("expected '+' or '*', got '{}'", c)))
         }
-        else if c == ')'
-        {
-            layers = layers - 1;
+    }
+    return Ok(Expr { first, rest });
+}
+
+fn parse_operand(chars: &[char], pos: &mut usize) -> Result<Operand, (usize, String)>
+{
+    skip_spaces(chars, pos);
+    let start = *pos;
+    match chars.get(start)
+    {
+        Some('(') => {
+            *pos = *pos + 1;
+            let exp = parse_sequence(chars, pos)?;
+            if chars.get(*pos) != Some(&')')
+            {
+                return Err((*pos, "expected ')'".to_string()));
+            }
+            *pos = *pos + 1;
+            return Ok(Operand::Brackets(Box::new(exp)));
         }
-        else if layers == 0
-        {
-            if c == '*' || c == '+' || c == ' '
+        Some(c) if c.is_ascii_digit() => {
+            while chars.get(*pos).map_or(false, |c| c.is_ascii_digit())
             {
-                return i;
+                *pos = *pos + 1;
             }
+            let digits: String = chars[start..*pos].iter().collect();
+            return digits.parse()
+                .map(Operand::Number)
+                .map_err(|_| (start, format!("{} is too large", digits)));
         }
-        i = i + 1;
+        Some(c) => return Err((start, format!("expected a number or '(', got '{}'", c))),
+        None => return Err((start, "expected a number or '('".to_string()))
     }
-    return i;
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+fn skip_spaces(chars: &[char], pos: &mut usize)
+{
+    while chars.get(*pos) == Some(&' ')
+    {
+        *pos = *pos + 1;
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn part1(exp: &str) -> i64
+    {
+        return evaluate_exp(&parse_exp(exp).unwrap());
+    }
+
+    fn part2(exp: &str) -> i64
+    {
+        return evaluate_exp2(&parse_exp(exp).unwrap());
+    }
+
     #[test]
     fn test_evaluate_exp() 
     {
-        assert_eq!(6, evaluate_exp(&"2 * 3"));
-        assert_eq!(20, evaluate_exp(&"4 * 5"));
-        assert_eq!(25, evaluate_exp(&"4 * 5 + 5"));
-        assert_eq!(432, evaluate_exp(&"8 * 3 + 9 + 3 * 4 * 3"));
-        assert_eq!(71, evaluate_exp(&"1 + 2 * 3 + 4 * 5 + 6"));
+        assert_eq!(6, part1("2 * 3"));
+        assert_eq!(20, part1("4 * 5"));
+        assert_eq!(25, part1("4 * 5 + 5"));
+        assert_eq!(432, part1("8 * 3 + 9 + 3 * 4 * 3"));
+        assert_eq!(71, part1("1 + 2 * 3 + 4 * 5 + 6"));
     }
 
     #[test]
     fn test_evaluate_exp_brackets() 
     {
-        assert_eq!(26, evaluate_exp(&"2 * 3 + (4 * 5)"));
-        assert_eq!(437, evaluate_exp(&"5 + (8 * 3 + 9 + 3 * 4 * 3)"));
-        assert_eq!(12240, evaluate_exp(&"5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))"));
-        assert_eq!(13632, evaluate_exp(&"((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2"));
+        assert_eq!(26, part1("2 * 3 + (4 * 5)"));
+        assert_eq!(437, part1("5 + (8 * 3 + 9 + 3 * 4 * 3)"));
+        assert_eq!(12240, part1("5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))"));
+        assert_eq!(13632, part1("((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2"));
     }
 
     #[test]
     fn test_evaluate_exp2() 
     {
-        assert_eq!(6, evaluate_exp2(&"2 * 3"));
-        assert_eq!(20, evaluate_exp2(&"4 * 5"));
-        assert_eq!(40, evaluate_exp2(&"4 * 5 + 5"));
-        assert_eq!(1440, evaluate_exp2(&"8 * 3 + 9 + 3 * 4 * 3"));
-        assert_eq!(231, evaluate_exp2(&"1 + 2 * 3 + 4 * 5 + 6"));
+        assert_eq!(6, part2("2 * 3"));
+        assert_eq!(20, part2("4 * 5"));
+        assert_eq!(40, part2("4 * 5 + 5"));
+        assert_eq!(1440, part2("8 * 3 + 9 + 3 * 4 * 3"));
+        assert_eq!(231, part2("1 + 2 * 3 + 4 * 5 + 6"));
     }
 
     #[test]
     fn test_evaluate_exp2_brackets() 
     {
-        assert_eq!(51, evaluate_exp2(&"1 + (2 * 3) + (4 * (5 + 6))"));
-        assert_eq!(46, evaluate_exp2(&"2 * 3 + (4 * 5)"));
-        assert_eq!(1445, evaluate_exp2(&"5 + (8 * 3 + 9 + 3 * 4 * 3)"));
-        assert_eq!(669060, evaluate_exp2(&"5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))"));
-        assert_eq!(23340, evaluate_exp2(&"((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2"));
+        assert_eq!(51, part2("1 + (2 * 3) + (4 * (5 + 6))"));
+        assert_eq!(46, part2("2 * 3 + (4 * 5)"));
+        assert_eq!(1445, part2("5 + (8 * 3 + 9 + 3 * 4 * 3)"));
+        assert_eq!(669060, part2("5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))"));
+        assert_eq!(23340, part2("((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2"));
+    }
+
+    #[test]
+    fn test_parse_errors()
+    {
+        assert_eq!(Err((4, "expected a number or '(', got '*'".to_string())), parse_exp("2 * * 3"));
+        assert_eq!(Err((11, "expected ')'".to_string())), parse_exp("2 * (3 + 4 "));
+        assert_eq!(Err((5, "unmatched ')'".to_string())), parse_exp("2 * 3) + 1"));
+        assert_eq!(Err((2, "expected '+' or '*', got '-'".to_string())), parse_exp("2 - 3"));
+        let err = Day::parse("1 + 2\n3 x 4\n").unwrap_err();
+        assert_eq!((2, 3), (err.line, err.column));
     }
-}
\ No newline at end of file
+}
@@ -1,28 +1,33 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
 extern crate regex;
+use aoc_common::{ParseError, ParseResult, Solution};
 use regex::Regex;
 
-fn main() 
+fn main() {
+    aoc_common::run_day::<Day>(2020, 19);
+}
+
+pub struct Day;
+
+impl Solution for Day
 {
-    if let Ok(lines) = read_lines("src/19/input.txt") {
-        let input:Vec<String> = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap())
-            .collect();
-        
-        let mut it = input.iter();
-        let mut rules = get_sorted_rules(&mut it);
-        let tests:Vec<&String> = it.collect();
-
-        let re1 = Regex::new(&get_rule(&rules)).unwrap();
-        let valid_rules1 = tests.iter()
-            .filter(|r| re1.is_match(r))
-            .count();
-        println!("Total Rules: {}", tests.len());
-        println!("Valid Rules 1: {}", valid_rules1);
+    type Parsed = (Vec<String>, Vec<String>);
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> ParseResult<(Vec<String>, Vec<String>)>
+    {
+        return parse(input);
+    }
+
+    fn part_one(input: &(Vec<String>, Vec<String>)) -> usize
+    {
+        let (rules, tests) = input;
+        return count_matches(&rules, &tests);
+    }
 
+    fn part_two(input: &(Vec<String>, Vec<String>)) -> usize
+    {
+        let (mut rules, tests) = input.clone();
         rules[8] = String::from("*42");
         let mut rule11 = String::new();
         for _i in 1..2
@@ -45,28 +50,73 @@ fn main()
         rule11.pop();
         rule11.pop();
         rules[11] = rule11;
-        let re2 = Regex::new(&get_rule(&rules)).unwrap();
-        let valid_rules2 = tests.iter()
-            .filter(|r| re2.is_match(r))
-            .count();
-        println!("Valid Rules 2: {}", valid_rules2);
+        return count_matches(&rules, &tests);
     }
 }
 
-pub fn get_sorted_rules(input: &mut dyn Iterator<Item = &String>) -> Vec<String>
+fn parse(input: &str) -> ParseResult<(Vec<String>, Vec<String>)>
 {
-    let mut rules = Vec::new();
-    for val in input
+    let lines: Vec<&str> = aoc_common::lines(input).collect();
+    let blank = lines.iter().position(|l| l.is_empty()).unwrap_or(lines.len());
+    let rules = get_sorted_rules(input, &lines[..blank])?;
+    let mut tests = Vec::new();
+    for test in lines.iter().skip(blank + 1)
     {
-        if val == ""
+        if let Some(i) = test.find(|c: char| c != 'a' && c != 'b')
         {
-            break;
+            return Err(ParseError::at_slice(input, &test[i..], "expected a message of 'a's and 'b's"));
         }
-        let split:Vec<&str> = val.split(": ").collect();
-        rules.push((split[0].parse::<usize>().unwrap(), split[1].to_owned()));
+        tests.push(test.to_string());
+    }
+    return Ok((rules, tests));
+}
+
+fn count_matches(rules: &Vec<String>, tests: &Vec<String>) -> usize
+{
+    let re = Regex::new(&get_rule(rules)).unwrap();
+    return tests.iter()
+        .filter(|r| re.is_match(r))
+        .count();
+}
+
+/// The rules on `lines` of `input`, ordered by id.  The ids must run from 0
+/// without gaps, and every rule must be a quoted letter or alternatives of
+/// rule ids.
+pub fn get_sorted_rules(input: &str, lines: &[&str]) -> ParseResult<Vec<String>>
+{
+    let mut rules = Vec::new();
+    for val in lines
+    {
+        let (id, rule) = val.split_once(": ")
+            .ok_or_else(|| ParseError::at_slice(input, val, "expected '<id>: <rule>'"))?;
+        let id = id.parse::<usize>()
+            .map_err(|_| ParseError::at_slice(input, id, "expected a rule id"))?;
+        rules.push((id, rule));
     }
     rules.sort_by_key(|r| r.0);
-    return rules.iter().map(|r| r.1.to_string()).collect();
+    for (i, (id, _)) in rules.iter().enumerate()
+    {
+        if *id != i
+        {
+            return Err(ParseError::at(input, 0, format!("expected rule {} to be defined exactly once", i)));
+        }
+    }
+    for (_, rule) in &rules
+    {
+        let letter = rule.len() == 3 && rule.starts_with('"') && rule.ends_with('"');
+        if letter
+        {
+            continue;
+        }
+        for id in rule.split(' ').filter(|s| *s != "|")
+        {
+            if id.parse::<usize>().map_or(true, |id| id >= rules.len())
+            {
+                return Err(ParseError::at_slice(input, id, "expected the id of a defined rule"));
+            }
+        }
+    }
+    return Ok(rules.iter().map(|r| r.1.to_string()).collect());
 }
 
 pub fn get_rule(rules: &Vec<String>) -> String
@@ -133,12 +183,6 @@ pub fn p_rule(rule: &str, rules: &Vec<String>) -> String
     }
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -196,4 +240,17 @@ mod tests {
         assert_eq!(false, re.is_match("aaabbb"));
         assert_eq!(false, re.is_match("aaaabbb"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_errors()
+    {
+        let input = "0: 1 2\n1: \"a\"\n2: 1 3 | 3 1\n3: \"b\"\n\naab\nabc\n";
+        assert!(parse(&input.replace("abc", "aba")).is_ok());
+        let err = parse(input).unwrap_err();
+        assert_eq!((7, 3), (err.line, err.column));
+        let err = parse(&input.replace("3 1", "4 1")).unwrap_err();
+        assert_eq!((3, 10), (err.line, err.column));
+        let err = parse(&input.replace("2: ", "4: ")).unwrap_err();
+        assert!(err.message.contains("rule 2"));
+    }
+}
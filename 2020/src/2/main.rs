@@ -1,38 +1,56 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use aoc_common::{ParseError, ParseResult, Solution};
 
 fn main() {
-    if let Ok(lines) = read_lines("src/2/input.txt") {
-        let mut total1 = 0;
-        let mut total2 = 0;
-        for line in lines {
-            if let Ok(ip) = line {
-                let parts: Vec<_> = ip.split(": ").collect();
-                let pattern_parts: Vec<_> = parts[0].split(" ").collect();
-
-                let qtys = parse_min_max_string(pattern_parts[0]);
-                let ch = pattern_parts[1].chars().next().unwrap();
-                let password = parts[1];
-                if is_valid_1(password, qtys, ch)
-                {
-                    total1 = total1 + 1
-                }
-                if is_valid_2(password, qtys, ch)
-                {
-                    total2 = total2 + 1
-                }
-            }
-        }
-        println!("1: {}", total1);
-        println!("2: {}", total2);
+    aoc_common::run_day::<Day>(2020, 2);
+}
+
+pub struct Day;
+
+impl Solution for Day
+{
+    type Parsed = Vec<Entry>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> ParseResult<Vec<Entry>>
+    {
+        return aoc_common::lines(input)
+            .enumerate()
+            .map(|(y, line)| parse_line(line).map_err(|e| ParseError::at_line(input, y, 0, e)))
+            .collect();
+    }
+
+    fn part_one(input: &Vec<Entry>) -> usize
+    {
+        return input.iter()
+            .filter(|(password, qtys, ch)| is_valid_1(password, *qtys, *ch))
+            .count();
+    }
+
+    fn part_two(input: &Vec<Entry>) -> usize
+    {
+        return input.iter()
+            .filter(|(password, qtys, ch)| is_valid_2(password, *qtys, *ch))
+            .count();
     }
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+/// A password with the two numbers and the letter of its policy.
+type Entry = (String, (usize, usize), char);
+
+fn parse_line(line: &str) -> Result<Entry, String>
+{
+    let (policy, password) = line.split_once(": ")
+        .ok_or_else(|| format!("expected 'policy: password', got '{}'", line))?;
+    let (qtys, ch) = policy.split_once(' ')
+        .ok_or_else(|| format!("expected 'min-max letter', got '{}'", policy))?;
+
+    let qtys = parse_min_max_string(qtys)?;
+    let mut chars = ch.chars();
+    return match (chars.next(), chars.next()) {
+        (Some(ch), None) => Ok((password.to_string(), qtys, ch)),
+        _ => Err(format!("expected a single letter, got '{}'", ch))
+    };
 }
 
 pub fn is_valid_1(password: &str, qtys: (usize, usize), ch: char) -> bool
@@ -43,15 +61,19 @@ pub fn is_valid_1(password: &str, qtys: (usize, usize), ch: char) -> bool
 
 pub fn is_valid_2(password: &str, qtys: (usize, usize), ch: char) -> bool
 {
-    let pos1 = password.chars().nth(qtys.0 - 1).map_or(false, |v| ch == v);
-    let pos2 = password.chars().nth(qtys.1 - 1).map_or(false, |v| ch == v);
+    // Positions count from 1, so there's nothing at 0.
+    let at = |pos: usize| pos.checked_sub(1).and_then(|i| password.chars().nth(i)) == Some(ch);
+    let pos1 = at(qtys.0);
+    let pos2 = at(qtys.1);
     return pos1 ^ pos2;
 }
 
-pub fn parse_min_max_string(input: &str) -> (usize, usize)
+pub fn parse_min_max_string(input: &str) -> Result<(usize, usize), String>
 {
-    let parts: Vec<_> = input.split('-').collect();
-    return (parts[0].parse::<usize>().unwrap(), parts[1].parse::<usize>().unwrap())
+    let number = |s: &str| s.parse::<usize>().map_err(|_| format!("expected a number, got '{}'", s));
+    let (min, max) = input.split_once('-')
+        .ok_or_else(|| format!("expected 'min-max', got '{}'", input))?;
+    return Ok((number(min)?, number(max)?));
 }
 
 pub fn count_chars(input: &str, ch: char) -> usize
@@ -75,9 +97,18 @@ mod tests {
     #[test]
     fn test_parse_min_max_string() 
     {
-        assert_eq!((1, 5), parse_min_max_string("1-5"));
-        assert_eq!((8, 9), parse_min_max_string("8-9"));
-        assert_eq!((1, 3), parse_min_max_string("1-3"));
+        assert_eq!(Ok((1, 5)), parse_min_max_string("1-5"));
+        assert_eq!(Ok((8, 9)), parse_min_max_string("8-9"));
+        assert_eq!(Ok((1, 3)), parse_min_max_string("1-3"));
+        assert!(parse_min_max_string("1-x").is_err());
+    }
+    #[test]
+    fn test_parse_errors()
+    {
+        let err = Day::parse("1-3 a: abcde\n1-3 ab: cdefg\n").unwrap_err();
+        assert_eq!((2, 1), (err.line, err.column));
+        assert_eq!("expected a single letter, got 'ab'", err.message);
+        assert!(Day::parse("1-3 a abcde").is_err());
     }
     #[test]
     fn test_count_chars() 
@@ -94,5 +125,6 @@ mod tests {
         assert!(!is_valid_2("aaaaaaaaa", (1, 2), 'b'));
         assert!(!is_valid_2("bbaaabaaa", (1, 2), 'b'));
         assert!(!is_valid_2("aaaaaaaaa", (998, 999), 'b'));
+        assert!(!is_valid_2("baaaaaaaa", (0, 3), 'b'));
     }
 }
\ No newline at end of file
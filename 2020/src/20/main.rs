@@ -1,29 +1,35 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use aoc_common::{ParseError, ParseResult, Solution};
 use threadpool::ThreadPool;
 use std::sync::mpsc::channel;
 use std::time::Duration;
-#[macro_use]
-extern crate lazy_static;
+use lazy_static::lazy_static;
 
-fn main() 
+fn main() {
+    aoc_common::run_day::<Day>(2020, 20);
+}
+
+pub struct Day;
+
+impl Solution for Day
 {
-    if let Ok(lines) = read_lines("src/20/input.txt") {
-        let input:Vec<String> = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap())
-            .collect();
-        let tiles:Vec<ImageTile> = parse_images(&input);
-        let corners = find_corners(&tiles);
-        println!("Corners: {:?}", corners);
-        println!("Result: {}", corners.iter().map(|v| *v as i64).product::<i64>());
-        let image = construct_image(&tiles);
-        println!("Image:");
-        for line in image.iter()
-        {
-            println!("{}", line);
-        }
+    type Parsed = Vec<ImageTile>;
+    type PartOne = i64;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> ParseResult<Vec<ImageTile>>
+    {
+        return parse_images(input);
+    }
+
+    fn part_one(input: &Vec<ImageTile>) -> i64
+    {
+        let corners = find_corners(input);
+        return corners.iter().map(|v| *v as i64).product::<i64>();
+    }
+
+    fn part_two(input: &Vec<ImageTile>) -> usize
+    {
+        let image = construct_image(input);
         let map = ImageTile{
             id: 1,
             data: image.to_vec(),
@@ -31,12 +37,10 @@ fn main()
             adj_tids: vec![0; 4],
         };
         let sea_monsters = count_sea_monsters(&map);
-        println!("No. Sea Monsters: {}", sea_monsters);
         let total_hash_tiles:usize = image.into_iter()
             .map(|line| line.chars().filter(|c| *c == '#').count())
             .sum();
-        println!("Total # tiles: {}", total_hash_tiles);
-        println!("Roughness: {}", total_hash_tiles -  (sea_monsters * 15));
+        return total_hash_tiles -  (sea_monsters * 15);
     }
 }
 
@@ -346,28 +350,40 @@ fn find_matching_tile(edge: &i32, ignore_tile: i32, tiles: &Vec<ImageTile>) -> O
         .map(|t| t.id);
 }
 
-fn parse_images(input: &Vec<String>) -> Vec<ImageTile>
+/// The tiles in `input`, each a `Tile <id>:` header above a square of `.` and
+/// `#`, all of the same size.
+fn parse_images(input: &str) -> ParseResult<Vec<ImageTile>>
 {
     let mut result = Vec::new();
-
-    let mut tile_id = 0;
-    let mut data = Vec::new();
-    for line in input
-    {
-        if line == ""
+    for tile in aoc_common::paragraphs(input)
+    {
+        let header = tile[0];
+        let tile_id = header.strip_prefix("Tile ")
+            .and_then(|h| h.strip_suffix(':'))
+            .and_then(|id| id.parse::<i32>().ok())
+            .ok_or_else(|| ParseError::at_slice(input, header, "expected 'Tile <id>:'"))?;
+        let data = &tile[1..];
+        let size = result.first().map_or(data.len(), |t: &ImageTile| t.data.len());
+        if data.len() < 2 || size > 31
         {
-            result.push(ImageTile::new(tile_id, &data));
-            data = Vec::new();
-            tile_id = 0;
+            return Err(ParseError::at_slice(input, header, "expected a tile of between 2 and 31 rows"));
         }
-        else if &line[0..5] == "Tile "
+        if data.len() != size
         {
-            tile_id = line[5..9].parse::<i32>().unwrap();
+            return Err(ParseError::at_slice(input, header, format!("expected {} rows like the first tile", size)));
         }
-        else
+        for line in data
         {
-            data.push(line.clone());
+            if let Some(i) = line.find(|c| c != '.' && c != '#')
+            {
+                return Err(ParseError::at_slice(input, &line[i..], "expected '.' or '#'"));
+            }
+            if line.len() != size
+            {
+                return Err(ParseError::at_slice(input, line, format!("expected {} pixels in the row", size)));
+            }
         }
+        result.push(ImageTile::new(tile_id, &data.iter().map(|l| l.to_string()).collect()));
     }
     let copy_result = result.clone();
     for tile in result.iter_mut()
@@ -376,7 +392,7 @@ fn parse_images(input: &Vec<String>) -> Vec<ImageTile>
             .map(|e| find_matching_tile(e, tile.id, &copy_result).unwrap_or(0))
             .collect();
     }
-    return result;
+    return Ok(result);
 }
 
 pub fn count_sea_monsters(map: &ImageTile) -> usize
@@ -460,12 +476,6 @@ pub fn matches_pattern(skip: usize, input: &String, pattern: &Vec<usize>) -> boo
     return pattern.iter().all(|idx| active_idxs.contains(idx));
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -677,7 +687,7 @@ mod tests {
             String::from("")
         ];
 
-        let result = find_corners(&parse_images(&input));
+        let result = find_corners(&parse_images(&input.join("\n")).unwrap());
         let expected = vec![1951, 1171, 2971, 3079];
         assert_eq!(expected, result);
     }
@@ -796,7 +806,7 @@ mod tests {
             String::from("")
         ];
 
-        let mut result = ImageTile::new(1, &construct_image(&parse_images(&input)));
+        let mut result = ImageTile::new(1, &construct_image(&parse_images(&input.join("\n")).unwrap()));
         result.turn(1);
         result.flip_y();
 
@@ -913,4 +923,19 @@ mod tests {
         assert_eq!(false, matches_pattern(1, &String::from("#.#.##.###.#.##.##.#####"), &get_pattern("#    ##    ##    ###")));
         assert_eq!(true,  matches_pattern(2, &String::from("#.#.##.###.#.##.##.#####"), &get_pattern("#    ##    ##    ###")));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_errors()
+    {
+        let input = "Tile 1:\n.#\n#.\n\nTile 2:\n##\n#x\n";
+        assert_eq!(2, parse_images(&input.replace("x", ".")).unwrap().len());
+        let err = parse_images(input).unwrap_err();
+        assert_eq!((7, 2), (err.line, err.column));
+        let err = parse_images(&input.replace("#x", "#..")).unwrap_err();
+        assert_eq!((7, 1), (err.line, err.column));
+        let err = parse_images(&input.replace("Tile 2:", "Tile two:")).unwrap_err();
+        assert_eq!((5, 1), (err.line, err.column));
+        let err = parse_images(&input.replace("\n#x", "")).unwrap_err();
+        assert_eq!((5, 1), (err.line, err.column));
+    }
+}
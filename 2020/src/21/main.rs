@@ -1,25 +1,37 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
 use std::collections::HashSet;
 use std::collections::HashMap;
+use aoc_common::{ParseError, ParseResult, Solution};
 use joinery::Joinable;
 
-fn main() 
+fn main() {
+    aoc_common::run_day::<Day>(2020, 21);
+}
+
+pub struct Day;
+
+impl Solution for Day
 {
-    if let Ok(lines) = read_lines("src/21/input.txt") {
-        let input:Vec<String> = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap())
-            .collect();
-        let foods = parse_foods(&input);
+    type Parsed = Vec<Food>;
+    type PartOne = usize;
+    type PartTwo = String;
+
+    fn parse(input: &str) -> ParseResult<Vec<Food>>
+    {
+        return parse_foods(input);
+    }
+
+    fn part_one(foods: &Vec<Food>) -> usize
+    {
         let safe_ingredients = get_safe_ingredients(&foods);
-        let safe_ingred_count:usize = safe_ingredients.iter()
+        return safe_ingredients.iter()
             .map(|i| foods.iter().filter(|f| f.ingredients.contains(i)).count())
             .sum();
-        println!("Safe ingredient count: {}", safe_ingred_count);
-        let dangerous_ingred_list = get_dangerous_ingredient_list(&foods);
-        println!("Dangerous ingredients: {}", dangerous_ingred_list.join_with(","));
+    }
+
+    fn part_two(input: &Vec<Food>) -> String
+    {
+        let dangerous_ingred_list = get_dangerous_ingredient_list(input);
+        return dangerous_ingred_list.join_with(",").to_string();
     }
 }
 
@@ -95,28 +107,23 @@ pub fn get_dangerous_ingredient_list(input: &Vec<Food>) -> Vec<String>
     return result.iter().map(|i| i.0).cloned().collect();
 }
 
-pub fn parse_foods(input: &Vec<String>) -> Vec<Food>
+/// Foods such as `mxmxvkd kfcds (contains dairy, fish)`, one per line.
+pub fn parse_foods(input: &str) -> ParseResult<Vec<Food>>
 {
-    return input.iter()
+    return aoc_common::lines(input)
         .map(|line| {
-            let mut parts = line.split(" (contains ");
-            let ingredients = parts.next().unwrap();
-            let allergens = parts.next().unwrap();
+            let (ingredients, allergens) = line.strip_suffix(')')
+                .and_then(|l| l.split_once(" (contains "))
+                .ok_or_else(|| ParseError::at_slice(input, line, "expected '<ingredients> (contains <allergens>)'"))?;
             let food = Food{
                 ingredients: ingredients.split(' ').map(|s| s.to_string()).collect(),
-                allergens: allergens[..allergens.len()-1].split(", ").map(|s| s.to_string()).collect()
+                allergens: allergens.split(", ").map(|s| s.to_string()).collect()
             };
-            return food;
+            return Ok(food);
         })
         .collect();
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -137,7 +144,7 @@ mod tests {
             String::from("sbzzf"),
             String::from("trh")
         ];
-        assert_eq!(expected, get_safe_ingredients(&parse_foods(&input)));
+        assert_eq!(expected, get_safe_ingredients(&parse_foods(&input.join("\n")).unwrap()));
     }
 
     #[test]
@@ -155,6 +162,15 @@ mod tests {
             String::from("sqjhc"),
             String::from("fvjkl"),
         ];
-        assert_eq!(expected, get_dangerous_ingredient_list(&parse_foods(&input)));
+        assert_eq!(expected, get_dangerous_ingredient_list(&parse_foods(&input.join("\n")).unwrap()));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_errors()
+    {
+        let input = "sqjhc fvjkl (contains soy)\nsqjhc mxmxvkd sbzzf\n";
+        let err = parse_foods(input).unwrap_err();
+        assert_eq!((2, 1), (err.line, err.column));
+        assert!(parse_foods(&input.replace("sbzzf", "sbzzf (contains fish")).is_err());
+    }
+}
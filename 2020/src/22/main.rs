@@ -1,31 +1,60 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
 use std::collections::VecDeque;
-use std::collections::HashSet;
+use aoc_common::{History, ParseError, ParseResult, Solution};
 
-fn main() 
+fn main() {
+    aoc_common::run_day::<Day>(2020, 22);
+}
+
+pub struct Day;
+
+impl Solution for Day
 {
-    if let Ok(lines) = read_lines("src/22/input.txt") {
-        let input:Vec<String> = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap())
-            .collect();
-        let player_2_start = input.iter().position(|x| x == "Player 2:").unwrap();
-        let player_1_deck = Deck::new(input.iter().skip(1).take(player_2_start - 2).map(|x| x.parse::<i64>().unwrap()).collect());
-        println!("Player 1: {:?}", player_1_deck);
-        let player_2_deck = Deck::new(input.iter().skip(player_2_start + 1).map(|x| x.parse::<i64>().unwrap()).collect());
-        println!("Player 2: {:?}", player_2_deck);
-
-        let decks = vec![player_1_deck.clone(), player_2_deck.clone()];
-        let res = play_game(&decks);
-        println!("Player {:?} wins with total score {:?}", res.0, res.1.score());
-        let res2 = play_recursive_combat(&decks);
-        println!("Player {:?} wins with total score {:?}", res2.0, res2.1.score());
+    type Parsed = Vec<Deck>;
+    type PartOne = i64;
+    type PartTwo = i64;
+
+    fn parse(input: &str) -> ParseResult<Vec<Deck>>
+    {
+        return parse(input);
+    }
+
+    fn part_one(input: &Vec<Deck>) -> i64
+    {
+        return play_game(input).1.score();
+    }
+
+    fn part_two(input: &Vec<Deck>) -> i64
+    {
+        return play_recursive_combat(input).1.score();
+    }
+}
+
+/// The two players' decks, each under a `Player <n>:` header with one card
+/// per line.
+fn parse(input: &str) -> ParseResult<Vec<Deck>>
+{
+    let players = aoc_common::paragraphs(input);
+    if players.len() != 2
+    {
+        return Err(ParseError::at(input, 0, format!("expected two players' decks, found {}", players.len())));
+    }
+    let mut decks = Vec::new();
+    for (i, lines) in players.iter().enumerate()
+    {
+        let header = format!("Player {}:", i + 1);
+        if lines[0] != header
+        {
+            return Err(ParseError::at_slice(input, lines[0], format!("expected '{}'", header)));
+        }
+        let cards = lines[1..].iter()
+            .map(|x| x.parse::<i64>().map_err(|_| ParseError::at_slice(input, x, "expected a card number")))
+            .collect::<ParseResult<Vec<i64>>>()?;
+        decks.push(Deck::new(cards));
     }
+    return Ok(decks);
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Deck {
     cards: VecDeque<i64>
 }
@@ -78,13 +107,6 @@ impl Deck
         return self.cards.len() as i64;
     }
 
-    pub fn get_string(&self) -> String
-    {
-        return self.cards.iter()
-            .map(|v| v.to_string())
-            .fold(String::new(), |a,b| a + "," + &b);
-    }
-
     pub fn get_slice(&self, size: usize) -> Deck
     {
         let mut q = VecDeque::new();
@@ -145,17 +167,14 @@ pub fn play_game(players: &Vec<Deck>) -> (usize, Deck)
 pub fn play_recursive_combat(players: &Vec<Deck>) -> (usize, Deck)
 {
     let mut game_state:Vec<Deck> = players.clone();
-    let mut seen_states = HashSet::<String>::new();
+    let mut seen_states = History::new();
 
     let mut round = 0;
     let max_rounds = 50000;
     while round < max_rounds
     {
         // println!("Player Decks: {:?}", game_state);
-        let game_state_str = game_state.iter()
-            .map(|d| d.get_string())
-            .fold(String::new(), |a,b| a + ";" + &b);
-        if !seen_states.insert(game_state_str)
+        if seen_states.record(game_state.clone()).is_some()
         {
             // println!("Seen the state, Player 1 automatically wins");
             return (1, game_state[0].clone());
@@ -225,12 +244,6 @@ pub fn play_recursive_combat(players: &Vec<Deck>) -> (usize, Deck)
 
 
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -322,4 +335,16 @@ mod tests {
 
         assert_eq!(291, deck.score());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_errors()
+    {
+        let input = "Player 1:\n9\n2\n\nPlayer 2:\n5\nten\n";
+        assert_eq!(Deck::new(vec![5, 10]), parse(&input.replace("ten", "10")).unwrap()[1]);
+        let err = parse(input).unwrap_err();
+        assert_eq!((7, 1), (err.line, err.column));
+        let err = parse(&input.replace("Player 2", "Player 3")).unwrap_err();
+        assert_eq!((5, 1), (err.line, err.column));
+        assert!(parse("Player 1:\n9\n").is_err());
+    }
+}
@@ -1,9 +1,61 @@
-fn main() 
+use aoc_common::{ParseError, ParseResult, Solution};
+
+fn main() {
+    aoc_common::run_day::<Day>(2020, 23);
+}
+
+pub struct Day;
+
+impl Solution for Day
+{
+    type Parsed = Vec<usize>;
+    type PartOne = String;
+    type PartTwo = usize;
+
+    // Part two is unfinished: play_million_cups returns a placeholder after an
+    // effectively endless game, so it isn't run.
+    const PARTS: u32 = 1;
+
+    fn parse(input: &str) -> ParseResult<Vec<usize>>
+    {
+        return parse(input);
+    }
+
+    fn part_one(input: &Vec<usize>) -> String
+    {
+        let result = play_game(input, 100);
+        let one = result.iter().position(|c| *c == 1).unwrap();
+        return (1..result.len())
+            .map(|i| result[(one + i) % result.len()].to_string())
+            .collect();
+    }
+
+    fn part_two(input: &Vec<usize>) -> usize
+    {
+        let result = play_million_cups(input);
+        return result[0] * result[1];
+    }
+}
+
+/// The cup labels, which must be the digits from 1 up to the number of cups,
+/// each used once.  A move needs at least five cups to choose from.
+fn parse(input: &str) -> ParseResult<Vec<usize>>
 {
-    let result = play_game(&vec![5,8,6,4,3,9,1,7,2], 100);
-    println!("Part 1: {:?}", result);
-    let result2 = part_two(&vec![5,8,6,4,3,9,1,7,2]);
-    println!("Part 2: {:?} * {:?} = {:?}", result2[0], result2[1], result2[0]*result2[1]);
+    let line = input.trim();
+    let mut cups = Vec::new();
+    for (i, c) in line.char_indices()
+    {
+        match c.to_digit(10)
+        {
+            Some(d) if d != 0 && !cups.contains(&(d as usize)) => cups.push(d as usize),
+            _ => return Err(ParseError::at_slice(input, &line[i..], "expected a distinct cup label from 1 to 9"))
+        }
+    }
+    if cups.len() < 5 || cups.iter().any(|c| *c > cups.len())
+    {
+        return Err(ParseError::at_slice(input, line, format!("expected the cups labelled 1 to {}", cups.len().max(5))));
+    }
+    return Ok(cups);
 }
 
 fn get_cup_str(cups: &Vec<usize>, mut offset: usize) -> String
@@ -50,7 +102,6 @@ fn play_game(cups: &Vec<usize>, limit: usize) -> Vec<usize>
     let mut offset = 0;
     while offset < limit
     {
-        println!("-- move {} --", offset + 1);
         // println!("cups: {}", get_cup_str(&result, offset));
         let cup = result[0];
 
@@ -101,7 +152,7 @@ fn play_game(cups: &Vec<usize>, limit: usize) -> Vec<usize>
     return result;
 }
 
-fn part_two(cups: &Vec<usize>) -> Vec<usize>
+fn play_million_cups(cups: &Vec<usize>) -> Vec<usize>
 {
     let mut input = vec![0; 1_000_000];
     let num_cups = cups.len();
@@ -143,7 +194,18 @@ mod tests {
     // #[test]
     // fn test_part_two() 
     // {
-    //     let result = part_two(&vec![3,8,9,1,2,5,4,6,7]);
+    //     let result = play_million_cups(&vec![3,8,9,1,2,5,4,6,7]);
     //     assert_eq!(vec![934001,159792], result);
     // }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_errors()
+    {
+        assert_eq!(vec![3, 8, 9, 1, 2, 5, 4, 6, 7], parse("389125467\n").unwrap());
+        let err = parse("389125437").unwrap_err();
+        assert_eq!((1, 8), (err.line, err.column));
+        assert!(parse("38912547").is_err());
+        assert!(parse("1234").is_err());
+        assert!(parse("").is_err());
+    }
+}
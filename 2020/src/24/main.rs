@@ -1,96 +1,56 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use aoc_common::{Automaton, Hex, HexCoord, HexDirection, ParseError, ParseResult, Rule, Solution};
 
-fn main() 
-{
-    if let Ok(lines) = read_lines("src/24/input.txt") {
-        let input:Vec<String> = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap())
-            .collect();
-
-        println!("num tiles: {}", process(&input));
-        println!("num tiles 2: {}", process2(&input));
-    }
+fn main() {
+    aoc_common::run_day::<Day>(2020, 24);
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
+pub struct Day;
 
-fn get_commands(line: &String) -> Vec<String>
+impl Solution for Day
 {
-    let mut it = line.chars();
-    let mut result = Vec::new();
+    type Parsed = Vec<Vec<HexDirection>>;
+    type PartOne = usize;
+    type PartTwo = usize;
 
-    while let Some(c) = it.next()
+    fn parse(input: &str) -> ParseResult<Vec<Vec<HexDirection>>>
     {
-        match c
-        {
-            'e' | 'w' => result.push(String::from(c)),
-            'n' | 's' => result.push(String::from(c) + &String::from(it.next().unwrap())),
-            _ => panic!("invalid char {}", c)
-        }
+        return parse(input);
     }
 
-    return result;
-}
-
-fn get_coords(commands: &Vec<String>) -> (i32, i32)
-{
-    let mut coords = (0, 0);
+    fn part_one(input: &Vec<Vec<HexDirection>>) -> usize
+    {
+        return process(input);
+    }
 
-    for cmd in commands
+    fn part_two(input: &Vec<Vec<HexDirection>>) -> usize
     {
-        if cmd == "e"
-        {
-            coords.0 = coords.0 + 2;
-        }
-        else if cmd == "w"
-        {
-            coords.0 = coords.0 - 2;
-        }
-        else if cmd == "ne"
-        {
-            coords.1 = coords.1 + 1;
-            coords.0 = coords.0 + 1;
-        }
-        else if cmd == "nw"
-        {
-            coords.1 = coords.1 + 1;
-            coords.0 = coords.0 - 1;
-        }
-        else if cmd == "se"
-        {
-            coords.1 = coords.1 - 1;
-            coords.0 = coords.0 + 1;
-        }
-        else if cmd == "sw"
-        {
-            coords.1 = coords.1 - 1;
-            coords.0 = coords.0 - 1;
-        }
-        else
-        {
-            panic!("invalid cmd {}", cmd);
-        }
+        return process2(input, 100);
     }
+}
 
-    return coords;
+fn parse(input: &str) -> ParseResult<Vec<Vec<HexDirection>>>
+{
+    return input
+        .lines()
+        .enumerate()
+        .map(|(y, line)| get_commands(line).map_err(|e| ParseError::at_line(input, y, e.column - 1, e.message)))
+        .collect();
+}
+
+fn get_commands(line: &str) -> ParseResult<Vec<HexDirection>>
+{
+    return HexDirection::parse_steps(line);
 }
 
-fn get_initial_state(input: &Vec<String>) -> Vec<(i32, i32)>
+fn get_initial_state(input: &Vec<Vec<HexDirection>>) -> Vec<HexCoord>
 {
-    let mut flipped_tiles:Vec<(i32,i32)> = Vec::new();
+    let mut flipped_tiles: Vec<HexCoord> = Vec::new();
 
-    for line in input
+    for commands in input
     {
-        let coords = get_coords(&get_commands(&line));
-        
-        let was_flipped = flipped_tiles.iter().position(|x| x.0 == coords.0 && x.1 == coords.1);
+        let coords = HexCoord::ORIGIN.walk(commands.iter().copied());
+
+        let was_flipped = flipped_tiles.iter().position(|x| *x == coords);
         if was_flipped.is_some()
         {
             flipped_tiles.swap_remove(was_flipped.unwrap());
@@ -103,67 +63,18 @@ fn get_initial_state(input: &Vec<String>) -> Vec<(i32, i32)>
     return flipped_tiles;
 }
 
-fn process(input: &Vec<String>) -> usize
+fn process(input: &Vec<Vec<HexDirection>>) -> usize
 {
     return get_initial_state(input).len();
 }
 
-fn get_count_adj(board: &Vec<Vec<u8>>, x: usize, y: usize) -> u8
+/// Black tiles stay black with one or two black neighbours, and white tiles
+/// with exactly two black neighbours turn black.
+fn process2(input: &Vec<Vec<HexDirection>>, days: usize) -> usize
 {
-    return board[y][x-2] + board[y+1][x-1] + board[y+1][x+1] +
-        board[y][x+2] + board[y-1][x+1] + board[y-1][x-1];
-}
-
-const MAX:usize = 500;
-const MID:i32 = MAX as i32 / 2;
-
-fn process2(input: &Vec<String>) -> usize
-{
-    let initial_state = get_initial_state(input);
-    let mut read_board = Vec::new();
-    for _i in 0..MAX
-    {
-        read_board.push(vec![0; MAX]);
-    }
-    for tile in initial_state
-    {
-        read_board[(tile.1+MID) as usize][(tile.0+MID) as usize] = 1;
-    }
-    let mut write_board:Vec<Vec<u8>> = read_board.iter().cloned().collect();
-
-    for _day in 0..100
-    {
-        for y in 2..MAX-2
-        {
-            for x in 2..MAX-2
-            {
-                let count = get_count_adj(&read_board, x, y);
-                let mut result = read_board[y][x];
-                if result == 1 && (count == 0 || count > 2)
-                {
-                    result = 0;
-                }
-                else if result == 0 && count == 2
-                {
-                    result = 1;
-                }
-                write_board[y][x] = result;
-            }
-        }
-
-        for y in 2..MAX-2
-        {
-            for x in 2..MAX-2
-            {
-                read_board[y][x] = write_board[y][x];
-            }
-        }
-    }
-
-    return read_board.iter()
-        .flatten()
-        .filter(|v| **v == 1)
-        .count();
+    let mut floor = Automaton::new(get_initial_state(input), Hex, Rule::new(&[2], &[1, 2]));
+    floor.run(days);
+    return floor.count();
 }
 
 #[cfg(test)]
@@ -195,7 +106,7 @@ mod tests {
             String::from("neswnwewnwnwseenwseesewsenwsweewe"),
             String::from("wseweeenwnesenwwwswnew")
         ];
-        assert_eq!(10, process(&input));
+        assert_eq!(10, process(&parse(&input.join("\n")).unwrap()));
     }
 
     #[test]
@@ -223,7 +134,10 @@ mod tests {
             String::from("neswnwewnwnwseenwseesewsenwsweewe"),
             String::from("wseweeenwnesenwwwswnew")
         ];
-        assert_eq!(2208, process2(&input));
+        let input = parse(&input.join("\n")).unwrap();
+        assert_eq!(15, process2(&input, 1));
+        assert_eq!(37, process2(&input, 10));
+        assert_eq!(2208, process2(&input, 100));
     }
 
     #[test]
@@ -251,6 +165,14 @@ mod tests {
             String::from("w"),
             String::from("sw")
         ];
-        assert_eq!(expected, get_commands(&String::from("sesenwnenenewseeswwswswwnenewsewsw")));
+        let expected: Vec<HexDirection> = expected.iter().map(|c| c.parse().unwrap()).collect();
+        assert_eq!(expected, get_commands("sesenwnenenewseeswwswswwnenewsewsw").unwrap());
+    }
+
+    #[test]
+    fn test_parse_error()
+    {
+        let err = parse("esew\nnwwxswee").unwrap_err();
+        assert_eq!((2, 4), (err.line, err.column));
     }
 }
\ No newline at end of file
@@ -1,34 +1,56 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use aoc_common::{ParseError, ParseResult, Solution};
 
 fn main() {
-    if let Ok(lines) = read_lines("src/3/input.txt") {
-        let mut list = Vec::new();
-        for line in lines {
-            if let Ok(ip) = line {
-                list.push(parse_line(&ip))
-            }
-        }
+    aoc_common::run_day::<Day>(2020, 3);
+}
+
+pub struct Day;
+
+impl Solution for Day
+{
+    type Parsed = Vec<Vec<bool>>;
+    type PartOne = u32;
+    type PartTwo = u32;
+
+    fn parse(input: &str) -> ParseResult<Vec<Vec<bool>>>
+    {
+        return parse(input);
+    }
 
-        let res1 = num_trees(&list, 1, 1);
-        println!("1,1 = {}", res1);
-        let res2 = num_trees(&list, 3, 1);
-        println!("3,1 = {}", res2);
-        let res3 = num_trees(&list, 5, 1);
-        println!("5,1 = {}", res3);
-        let res4 = num_trees(&list, 7, 1);
-        println!("7,1 = {}", res4);
-        let res5 = num_trees(&list, 1, 2);
-        println!("1,2 = {}", res5);
-        println!("total = {}", res1*res2*res3*res4*res5);
+    fn part_one(input: &Vec<Vec<bool>>) -> u32
+    {
+        return num_trees(input, 3, 1);
+    }
+
+    fn part_two(map: &Vec<Vec<bool>>) -> u32
+    {
+        return [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)].iter()
+            .map(|(x, y)| num_trees(&map, *x, *y))
+            .product();
     }
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+/// The map, whose rows must all be the same width.
+fn parse(input: &str) -> ParseResult<Vec<Vec<bool>>>
+{
+    let lines: Vec<&str> = aoc_common::lines(input).collect();
+    let width = lines.first().map_or(0, |l| l.chars().count());
+    if width == 0
+    {
+        return Err(ParseError::at(input, 0, "expected a map"));
+    }
+    for (y, line) in lines.iter().enumerate()
+    {
+        if let Some((x, c)) = line.chars().enumerate().find(|(_, c)| *c != '.' && *c != '#')
+        {
+            return Err(ParseError::at_line(input, y, x, format!("unexpected character '{}'", c)));
+        }
+        if line.chars().count() != width
+        {
+            return Err(ParseError::at_line(input, y, 0, format!("expected {} squares in the row", width)));
+        }
+    }
+    return Ok(lines.into_iter().map(parse_line).collect());
 }
 
 pub fn parse_line(input: &str) -> Vec<bool>
@@ -96,4 +118,14 @@ mod tests {
 
         assert_eq!(2, num_trees(&list, 3, 2));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_errors()
+    {
+        let err = Day::parse("..#\n.x.\n").unwrap_err();
+        assert_eq!((2, 2), (err.line, err.column));
+        let err = Day::parse("..#\n.#\n").unwrap_err();
+        assert_eq!((2, 1), (err.line, err.column));
+        assert!(Day::parse("").is_err());
+    }
+}
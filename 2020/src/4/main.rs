@@ -1,56 +1,77 @@
-#[macro_use] extern crate lazy_static;
+use aoc_common::{ParseError, ParseResult, Solution};
+use lazy_static::lazy_static;
 extern crate regex;
 
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
 use std::collections::HashSet;
 use std::collections::HashMap;
 use regex::Regex;
 
 fn main() {
-    if let Ok(lines) = read_lines("src/4/input.txt") {
-        let required = make_hash_set(vec!["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"]);
+    aoc_common::run_day::<Day>(2020, 4);
+}
 
-        let mut passports = Vec::new();
-        let mut cur_pass = HashMap::new();
-        for line in lines {
-            if let Ok(ip) = line {
-                if "" == ip
-                {
-                    passports.push(cur_pass);
-                    cur_pass = HashMap::new();
-                }
-                else
-                {
-                    cur_pass.extend(parse_data(&ip));
-                }
-            }
-        }
-        passports.push(cur_pass);
+pub struct Day;
 
-        let count1 = passports.iter()
+impl Solution for Day
+{
+    type Parsed = Vec<HashMap<String, String>>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> ParseResult<Vec<HashMap<String, String>>>
+    {
+        return parse_passports(input);
+    }
+
+    fn part_one(input: &Vec<HashMap<String, String>>) -> usize
+    {
+        let required = required_fields();
+        return input.iter()
             .filter(|x| is_valid1(&mut x.keys(), &required))
             .count();
-        println!("valid 1 = {}", count1);
-        let count2 = passports.iter()
+    }
+
+    fn part_two(input: &Vec<HashMap<String, String>>) -> usize
+    {
+        let required = required_fields();
+        return input.iter()
             .filter(|x| is_valid2(x, &required))
             .count();
-        println!("valid 2 = {}", count2);
     }
 }
 
-pub fn parse_data(input: &str) -> HashMap<String, String>
+fn required_fields() -> HashSet<String>
+{
+    return make_hash_set(vec!["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"]);
+}
+
+fn parse_passports(input: &str) -> ParseResult<Vec<HashMap<String, String>>>
+{
+    let mut passports = Vec::new();
+    for lines in aoc_common::paragraphs(input)
+    {
+        let mut cur_pass = HashMap::new();
+        for line in lines
+        {
+            cur_pass.extend(parse_data(line).map_err(|part| ParseError::at_slice(input, part, "expected 'key:value'"))?);
+        }
+        passports.push(cur_pass);
+    }
+    return Ok(passports);
+}
+
+/// The fields on one line, or the first that isn't a `key:value` pair.
+pub fn parse_data(input: &str) -> Result<HashMap<String, String>, &str>
 {
     let parts = input.split(' ');
     let mut result = HashMap::new();
 
     for part in parts
     {
-        let mut s = part.split(':');
-        result.insert(String::from(s.next().unwrap()), String::from(s.next().unwrap()));
+        let (key, value) = part.split_once(':').ok_or(part)?;
+        result.insert(String::from(key), String::from(value));
     }
-    return result;
+    return Ok(result);
 }
 
 pub fn is_valid1(input: &mut dyn Iterator<Item = &String>, test: &HashSet<String>) -> bool
@@ -142,12 +163,6 @@ pub fn valid_height(input: &String) -> bool
     }
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -159,17 +174,25 @@ mod tests {
         map.insert(String::from("hgt"), String::from("1"));
         map.insert(String::from("wgt"), String::from("2"));
         map.insert(String::from("test"), String::from("3"));
-        assert_eq!(map, parse_data("hgt:1 wgt:2 test:3"));
+        assert_eq!(Ok(map), parse_data("hgt:1 wgt:2 test:3"));
+        assert_eq!(Err("wgt"), parse_data("hgt:1 wgt test:3"));
+    }
+
+    #[test]
+    fn test_parse_errors()
+    {
+        let err = Day::parse("hgt:1\n\nbyr:2 hgt\n").unwrap_err();
+        assert_eq!((3, 7), (err.line, err.column));
     }
     
     #[test]
     fn test_is_valid1() 
     {
-        assert_eq!(false, is_valid1(&mut parse_data("hgt:1 wgt:2 test:3").keys(), &make_hash_set(vec!["hgt", "wgt", "test", "other"])));
-        assert_eq!(false, is_valid1(&mut parse_data("hgt:1 wgt:2 test:3").keys(), &make_hash_set(vec!["other"])));
-        assert_eq!(true, is_valid1(&mut parse_data("hgt:1 wgt:2 test:3").keys(), &make_hash_set(vec!["hgt", "wgt", "test"])));
-        assert_eq!(true, is_valid1(&mut parse_data("hgt:1 wgt:2 test:3").keys(), &make_hash_set(vec!["hgt", "wgt"])));
-        assert_eq!(true, is_valid1(&mut parse_data("hgt:1 wgt:2 test:3 test:3").keys(), &make_hash_set(vec!["hgt", "wgt", "test"])));
+        assert_eq!(false, is_valid1(&mut parse_data("hgt:1 wgt:2 test:3").unwrap().keys(), &make_hash_set(vec!["hgt", "wgt", "test", "other"])));
+        assert_eq!(false, is_valid1(&mut parse_data("hgt:1 wgt:2 test:3").unwrap().keys(), &make_hash_set(vec!["other"])));
+        assert_eq!(true, is_valid1(&mut parse_data("hgt:1 wgt:2 test:3").unwrap().keys(), &make_hash_set(vec!["hgt", "wgt", "test"])));
+        assert_eq!(true, is_valid1(&mut parse_data("hgt:1 wgt:2 test:3").unwrap().keys(), &make_hash_set(vec!["hgt", "wgt"])));
+        assert_eq!(true, is_valid1(&mut parse_data("hgt:1 wgt:2 test:3 test:3").unwrap().keys(), &make_hash_set(vec!["hgt", "wgt", "test"])));
     }
 
     #[test]
@@ -1,27 +1,65 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
-use std::collections::BinaryHeap;
+use aoc_common::{ParseError, ParseResult, Solution};
 
 fn main() {
-    if let Ok(lines) = read_lines("src/5/input.txt") {
-        let heap: BinaryHeap<_> = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap())
-            .map(|line| find_seat(&line))
-            .map(|seat| seat_id(seat))
-            .collect();
-
-        println!("Max: {}", heap.peek().unwrap());
-
-        let my_seat_id = find_missing_seat(&heap.into_sorted_vec());
-        println!("My Seat ID: {}", my_seat_id);
-        let col = my_seat_id % 8;
-        let row = (my_seat_id - col) / 8;
-        println!("Col: {}   Row: {}", col, row);
+    aoc_common::run_day::<Day>(2020, 5);
+}
+
+pub struct Day;
+
+impl Solution for Day
+{
+    type Parsed = Vec<u32>;
+    type PartOne = u32;
+    type PartTwo = u32;
+
+    fn parse(input: &str) -> ParseResult<Vec<u32>>
+    {
+        return parse_seat_ids(input);
+    }
+
+    fn part_one(input: &Vec<u32>) -> u32
+    {
+        return *input.last().unwrap();
+    }
+
+    fn part_two(input: &Vec<u32>) -> u32
+    {
+        return find_missing_seat(input);
     }
 }
 
+/// The seat ids of every boarding pass, in ascending order.
+fn parse_seat_ids(input: &str) -> ParseResult<Vec<u32>>
+{
+    let mut ids = Vec::new();
+    for (y, line) in aoc_common::lines(input).enumerate()
+    {
+        for (x, c) in line.chars().enumerate()
+        {
+            let expected = if x < 7 { ['F', 'B'] } else { ['L', 'R'] };
+            if x >= 10 || !expected.contains(&c)
+            {
+                let message = match x {
+                    0..=9 => format!("expected {} or {}, got '{}'", expected[0], expected[1], c),
+                    _ => "expected 10 characters".to_string()
+                };
+                return Err(ParseError::at_line(input, y, x, message));
+            }
+        }
+        if line.len() < 10
+        {
+            return Err(ParseError::at_line(input, y, line.len(), "expected 10 characters"));
+        }
+        ids.push(seat_id(find_seat(line)));
+    }
+    if ids.is_empty()
+    {
+        return Err(ParseError::at(input, 0, "no boarding passes"));
+    }
+    ids.sort();
+    return Ok(ids);
+}
+
 pub fn find_missing_seat(input: &Vec<u32>) -> u32
 {
     let mut iter = input.iter();
@@ -61,12 +99,6 @@ pub fn seat_id(seat: (u32, u32)) -> u32
     return (seat.0 * 8) + seat.1;
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -99,4 +131,15 @@ mod tests {
         assert_eq!(119, seat_id((14, 7)));
         assert_eq!(820, seat_id((102, 4)));
     }
+
+    #[test]
+    fn test_parse_errors()
+    {
+        assert_eq!(Ok(vec![119, 567]), Day::parse("BFFFBBFRRR\nFFFBBBFRRR\n"));
+        let err = Day::parse("BFFFBBFRRR\nFFFBBBFRXR\n").unwrap_err();
+        assert_eq!((2, 9), (err.line, err.column));
+        assert_eq!("expected L or R, got 'X'", err.message);
+        assert_eq!((1, 4), Day::parse("BFF\n").map_err(|e| (e.line, e.column)).unwrap_err());
+        assert!(Day::parse("").is_err());
+    }
 }
\ No newline at end of file
@@ -1,83 +1,74 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
-use std::collections::HashSet;
-use std::iter::FromIterator;
+use aoc_common::{BitSet, ParseError, ParseResult, Solution};
 
 fn main() {
-    if let Ok(lines) = read_lines("src/6/input.txt") {
+    aoc_common::run_day::<Day>(2020, 6);
+}
+
+pub struct Day;
+
+impl Solution for Day
+{
+    type Parsed = Vec<Vec<String>>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> ParseResult<Vec<Vec<String>>>
+    {
+        return get_forms(input);
+    }
 
-        let mut it = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap());
-        let forms = get_forms(&mut it);
-        
-        let total1:usize = forms.iter()
+    fn part_one(input: &Vec<Vec<String>>) -> usize
+    {
+        return input.iter()
             .map(|x| get_any_yeses(&x))
             .map(|x| x.len())
             .sum();
-        println!("Total (any): {}", total1);
-        let total2:usize = forms.iter()
+    }
+
+    fn part_two(input: &Vec<Vec<String>>) -> usize
+    {
+        return input.iter()
             .map(|x| get_all_yeses(&x))
             .map(|x| x.len())
             .sum();
-        println!("Total (all): {}", total2);
     }
 }
 
-pub fn get_forms(items: &mut dyn Iterator<Item = String>) -> Vec<Vec<String>>
+/// Each group's answers, one line of questions a-z per person.
+pub fn get_forms(input: &str) -> ParseResult<Vec<Vec<String>>>
 {
-    let mut forms = Vec::new();
-    let mut cur_form = Vec::new();
-
-    for it in items
+    for line in aoc_common::lines(input)
     {
-        if "" == it
+        if let Some(x) = line.find(|c: char| !c.is_ascii_lowercase())
         {
-            forms.push(cur_form);
-            cur_form = Vec::new();
-        }
-        else
-        {
-            cur_form.push(it);
+            return Err(ParseError::at_slice(input, &line[x..], "expected a question a-z"));
         }
     }
-    forms.push(cur_form);
-    return forms;
+    return Ok(aoc_common::paragraphs(input).iter()
+        .map(|x| x.iter().map(|l| l.to_string()).collect())
+        .collect());
 }
 
 pub fn get_any_yeses(input: &Vec<String>) -> Vec<char>
 {
-    let answers:HashSet<char> = input.iter()
-        .flat_map(|x| x.chars())
+    return input.iter()
+        .map(|x| BitSet::from_letters(x))
+        .fold(BitSet::new(), |a, b| a.union(&b))
+        .letters()
         .collect();
-
-    let mut result:Vec<char> = answers.into_iter().collect();
-    result.sort();
-    return result;
 }
 
 
 pub fn get_all_yeses(input: &Vec<String>) -> Vec<char>
 {
-    let mut it = input.iter();
-    let init = HashSet::<char>::from_iter(it.next().unwrap().chars());
-    let answers:HashSet<char> = it
-        .map(|x| HashSet::<char>::from_iter(x.chars()))
-        .fold(init, |a,b| a.intersection(&b).cloned().collect());
-
-    let mut result:Vec<char> = answers.into_iter().collect();
-    result.sort();
-    return result;
+    return input.iter()
+        .map(|x| BitSet::from_letters(x))
+        .fold(BitSet::full(), |a, b| a.intersection(&b))
+        .letters()
+        .collect();
 }
 
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -101,4 +92,12 @@ mod tests {
         assert_eq!(vec!['a'], get_all_yeses(&vec![String::from("a"), String::from("a"), String::from("a"), String::from("a")]));
         assert_eq!(vec!['b'], get_all_yeses(&vec![String::from("b")]));
     }
+
+    #[test]
+    fn test_get_forms()
+    {
+        assert_eq!(Ok(vec![vec![String::from("ab")], vec![String::from("c"), String::from("d")]]), get_forms("ab\n\nc\nd\n"));
+        let err = get_forms("ab\n\nc\ndE\n").unwrap_err();
+        assert_eq!((4, 2), (err.line, err.column));
+    }
 }
\ No newline at end of file
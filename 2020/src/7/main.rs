@@ -1,60 +1,74 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
 use std::collections::HashSet;
 use std::collections::HashMap;
+use aoc_common::{ParseError, ParseResult, Solution};
 
 fn main() {
-    if let Ok(lines) = read_lines("src/7/input.txt") {
-        let it:Vec<String> = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap())
-            .collect();
-        let containers = get_all_possible_containers(&mut it.iter(), &String::from("shiny gold"));
-        println!("Total Containers: {}", containers.len());
-        let total_bags = get_total_bags(&mut it.iter(), &String::from("shiny gold"));
-        println!("Total Bags Inside: {}", total_bags);
-    }
+    aoc_common::run_day::<Day>(2020, 7);
 }
 
-pub fn get_containing_bags(input: &String) -> Vec<String>
+pub struct Day;
+
+impl Solution for Day
 {
-    return get_containing_bags_and_counts(input).iter()
-        .map(|x| x.1.clone())
-        .collect();
+    type Parsed = Vec<Rule>;
+    type PartOne = usize;
+    type PartTwo = u32;
+
+    fn parse(input: &str) -> ParseResult<Vec<Rule>>
+    {
+        return parse(input);
+    }
+
+    fn part_one(rules: &Vec<Rule>) -> usize
+    {
+        return get_all_possible_containers(rules, &String::from("shiny gold")).len();
+    }
+
+    fn part_two(rules: &Vec<Rule>) -> u32
+    {
+        return get_total_bags(rules, &String::from("shiny gold"));
+    }
 }
 
-pub fn get_containing_bags_and_counts(input: &String) -> Vec<(u32, String)>
+/// A bag and how many of each other bag it contains.
+pub type Rule = (String, Vec<(u32, String)>);
+
+fn parse(input: &str) -> ParseResult<Vec<Rule>>
 {
-    let rhs = input.split(" contain ").skip(1).next().unwrap().to_string();
-    if rhs == "no other bags."
-    {
-        return Vec::new();
-    }
-    let bags = rhs.split(',');
-    return bags
-        .map(|x| x.trim())
-        .map(|x| x.trim_end_matches('.'))
-        .map(|x| x.trim_end_matches(" bags"))
-        .map(|x| x.trim_end_matches(" bag"))
-        .map(|x| (x[0..1].parse::<u32>().unwrap(), String::from(&x[2..])))
+    return aoc_common::lines(input)
+        .map(|line| parse_rule(input, line))
         .collect();
 }
 
-pub fn get_outer_bag(input: &String) -> String
+/// A rule such as `shiny gold bags contain 1 dark olive bag, 2 vibrant plum
+/// bags.`, from a line of `input`.
+pub fn parse_rule(input: &str, line: &str) -> ParseResult<Rule>
 {
-    return input.split(" bags contain ").next().unwrap().to_string();
+    let (outer, rhs) = line.split_once(" bags contain ")
+        .ok_or_else(|| ParseError::at_slice(input, line, "expected '<colour> bags contain ...'"))?;
+    let rhs = rhs.strip_suffix('.')
+        .ok_or_else(|| ParseError::at_slice(input, &line[line.len()..], "expected a full stop"))?;
+    if rhs == "no other bags"
+    {
+        return Ok((outer.to_string(), Vec::new()));
+    }
+    let mut inner = Vec::new();
+    for bag in rhs.split(", ")
+    {
+        let error = || ParseError::at_slice(input, bag, "expected '<count> <colour> bags'");
+        let (count, colour) = bag.split_once(' ').ok_or_else(error)?;
+        let count = count.parse::<u32>().map_err(|_| error())?;
+        let colour = colour.strip_suffix(" bags")
+            .or_else(|| colour.strip_suffix(" bag"))
+            .ok_or_else(error)?;
+        inner.push((count, colour.to_string()));
+    }
+    return Ok((outer.to_string(), inner));
 }
 
-pub fn get_total_bags(input: &mut dyn Iterator<Item = &String>, target: &String) -> u32
+pub fn get_total_bags(rules: &[Rule], target: &String) -> u32
 {
-    let mut map = HashMap::new();
-    for item in input
-    {
-        let outer = get_outer_bag(item);
-        let bags = get_containing_bags_and_counts(item);
-        map.insert(outer, bags);
-    }
+    let map = rules.iter().cloned().collect();
     return get_total_bags_recursive(&map, target) - 1;
 }
 
@@ -66,9 +80,9 @@ pub fn get_total_bags_recursive(map: &HashMap<String, Vec<(u32, String)>>, targe
         .sum::<u32>() + 1;
 }
 
-pub fn get_all_possible_containers(input: &mut dyn Iterator<Item = &String>, target: &String) -> Vec<String>
+pub fn get_all_possible_containers(rules: &[Rule], target: &String) -> Vec<String>
 {
-    let map = build_container_map(input);
+    let map = build_container_map(rules);
     return get_all_containers(&map, target);
 }
 
@@ -96,54 +110,45 @@ pub fn get_all_containers_recursive(map: &HashMap<String, Vec<String>>, set: &mu
     set.remove(target);
 }
 
-pub fn build_container_map(input: &mut dyn Iterator<Item = &String>) -> HashMap<String, Vec<String>>
+pub fn build_container_map(rules: &[Rule]) -> HashMap<String, Vec<String>>
 {
     let mut map = HashMap::new();
-    for item in input
+    for (outer, bags) in rules
     {
-        let outer = get_outer_bag(item);
-        let bags = get_containing_bags(item);
-        
-        for key in bags
+        for (_, key) in bags
         {
-            let values: &mut Vec<String> = map.entry(key).or_insert_with(|| Vec::new());
+            let values: &mut Vec<String> = map.entry(key.clone()).or_insert_with(|| Vec::new());
             values.push(outer.clone());
         }
     }
     return map;
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_get_containing_bags() 
+    fn rule(line: &str) -> Rule
     {
-        assert_eq!(0, get_containing_bags(&String::from("faded blue bags contain no other bags.")).len());
-        assert_eq!(vec!["faded blue", "dotted black"], get_containing_bags(&String::from("vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.")));
-        assert_eq!(vec!["dark olive", "vibrant plum"], get_containing_bags(&String::from("shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.")));
+        return parse_rule(line, line).unwrap();
     }
 
     #[test]
-    fn test_get_containing_bags_and_counts() 
+    fn test_parse_rule() 
     {
-        assert_eq!(0, get_containing_bags_and_counts(&String::from("faded blue bags contain no other bags.")).len());
-        assert_eq!(vec![(5, String::from("faded blue")), (6, String::from("dotted black"))], get_containing_bags_and_counts(&String::from("vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.")));
-        assert_eq!(vec![(1, String::from("dark olive")), (2, String::from("vibrant plum"))], get_containing_bags_and_counts(&String::from("shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.")));
+        assert_eq!((String::from("faded blue"), vec![]), rule("faded blue bags contain no other bags."));
+        assert_eq!((String::from("vibrant plum"), vec![(5, String::from("faded blue")), (6, String::from("dotted black"))]), rule("vibrant plum bags contain 5 faded blue bags, 6 dotted black bags."));
+        assert_eq!((String::from("shiny gold"), vec![(1, String::from("dark olive")), (12, String::from("vibrant plum"))]), rule("shiny gold bags contain 1 dark olive bag, 12 vibrant plum bags."));
     }
 
     #[test]
-    fn test_get_outer_bag() 
+    fn test_parse_errors() 
     {
-        assert_eq!("faded blue", get_outer_bag(&String::from("faded blue bags contain no other bags.")));
-        assert_eq!("vibrant plum", get_outer_bag(&String::from("vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.")));
+        let err = Day::parse("faded blue bags contain no other bags.\nvibrant plum bags contain 5 faded blue bags, x dotted black bags.\n").unwrap_err();
+        assert_eq!((2, 46), (err.line, err.column));
+        assert!(Day::parse("faded blue bags hold no other bags.").is_err());
+        assert!(Day::parse("faded blue bags contain no other bags").is_err());
+        assert!(Day::parse("faded blue bags contain 5 faded.").is_err());
     }
 
     #[test]
@@ -196,7 +201,7 @@ mod tests {
             String::from("faded blue bags contain no other bags."),
             String::from("dotted black bags contain no other bags.")
         ];
-        assert_eq!(vec!["bright white", "dark orange", "light red", "muted yellow"], get_all_possible_containers(&mut input.iter(), &String::from("shiny gold")));
+        assert_eq!(vec!["bright white", "dark orange", "light red", "muted yellow"], get_all_possible_containers(&input.iter().map(|l| rule(l)).collect::<Vec<_>>(), &String::from("shiny gold")));
     }
 
     #[test]
@@ -213,7 +218,7 @@ mod tests {
             String::from("faded blue bags contain no other bags."),
             String::from("dotted black bags contain no other bags.")
         ];
-        assert_eq!(32, get_total_bags(&mut input.iter(), &String::from("shiny gold")));
+        assert_eq!(32, get_total_bags(&input.iter().map(|l| rule(l)).collect::<Vec<_>>(), &String::from("shiny gold")));
     }
 
     #[test]
@@ -228,6 +233,6 @@ mod tests {
             String::from("dark blue bags contain 2 dark violet bags."),
             String::from("dark violet bags contain no other bags."),
         ];
-        assert_eq!(126, get_total_bags(&mut input.iter(), &String::from("shiny gold")));
+        assert_eq!(126, get_total_bags(&input.iter().map(|l| rule(l)).collect::<Vec<_>>(), &String::from("shiny gold")));
     }
 }
\ No newline at end of file
@@ -1,26 +1,48 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
 use std::collections::HashSet;
+use std::str::FromStr;
+use aoc_common::{Debuggable, Flow, InstructionSet, Machine, ParseError, ParseResult, Solution};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct VMState {
-    acc: i32,
-    pc: i32,
-    visited: HashSet<i32>
+    acc: i32
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Op {
+    Acc,
+    Jmp,
+    Nop
 }
 
 #[derive(PartialEq, Clone, Debug)]
 pub struct Instruction {
-    op_code: String,
+    op_code: Op,
     arg: i32
 }
 
+/// The handheld console, whose programs are decoded as they're parsed.
+#[derive(Debug, Clone)]
+pub struct Console;
+
+impl FromStr for Op {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Op, String>
+    {
+        return match s {
+            "acc" => Ok(Op::Acc),
+            "jmp" => Ok(Op::Jmp),
+            "nop" => Ok(Op::Nop),
+            _ => Err(format!("unknown operation '{}'", s))
+        };
+    }
+}
 
 impl Instruction {
+    #[allow(dead_code)]
     fn from(o: &str, a: i32) -> Instruction {
         return Instruction {
-            op_code: String::from(o),
+            op_code: o.parse().unwrap(),
             arg: a
         };
     }
@@ -29,82 +51,151 @@ impl Instruction {
 impl VMState {
     fn new() -> VMState {
         return VMState {
-            acc: 0,
-            pc: 0,
-            visited: HashSet::new()
+            acc: 0
         };
     }
+}
 
-    fn process(&mut self, ins: &Instruction)
+impl InstructionSet for Console
+{
+    type Code = Instruction;
+    type Instruction = Instruction;
+    type State = VMState;
+    type Undo = VMState;
+
+    fn decode(&self, code: &[Instruction], ip: usize) -> Result<(Instruction, usize), String>
     {
-        if ins.op_code == "jmp"
-        {
-            self.pc = self.pc + (ins.arg - 1);
-        }
-        else if ins.op_code == "acc"
-        {
-            self.acc = self.acc + ins.arg;
-        }
-        self.pc = self.pc + 1;
+        return Ok((code[ip].clone(), 1));
+    }
+
+    fn execute(&self, ins: &Instruction, state: &mut VMState) -> Result<Flow, String>
+    {
+        return Ok(match ins.op_code {
+            Op::Acc => {
+                state.acc = state.acc + ins.arg;
+                Flow::Next
+            }
+            Op::Jmp => Flow::Jump(ins.arg as i64),
+            Op::Nop => Flow::Next
+        });
     }
 
-    fn has_visited(&mut self) -> bool
+    fn save(&self, _ins: &Instruction, state: &VMState) -> VMState
     {
-        return !self.visited.insert(self.pc);
+        return state.clone();
+    }
+
+    fn restore(&self, undo: VMState, state: &mut VMState)
+    {
+        *state = undo;
+    }
+}
+
+impl Debuggable for Console
+{
+    fn mnemonic(&self, ins: &Instruction) -> String
+    {
+        return format!("{:?}", ins.op_code).to_lowercase();
+    }
+
+    fn describe(&self, ins: &Instruction) -> String
+    {
+        return format!("{} {:+}", self.mnemonic(ins), ins.arg);
+    }
+
+    fn registers(&self, state: &VMState) -> Vec<(String, String)>
+    {
+        return vec![(String::from("acc"), state.acc.to_string())];
     }
 }
 
 fn main() {
-    if let Ok(lines) = read_lines("src/8/input.txt") {
-        let it:Vec<Instruction> = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap())
-            .map(|r| parse_instruction(&r))
-            .collect();
-        let broken = execute_until_duplicate(&it);
-        println!("Broken Result: {:?}", broken.acc);
-        let fixed = fix_and_run_program(&it);
-        println!("Fixed Result: {:?}", fixed.acc);
+    aoc_common::run_debuggable::<Day, _>(2020, 8, debug_machine);
+}
+
+/// Part one debugs the program as given and part two the fixed one.
+fn debug_machine(program: &Vec<Instruction>, part: u32) -> Machine<Console>
+{
+    if part == 1
+    {
+        return Machine::new(Console, program.to_vec(), VMState::new());
     }
+    return Machine::new(Console, fix_program(program), VMState::new());
 }
 
-pub fn execute_until_duplicate(program: &Vec<Instruction>) -> VMState
+pub struct Day;
+
+impl Solution for Day
 {
-    let mut state = VMState::new();
-    let len = program.len() as i32;
-    while state.pc != len
+    type Parsed = Vec<Instruction>;
+    type PartOne = i32;
+    type PartTwo = i32;
+
+    fn parse(input: &str) -> ParseResult<Vec<Instruction>>
     {
-        if state.has_visited()
-        {
-            break;
-        }
+        return parse(input);
+    }
 
-        state.process(&program[state.pc as usize]);
+    fn part_one(input: &Vec<Instruction>) -> i32
+    {
+        return execute_until_duplicate(input).acc;
     }
-    return state;
+
+    fn part_two(input: &Vec<Instruction>) -> i32
+    {
+        return fix_and_run_program(input).acc;
+    }
+}
+
+fn parse(input: &str) -> ParseResult<Vec<Instruction>>
+{
+    return aoc_common::lines(input)
+        .enumerate()
+        .map(|(y, line)| parse_instruction(line).map_err(|e| ParseError::at_line(input, y, 0, e)))
+        .collect();
+}
+
+fn run_until_duplicate(program: &Vec<Instruction>) -> Machine<Console>
+{
+    let mut machine = Machine::new(Console, program.to_vec(), VMState::new());
+    machine.run_until_loop().unwrap_or_else(|e| panic!("{}", e));
+    return machine;
+}
+
+pub fn execute_until_duplicate(program: &Vec<Instruction>) -> VMState
+{
+    return run_until_duplicate(program).into_state();
 }
 
 pub fn fix_and_run_program(program: &Vec<Instruction>) -> VMState
 {
-    let looping_result = execute_until_duplicate(program);
-    
-    let result = find_back(program, &looping_result.visited, program.len() as i32).unwrap();
+    return execute_until_duplicate(&fix_program(program));
+}
+
+/// The program with the one instruction switched that lets it run to the end.
+fn fix_program(program: &Vec<Instruction>) -> Vec<Instruction>
+{
+    let visited: HashSet<i32> = run_until_duplicate(program)
+        .visited()
+        .map(|ip| ip as i32)
+        .collect();
+
+    let result = find_back(program, &visited, program.len() as i32).unwrap();
 
     let mut program_copy = program.to_vec();
     program_copy[result as usize] = switch_instruction(&program[result as usize]);
-    
-    return execute_until_duplicate(&program_copy);
+    return program_copy;
 }
 
 pub fn switch_instruction(ins: &Instruction) -> Instruction
 {
-    if ins.op_code == "nop"
+    if ins.op_code == Op::Nop
     {
-        return Instruction::from("jmp", ins.arg);
+        return Instruction { op_code: Op::Jmp, arg: ins.arg };
     }
     else
     {
-        return Instruction::from("nop", ins.arg);
+        return Instruction { op_code: Op::Nop, arg: ins.arg };
     }
 }
 
@@ -136,7 +227,7 @@ pub fn find_previous_jumps(program: &Vec<Instruction>, target: i32) -> Vec<i32>
 {
     let mut results = Vec::new();
 
-    if program.get((target - 1) as usize).map_or(false, |x| x.op_code == "nop" || x.op_code == "acc")
+    if program.get((target - 1) as usize).map_or(false, |x| x.op_code == Op::Nop || x.op_code == Op::Acc)
     {
         results.push(target - 1)
     }
@@ -144,7 +235,7 @@ pub fn find_previous_jumps(program: &Vec<Instruction>, target: i32) -> Vec<i32>
     for i in 0..program.len()
     {
         let ins = &program[i];
-        if ins.op_code == "jmp" && ins.arg + (i as i32) == target
+        if ins.op_code == Op::Jmp && ins.arg + (i as i32) == target
         {
             results.push(i as i32);
         }
@@ -157,7 +248,7 @@ pub fn find_previous_jumps_if_switched(program: &Vec<Instruction>, target: i32)
 {
     let mut results = Vec::new();
 
-    if program.get((target - 1) as usize).map_or(false, |x| x.op_code == "jmp")
+    if program.get((target - 1) as usize).map_or(false, |x| x.op_code == Op::Jmp)
     {
         results.push(target - 1)
     }
@@ -165,7 +256,7 @@ pub fn find_previous_jumps_if_switched(program: &Vec<Instruction>, target: i32)
     for i in 0..program.len()
     {
         let ins = &program[i];
-        if ins.op_code == "nop" && ins.arg + (i as i32) == target
+        if ins.op_code == Op::Nop && ins.arg + (i as i32) == target
         {
             results.push(i as i32);
         }
@@ -173,18 +264,13 @@ pub fn find_previous_jumps_if_switched(program: &Vec<Instruction>, target: i32)
     return results;
 }
 
-pub fn parse_instruction(ins: &String) -> Instruction
+pub fn parse_instruction(ins: &str) -> Result<Instruction, String>
 {
     let mut parts = ins.split(" ");
-    let op_code = parts.next().unwrap().to_string();
-    let arg = parts.next().unwrap();
-    return Instruction{op_code: op_code, arg: arg.parse::<i32>().unwrap()};
-}
-
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+    let op_code = parts.next().unwrap().parse::<Op>()?;
+    let arg = parts.next().ok_or("expected an argument")?;
+    let arg = arg.parse::<i32>().map_err(|_| format!("invalid argument '{}'", arg))?;
+    return Ok(Instruction{op_code: op_code, arg: arg});
 }
 
 #[cfg(test)]
@@ -194,9 +280,17 @@ mod tests {
     #[test]
     fn test_parse_instruction() 
     {
-        assert_eq!(Instruction::from("nop", 0), parse_instruction(&String::from("nop +0")));
-        assert_eq!(Instruction::from("acc", 1), parse_instruction(&String::from("acc +1")));
-        assert_eq!(Instruction::from("jmp", -3), parse_instruction(&String::from("jmp -3")));
+        assert_eq!(Ok(Instruction::from("nop", 0)), parse_instruction(&String::from("nop +0")));
+        assert_eq!(Ok(Instruction::from("acc", 1)), parse_instruction(&String::from("acc +1")));
+        assert_eq!(Ok(Instruction::from("jmp", -3)), parse_instruction(&String::from("jmp -3")));
+    }
+
+    #[test]
+    fn test_parse_error()
+    {
+        let err = Day::parse("nop +0\nhcf +1\n").unwrap_err();
+        assert_eq!((2, 1), (err.line, err.column));
+        assert_eq!("unknown operation 'hcf'", err.message);
     }
 
     #[test]
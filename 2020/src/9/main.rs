@@ -1,25 +1,42 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use aoc_common::{ParseResult, Solution};
 
 fn main() {
-    if let Ok(lines) = read_lines("src/9/input.txt") {
-        let input:Vec<i64> = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap())
-            .map(|s| s.parse::<i64>().unwrap())
-            .collect();
+    aoc_common::run_day::<Day>(2020, 9);
+}
+
+pub struct Day;
+
+impl Solution for Day
+{
+    type Parsed = Vec<i64>;
+    type PartOne = i64;
+    type PartTwo = i64;
+
+    fn parse(input: &str) -> ParseResult<Vec<i64>>
+    {
+        return parse(input);
+    }
+
+    fn part_one(input: &Vec<i64>) -> i64
+    {
+        return find_first_failure(input, 25).unwrap();
+    }
 
-        let f = find_first_failure(&input, 25).unwrap();
-        println!("Invalid Value: {:?}", f);
-        let r = find_range_that_sums_to(&input, f).unwrap();
-        let hi = input[r.0..r.1].iter().max().unwrap();
-        let low = input[r.0..r.1].iter().min().unwrap();
-        println!("Range: {:?}", &input[r.0..r.1]);
-        println!("Sum: {} + {} = {}", low, hi, low + hi);
+    fn part_two(numbers: &Vec<i64>) -> i64
+    {
+        let f = find_first_failure(&numbers, 25).unwrap();
+        let r = find_range_that_sums_to(&numbers, f).unwrap();
+        let hi = numbers[r.0..r.1].iter().max().unwrap();
+        let low = numbers[r.0..r.1].iter().min().unwrap();
+        return low + hi;
     }
 }
 
+fn parse(input: &str) -> ParseResult<Vec<i64>>
+{
+    return aoc_common::numbers(input);
+}
+
 pub fn has_sum_to(input: &[i64], target: i64) -> bool
 {
     for i in 0..input.len()
@@ -73,12 +90,6 @@ pub fn find_range_that_sums_to(input: &Vec<i64>, target: i64) -> Option<(usize,
     return None;
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
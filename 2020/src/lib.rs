@@ -0,0 +1,75 @@
+//! Each day's solution as a module so the `aoc` runner can call into it.
+//! A day's `main` is only used by its own binary.
+#![allow(dead_code)]
+
+#[path = "1/main.rs"]
+pub mod day1;
+
+#[path = "2/main.rs"]
+pub mod day2;
+
+#[path = "3/main.rs"]
+pub mod day3;
+
+#[path = "4/main.rs"]
+pub mod day4;
+
+#[path = "5/main.rs"]
+pub mod day5;
+
+#[path = "6/main.rs"]
+pub mod day6;
+
+#[path = "7/main.rs"]
+pub mod day7;
+
+#[path = "8/main.rs"]
+pub mod day8;
+
+#[path = "9/main.rs"]
+pub mod day9;
+
+#[path = "10/main.rs"]
+pub mod day10;
+
+#[path = "11/main.rs"]
+pub mod day11;
+
+#[path = "12/main.rs"]
+pub mod day12;
+
+#[path = "13/main.rs"]
+pub mod day13;
+
+#[path = "14/main.rs"]
+pub mod day14;
+
+#[path = "15/main.rs"]
+pub mod day15;
+
+#[path = "16/main.rs"]
+pub mod day16;
+
+#[path = "17/main.rs"]
+pub mod day17;
+
+#[path = "18/main.rs"]
+pub mod day18;
+
+#[path = "19/main.rs"]
+pub mod day19;
+
+#[path = "20/main.rs"]
+pub mod day20;
+
+#[path = "21/main.rs"]
+pub mod day21;
+
+#[path = "22/main.rs"]
+pub mod day22;
+
+#[path = "23/main.rs"]
+pub mod day23;
+
+#[path = "24/main.rs"]
+pub mod day24;
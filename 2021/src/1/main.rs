@@ -1,24 +1,36 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use aoc_common::{ParseResult, Solution};
 
 fn main() {
-    if let Ok(lines) = read_lines("src/1/input.txt") {
-        let list = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap())
-            .map(|r| r.parse::<u32>().unwrap())
-            .collect();
+    aoc_common::run_day::<Day>(2021, 1);
+}
+
+pub struct Day;
+
+impl Solution for Day
+{
+    type Parsed = Vec<u32>;
+    type PartOne = u32;
+    type PartTwo = u32;
+
+    fn parse(input: &str) -> ParseResult<Vec<u32>>
+    {
+        return parse(input);
+    }
+
+    fn part_one(input: &Vec<u32>) -> u32
+    {
+        return num_increases(input);
+    }
 
-        println!("Num Increases: {}", num_increases(&list));
-        println!("Num Increases2: {}", num_increases2(&list));
+    fn part_two(input: &Vec<u32>) -> u32
+    {
+        return num_increases2(input);
     }
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+fn parse(input: &str) -> ParseResult<Vec<u32>>
+{
+    return aoc_common::numbers(input);
 }
 
 pub fn num_increases(numbers: &Vec<u32>) -> u32
@@ -1,41 +1,76 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use aoc_common::{ParseError, ParseResult, Solution};
 
 fn main() {
-    if let Ok(lines) = read_lines("src/10/input.txt") {
-        let input:Vec<String> = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap())
-            .collect();
+    aoc_common::run_day::<Day>(2021, 10);
+}
 
-        println!("Part 1: {}", part_one(&input));
-        println!("Part 2: {}", part_two(&input));
+pub struct Day;
+
+impl Solution for Day
+{
+    type Parsed = Vec<String>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> ParseResult<Vec<String>>
+    {
+        return parse(input);
     }
-}
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+    fn part_one(lines: &Vec<String>) -> usize
+    {
+        return lines.iter()
+            .map(|l| first_illegal_character(l))
+            .filter(|o| o.is_some())
+            .map(|o| 
+                return match o.unwrap()
+                {
+                    ')' => 3,
+                    ']' => 57,
+                    '}' => 1197,
+                    '>' => 25137,
+                    _ => panic!("invalid character {}", o.unwrap())
+                }
+            )
+            .fold(0, |a,b| a + b);
+    }
+
+    fn part_two(lines: &Vec<String>) -> usize
+    {
+        let mut items:Vec<usize> = lines.iter()
+            .map(|l| find_replacement(l))
+            .filter(|o| o.is_some())
+            .map(|l| {
+                l.unwrap().iter()
+                    .map(|o| 
+                        return match o
+                        {
+                            '(' => 1,
+                            '[' => 2,
+                            '{' => 3,
+                            '<' => 4,
+                            _ => panic!("invalid character {}", o)
+                        })
+                    .fold(0, |a,b| (a*5) + b)
+            })
+            .collect();
+
+        items.sort();
+        return items[items.len() / 2];
+    }
 }
 
-fn part_one(lines: &Vec<String>) -> usize
+/// The lines of the navigation subsystem, which may only hold brackets.
+fn parse(input: &str) -> ParseResult<Vec<String>>
 {
-    return lines.iter()
-        .map(|l| first_illegal_character(l))
-        .filter(|o| o.is_some())
-        .map(|o| 
-            return match o.unwrap()
-            {
-                ')' => 3,
-                ']' => 57,
-                '}' => 1197,
-                '>' => 25137,
-                _ => panic!("invalid character {}", o.unwrap())
-            }
-        )
-        .fold(0, |a,b| a + b);
+    for line in aoc_common::lines(input)
+    {
+        if let Some(i) = line.find(|c| !"()[]{}<>".contains(c))
+        {
+            return Err(ParseError::at_slice(input, &line[i..], "expected one of '()[]{}<>'"));
+        }
+    }
+    return Ok(aoc_common::owned_lines(input));
 }
 
 fn first_illegal_character(line: &String) -> Option<char>
@@ -62,30 +97,6 @@ fn first_illegal_character(line: &String) -> Option<char>
     return None;
 }
 
-fn part_two(lines: &Vec<String>) -> usize
-{
-    let mut items:Vec<usize> = lines.iter()
-        .map(|l| find_replacement(l))
-        .filter(|o| o.is_some())
-        .map(|l| {
-            l.unwrap().iter()
-                .map(|o| 
-                    return match o
-                    {
-                        '(' => 1,
-                        '[' => 2,
-                        '{' => 3,
-                        '<' => 4,
-                        _ => panic!("invalid character {}", o)
-                    })
-                .fold(0, |a,b| (a*5) + b)
-        })
-        .collect();
-
-    items.sort();
-    return items[items.len() / 2];
-}
-
 fn find_replacement(line: &String) -> Option<Vec<char>>
 {
     let mut stack = Vec::new();
@@ -140,7 +151,7 @@ mod tests {
             String::from("<{([{{}}[<[[[<>{}]]]>[]]")
 
         ];
-        assert_eq!(26397, part_one(&input));
+        assert_eq!(26397, Day::part_one(&Day::parse(&input.join("\n")).unwrap()));
     }
 
     #[test]
@@ -159,6 +170,13 @@ mod tests {
             String::from("<{([{{}}[<[[[<>{}]]]>[]]")
 
         ];
-        assert_eq!(288957, part_two(&input));
+        assert_eq!(288957, Day::part_two(&Day::parse(&input.join("\n")).unwrap()));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_errors()
+    {
+        let err = Day::parse("[({(<(())[]>[[{[]{<()<>>\n[(()[<>]x)]\n").unwrap_err();
+        assert_eq!((2, 9), (err.line, err.column));
+    }
+}
@@ -1,72 +1,86 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+//! Flashing octopuses. Each octopus's neighbours are the shared automaton
+//! engine's [`Moore`] neighbourhood, but a step can't be an [`Automaton`]
+//! generation: an octopus carries an energy level rather than being live or
+//! dead, and a flash sets off its neighbours within the same step, where a
+//! birth/survival [`Rule`] only counts what was live in the last generation.
+//!
+//! [`Automaton`]: aoc_common::Automaton
+//! [`Rule`]: aoc_common::Rule
+
+use aoc_common::{find_cycle, Grid, Moore, Neighbourhood, ParseResult, Pos, Solution};
 
 fn main() {
-    if let Ok(lines) = read_lines("src/11/input.txt") {
-        let input:Vec<Vec<u8>> = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap())
-            .map(|r| r.chars().map(|v| v.to_string().parse::<u8>().unwrap()).collect())
-            .collect();
-
-        println!("Part 1: {}", part_one(&input, 100));
-        println!("Part 2: {}", part_two(&input));
-    }
+    aoc_common::run_day::<Day>(2021, 11);
 }
 
+pub struct Day;
+
+impl Solution for Day
+{
+    type Parsed = Grid<u8>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> ParseResult<Grid<u8>>
+    {
+        return Grid::digits(input);
+    }
+
+    fn part_one(input: &Grid<u8>) -> usize
+    {
+        return count_flashes(input, 100);
+    }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+    fn part_two(input: &Grid<u8>) -> usize
+    {
+        return first_synchronised_flash(input);
+    }
 }
 
-fn trigger_flash(input: &mut Vec<Vec<u8>>, x: usize, y: usize)
+/// The octopuses around `pos`, as the automaton engine sees them.
+fn neighbours(input: &Grid<u8>, pos: Pos) -> Vec<Pos>
 {
-    input[y][x] = input[y][x] + 1;
-    if input[y][x] == 10
+    return Moore.neighbours(&[pos.0 as i64, pos.1 as i64])
+        .iter()
+        .filter(|[x, y]| *x >= 0 && *y >= 0)
+        .map(|&[x, y]| (x as usize, y as usize))
+        .filter(|&n| input.contains(n))
+        .collect();
+}
+
+fn trigger_flash(input: &mut Grid<u8>, pos: Pos)
+{
+    input[pos] = input[pos] + 1;
+    if input[pos] == 10
     {
-        let mods = vec![(-1,-1), (-1,0), (-1,1), (0,-1), (0,1), (1,-1), (1,0), (1,1)];
-        for m in mods
+        for n in neighbours(input, pos)
         {
-            let xm = x as i64 + m.0;
-            let ym = y as i64 + m.1;
-            if xm >= 0 && xm < input[0].len() as i64 &&
-                ym >= 0 && ym < input.len() as i64
-            {
-                trigger_flash(input, xm as usize, ym as usize);
-            }
+            trigger_flash(input, n);
         }
     }
 }
 
-fn run_step(input: &mut Vec<Vec<u8>>) -> usize
+fn run_step(input: &mut Grid<u8>) -> usize
 {
-    for y in 0..input.len()
+    let positions:Vec<Pos> = input.positions().collect();
+    for &pos in &positions
     {
-        for x in 0..input[0].len()
-        {
-            trigger_flash(input, x, y);
-        }
+        trigger_flash(input, pos);
     }
 
     let mut count = 0;
-    for y in 0..input.len()
+    for &pos in &positions
     {
-        for x in 0..input[0].len()
+        if input[pos] > 9
         {
-            if input[y][x] > 9
-            {
-                input[y][x] = 0;
-                count = count + 1;
-            }
+            input[pos] = 0;
+            count = count + 1;
         }
     }
     return count;
 }
 
-fn part_one(input: &Vec<Vec<u8>>, steps: usize) -> usize
+fn count_flashes(input: &Grid<u8>, steps: usize) -> usize
 {
     let mut current = input.clone();
 
@@ -79,21 +93,22 @@ fn part_one(input: &Vec<Vec<u8>>, steps: usize) -> usize
     return total;
 }
 
-fn part_two(input: &Vec<Vec<u8>>) -> usize
+fn first_synchronised_flash(input: &Grid<u8>) -> usize
 {
-    let mut current = input.clone();
-    let num_octopuses = input.len() * input[0].len();
-
-    let mut step = 1;
-    loop
+    // the octopuses settle into a cycle, so if they ever all flash together
+    // it's somewhere before the first repeated state
+    let history = find_cycle(input.clone(), |current|
     {
-        let num_flashes = run_step(&mut current);
-        if num_flashes == num_octopuses
-        {
-            return step;
-        }
-        step = step + 1;
-    }
+        let mut next = current.clone();
+        run_step(&mut next);
+        return next;
+    });
+    return history.states()
+        .iter()
+        .skip(1)
+        .position(|state| state.cells().all(|&energy| energy == 0))
+        .map(|step| step + 1)
+        .expect("the octopuses never flash together");
 }
 
 #[cfg(test)]
@@ -103,7 +118,7 @@ mod tests {
     #[test]
     fn test_part_one() 
     {
-        let input = vec![
+        let input = Grid::from_rows(vec![
             vec![5,4,8,3,1,4,3,2,2,3],
             vec![2,7,4,5,8,5,4,7,1,1],
             vec![5,2,6,4,5,5,6,1,7,3],
@@ -114,16 +129,16 @@ mod tests {
             vec![6,8,8,2,8,8,1,1,3,4],
             vec![4,8,4,6,8,4,8,5,5,4],
             vec![5,2,8,3,7,5,1,5,2,6]
-        ];
-        assert_eq!(0, part_one(&input, 1));
-        assert_eq!(204, part_one(&input, 10));
-        assert_eq!(1656, part_one(&input, 100));
+        ]);
+        assert_eq!(0, count_flashes(&input, 1));
+        assert_eq!(204, count_flashes(&input, 10));
+        assert_eq!(1656, count_flashes(&input, 100));
     }
 
     #[test]
     fn test_part_two() 
     {
-        let input = vec![
+        let input = Grid::from_rows(vec![
             vec![5,4,8,3,1,4,3,2,2,3],
             vec![2,7,4,5,8,5,4,7,1,1],
             vec![5,2,6,4,5,5,6,1,7,3],
@@ -134,7 +149,7 @@ mod tests {
             vec![6,8,8,2,8,8,1,1,3,4],
             vec![4,8,4,6,8,4,8,5,5,4],
             vec![5,2,8,3,7,5,1,5,2,6]
-        ];
-        assert_eq!(195, part_two(&input));
+        ]);
+        assert_eq!(195, first_synchronised_flash(&input));
     }
 }
\ No newline at end of file
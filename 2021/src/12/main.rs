@@ -1,35 +1,65 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
 use std::collections::HashMap;
+use aoc_common::{ParseError, ParseResult, Solution};
 
 fn main() {
-    if let Ok(lines) = read_lines("src/12/input.txt") {
-        let input:Vec<String> = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap())
-            .collect();
-
-        println!("Part 1: {}", part_one(&input));
-        println!("Part 2: {}", part_two(&input));
-    }
+    aoc_common::run_day::<Day>(2021, 12);
 }
 
+pub struct Day;
+
+impl Solution for Day
+{
+    type Parsed = Vec<String>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> ParseResult<Vec<String>>
+    {
+        return parse(input);
+    }
+
+    fn part_one(input: &Vec<String>) -> usize
+    {
+        return get_paths(&get_mappings(input), false).len()
+    }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+    fn part_two(input: &Vec<String>) -> usize
+    {
+        return get_paths(&get_mappings(input), true).len();
+    }
 }
 
+/// The tunnels between caves, such as `start-A`.  Two big caves can't be
+/// joined, or there would be endless paths between them.
+fn parse(input: &str) -> ParseResult<Vec<String>>
+{
+    for line in aoc_common::lines(input)
+    {
+        let (a, b) = line.split_once('-')
+            .ok_or_else(|| ParseError::at_slice(input, line, "expected '<cave>-<cave>'"))?;
+        for cave in [a, b]
+        {
+            if cave.is_empty() || !cave.chars().all(|c| c.is_ascii_alphabetic())
+            {
+                return Err(ParseError::at_slice(input, cave, "expected a cave name"));
+            }
+        }
+        if !is_small_cave(&a.to_string()) && !is_small_cave(&b.to_string())
+        {
+            return Err(ParseError::at_slice(input, line, "expected at most one big cave in a tunnel"));
+        }
+    }
+    return Ok(aoc_common::owned_lines(input));
+}
+
+
 fn get_mappings(input: &Vec<String>) -> HashMap<String, Vec<String>>
 {
     let mut mappings = HashMap::new();
     for line in input
     {
-        let mut parts = line.split("-");
-        let key = String::from(parts.next().unwrap());
-        let val = String::from(parts.next().unwrap());
+        let (key, val) = line.split_once('-').unwrap();
+        let (key, val) = (String::from(key), String::from(val));
 
         if key != "end" && val != "start"
         {
@@ -70,7 +100,7 @@ fn has_dupe_small_caves(route: &Vec<&String>) -> bool
 fn get_paths(mappings: &HashMap<String, Vec<String>>, part_two: bool) -> Vec<Vec<&String>>
 {
     let mut unfinished_routes:Vec<Vec<&String>> = Vec::new();
-    for init in mappings.get("start").unwrap()
+    for init in mappings.get("start").into_iter().flatten()
     {
         unfinished_routes.push(vec![init]);
     }
@@ -82,9 +112,7 @@ fn get_paths(mappings: &HashMap<String, Vec<String>>, part_two: bool) -> Vec<Vec
         for r in unfinished_routes
         {
             let current = r[r.len() - 1];
-            let possibles = mappings.get(current).unwrap();
-
-            for poss in possibles
+            for poss in mappings.get(current).into_iter().flatten()
             {
                 if poss == "end"
                 {
@@ -112,16 +140,6 @@ fn get_paths(mappings: &HashMap<String, Vec<String>>, part_two: bool) -> Vec<Vec
     return routes;
 }
 
-fn part_one(input: &Vec<String>) -> usize
-{
-    return get_paths(&get_mappings(input), false).len()
-}
-
-fn part_two(input: &Vec<String>) -> usize
-{
-    return get_paths(&get_mappings(input), true).len();
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -138,7 +156,7 @@ mod tests {
             String::from("A-end"),
             String::from("b-end")
         ];
-        assert_eq!(10, part_one(&input));
+        assert_eq!(10, Day::part_one(&Day::parse(&input.join("\n")).unwrap()));
     }
 
     #[test]
@@ -156,7 +174,7 @@ mod tests {
             String::from("kj-HN"),
             String::from("kj-dc")
         ];
-        assert_eq!(19, part_one(&input));
+        assert_eq!(19, Day::part_one(&Day::parse(&input.join("\n")).unwrap()));
     }
 
     #[test]
@@ -182,7 +200,7 @@ mod tests {
             String::from("pj-fs"),
             String::from("start-RW")
         ];
-        assert_eq!(226, part_one(&input));
+        assert_eq!(226, Day::part_one(&Day::parse(&input.join("\n")).unwrap()));
     }
 
     #[test]
@@ -197,7 +215,7 @@ mod tests {
             String::from("A-end"),
             String::from("b-end")
         ];
-        assert_eq!(36, part_two(&input));
+        assert_eq!(36, Day::part_two(&Day::parse(&input.join("\n")).unwrap()));
     }
 
     #[test]
@@ -215,7 +233,7 @@ mod tests {
             String::from("kj-HN"),
             String::from("kj-dc")
         ];
-        assert_eq!(103, part_two(&input));
+        assert_eq!(103, Day::part_two(&Day::parse(&input.join("\n")).unwrap()));
     }
 
     #[test]
@@ -241,6 +259,17 @@ mod tests {
             String::from("pj-fs"),
             String::from("start-RW")
         ];
-        assert_eq!(3509, part_two(&input));
+        assert_eq!(3509, Day::part_two(&Day::parse(&input.join("\n")).unwrap()));
+    }
+
+    #[test]
+    fn test_parse_errors()
+    {
+        assert_eq!(0, Day::part_one(&Day::parse("start-A\nb-end\n").unwrap()));
+        let err = Day::parse("start-A\nA-B\n").unwrap_err();
+        assert_eq!((2, 1), (err.line, err.column));
+        let err = Day::parse("start-A\nA-\n").unwrap_err();
+        assert_eq!((2, 3), (err.line, err.column));
+        assert!(Day::parse("start-A\nA end\n").is_err());
     }
-}
\ No newline at end of file
+}
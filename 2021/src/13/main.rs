@@ -1,39 +1,69 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use aoc_common::{ParseError, ParseResult, Solution};
 
 fn main() {
-    if let Ok(lines) = read_lines("src/13/input.txt") {
-        let mut itr = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap());
-        let mut state = Vec::new();
-        for line in &mut itr
-        {
-            if line == ""
-            {
-                break;
-            }
-            let mut s = line.split(",");
-            state.push((s.next().unwrap().parse::<i32>().unwrap(), s.next().unwrap().parse::<i32>().unwrap()))
-        }
-        let folds = itr.map(|s| {
-                let mut i = s[11..].split("=");
-                return (i.next().unwrap().chars().nth(0).unwrap(), i.next().unwrap().parse::<i32>().unwrap());
-            })
-            .collect();
+    aoc_common::run_day::<Day>(2021, 13);
+}
 
-        println!("Part 1: {}", part_one(&state, &folds));
-        let s = part_two(&state, &folds);
-        print_state(&s);
+pub struct Day;
+
+impl Solution for Day
+{
+    type Parsed = (Vec<(i32, i32)>, Vec<(char, i32)>);
+    type PartOne = usize;
+    type PartTwo = String;
+
+    fn parse(input: &str) -> ParseResult<(Vec<(i32, i32)>, Vec<(char, i32)>)>
+    {
+        return parse(input);
     }
-}
 
+    fn part_one(input: &(Vec<(i32, i32)>, Vec<(char, i32)>)) -> usize
+    {
+        let (state, folds) = input;
+        return count_after_first_fold(&state, &folds);
+    }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+    fn part_two(input: &(Vec<(i32, i32)>, Vec<(char, i32)>)) -> String
+    {
+        let (state, folds) = input;
+        return render_state(&fold_all(&state, &folds));
+    }
+}
+
+/// The dots, as `x,y` lines, then after a blank line at least one fold such
+/// as `fold along y=7`.
+fn parse(input: &str) -> ParseResult<(Vec<(i32, i32)>, Vec<(char, i32)>)>
+{
+    let mut itr = aoc_common::lines(input);
+    let mut state = Vec::new();
+    for line in &mut itr
+    {
+        if line == ""
+        {
+            break;
+        }
+        let dot = line.split_once(',')
+            .and_then(|(x, y)| Some((x.parse::<u16>().ok()?, y.parse::<u16>().ok()?)))
+            .ok_or_else(|| ParseError::at_slice(input, line, "expected a dot such as '6,10'"))?;
+        state.push((dot.0 as i32, dot.1 as i32));
+    }
+    let folds = itr.map(|line| {
+            let fold = line.strip_prefix("fold along ")
+                .and_then(|f| f.split_once('='))
+                .and_then(|(axis, at)| Some((axis, at.parse::<u16>().ok()?)));
+            return match fold
+            {
+                Some(("x", at)) => Ok(('x', at as i32)),
+                Some(("y", at)) => Ok(('y', at as i32)),
+                _ => Err(ParseError::at_slice(input, line, "expected a fold such as 'fold along y=7'"))
+            };
+        })
+        .collect::<ParseResult<Vec<(char, i32)>>>()?;
+    if folds.is_empty()
+    {
+        return Err(ParseError::at(input, input.len(), "expected at least one fold"));
+    }
+    return Ok((state, folds));
 }
 
 fn fold(state: &mut Vec<(i32, i32)>, along: &(char, i32))
@@ -62,7 +92,7 @@ fn fold(state: &mut Vec<(i32, i32)>, along: &(char, i32))
     }
 }
 
-fn part_one(state: &Vec<(i32, i32)>, folds: &Vec<(char, i32)>) -> usize
+fn count_after_first_fold(state: &Vec<(i32, i32)>, folds: &Vec<(char, i32)>) -> usize
 {
     let mut board = state.clone();
     fold(&mut board, &folds[0]);
@@ -71,7 +101,7 @@ fn part_one(state: &Vec<(i32, i32)>, folds: &Vec<(char, i32)>) -> usize
     return board.len();
 }
 
-fn part_two(state: &Vec<(i32, i32)>, folds: &Vec<(char, i32)>) -> Vec<(i32, i32)>
+fn fold_all(state: &Vec<(i32, i32)>, folds: &Vec<(char, i32)>) -> Vec<(i32, i32)>
 {
     let mut board = state.clone();
     for f in folds
@@ -83,31 +113,33 @@ fn part_two(state: &Vec<(i32, i32)>, folds: &Vec<(char, i32)>) -> Vec<(i32, i32)
     return board;
 }
 
-fn print_state(state: &Vec<(i32, i32)>)
+fn render_state(state: &Vec<(i32, i32)>) -> String
 {
-    println!("state: {:?}", state);
     let max_x = state.iter().map(|x| x.0).fold(0, |a,b| i32::max(a,b));
     let max_y = state.iter().map(|x| x.1).fold(0, |a,b| i32::max(a,b));
 
+    let mut result = String::new();
     for y in 0..=max_y
     {
+        if y > 0
+        {
+            result.push('\n');
+        }
         for x in 0..=max_x
         {
             if state.contains(&(x, y))
             {
-                print!("#");
+                result.push('#');
             }
             else
             {
-                print!(".");
+                result.push('.');
             }
         }
-        println!("");
     }
+    return result;
 }
 
-
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -139,6 +171,18 @@ mod tests {
             ('y', 7),
             ('x', 5)
         ];
-        assert_eq!(17, part_one(&state, &folds));
+        assert_eq!(17, count_after_first_fold(&state, &folds));
+    }
+
+    #[test]
+    fn test_parse_errors()
+    {
+        let input = "6,10\n0,14\n\nfold along y=7\nfold along z=5\n";
+        assert_eq!(vec![('y', 7), ('x', 5)], parse(&input.replace("z", "x")).unwrap().1);
+        let err = parse(input).unwrap_err();
+        assert_eq!((5, 1), (err.line, err.column));
+        let err = parse(&input.replace("0,14", "0;14")).unwrap_err();
+        assert_eq!((2, 1), (err.line, err.column));
+        assert!(parse("6,10\n0,14\n").is_err());
     }
-}
\ No newline at end of file
+}
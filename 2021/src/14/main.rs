@@ -1,46 +1,87 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use aoc_common::{ParseError, ParseResult, Solution};
 
 fn main() {
-    if let Ok(lines) = read_lines("src/14/input.txt") {
-        let mut itr = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap());
-        let init = itr.next().unwrap();
-        itr.next();
-
-        let mappings = get_mappings(&itr.collect());
-        println!("Part 1: {}", parts(&init, &mappings, 10));
-        println!("Part 2: {}", parts(&init, &mappings, 40));
+    aoc_common::run_day::<Day>(2021, 14);
+}
+
+pub struct Day;
+
+impl Solution for Day
+{
+    type Parsed = (String, Vec<Vec<usize>>);
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> ParseResult<(String, Vec<Vec<usize>>)>
+    {
+        return parse(input);
+    }
+
+    fn part_one(input: &(String, Vec<Vec<usize>>)) -> usize
+    {
+        let (init, mappings) = input;
+        return parts(&init, &mappings, 10);
+    }
+
+    fn part_two(input: &(String, Vec<Vec<usize>>)) -> usize
+    {
+        let (init, mappings) = input;
+        return parts(&init, &mappings, 40);
     }
 }
 
+/// The polymer template, then after a blank line the insertion rules such as
+/// `CH -> B`.  Elements are capital letters.
+fn parse(input: &str) -> ParseResult<(String, Vec<Vec<usize>>)>
+{
+    let lines: Vec<&str> = aoc_common::lines(input).collect();
+    let init = lines.first().copied().unwrap_or("");
+    if init.is_empty()
+    {
+        return Err(ParseError::at(input, 0, "expected a polymer template"));
+    }
+    element_indices(input, init)?;
+    if lines.get(1).map_or(false, |l| !l.is_empty())
+    {
+        return Err(ParseError::at_line(input, 1, 0, "expected a blank line after the template"));
+    }
+    let mappings = get_mappings(input, lines.get(2..).unwrap_or(&[]))?;
+    return Ok((init.to_string(), mappings));
+}
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+/// The index of each element in `elements`, a slice of `input`.
+fn element_indices(input: &str, elements: &str) -> ParseResult<Vec<usize>>
+{
+    return elements.char_indices()
+        .map(|(i, c)| match c
+        {
+            'A'..='Z' => Ok(c.to_digit(36).unwrap() as usize),
+            _ => Err(ParseError::at_slice(input, &elements[i..], "expected an element from 'A' to 'Z'"))
+        })
+        .collect();
 }
 
-fn get_mappings(input: &Vec<String>) -> Vec<Vec<usize>>
+fn get_mappings(input: &str, lines: &[&str]) -> ParseResult<Vec<Vec<usize>>>
 {
     let mut mappings = Vec::new();
     for _i in 0..36
     {
         mappings.push(vec![0; 36]);
     }
-    for line in input
+
+    for line in lines
     {
-        let mut i = line.split(" -> ");
-        let key = i.next().unwrap();
-        let val = i.next().unwrap().chars().nth(0).unwrap();
-        
-        let idx_1 = key.chars().nth(0).unwrap().to_digit(36).unwrap() as usize;
-        let idx_2 = key.chars().nth(1).unwrap().to_digit(36).unwrap() as usize;
-        mappings[idx_1][idx_2] = val.to_digit(36).unwrap() as usize;
+        let (key, val) = line.split_once(" -> ")
+            .ok_or_else(|| ParseError::at_slice(input, line, "expected a rule such as 'CH -> B'"))?;
+        let key = element_indices(input, key)?;
+        let val = element_indices(input, val)?;
+        if key.len() != 2 || val.len() != 1
+        {
+            return Err(ParseError::at_slice(input, line, "expected a pair of elements and the one to insert"));
+        }
+        mappings[key[0]][key[1]] = val[0];
     }
-    return mappings;
+    return Ok(mappings);
 }
 
 #[allow(dead_code)]
@@ -125,7 +166,9 @@ mod tests {
     #[test]
     fn test_part_one() 
     {
-        let mappings = get_mappings(&vec![
+        let (init, mappings) = parse(&vec![
+            String::from("NNCB"),
+            String::from(""),
             String::from("CH -> B"),
             String::from("HH -> N"),
             String::from("CB -> H"),
@@ -142,15 +185,16 @@ mod tests {
             String::from("BC -> B"),
             String::from("CC -> N"),
             String::from("CN -> C")
-        ]);
-        let init = String::from("NNCB");
+        ].join("\n")).unwrap();
 
         assert_eq!(1588, parts(&init, &mappings, 10));
     }
     #[test]
     fn test_part_two() 
     {
-        let mappings = get_mappings(&vec![
+        let (init, mappings) = parse(&vec![
+            String::from("NNCB"),
+            String::from(""),
             String::from("CH -> B"),
             String::from("HH -> N"),
             String::from("CB -> H"),
@@ -167,9 +211,22 @@ mod tests {
             String::from("BC -> B"),
             String::from("CC -> N"),
             String::from("CN -> C")
-        ]);
-        let init = String::from("NNCB");
+        ].join("\n")).unwrap();
 
         assert_eq!(2188189693529, parts(&init, &mappings, 40));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_errors()
+    {
+        let input = "NNCB\n\nCH -> B\nHH -> n\n";
+        assert_eq!("NNCB", parse(&input.replace("n", "N")).unwrap().0);
+        let err = parse(input).unwrap_err();
+        assert_eq!((4, 7), (err.line, err.column));
+        let err = parse(&input.replace("CH -> B", "CH > B")).unwrap_err();
+        assert_eq!((3, 1), (err.line, err.column));
+        let err = parse(&input.replace("CH", "CHH")).unwrap_err();
+        assert_eq!((3, 1), (err.line, err.column));
+        assert!(parse("").is_err());
+    }
+}
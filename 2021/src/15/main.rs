@@ -1,155 +1,58 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use aoc_common::{Grid, ParseResult, Solution};
 
 fn main() {
-    if let Ok(lines) = read_lines("src/15/input.txt") {
-        let map:Vec<Vec<u8>> = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap())
-            .map(|r| r.chars().map(|v| v.to_string().parse::<u8>().unwrap()).collect())
-            .collect();
-
-        println!("Part 1: {}", part_one(&map));
-        println!("Part 2: {}", part_two(&map));
-    }
+    aoc_common::run_day::<Day>(2021, 15);
 }
 
+pub struct Day;
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
-
-const MAX:usize = 9999999999;
-
-fn solve(map: &Vec<Vec<u8>>) -> usize
+impl Solution for Day
 {
-    let mut weights = Vec::new();
-    for _i in 0..map.len()
+    type Parsed = Grid<u8>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> ParseResult<Grid<u8>>
     {
-        weights.push(vec![MAX; map[0].len()]);
+        return Grid::digits(input);
     }
-    weights[0][0] = 0;
 
-    let max_y = map.len();
-    let max_x = map[0].len();
-    let mut step = 0;
+    fn part_one(map: &Grid<u8>) -> usize
+    {
+        return solve(map);
+    }
 
-    loop
+    fn part_two(map: &Grid<u8>) -> usize
     {
-        let mut changed = false;
-        for y in 0..max_y
-        {
-            for x in 0..max_x
+        let max_x = map.width();
+        let max_y = map.height();
+        let new_map = Grid::from_fn(max_x * 5, max_y * 5, |(x, y)| {
+            let a = map[(x % max_x, y % max_y)] + (x / max_x + y / max_y) as u8;
+            if a > 9
             {
-                let risk = map[y][x] as usize;
-                let current = weights[y][x];
-
-                let mut new_weight = MAX;
-                if y+1 < max_y
-                {
-                    new_weight = usize::min(new_weight, risk + weights[y+1][x]);
-                }
-                if y > 0
-                {
-                    new_weight = usize::min(new_weight, risk + weights[y-1][x]);
-                }
-                if x+1 < max_x
-                {
-                    new_weight = usize::min(new_weight, risk + weights[y][x+1]);
-                }
-                if x > 0
-                {
-                    new_weight = usize::min(new_weight, risk + weights[y][x-1]);
-                }
-
-                if new_weight < current
-                {
-                    weights[y][x] = new_weight;
-                    changed = true;
-                }
+                return a-9;
             }
-        }
+            else
+            {
+                return a;
+            }
+        });
 
-        step = step + 1;
-        if !changed
-        {
-            break;
-        }
+        return solve(&new_map);
     }
-    println!("converged after {} steps", step);
-    // for y in 0..max_y
-    // {
-    //     for x in 0..max_x
-    //     {
-    //         print!("{} ", weights[y][x]);
-    //     }
-    //     println!("");
-    // }
-
-    return weights[max_y-1][max_x-1];
-}
-
-
-fn part_one(map: &Vec<Vec<u8>>) -> usize
-{
-    return solve(map);
 }
 
-fn part_two(map: &Vec<Vec<u8>>) -> usize
+fn solve(map: &Grid<u8>) -> usize
 {
-    let mut new_map = Vec::new();
-    let max_y = map.len();
-    let max_x = map[0].len();
-
-    for y in 0..max_y
-    {
-        let mut row = Vec::new();
-        for step in 0..5
-        {
-            for x in 0..max_x
-            {
-                let a = map[y][x] + step;
-                if a > 9
-                {
-                    row.push(a-9);
-                }
-                else
-                {
-                    row.push(a);
-                }
-            }
-        }
-        new_map.push(row);
-    }
-    
-    for step in 1..5
-    {
-        for y in 0..max_y
-        {
-            let row = new_map[y].iter()
-                .map(|x| {
-                    let a = x + step;
-                    if a > 9
-                    {
-                        return a-9;
-                    }
-                    else
-                    {
-                        return a;
-                    }
-                })
-                .collect();
-            new_map.push(row);
-        }
-    }
-
-    return solve(&new_map);
+    let end = (map.width()-1, map.height()-1);
+    let found = aoc_common::astar(
+        (0, 0),
+        |&pos| map.neighbours4(pos).map(|n| (n, map[n] as usize)),
+        |&(x, y)| (end.0 - x) + (end.1 - y),
+        |&pos| pos == end);
+    return found.expect("the bottom right is always reachable").cost;
 }
 
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -157,38 +60,34 @@ mod tests {
     #[test]
     fn test_part_one() 
     {
-        let map = vec![
-            vec![1,1,6,3,7,5,1,7,4,2],
-            vec![1,3,8,1,3,7,3,6,7,2],
-            vec![2,1,3,6,5,1,1,3,2,8],
-            vec![3,6,9,4,9,3,1,5,6,9],
-            vec![7,4,6,3,4,1,7,1,1,1],
-            vec![1,3,1,9,1,2,8,1,3,7],
-            vec![1,3,5,9,9,1,2,4,2,1],
-            vec![3,1,2,5,4,2,1,6,3,9],
-            vec![1,2,9,3,1,3,8,5,2,1],
-            vec![2,3,1,1,9,4,4,5,8,1]
-        ];
-
-        assert_eq!(40, part_one(&map));
+        let map = "1163751742
+1381373672
+2136511328
+3694931569
+7463417111
+1319128137
+1359912421
+3125421639
+1293138521
+2311944581";
+
+        assert_eq!(40, Day::part_one(&Day::parse(map).unwrap()));
     }
 
     #[test]
     fn test_part_two() 
     {
-        let map = vec![
-            vec![1,1,6,3,7,5,1,7,4,2],
-            vec![1,3,8,1,3,7,3,6,7,2],
-            vec![2,1,3,6,5,1,1,3,2,8],
-            vec![3,6,9,4,9,3,1,5,6,9],
-            vec![7,4,6,3,4,1,7,1,1,1],
-            vec![1,3,1,9,1,2,8,1,3,7],
-            vec![1,3,5,9,9,1,2,4,2,1],
-            vec![3,1,2,5,4,2,1,6,3,9],
-            vec![1,2,9,3,1,3,8,5,2,1],
-            vec![2,3,1,1,9,4,4,5,8,1]
-        ];
-
-        assert_eq!(315, part_two(&map));
+        let map = "1163751742
+1381373672
+2136511328
+3694931569
+7463417111
+1319128137
+1359912421
+3125421639
+1293138521
+2311944581";
+
+        assert_eq!(315, Day::part_two(&Day::parse(map).unwrap()));
     }
 }
\ No newline at end of file
@@ -1,93 +1,61 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use aoc_common::{ParseError, ParseResult, Solution};
 
 fn main() {
-    if let Ok(lines) = read_lines("src/16/input.txt") {
-        let input:String = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap())
-            .find(|_x| true)
-            .unwrap();
-
-        println!("Part 1: {}", part_one(&input));
-        println!("Part 2: {}", part_two(&input));
-    }
+    aoc_common::run_day::<Day>(2021, 16);
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
+pub struct Day;
 
-fn expand(input: &String) -> Vec<u8>
+impl Solution for Day
 {
-    let mut result = Vec::new();
-    for c in input.chars()
+    type Parsed = Instruction;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> ParseResult<Instruction>
     {
-        let mut app:Vec<u8> = match c {
-            '0' => vec![0,0,0,0],
-            '1' => vec![0,0,0,1],
-            '2' => vec![0,0,1,0],
-            '3' => vec![0,0,1,1],
-            '4' => vec![0,1,0,0],
-            '5' => vec![0,1,0,1],
-            '6' => vec![0,1,1,0],
-            '7' => vec![0,1,1,1],
-            '8' => vec![1,0,0,0],
-            '9' => vec![1,0,0,1],
-            'A' => vec![1,0,1,0],
-            'B' => vec![1,0,1,1],
-            'C' => vec![1,1,0,0],
-            'D' => vec![1,1,0,1],
-            'E' => vec![1,1,1,0],
-            'F' => vec![1,1,1,1],
-            _ => panic!("bad input character {}", c)
-        };
-        result.append(&mut app);
+        let bits = expand(input.trim()).map_err(|x| ParseError::at_line(input, 0, x, "expected a hex digit"))?;
+        let mut stream = bits.iter();
+        return parse_instruction(&mut stream)
+            .map(|i| i.0)
+            .map_err(|e| ParseError::at_line(input, 0, (bits.len() - stream.len()) / 4, e));
     }
-    return result;
-}
 
-fn read_3(stream: &mut std::slice::Iter<'_, u8>) -> u8
-{
-    let mut val:u8 = 0;
-    for i in 0..3
+    fn part_one(input: &Instruction) -> usize
     {
-        val = val + (*stream.next().unwrap() << (2 - i));
+        return sum_versions(input);
     }
-    return val;
-}
 
-fn read_5(stream: &mut std::slice::Iter<'_, u8>) -> u8
-{
-    let mut val:u8 = 0;
-    for i in 0..5
+    fn part_two(input: &Instruction) -> usize
     {
-        val = val + (*stream.next().unwrap() << (4 - i));
+        return evaluate_instruction(input);
     }
-    return val;
 }
 
-fn read_11(stream: &mut std::slice::Iter<'_, u8>) -> u32
+/// The bits of the hex digits, or the column of the first that isn't one.
+fn expand(input: &str) -> Result<Vec<u8>, usize>
 {
-    let mut val:u32 = 0;
-    for i in 0..11
+    let mut result = Vec::new();
+    for (x, c) in input.chars().enumerate()
     {
-        val = val + ((*stream.next().unwrap() as u32) << (10 - i));
+        let digit = c.to_digit(16).filter(|_| !c.is_ascii_lowercase()).ok_or(x)?;
+        for i in (0..4).rev()
+        {
+            result.push(((digit >> i) & 1) as u8);
+        }
     }
-    return val;
+    return Ok(result);
 }
 
-fn read_15(stream: &mut std::slice::Iter<'_, u8>) -> u32
+fn read(stream: &mut std::slice::Iter<'_, u8>, bits: usize) -> Result<u32, String>
 {
     let mut val:u32 = 0;
-    for i in 0..15
+    for _ in 0..bits
     {
-        val = val + ((*stream.next().unwrap() as u32) << (14 - i));
+        let bit = stream.next().ok_or("the packet ends early")?;
+        val = (val << 1) + *bit as u32;
     }
-    return val;
+    return Ok(val);
 }
 
 #[derive(Debug)]
@@ -99,10 +67,10 @@ pub struct Instruction
     sub_packets: Vec<Instruction>
 }
 
-fn parse_instruction(stream: &mut std::slice::Iter<'_, u8>) -> (Instruction, usize)
+fn parse_instruction(stream: &mut std::slice::Iter<'_, u8>) -> Result<(Instruction, usize), String>
 {
-    let ver = read_3(stream);
-    let id = read_3(stream);
+    let ver = read(stream, 3)? as u8;
+    let id = read(stream, 3)? as u8;
     let sub_packets;
     let mut literal = 0;
     let mut bits_read = 6;
@@ -111,31 +79,31 @@ fn parse_instruction(stream: &mut std::slice::Iter<'_, u8>) -> (Instruction, usi
     {
         sub_packets = Vec::new();
 
-        let mut bit = read_5(stream);
+        let mut bit = read(stream, 5)?;
         literal = literal + (bit & 15) as usize;
         bits_read = bits_read + 5;
 
         while bit & 16 > 0
         {
-            bit = read_5(stream);
-            literal = (literal << 4) + (bit & 15) as usize;
+            bit = read(stream, 5)?;
+            literal = literal.checked_mul(16).ok_or("the literal is too large")? + (bit & 15) as usize;
             bits_read = bits_read + 5;
         }
     }
     else
     {
-        let l = *stream.next().unwrap();
+        let l = read(stream, 1)?;
         bits_read = bits_read + 1;
         if l == 0
         {
-            let mut num_sub_bits = read_15(stream) as usize;
+            let mut num_sub_bits = read(stream, 15)? as usize;
             bits_read = bits_read + 15;
             
             let mut packets = Vec::new();
             while num_sub_bits > 0
             {
-                let result = parse_instruction(stream);
-                num_sub_bits = num_sub_bits - result.1;
+                let result = parse_instruction(stream)?;
+                num_sub_bits = num_sub_bits.checked_sub(result.1).ok_or("the sub-packets overrun their length")?;
                 bits_read = bits_read + result.1;
                 packets.push(result.0);
             }
@@ -144,24 +112,32 @@ fn parse_instruction(stream: &mut std::slice::Iter<'_, u8>) -> (Instruction, usi
         }
         else
         {
-            let num_sub_packets = read_11(stream);
+            let num_sub_packets = read(stream, 11)?;
             bits_read = bits_read + 11;
-            sub_packets = (0..num_sub_packets)
-                .map(|_x| parse_instruction(stream))
-                .map(|x| {
-                    bits_read = bits_read + x.1;
-                    return x.0;
-                })
-                .collect();
+            let mut packets = Vec::new();
+            for _ in 0..num_sub_packets
+            {
+                let result = parse_instruction(stream)?;
+                bits_read = bits_read + result.1;
+                packets.push(result.0);
+            }
+            sub_packets = packets;
+        }
+
+        match (id, sub_packets.len())
+        {
+            (0..=3, 0) => return Err(format!("operator {} has no sub-packets", id)),
+            (5..=7, n) if n != 2 => return Err(format!("comparison {} needs 2 sub-packets, got {}", id, n)),
+            _ => {}
         }
     }
 
-    return (Instruction{
+    return Ok((Instruction{
         version: ver,
         id: id,
         sub_packets: sub_packets,
         literal: literal
-    }, bits_read);
+    }, bits_read));
 }
 
 fn sum_versions(instr: &Instruction) -> usize
@@ -174,13 +150,6 @@ fn sum_versions(instr: &Instruction) -> usize
     return sum;
 }
 
-fn part_one(input: &String) -> usize
-{
-    let bits = expand(input);
-    let i:Instruction = parse_instruction(&mut bits.iter()).0;
-    return sum_versions(&i);
-}
-
 fn bool_to_int(val: bool) -> usize
 {
     if val
@@ -202,23 +171,15 @@ fn evaluate_instruction(instr: &Instruction) -> usize
     {
         0 => sub_values.iter().fold(0, |a,b| a + b),
         1 => sub_values.iter().fold(1, |a,b| a * b),
-        2 => sub_values.iter().fold(9999999, |a,b| usize::min(a, *b)),
-        3 => sub_values.iter().fold(0, |a,b| usize::max(a, *b)),
+        2 => *sub_values.iter().min().unwrap(),
+        3 => *sub_values.iter().max().unwrap(),
         4 => instr.literal,
         5 => bool_to_int(sub_values[0] > sub_values[1]),
         6 => bool_to_int(sub_values[0] < sub_values[1]),
-        7 => bool_to_int(sub_values[0] == sub_values[1]),
-        _ => panic!("unsupported instruction id {}", instr.id)
+        _ => bool_to_int(sub_values[0] == sub_values[1])
     };
 }
 
-fn part_two(input: &String) -> usize
-{
-    let bits = expand(input);
-    let i:Instruction = parse_instruction(&mut bits.iter()).0;
-    return evaluate_instruction(&i);
-}
-
 
 #[cfg(test)]
 mod tests {
@@ -227,77 +188,88 @@ mod tests {
     #[test]
     fn test_part_one1() 
     {
-        assert_eq!(6, part_one(&String::from("D2FE28")));
+        assert_eq!(6, Day::part_one(&Day::parse(&String::from("D2FE28")).unwrap()));
     }
     #[test]
     fn test_part_one2() 
     {
-        assert_eq!(9, part_one(&String::from("38006F45291200")));
+        assert_eq!(9, Day::part_one(&Day::parse(&String::from("38006F45291200")).unwrap()));
     }
     #[test]
     fn test_part_one3() 
     {
-        assert_eq!(14, part_one(&String::from("EE00D40C823060")));
+        assert_eq!(14, Day::part_one(&Day::parse(&String::from("EE00D40C823060")).unwrap()));
     }
     #[test]
     fn test_part_one4() 
     {
-        assert_eq!(16, part_one(&String::from("8A004A801A8002F478")));
+        assert_eq!(16, Day::part_one(&Day::parse(&String::from("8A004A801A8002F478")).unwrap()));
     }
     #[test]
     fn test_part_one5() 
     {
-        assert_eq!(12, part_one(&String::from("620080001611562C8802118E34")));
+        assert_eq!(12, Day::part_one(&Day::parse(&String::from("620080001611562C8802118E34")).unwrap()));
     }
     #[test]
     fn test_part_one6() 
     {
-        assert_eq!(23, part_one(&String::from("C0015000016115A2E0802F182340")));
+        assert_eq!(23, Day::part_one(&Day::parse(&String::from("C0015000016115A2E0802F182340")).unwrap()));
     }
     #[test]
     fn test_part_one7() 
     {
-        assert_eq!(31, part_one(&String::from("A0016C880162017C3686B18A3D4780")));
+        assert_eq!(31, Day::part_one(&Day::parse(&String::from("A0016C880162017C3686B18A3D4780")).unwrap()));
     }
 
     #[test]
     fn test_part_two1() 
     {
-        assert_eq!(3, part_two(&String::from("C200B40A82")));
+        assert_eq!(3, Day::part_two(&Day::parse(&String::from("C200B40A82")).unwrap()));
     }
     #[test]
     fn test_part_two2() 
     {
-        assert_eq!(54, part_two(&String::from("04005AC33890")));
+        assert_eq!(54, Day::part_two(&Day::parse(&String::from("04005AC33890")).unwrap()));
     }
     #[test]
     fn test_part_two3() 
     {
-        assert_eq!(7, part_two(&String::from("880086C3E88112")));
+        assert_eq!(7, Day::part_two(&Day::parse(&String::from("880086C3E88112")).unwrap()));
     }
     #[test]
     fn test_part_two4() 
     {
-        assert_eq!(9, part_two(&String::from("CE00C43D881120")));
+        assert_eq!(9, Day::part_two(&Day::parse(&String::from("CE00C43D881120")).unwrap()));
     }
     #[test]
     fn test_part_two5() 
     {
-        assert_eq!(1, part_two(&String::from("D8005AC2A8F0")));
+        assert_eq!(1, Day::part_two(&Day::parse(&String::from("D8005AC2A8F0")).unwrap()));
     }
     #[test]
     fn test_part_two6() 
     {
-        assert_eq!(0, part_two(&String::from("F600BC2D8F")));
+        assert_eq!(0, Day::part_two(&Day::parse(&String::from("F600BC2D8F")).unwrap()));
     }
     #[test]
     fn test_part_two7() 
     {
-        assert_eq!(0, part_two(&String::from("9C005AC2F8F0")));
+        assert_eq!(0, Day::part_two(&Day::parse(&String::from("9C005AC2F8F0")).unwrap()));
     }
     #[test]
     fn test_part_two8() 
     {
-        assert_eq!(1, part_two(&String::from("9C0141080250320F1802104A08")));
+        assert_eq!(1, Day::part_two(&Day::parse(&String::from("9C0141080250320F1802104A08")).unwrap()));
     }
-}
\ No newline at end of file
+    #[test]
+    fn test_parse_errors()
+    {
+        let err = Day::parse("D2FG28").unwrap_err();
+        assert_eq!((1, 4, "expected a hex digit"), (err.line, err.column, err.message.as_str()));
+        // A literal cut off in its second group.
+        let err = Day::parse("D2F").unwrap_err();
+        assert_eq!((4, "the packet ends early"), (err.column, err.message.as_str()));
+        // A less-than packet with one sub-packet.
+        assert_eq!("comparison 6 needs 2 sub-packets, got 1", Day::parse("DA004408").unwrap_err().message);
+    }
+}
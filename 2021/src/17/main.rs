@@ -1,18 +1,64 @@
-
-use num_integer::binomial;
+use aoc_common::{ParseError, ParseResult, Solution};
 
 fn main() {
-    //target area: x=156..202, y=-110..-69
-    println!("Part 1: {}", part_one(-110));
-    println!("Part 2: {}", part_two(202, 156, -69, -110));
+    aoc_common::run_day::<Day>(2021, 17);
 }
 
-fn part_one(min_y: i64) -> i64
+pub struct Day;
+
+impl Solution for Day
 {
-    return binomial(-min_y, 2)
+    type Parsed = (i64, i64, i64, i64);
+    type PartOne = i64;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> ParseResult<(i64, i64, i64, i64)>
+    {
+        return parse(input);
+    }
+
+    fn part_one(input: &(i64, i64, i64, i64)) -> i64
+    {
+        let (_, _, _, min_y) = *input;
+        return highest_y(min_y);
+    }
+
+    fn part_two(input: &(i64, i64, i64, i64)) -> usize
+    {
+        let (max_x, min_x, max_y, min_y) = *input;
+        return count_velocities(max_x, min_x, max_y, min_y);
+    }
 }
 
-fn part_two(max_x: i64, min_x: i64, max_y: i64, min_y: i64) -> usize
+// target area: x=156..202, y=-110..-69
+// The solution assumes the target is ahead in x and below the launcher in y.
+fn parse(input: &str) -> ParseResult<(i64, i64, i64, i64)>
+{
+    let line = input.trim();
+    let ranges = line.strip_prefix("target area: x=")
+        .and_then(|r| r.split_once(", y="))
+        .and_then(|(x, y)| Some((parse_range(x)?, parse_range(y)?)));
+    let ((min_x, max_x), (min_y, max_y)) = ranges
+        .ok_or_else(|| ParseError::at_slice(input, line, "expected 'target area: x=<min>..<max>, y=<min>..<max>'"))?;
+    if min_x <= 0 || min_x > max_x || min_y > max_y || max_y >= 0
+    {
+        return Err(ParseError::at_slice(input, line, "expected a target area ahead of and below the probe"));
+    }
+    return Ok((max_x, min_x, max_y, min_y));
+}
+
+fn parse_range(range: &str) -> Option<(i64, i64)>
+{
+    let (min, max) = range.split_once("..")?;
+    return Some((min.parse::<i32>().ok()? as i64, max.parse::<i32>().ok()? as i64));
+}
+
+fn highest_y(min_y: i64) -> i64
+{
+    return aoc_common::binomial(-min_y as i128, 2).unwrap() as i64;
+}
+
+fn count_velocities(max_x: i64, min_x: i64, max_y: i64, min_y: i64) -> usize
 {
     let mut vals = Vec::new();
     let max = i64::max(max_x, -max_y);
@@ -80,12 +126,22 @@ mod tests {
     #[test]
     fn test_part_one() 
     {
-        assert_eq!(45, part_one(-10));
+        assert_eq!(45, highest_y(-10));
     }
 
     #[test]
     fn test_part_two() 
     {
-        assert_eq!(112, part_two(30, 20, -5, -10));
+        assert_eq!(112, count_velocities(30, 20, -5, -10));
+    }
+
+    #[test]
+    fn test_parse_errors()
+    {
+        assert_eq!((30, 20, -5, -10), parse("target area: x=20..30, y=-10..-5\n").unwrap());
+        assert!(parse("target area: x=20..30, y=-10..5\n").is_err());
+        assert!(parse("target area: x=30..20, y=-10..-5\n").is_err());
+        let err = parse("target area: x=20..30, y=-10..\n").unwrap_err();
+        assert_eq!((1, 1), (err.line, err.column));
     }
-}
\ No newline at end of file
+}
@@ -1,23 +1,100 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use aoc_common::{ParseError, ParseResult, Solution};
 
 fn main() {
-    if let Ok(lines) = read_lines("src/18/input.txt") {
-        let input:Vec<String> = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap())
-            .collect();
-
-        println!("Part 1: {}", part_one(&input));
-        println!("Part 2: {}", part_two(&input));
+    aoc_common::run_day::<Day>(2021, 18);
+}
+
+pub struct Day;
+
+impl Solution for Day
+{
+    type Parsed = Vec<String>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> ParseResult<Vec<String>>
+    {
+        return parse(input);
+    }
+
+    fn part_one(input: &Vec<String>) -> usize
+    {
+        let mut it = input.iter();
+        let mut prev = it.next().unwrap().clone();
+
+        for line in it
+        {
+            let val = format!("[{},{}]", prev, line);
+            prev = reduce(&val);
+        }
+        return magnetude(&prev);
+    }
+
+    fn part_two(input: &Vec<String>) -> usize
+    {
+        return input.iter()
+            .flat_map(|line| 
+                input.iter().filter(move |l| *l != line).map(move |l| {
+                    let val = format!("[{},{}]", line, l);
+                    return magnetude(&reduce(&val));
+                })
+            )
+            .max()
+            .unwrap_or(0);
+    }
+}
+
+/// The snailfish numbers, one per line.  Each must already be reduced, so
+/// pairs nest at most four deep and regular numbers are single digits.
+fn parse(input: &str) -> ParseResult<Vec<String>>
+{
+    let lines: Vec<&str> = aoc_common::lines(input).collect();
+    if lines.is_empty()
+    {
+        return Err(ParseError::at(input, 0, "expected a snailfish number"));
+    }
+    for line in &lines
+    {
+        let end = check_pair(input, line, 0, 1)?;
+        if end != line.len()
+        {
+            return Err(ParseError::at_slice(input, &line[end..], "unexpected text after the number"));
+        }
     }
+    return Ok(lines.iter().map(|l| l.to_string()).collect());
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+/// Checks the pair starting at `pos` in `line`, nested `depth` deep, and
+/// returns the position after it.
+fn check_pair(input: &str, line: &str, pos: usize, depth: usize) -> ParseResult<usize>
+{
+    let expect = |pos: usize, c: u8| -> ParseResult<usize>
+    {
+        if line.as_bytes().get(pos) == Some(&c)
+        {
+            return Ok(pos + 1);
+        }
+        return Err(ParseError::at_slice(input, &line[pos..], format!("expected '{}'", c as char)));
+    };
+    if depth > 4
+    {
+        return Err(ParseError::at_slice(input, &line[pos..], "expected pairs nested at most four deep"));
+    }
+    let pos = expect(pos, b'[')?;
+    let pos = check_element(input, line, pos, depth)?;
+    let pos = expect(pos, b',')?;
+    let pos = check_element(input, line, pos, depth)?;
+    return expect(pos, b']');
+}
+
+fn check_element(input: &str, line: &str, pos: usize, depth: usize) -> ParseResult<usize>
+{
+    return match line.as_bytes().get(pos)
+    {
+        Some(b'[') => check_pair(input, line, pos, depth + 1),
+        Some(c) if c.is_ascii_digit() => Ok(pos + 1),
+        _ => Err(ParseError::at_slice(input, &line[pos..], "expected a digit or a pair"))
+    };
 }
 
 fn get_parts(input: &str) -> (&str, &str)
@@ -186,32 +263,6 @@ fn reduce(input: &String) -> String
     panic!("too many iterations");
 }
 
-fn part_one(input: &Vec<String>) -> usize
-{
-    let mut it = input.iter();
-    let mut prev = it.next().unwrap().clone();
-
-    for line in it
-    {
-        let val = format!("[{},{}]", prev, line);
-        prev = reduce(&val);
-    }
-    return magnetude(&prev);
-}
-
-fn part_two(input: &Vec<String>) -> usize
-{
-    return input.iter()
-        .map(|line| 
-            input.iter().filter(|l| *l != line).map(|l| {
-                let val = format!("[{},{}]", line, l);
-                return magnetude(&reduce(&val));
-            }).max().unwrap()
-        )
-        .max()
-        .unwrap();
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -267,7 +318,7 @@ mod tests {
             String::from("[[2,[[7,7],7]],[[5,8],[[9,3],[0,2]]]]"),
             String::from("[[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]")
         ];
-        assert_eq!(4140, part_one(&input));
+        assert_eq!(4140, Day::part_one(&Day::parse(&input.join("\n")).unwrap()));
     }
 
     #[test]
@@ -285,6 +336,21 @@ mod tests {
             String::from("[[2,[[7,7],7]],[[5,8],[[9,3],[0,2]]]]"),
             String::from("[[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]")
         ];
-        assert_eq!(3993, part_two(&input));
+        assert_eq!(3993, Day::part_two(&Day::parse(&input.join("\n")).unwrap()));
+    }
+
+    #[test]
+    fn test_parse_errors()
+    {
+        assert_eq!(vec!["[[1,2],3]", "[9,[8,7]]"], parse("[[1,2],3]\n[9,[8,7]]\n").unwrap());
+        let err = parse("[[1,2],3]\n[9,[8,x]]\n").unwrap_err();
+        assert_eq!((2, 7), (err.line, err.column));
+        let err = parse("[[1,2],3]\n[9,[8,7]\n").unwrap_err();
+        assert_eq!((2, 9), (err.line, err.column));
+        let err = parse("[[1,2],3]]\n").unwrap_err();
+        assert_eq!((1, 10), (err.line, err.column));
+        assert!(parse("[12,3]").is_err());
+        assert!(parse("[[[[[1,2],3],4],5],6]").is_err());
+        assert!(parse("").is_err());
     }
-}
\ No newline at end of file
+}
@@ -1,77 +1,98 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use aoc_common::{ParseError, ParseResult, Solution};
 
 fn main() {
-    if let Ok(lines) = read_lines("src/2/input.txt") {
-        let list = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap())
-            .collect();
-
-        let pos = get_pos(&list);
-        println!("Position: {} * {} = {}", pos.0, pos.1, pos.0*pos.1);
-        let pos2 = get_pos2(&list);
-        println!("Position 2: {} * {} = {}", pos2.0, pos2.1, pos2.0*pos2.1);
+    aoc_common::run_day::<Day>(2021, 2);
+}
+
+pub struct Day;
+
+impl Solution for Day
+{
+    type Parsed = Vec<Command>;
+    type PartOne = i32;
+    type PartTwo = i32;
+
+    fn parse(input: &str) -> ParseResult<Vec<Command>>
+    {
+        return parse(input);
     }
+
+    fn part_one(input: &Vec<Command>) -> i32
+    {
+        let result = get_pos(input);
+        return result.0 * result.1;
+    }
+
+    fn part_two(input: &Vec<Command>) -> i32
+    {
+        let result = get_pos2(input);
+        return result.0 * result.1;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command
+{
+    Forward(i32),
+    Down(i32),
+    Up(i32)
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+fn parse(input: &str) -> ParseResult<Vec<Command>>
+{
+    return aoc_common::lines(input)
+        .map(|line| parse_command(input, line))
+        .collect();
+}
+
+/// A command such as `forward 5`, from a line of `input`.
+fn parse_command(input: &str, line: &str) -> ParseResult<Command>
+{
+    let (cmd, value) = line.split_once(' ')
+        .ok_or_else(|| ParseError::at_slice(input, line, "expected '<command> <distance>'"))?;
+    let value = value.parse::<i32>()
+        .map_err(|_| ParseError::at_slice(input, value, "expected a distance"))?;
+    return match cmd
+    {
+        "forward" => Ok(Command::Forward(value)),
+        "down" => Ok(Command::Down(value)),
+        "up" => Ok(Command::Up(value)),
+        _ => Err(ParseError::at_slice(input, cmd, "expected 'forward', 'down' or 'up'"))
+    };
 }
 
-pub fn get_pos(commands: &Vec<String>) -> (i32, i32)
+pub fn get_pos(commands: &Vec<Command>) -> (i32, i32)
 {
     let mut pos = (0, 0);
 
     for cmd in commands
     {
-        if cmd.starts_with("forward ")
-        {
-            pos.0 = pos.0 + cmd["forward ".len()..].parse::<i32>().unwrap();
-        }
-        else if cmd.starts_with("down ")
-        {
-            pos.1 = pos.1 + cmd["down ".len()..].parse::<i32>().unwrap();
-        }
-        else if cmd.starts_with("up ")
+        match cmd
         {
-            pos.1 = pos.1 - cmd["up ".len()..].parse::<i32>().unwrap();
-        }
-        else
-        {
-            panic!("invalid value: {:?}", cmd);
+            Command::Forward(x) => pos.0 = pos.0 + x,
+            Command::Down(x) => pos.1 = pos.1 + x,
+            Command::Up(x) => pos.1 = pos.1 - x
         }
     }
     return pos;
 }
 
-pub fn get_pos2(commands: &Vec<String>) -> (i32, i32)
+pub fn get_pos2(commands: &Vec<Command>) -> (i32, i32)
 {
     let mut pos = (0, 0);
     let mut aim = 0;
 
     for cmd in commands
     {
-        if cmd.starts_with("forward ")
+        match cmd
         {
-            let x = cmd["forward ".len()..].parse::<i32>().unwrap();
-            pos.0 = pos.0 + x;
-            pos.1 = pos.1 + (aim * x);
-        }
-        else if cmd.starts_with("down ")
-        {
-            aim = aim + cmd["down ".len()..].parse::<i32>().unwrap();
-        }
-        else if cmd.starts_with("up ")
-        {
-            aim = aim - cmd["up ".len()..].parse::<i32>().unwrap();
-        }
-        else
-        {
-            panic!("invalid value: {:?}", cmd);
+            Command::Forward(x) =>
+            {
+                pos.0 = pos.0 + x;
+                pos.1 = pos.1 + (aim * x);
+            },
+            Command::Down(x) => aim = aim + x,
+            Command::Up(x) => aim = aim - x
         }
     }
     return pos;
@@ -81,31 +102,30 @@ pub fn get_pos2(commands: &Vec<String>) -> (i32, i32)
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = "forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2\n";
+
     #[test]
     fn test_get_pos() 
     {
-        let input = vec![
-            String::from("forward 5"),
-            String::from("down 5"),
-            String::from("forward 8"),
-            String::from("up 3"),
-            String::from("down 8"),
-            String::from("forward 2")
-        ];
+        let input = parse(EXAMPLE).unwrap();
         assert_eq!((15, 10), get_pos(&input));
     }
 
     #[test]
     fn test_get_pos2() 
     {
-        let input = vec![
-            String::from("forward 5"),
-            String::from("down 5"),
-            String::from("forward 8"),
-            String::from("up 3"),
-            String::from("down 8"),
-            String::from("forward 2")
-        ];
+        let input = parse(EXAMPLE).unwrap();
         assert_eq!((15, 60), get_pos2(&input));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_errors()
+    {
+        assert_eq!(vec![Command::Forward(5), Command::Up(3)], parse("forward 5\nup 3\n").unwrap());
+        let err = parse("forward 5\nback 3\n").unwrap_err();
+        assert_eq!((2, 1), (err.line, err.column));
+        let err = parse("forward 5\nup three\n").unwrap_err();
+        assert_eq!((2, 4), (err.line, err.column));
+        assert!(parse("forward5").is_err());
+    }
+}
@@ -1,66 +1,140 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
-use std::collections::HashMap;
+use aoc_common::{Grid, ParseError, ParseResult, Solution, Unsolved};
 
 fn main() {
-    if let Ok(lines) = read_lines("src/20/input.txt") {
-        let mut it = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap());
-        let algo = process_algo(&it.next().unwrap());
-        it.next();
-        let image = process_image(&it.collect());
-
-        println!("Part 1: {}", part_one(&algo, &image));
-        // println!("Part 2: {}", part_two(&input));
+    aoc_common::run_day::<Day>(2021, 20);
+}
+
+pub struct Day;
+
+impl Solution for Day
+{
+    type Parsed = (Vec<bool>, Image);
+    type PartOne = usize;
+    type PartTwo = Unsolved;
+
+    const PARTS: u32 = 1;
+
+    fn parse(input: &str) -> ParseResult<(Vec<bool>, Image)>
+    {
+        return parse(input);
+    }
+
+    fn part_one(input: &(Vec<bool>, Image)) -> usize
+    {
+        let (algo, image) = input;
+        return count_after_enhancing(&algo, &image);
+    }
+
+    fn part_two(_input: &(Vec<bool>, Image)) -> Unsolved
+    {
+        return Unsolved;
     }
 }
 
-fn process_algo(input: &String) -> Vec<bool>
+fn parse(input: &str) -> ParseResult<(Vec<bool>, Image)>
 {
-    return input.chars()
-        .map(|c| match c {
-            '.' => false,
-            '#' => true,
-            _ => panic!("unexpected aglo character {}", c)
-        })
-        .collect();
+    let mut it = aoc_common::lines(input).map(String::from);
+    let algo = process_algo(input, &it.next().unwrap_or_default())?;
+    it.next();
+    let image = process_image(input, &it.collect())?;
+    return Ok((algo, image));
 }
 
-struct Image
+fn process_algo(input: &str, algo: &String) -> ParseResult<Vec<bool>>
 {
-    pixels: HashMap<(usize, usize), bool>
+    if algo.is_empty()
+    {
+        return Err(ParseError::at(input, 0, "expected the enhancement algorithm"));
+    }
+
+    let algo = algo.chars()
+        .enumerate()
+        .map(|(x, c)| match c {
+            '.' => Ok(false),
+            '#' => Ok(true),
+            _ => Err(ParseError::at_line(input, 0, x, format!("unexpected algorithm character '{}'", c)))
+        })
+        .collect::<ParseResult<Vec<bool>>>()?;
+    if algo.len() != 512
+    {
+        let message = format!("expected 512 algorithm characters, found {}", algo.len());
+        return Err(ParseError::at_line(input, 0, 0, message));
+    }
+    return Ok(algo);
 }
 
-fn process_image(input: &Vec<String>) -> Image
+/// The lit pixels around the input image, and whether every pixel beyond them
+/// is lit. The background starts dark, but an algorithm that lights a pixel
+/// with nothing lit around it flips the whole infinite background.
+pub struct Image
 {
-    let mut map:HashMap<(usize, usize), bool> = HashMap::new();
+    pixels: Grid<bool>,
+    background: bool
+}
 
-    for y in 0..input.len()
+impl Image
+{
+    fn pixel(&self, x: i64, y: i64) -> bool
     {
-        let mut x = 0;
-        for c in input[y].chars()
+        if x < 0 || y < 0
         {
-            if c == '#'
+            return self.background;
+        }
+        return *self.pixels.get((x as usize, y as usize)).unwrap_or(&self.background);
+    }
+
+    /// The image one step on, a pixel bigger on each side since that's as far
+    /// as the lit pixels can spread.
+    fn enhance(&self, algo: &Vec<bool>) -> Image
+    {
+        let pixels = Grid::from_fn(self.pixels.width() + 2, self.pixels.height() + 2, |(x, y)| {
+            let mut index = 0;
+            for dy in -1..=1
             {
-                map.insert((x, y), true);
+                for dx in -1..=1
+                {
+                    let lit = self.pixel(x as i64 - 1 + dx, y as i64 - 1 + dy);
+                    index = index * 2 + lit as usize;
+                }
             }
-            x = x + 1;
-        }
+            return algo[index];
+        });
+        let background = algo[if self.background { 511 } else { 0 }];
+        return Image { pixels: pixels, background: background };
     }
-    return Image{ pixels: map };
 }
 
-fn part_one(algo: &Vec<bool>, input: &Image) -> usize
+/// The image from `lines`, which start on the third line of `input`.
+fn process_image(input: &str, lines: &Vec<String>) -> ParseResult<Image>
 {
-    return 0;
+    let width = lines.first().map_or(0, |l| l.chars().count());
+    let mut rows = vec![];
+    for (y, line) in lines.iter().enumerate()
+    {
+        let row = line.chars()
+            .enumerate()
+            .map(|(x, c)| match c {
+                '.' => Ok(false),
+                '#' => Ok(true),
+                _ => Err(ParseError::at_line(input, y + 2, x, format!("unexpected image character '{}'", c)))
+            })
+            .collect::<ParseResult<Vec<bool>>>()?;
+        if row.len() != width
+        {
+            let message = format!("expected {} pixels in the row, found {}", width, row.len());
+            return Err(ParseError::at_line(input, y + 2, 0, message));
+        }
+        rows.push(row);
+    }
+    return Ok(Image{ pixels: Grid::from_rows(rows), background: false });
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+/// How many pixels are lit after enhancing twice. Counts only the pixels near
+/// the image, so a lit background isn't counted.
+fn count_after_enhancing(algo: &Vec<bool>, input: &Image) -> usize
+{
+    let image = input.enhance(algo).enhance(algo);
+    return image.pixels.cells().filter(|&&lit| lit).count();
 }
 
 #[cfg(test)]
@@ -78,6 +152,38 @@ mod tests {
             String::from("..#.."),
             String::from("..###")
         ];
-        assert_eq!(35, part_one(&process_algo(&algo_str), &process_image(&img_str)));
+        let input = format!("{}\n\n{}\n", algo_str, img_str.join("\n"));
+        assert_eq!(35, count_after_enhancing(&process_algo(&input, &algo_str).unwrap(), &process_image(&input, &img_str).unwrap()));
+    }
+
+    #[test]
+    fn test_flashing_background()
+    {
+        // Nothing lit lights a pixel and everything lit darkens it, so the
+        // background flips on the first step and back on the second.
+        let mut algo = vec![false; 512];
+        algo[0] = true;
+        let image = Image { pixels: Grid::from_rows(vec![vec![false]]), background: false };
+        let once = image.enhance(&algo);
+        assert!(once.background);
+        assert_eq!(9, once.pixels.cells().filter(|&&lit| lit).count());
+        assert_eq!(0, count_after_enhancing(&algo, &image));
+    }
+
+    #[test]
+    fn test_parse_error()
+    {
+        let err = Day::parse("..#x#\n\n#..#.\n").err().unwrap();
+        assert_eq!((1, 4), (err.line, err.column));
+        let err = Day::parse("..#.#\n\n#..#.\n").err().unwrap();
+        assert_eq!((1, 1), (err.line, err.column));
+        assert_eq!("expected 512 algorithm characters, found 5", err.message);
+
+        let algo = ".".repeat(512);
+        let err = Day::parse(&format!("{}\n\n#..#.\n#..\n", algo)).err().unwrap();
+        assert_eq!((4, 1), (err.line, err.column));
+        assert_eq!("expected 5 pixels in the row, found 3", err.message);
+        let err = Day::parse(&format!("{}\n\n#..#.\n#.x#.\n", algo)).err().unwrap();
+        assert_eq!((4, 3), (err.line, err.column));
     }
 }
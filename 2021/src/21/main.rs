@@ -1,11 +1,61 @@
+use aoc_common::{memoize, ParseError, ParseResult, Solution};
+
 
 fn main() {
-    println!("Part 1: {}", part_one(8, 4));
-    let res = part_two(8, 4, 21);
-    println!("Part 2: {:?} => {:?}", res, usize::max(res.0, res.1));
+    aoc_common::run_day::<Day>(2021, 21);
+}
+
+pub struct Day;
+
+impl Solution for Day
+{
+    type Parsed = (usize, usize);
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> ParseResult<(usize, usize)>
+    {
+        return parse(input);
+    }
+
+    fn part_one(input: &(usize, usize)) -> usize
+    {
+        let (p1_start, p2_start) = *input;
+        return deterministic_game(p1_start, p2_start);
+    }
+
+    fn part_two(input: &(usize, usize)) -> usize
+    {
+        let (p1_start, p2_start) = *input;
+        let res = count_universes(p1_start, p2_start, 21);
+        return usize::max(res.0, res.1);
+    }
 }
 
-fn part_one(p1_start: usize, p2_start: usize) -> usize
+/// The starting spaces of the two players, from 1 to 10.
+fn parse(input: &str) -> ParseResult<(usize, usize)>
+{
+    let lines: Vec<&str> = aoc_common::lines(input).collect();
+    if lines.len() != 2
+    {
+        return Err(ParseError::at(input, 0, "expected the starting positions of two players"));
+    }
+    let mut starts = Vec::new();
+    for (i, line) in lines.iter().enumerate()
+    {
+        let prefix = format!("Player {} starting position: ", i + 1);
+        let start = line.strip_prefix(&prefix)
+            .ok_or_else(|| ParseError::at_slice(input, line, format!("expected '{}<n>'", prefix)))?;
+        match start.parse::<usize>()
+        {
+            Ok(start) if (1..=10).contains(&start) => starts.push(start),
+            _ => return Err(ParseError::at_slice(input, start, "expected a space from 1 to 10"))
+        }
+    }
+    return Ok((starts[0], starts[1]));
+}
+
+fn deterministic_game(p1_start: usize, p2_start: usize) -> usize
 {
     let mut players = vec![
         (p1_start - 1, 0),
@@ -54,61 +104,38 @@ const OUTCOMES:&[(usize,usize)] = &[
     (9, 1)
 ];
 
-fn player_two_roll(p1: usize, p2: usize, p1_score: usize, p2_score: usize, target: usize) -> (usize, usize)
-{
-    let mut universes = (0, 0);
+/// Both positions then both scores, the player about to roll's first.
+type Turn = (usize, usize, usize, usize);
 
-    for o in OUTCOMES
-    {
-        let roll = o.0;
-        
-        let p2_new = (p2 + roll) % 10;
-        let p2_score_new = p2_score + p2_new + 1;
-        if p2_score_new >= target
-        {
-            universes.1 = universes.1 + o.1;
-        }
-        else
-        {
-            let outcome = player_one_roll(p1, p2_new, p1_score, p2_score_new, target);
-            universes.0 = universes.0 + (outcome.0 * o.1);
-            universes.1 = universes.1 + (outcome.1 * o.1);
-        }
-    }
-
-    // println!("p2 {:?}", universes);
-    return universes;
-}
-
-fn player_one_roll(p1: usize, p2: usize, p1_score: usize, p2_score: usize, target: usize) -> (usize, usize)
+fn count_universes(p1_start: usize, p2_start: usize, target: usize) -> (usize, usize)
 {
-    let mut universes = (0, 0);
-
-    for o in OUTCOMES
+    // universes won by the player about to roll and by the other player
+    let mut player_roll = memoize(|player_roll, (pos, other_pos, score, other_score): Turn|
     {
-        let roll = o.0;
-        let weight = o.1;
-        
-        let p1_new = (p1 + roll) % 10;
-        let p1_score_new = p1_score + p1_new + 1;
-        if p1_score_new >= target
-        {
-            universes.0 = universes.0 + weight;
-        }
-        else
+        let mut universes = (0, 0);
+
+        for o in OUTCOMES
         {
-            let outcome = player_two_roll(p1_new, p2, p1_score_new, p2_score, target);
-            universes.0 = universes.0 + (outcome.0 * weight);
-            universes.1 = universes.1 + (outcome.1 * weight);
-        }
-    }
-    // println!("p1 {:?}", universes);
-    return universes;
-}
+            let roll = o.0;
+            let weight = o.1;
 
-fn part_two(p1_start: usize, p2_start: usize, target: usize) -> (usize, usize)
-{
-    return player_one_roll(p1_start - 1, p2_start - 1, 0, 0, target);
+            let pos_new = (pos + roll) % 10;
+            let score_new = score + pos_new + 1;
+            if score_new >= target
+            {
+                universes.0 = universes.0 + weight;
+            }
+            else
+            {
+                // the other player rolls next, so their wins come first
+                let outcome: (usize, usize) = player_roll((other_pos, pos_new, other_score, score_new));
+                universes.0 = universes.0 + (outcome.1 * weight);
+                universes.1 = universes.1 + (outcome.0 * weight);
+            }
+        }
+        return universes;
+    });
+    return player_roll.call((p1_start - 1, p2_start - 1, 0, 0));
 }
 
 #[cfg(test)]
@@ -118,14 +145,27 @@ mod tests {
     #[test]
     fn test_part_one() 
     {
-        assert_eq!(739785, part_one(4, 8));
+        assert_eq!(739785, deterministic_game(4, 8));
     }
 
     #[test]
     fn test_part_two() 
     {
-        assert_eq!((27, 0), part_two(1, 1, 1));
-        assert_eq!((53, 26), part_two(1, 1, 5));
-        assert_eq!((444356092776315, 341960390180808), part_two(4, 8, 21));
+        assert_eq!((27, 0), count_universes(1, 1, 1));
+        assert_eq!((53, 26), count_universes(1, 1, 5));
+        assert_eq!((444356092776315, 341960390180808), count_universes(4, 8, 21));
+        assert_eq!((203614077328429517, 165932336280294082), count_universes(4, 8, 25));
+    }
+
+    #[test]
+    fn test_parse_errors()
+    {
+        let input = "Player 1 starting position: 4\nPlayer 2 starting position: 8\n";
+        assert_eq!((4, 8), parse(input).unwrap());
+        let err = parse(&input.replace("8", "11")).unwrap_err();
+        assert_eq!((2, 29), (err.line, err.column));
+        let err = parse(&input.replace("Player 2", "Player 3")).unwrap_err();
+        assert_eq!((2, 1), (err.line, err.column));
+        assert!(parse("Player 1 starting position: 4\n").is_err());
     }
-}
\ No newline at end of file
+}
@@ -1,25 +1,57 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use aoc_common::{ParseError, ParseResult, Solution};
 
 fn main() {
-    if let Ok(lines) = read_lines("src/3/input.txt") {
-        let list = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap())
-            .collect();
-
-        let diag = get_diag(&list);
-        println!("Diag: {} * {} = {}", diag.0, diag.1, diag.0*diag.1);
-        let oxy = get_oxy(&list);
-        println!("Oxy: {} * {} = {}", oxy.0, oxy.1, oxy.0*oxy.1);
+    aoc_common::run_day::<Day>(2021, 3);
+}
+
+pub struct Day;
+
+impl Solution for Day
+{
+    type Parsed = Vec<String>;
+    type PartOne = i32;
+    type PartTwo = i32;
+
+    fn parse(input: &str) -> ParseResult<Vec<String>>
+    {
+        return parse(input);
+    }
+
+    fn part_one(input: &Vec<String>) -> i32
+    {
+        let result = get_diag(input);
+        return result.0 * result.1;
+    }
+
+    fn part_two(input: &Vec<String>) -> i32
+    {
+        let result = get_oxy(input);
+        return result.0 * result.1;
     }
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+/// The diagnostic report, which must be binary numbers of the same width
+/// that fit in an `i32`.
+fn parse(input: &str) -> ParseResult<Vec<String>>
+{
+    let lines: Vec<&str> = aoc_common::lines(input).collect();
+    let width = match lines.first()
+    {
+        Some(line) if !line.is_empty() && line.len() < 32 => line.len(),
+        _ => return Err(ParseError::at(input, 0, "expected binary numbers of 1 to 31 digits"))
+    };
+    for line in &lines
+    {
+        if let Some(i) = line.find(|c| c != '0' && c != '1')
+        {
+            return Err(ParseError::at_slice(input, &line[i..], "expected a binary digit"));
+        }
+        if line.len() != width
+        {
+            return Err(ParseError::at_slice(input, line, format!("expected {} digits", width)));
+        }
+    }
+    return Ok(lines.iter().map(|l| l.to_string()).collect());
 }
 
 pub fn get_diag(commands: &Vec<String>) -> (i32, i32)
@@ -209,4 +241,15 @@ mod tests {
     {
         assert_eq!(23, to_bin(&String::from("10111")));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_errors()
+    {
+        assert_eq!(vec!["0010", "1110"], parse("0010\n1110\n").unwrap());
+        let err = parse("0010\n1120\n").unwrap_err();
+        assert_eq!((2, 3), (err.line, err.column));
+        let err = parse("0010\n111\n").unwrap_err();
+        assert_eq!((2, 1), (err.line, err.column));
+        assert!(parse("").is_err());
+    }
+}
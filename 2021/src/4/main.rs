@@ -1,34 +1,60 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use aoc_common::{ParseError, ParseResult, Solution};
 
 fn main() {
-    if let Ok(lines) = read_lines("src/4/input.txt") {
-        let mut list = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap());
-
-        let input = list.next().unwrap()
-            .split(',')
-            .map(|r| r.parse::<i32>().unwrap())
-            .collect();
-
-        let mut boards = parse_boards(&list.skip(1).collect());
-
-        let score = get_score(&input, &mut boards);
-        println!("Score: {}*{} = {}", score.0, score.1, score.0*score.1);
-        let score_last = last_winner(&input, &mut boards);
-        println!("Last: {}*{} = {}", score_last.0, score_last.1, score_last.0*score_last.1);
+    aoc_common::run_day::<Day>(2021, 4);
+}
+
+pub struct Day;
+
+impl Solution for Day
+{
+    type Parsed = (Vec<i32>, Vec<Board>);
+    type PartOne = i32;
+    type PartTwo = i32;
+
+    fn parse(input: &str) -> ParseResult<(Vec<i32>, Vec<Board>)>
+    {
+        return parse(input);
+    }
+
+    fn part_one(input: &(Vec<i32>, Vec<Board>)) -> i32
+    {
+        let (numbers, mut boards) = input.clone();
+        let score = get_score(&numbers, &mut boards);
+        return score.0 * score.1;
+    }
+
+    fn part_two(input: &(Vec<i32>, Vec<Board>)) -> i32
+    {
+        let (numbers, mut boards) = input.clone();
+        let score = last_winner(&numbers, &mut boards);
+        return score.0 * score.1;
     }
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+fn parse(input: &str) -> ParseResult<(Vec<i32>, Vec<Board>)>
+{
+    let paragraphs = aoc_common::paragraphs(input);
+    let numbers = match paragraphs.first()
+    {
+        Some(p) if p.len() == 1 => p[0],
+        _ => return Err(ParseError::at(input, 0, "expected a line of drawn numbers"))
+    };
+    let numbers = numbers.split(',')
+        .map(|r| r.parse::<i32>().map_err(|_| ParseError::at_slice(input, r, "expected a number")))
+        .collect::<ParseResult<Vec<i32>>>()?;
+
+    let boards = paragraphs[1..].iter()
+        .map(|rows| parse_board(input, rows))
+        .collect::<ParseResult<Vec<Board>>>()?;
+    if boards.is_empty()
+    {
+        return Err(ParseError::at(input, input.len(), "expected some boards"));
+    }
+    return Ok((numbers, boards));
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Board
 {
     rows: Vec<Vec<i32>>,
@@ -79,36 +105,35 @@ fn derive_cols(rows: &Vec<Vec<i32>>) -> Vec<Vec<i32>>
     return cols;
 }
 
-pub fn parse_boards(input: &Vec<String>) -> Vec<Board>
+/// The blank-line separated boards in `input`.
+pub fn parse_boards(input: &str) -> ParseResult<Vec<Board>>
+{
+    return aoc_common::paragraphs(input).iter()
+        .map(|rows| parse_board(input, rows))
+        .collect();
+}
+
+/// A square board from `lines` of `input`, with numbers separated by spaces.
+fn parse_board(input: &str, lines: &[&str]) -> ParseResult<Board>
 {
-    let mut result = Vec::new();
     let mut rows = Vec::new();
-    for line in input
+    for line in lines
     {
-        if line == ""
+        let row = line.split(' ')
+            .filter(|r| r.len() > 0)
+            .map(|r| r.parse::<i32>().map_err(|_| ParseError::at_slice(input, r, "expected a number")))
+            .collect::<ParseResult<Vec<i32>>>()?;
+        if row.len() != lines.len()
         {
-            let cols = derive_cols(&rows);
-            result.push(Board {
-                rows: rows,
-                cols: cols
-            });
-            rows = Vec::new();
-        }
-        else
-        {
-            let row = line.split(' ')
-                .filter(|r| r.len() > 0)
-                .map(|r| r.parse::<i32>().unwrap())
-                .collect();
-            rows.push(row);
+            return Err(ParseError::at_slice(input, line, format!("expected {} numbers in the row", lines.len())));
         }
+        rows.push(row);
     }
     let cols = derive_cols(&rows);
-    result.push(Board {
+    return Ok(Board {
         rows: rows,
         cols: cols
     });
-    return result;
 }
 
 pub fn get_score(input: &Vec<i32>, boards: &mut Vec<Board>) -> (i32, i32)
@@ -172,7 +197,7 @@ mod tests {
             String::from("18  8 23 26 20"),
             String::from("22 11 13  6  5"),
             String::from(" 2  0 12  3  7")
-        ]);
+        ].join("\n")).unwrap();
         assert_eq!((188, 24), get_score(&input, &mut boards));
     }
 
@@ -198,7 +223,21 @@ mod tests {
             String::from("18  8 23 26 20"),
             String::from("22 11 13  6  5"),
             String::from(" 2  0 12  3  7")
-        ]);
+        ].join("\n")).unwrap();
         assert_eq!((148, 13), last_winner(&input, &mut boards));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_errors()
+    {
+        let (numbers, boards) = parse("7,4,9\n\n1 2\n3 4\n\n 5 6\n 7 8\n").unwrap();
+        assert_eq!((vec![7, 4, 9], 2), (numbers, boards.len()));
+        let err = parse("7,x,9\n\n1 2\n3 4\n").unwrap_err();
+        assert_eq!((1, 3), (err.line, err.column));
+        let err = parse("7,4,9\n\n1 2\n3 4 5\n").unwrap_err();
+        assert_eq!((4, 1), (err.line, err.column));
+        let err = parse("7,4,9\n\n1 2\n3 -\n").unwrap_err();
+        assert_eq!((4, 3), (err.line, err.column));
+        assert!(parse("7,4,9\n").is_err());
+    }
+}
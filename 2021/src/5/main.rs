@@ -1,30 +1,69 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use aoc_common::{ParseError, ParseResult, Solution};
 
 fn main() {
-    if let Ok(lines) = read_lines("src/5/input.txt") {
-        let input:Vec<LineSegment> = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap())
-            .map(|r| r.replace(" -> ", ","))
-            .map(|r| r.split(',').map(|r| r.parse::<i32>().unwrap()).collect())
-            .map(|r| LineSegment::from_vec(&r))
-            .collect();
+    aoc_common::run_day::<Day>(2021, 5);
+}
+
+pub struct Day;
+
+impl Solution for Day
+{
+    type Parsed = Vec<LineSegment>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> ParseResult<Vec<LineSegment>>
+    {
+        return parse(input);
+    }
 
+    fn part_one(input: &Vec<LineSegment>) -> usize
+    {
         let orth_lines = input.iter()
             .filter(|r| is_orth(r))
             .cloned()
             .collect();
-        println!("Num Intersections: {}", num_crosses(&orth_lines));
-        println!("Num Intersections2: {}", num_crosses(&input));
+        return num_crosses(&orth_lines);
+    }
+
+    fn part_two(input: &Vec<LineSegment>) -> usize
+    {
+        return num_crosses(input);
     }
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+fn parse(input: &str) -> ParseResult<Vec<LineSegment>>
+{
+    return aoc_common::lines(input)
+        .map(|line| parse_segment(input, line))
+        .collect();
+}
+
+/// A segment such as `0,9 -> 5,9`, which must be horizontal, vertical or at
+/// 45 degrees, with coordinates that aren't negative.
+fn parse_segment(input: &str, line: &str) -> ParseResult<LineSegment>
+{
+    let mut vals = Vec::new();
+    for point in line.split(" -> ")
+    {
+        for val in point.split(',')
+        {
+            let val = val.parse::<u16>()
+                .map_err(|_| ParseError::at_slice(input, val, "expected a coordinate"))?;
+            vals.push(val as i32);
+        }
+    }
+    if vals.len() != 4
+    {
+        return Err(ParseError::at_slice(input, line, "expected 'x1,y1 -> x2,y2'"));
+    }
+    let segment = LineSegment::from_vec(&vals);
+    let (dx, dy) = (segment.x1 - segment.x0, segment.y1 - segment.y0);
+    if dx != 0 && dy != 0 && dx.abs() != dy.abs()
+    {
+        return Err(ParseError::at_slice(input, line, "expected a horizontal, vertical or diagonal line"));
+    }
+    return Ok(segment);
 }
 
 #[derive(Debug)]
@@ -80,7 +119,7 @@ fn draw_line(map: &mut Vec<Vec<usize>>, line: &LineSegment)
     {
         len = i32::abs(dir.1);
     }
-    let step = (dir.0 / len, dir.1 / len);
+    let step = if len == 0 { (0, 0) } else { (dir.0 / len, dir.1 / len) };
 
     for i in 0..len+1
     {
@@ -178,4 +217,15 @@ mod tests {
         ];
         assert_eq!(12, num_crosses(&input));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_errors()
+    {
+        assert_eq!(1, num_crosses(&parse("0,9 -> 5,9\n3,9 -> 3,9\n").unwrap()));
+        let err = parse("0,9 -> 5,9\n8,0 -> 0,-8\n").unwrap_err();
+        assert_eq!((2, 10), (err.line, err.column));
+        let err = parse("0,9 -> 5,9\n8,0 -> 0,7\n").unwrap_err();
+        assert_eq!((2, 1), (err.line, err.column));
+        assert!(parse("0,9 -> 5\n").is_err());
+    }
+}
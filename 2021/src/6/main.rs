@@ -1,25 +1,36 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use aoc_common::{ParseResult, Solution};
 
 fn main() {
-    if let Ok(lines) = read_lines("src/6/input.txt") {
-        let init:Vec<i32> = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap())
-            .map(|r| r.split(',').map(|r| r.parse::<i32>().unwrap()).collect::<Vec<i32>>())
-            .flatten()
-            .collect();
-
-        println!("80 Days: {}", simulate_fish(&init, 80));
-        println!("256 Days: {}", simulate_fish(&init, 256));
+    aoc_common::run_day::<Day>(2021, 6);
+}
+
+pub struct Day;
+
+impl Solution for Day
+{
+    type Parsed = Vec<i32>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> ParseResult<Vec<i32>>
+    {
+        return parse(input);
+    }
+
+    fn part_one(input: &Vec<i32>) -> usize
+    {
+        return simulate_fish(input, 80);
+    }
+
+    fn part_two(input: &Vec<i32>) -> usize
+    {
+        return simulate_fish(input, 256);
     }
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+fn parse(input: &str) -> ParseResult<Vec<i32>>
+{
+    return aoc_common::numbers(input);
 }
 
 fn simulate_fish(initial: &Vec<i32>, days: usize) -> usize
@@ -1,28 +1,36 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
-use num_integer::binomial;
+use aoc_common::{ParseResult, Solution};
 
 fn main() {
-    if let Ok(lines) = read_lines("src/7/input.txt") {
-        let init:Vec<i32> = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap())
-            .map(|r| r.split(',').map(|r| r.parse::<i32>().unwrap()).collect::<Vec<i32>>())
-            .flatten()
-            .collect();
-
-        let result = get_fuel_needed(&init);
-        println!("Position: {},  Fuel: {}", result.0, result.1);
-        let result1 = get_fuel_needed2(&init);
-        println!("Position2: {},  Fuel2: {}", result1.0, result1.1);
+    aoc_common::run_day::<Day>(2021, 7);
+}
+
+pub struct Day;
+
+impl Solution for Day
+{
+    type Parsed = Vec<i32>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> ParseResult<Vec<i32>>
+    {
+        return parse(input);
+    }
+
+    fn part_one(input: &Vec<i32>) -> usize
+    {
+        return get_fuel_needed(input).1;
+    }
+
+    fn part_two(input: &Vec<i32>) -> usize
+    {
+        return get_fuel_needed2(input).1;
     }
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+fn parse(input: &str) -> ParseResult<Vec<i32>>
+{
+    return aoc_common::numbers(input);
 }
 
 fn get_fuel_needed(initial: &Vec<i32>) -> (usize, usize)
@@ -72,7 +80,7 @@ fn get_fuel_to2(initial: &Vec<i32>, position: i32) -> usize
 {
     return initial.iter()
         .map(|v| i32::abs(v - position) as usize)
-        .map(|v| binomial(v+1, 2))
+        .map(|v| aoc_common::binomial(v as i128 + 1, 2).unwrap() as usize)
         .fold(0, |a,b| a + b)
 }
 
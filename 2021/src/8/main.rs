@@ -1,35 +1,75 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
 use std::iter::FromIterator;
+use aoc_common::{ParseError, ParseResult, Solution};
 
 fn main() {
-    if let Ok(lines) = read_lines("src/8/input.txt") {
-        let input:Vec<String> = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap())
+    aoc_common::run_day::<Day>(2021, 8);
+}
+
+pub struct Day;
+
+impl Solution for Day
+{
+    type Parsed = Vec<Entry>;
+    type PartOne = usize;
+    type PartTwo = i32;
+
+    fn parse(input: &str) -> ParseResult<Vec<Entry>>
+    {
+        return aoc_common::lines(input)
+            .map(|line| parse_line(input, line))
             .collect();
+    }
 
-        println!("Part 1: {}", part_one(&input));
-        println!("Part 2: {}", part_two(&input));
+    fn part_one(input: &Vec<Entry>) -> usize
+    {
+        return input.iter()
+            .map(|v| v.1.iter())
+            .flatten()
+            .map(|v| v.len())
+            .filter(|v| *v==2 || *v==4 || *v==3 || *v==7)
+            .count();
     }
-}
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+    fn part_two(input: &Vec<Entry>) -> i32
+    {
+        return input.iter()
+            .map(|v| decode_line(v))
+            .fold(0, |a,b| a+b);
+    }
 }
 
-fn part_one(input: &Vec<String>) -> usize
+/// The ten unique signal patterns and the four output digits of one display.
+type Entry = (Vec<String>, Vec<String>);
+
+/// Parses `line`, a line of `input`.
+fn parse_line(input: &str, line: &str) -> ParseResult<Entry>
 {
-    return input.iter()
-        .map(|v| v.split(" | ").nth(1))
-        .map(|v| v.unwrap().split(" "))
-        .flatten()
-        .map(|v| v.len())
-        .filter(|v| *v==2 || *v==4 || *v==3 || *v==7)
-        .count();
+    let (patterns, outputs) = line.split_once(" | ")
+        .ok_or_else(|| ParseError::at_slice(input, line, "expected 'patterns | outputs'"))?;
+    let words = |text: &str, count: usize| -> ParseResult<Vec<String>> {
+        let words: Vec<&str> = text.split(' ').collect();
+        if words.len() != count
+        {
+            return Err(ParseError::at_slice(input, text, format!("expected {} patterns, got {}", count, words.len())));
+        }
+        for word in &words
+        {
+            if !(2..=7).contains(&word.len()) || !word.chars().all(|c| ('a'..='g').contains(&c))
+            {
+                return Err(ParseError::at_slice(input, word, "expected 2 to 7 segments a-g"));
+            }
+        }
+        return Ok(words.iter().map(|w| w.to_string()).collect());
+    };
+    let patterns = words(patterns, 10)?;
+    for len in [2, 4].iter()
+    {
+        if !patterns.iter().any(|p| p.len() == *len)
+        {
+            return Err(ParseError::at_slice(input, line, format!("no pattern with {} segments", len)));
+        }
+    }
+    return Ok((patterns, words(outputs, 4)?));
 }
 
 fn overlap_count(a: &str, b: &String) -> i32
@@ -71,10 +111,9 @@ fn translate_six(item: &String, one: &str, four: &str) -> i32
     }
 }
 
-fn decode_line(input: &String) -> i32
+fn decode_line(input: &Entry) -> i32
 {
-    let replaced = input.replace(" | ", " ");
-    let items:Vec<&str> = replaced.split(" ").collect();
+    let items:Vec<&str> = input.0.iter().chain(input.1.iter()).map(|v| v.as_str()).collect();
     let one = items.iter().find(|v| v.len() == 2).cloned().unwrap();
     let four = items.iter().find(|v| v.len() == 4).cloned().unwrap();
 
@@ -105,13 +144,6 @@ fn decode_line(input: &String) -> i32
         (translated[end-4]*1000);
 }
 
-fn part_two(input: &Vec<String>) -> i32
-{
-    return input.iter()
-        .map(|v| decode_line(v))
-        .fold(0, |a,b| a+b);
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -131,7 +163,7 @@ mod tests {
             String::from("egadfb cdbfeg cegd fecab cgb gbdefca cg fgcdab egfdb bfceg | gbdfcae bgc cg cgb"),
             String::from("gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce")
         ];
-        assert_eq!(26, part_one(&input));
+        assert_eq!(26, Day::part_one(&Day::parse(&input.join("\n")).unwrap()));
     }
 
     #[test]
@@ -149,13 +181,24 @@ mod tests {
             String::from("egadfb cdbfeg cegd fecab cgb gbdefca cg fgcdab egfdb bfceg | gbdfcae bgc cg cgb"),
             String::from("gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce")
         ];
-        assert_eq!(61229, part_two(&input));
+        assert_eq!(61229, Day::part_two(&Day::parse(&input.join("\n")).unwrap()));
     }
 
     #[test]
     fn test_decode_line() 
     {
-        let input = String::from("acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf");
-        assert_eq!(5353, decode_line(&input));
+        let input = "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf";
+        assert_eq!(5353, decode_line(&parse_line(input, input).unwrap()));
+    }
+
+    #[test]
+    fn test_parse_errors()
+    {
+        let input = "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbah";
+        let err = Day::parse(input).unwrap_err();
+        assert_eq!((1, 80), (err.line, err.column));
+        assert_eq!("expected 2 to 7 segments a-g", err.message);
+        assert_eq!("expected 4 patterns, got 3", Day::parse(&input[..78]).unwrap_err().message);
+        assert!(Day::parse("ab cd").is_err());
     }
 }
\ No newline at end of file
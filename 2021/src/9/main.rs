@@ -1,124 +1,71 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use aoc_common::{Grid, ParseResult, Pos, Solution};
 
 fn main() {
-    if let Ok(lines) = read_lines("src/9/input.txt") {
-        let input:Vec<Vec<u8>> = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap())
-            .map(|r| r.chars().map(|v| v.to_string().parse::<u8>().unwrap()).collect())
-            .collect();
-
-        println!("Part 1: {}", part_one(&input));
-        println!("Part 2: {}", part_two(&input));
-    }
+    aoc_common::run_day::<Day>(2021, 9);
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
+pub struct Day;
 
-fn check_row(input: &Vec<u8>, val: u8, x: usize) -> bool
+impl Solution for Day
 {
-    let mut result = input[x] >= val &&
-        input.get(x + 1).map(|v| *v >= val).unwrap_or(true);
+    type Parsed = Grid<u8>;
+    type PartOne = usize;
+    type PartTwo = usize;
 
-    if x > 0
+    fn parse(input: &str) -> ParseResult<Grid<u8>>
     {
-        result = result && input.get(x - 1).map(|v| *v >= val).unwrap_or(true);
+        return Grid::digits(input);
     }
-    return result;
-}
 
-fn is_low_point(input: &Vec<Vec<u8>>, x: usize, y: usize) -> bool
-{
-    let target = input[y][x];
-
-    let mut result = check_row(&input[y], target, x) &&
-        input.get(y + 1).map(|v| check_row(v, target, x)).unwrap_or(true);
-
-    if y > 0
+    fn part_one(input: &Grid<u8>) -> usize
     {
-        result = result && input.get(y - 1).map(|v| check_row(v, target, x)).unwrap_or(true);
+        return get_low_points(input).iter()
+            .map(|c| input[*c])
+            .map(|v| (v + 1) as usize)
+            .fold(0, |a,b| a + b);
     }
-    return result;
-}
 
-fn get_low_points(input: &Vec<Vec<u8>>) -> Vec<(usize, usize)>
-{
-    let mut low_points = Vec::new();
-    for y in 0..input.len()
+    fn part_two(input: &Grid<u8>) -> usize
     {
-        for x in 0..input[0].len()
-        {
-            if is_low_point(input, x, y)
-            {
-                low_points.push((x,y));
-            }
-        }
+        let mut sizes:Vec<usize> = get_low_points(input).iter()
+            .map(|c| get_basin(input, c).len())
+            .collect();
+        sizes.sort();
+
+        return sizes[sizes.len() - 3..].iter().fold(1, |a,b| a * b);
     }
-    return low_points;
 }
 
-fn part_one(input: &Vec<Vec<u8>>) -> usize
+fn is_low_point(input: &Grid<u8>, point: Pos) -> bool
 {
-    return get_low_points(input).iter()
-        .map(|c| input[c.1][c.0])
-        .map(|v| (v + 1) as usize)
-        .fold(0, |a,b| a + b);
+    let target = input[point];
+    return input.neighbours4(point).all(|p| input[p] >= target);
 }
 
-fn get_basin(input: &Vec<Vec<u8>>, point: &(usize,usize)) -> Vec<(usize, usize)>
+fn get_low_points(input: &Grid<u8>) -> Vec<Pos>
 {
-    // println!("checking {:?}", point);
-    let mut candidates = Vec::new();
-
-    if point.1 < input.len()-1
-    {
-        candidates.push((point.0, point.1 + 1));
-    }
-    if point.1 > 0
-    {
-        candidates.push((point.0, point.1 - 1));
-    }
-    if point.0 < input[0].len()-1
-    {
-        candidates.push((point.0 + 1, point.1));
-    }
-    if point.0 > 0
-    {
-        candidates.push((point.0 - 1, point.1));
-    }
+    return input.positions()
+        .filter(|p| is_low_point(input, *p))
+        .collect();
+}
 
-    let current = input[point.1][point.0];
-    let mut result:Vec<(usize, usize)> = candidates.iter()
+fn get_basin(input: &Grid<u8>, point: &Pos) -> Vec<Pos>
+{
+    let current = input[*point];
+    let mut result:Vec<Pos> = input.neighbours4(*point)
         .filter(|c| {
-            let val = input[c.1][c.0];
+            let val = input[*c];
             return val != 9 && val > current;
         })
-        .map(|c| get_basin(input, c))
+        .map(|c| get_basin(input, &c))
         .flatten()
         .collect();
     result.push(*point);
     result.sort_unstable();
     result.dedup();
-    // println!("returning {:?}", result);
     return result;
 }
 
-fn part_two(input: &Vec<Vec<u8>>) -> usize
-{
-    let mut sizes:Vec<usize> = get_low_points(input).iter()
-        .map(|c| get_basin(input, c).len())
-        .collect();
-    sizes.sort();
-
-    return sizes[sizes.len() - 3..].iter().fold(1, |a,b| a * b);
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -126,26 +73,24 @@ mod tests {
     #[test]
     fn test_part_one() 
     {
-        let input = vec![
-            vec![2,1,9,9,9,4,3,2,1,0],
-            vec![3,9,8,7,8,9,4,9,2,1],
-            vec![9,8,5,6,7,8,9,8,9,2],
-            vec![8,7,6,7,8,9,6,7,8,9],
-            vec![9,8,9,9,9,6,5,6,7,8]
-        ];
-        assert_eq!(15, part_one(&input));
+        let input = "2199943210
+3987894921
+9856789892
+8767896789
+9899965678";
+        assert_eq!(15, Day::part_one(&Day::parse(input).unwrap()));
     }
 
     #[test]
     fn test_get_basin() 
     {
-        let input = vec![
+        let input = Grid::from_rows(vec![
             vec![2,1,9,9,9,4,3,2,1,0],
             vec![3,9,8,7,8,9,4,9,2,1],
             vec![9,8,5,6,7,8,9,8,9,2],
             vec![8,7,6,7,8,9,6,7,8,9],
             vec![9,8,9,9,9,6,5,6,7,8]
-        ];
+        ]);
         // assert_eq!(3, get_basin(&input, &(1, 0)).len());
         // assert_eq!(9, get_basin(&input, &(9, 0)).len());
         assert_eq!(14, get_basin(&input, &(2, 2)).len());
@@ -155,13 +100,11 @@ mod tests {
     #[test]
     fn test_part_two() 
     {
-        let input = vec![
-            vec![2,1,9,9,9,4,3,2,1,0],
-            vec![3,9,8,7,8,9,4,9,2,1],
-            vec![9,8,5,6,7,8,9,8,9,2],
-            vec![8,7,6,7,8,9,6,7,8,9],
-            vec![9,8,9,9,9,6,5,6,7,8]
-        ];
-        assert_eq!(1134, part_two(&input));
+        let input = "2199943210
+3987894921
+9856789892
+8767896789
+9899965678";
+        assert_eq!(1134, Day::part_two(&Day::parse(input).unwrap()));
     }
 }
\ No newline at end of file
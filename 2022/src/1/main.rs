@@ -1,47 +1,53 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use aoc_common::{ParseError, ParseResult, Solution};
 
 fn main() {
-    if let Ok(lines) = read_lines("src/1/input.txt") {
-        let list = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap())
-            .collect();
-
-        let result = highest_elf(&list);
-        println!("Part 1: {}", result[0]);
-        println!("Part 2: {}", result[0]+result[1]+result[2]);
-    }
+    aoc_common::run_day::<Day>(2022, 1);
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
+pub struct Day;
 
-pub fn highest_elf(numbers: &Vec<String>) -> Vec<u32>
+impl Solution for Day
 {
-    let mut items = Vec::new();
-    let mut max = Vec::new();
+    type Parsed = Vec<Vec<u32>>;
+    type PartOne = u32;
+    type PartTwo = u32;
 
-    for item in numbers
+    fn parse(input: &str) -> ParseResult<Vec<Vec<u32>>>
     {
-        if item.is_empty()
-        {
-            let sum = items.iter().fold(0, |acc, x| acc + x);
-            items.clear();
+        return parse(input);
+    }
 
-            max.push(sum);
-        }
-        else
-        {
-            items.push(item.parse::<u32>().unwrap());
-        }
+    fn part_one(input: &Vec<Vec<u32>>) -> u32
+    {
+        return highest_elf(input)[0];
     }
-    let sum = items.iter().fold(0, |acc, x| acc + x);
-    max.push(sum);
+
+    fn part_two(input: &Vec<Vec<u32>>) -> u32
+    {
+        return highest_elf(input).iter().take(3).sum();
+    }
+}
+
+/// The calories carried by each elf, in blank-line separated groups.
+fn parse(input: &str) -> ParseResult<Vec<Vec<u32>>>
+{
+    let elves = aoc_common::paragraphs(input).iter()
+        .map(|items| items.iter()
+            .map(|item| item.parse::<u32>().map_err(|_| ParseError::at_slice(input, item, "expected a number of calories")))
+            .collect::<ParseResult<Vec<u32>>>())
+        .collect::<ParseResult<Vec<Vec<u32>>>>()?;
+    if elves.is_empty()
+    {
+        return Err(ParseError::at(input, 0, "expected at least one elf"));
+    }
+    return Ok(elves);
+}
+
+pub fn highest_elf(elves: &Vec<Vec<u32>>) -> Vec<u32>
+{
+    let mut max: Vec<u32> = elves.iter()
+        .map(|items| items.iter().fold(0, |acc, x| acc + x))
+        .collect();
 
     max.sort_by(|a, b| b.cmp(a));
 
@@ -56,7 +62,7 @@ mod tests {
     #[test]
     fn test_highest_elf() 
     {
-        let input = vec![
+        let input = parse(&vec![
             String::from("1000"),
             String::from("2000"),
             String::from("3000"),
@@ -71,9 +77,17 @@ mod tests {
             String::from("9000"),
             String::from(""),
             String::from("10000")
-        ];
+        ].join("\n")).unwrap();
         assert_eq!(24000, highest_elf(&input)[0]);
         assert_eq!(11000, highest_elf(&input)[1]);
         assert_eq!(10000, highest_elf(&input)[2]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_errors()
+    {
+        let err = parse("199\n200\nx\n").unwrap_err();
+        assert_eq!((3, 1), (err.line, err.column));
+        assert!(parse("").is_err());
+    }
+}
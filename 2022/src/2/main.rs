@@ -1,45 +1,67 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use aoc_common::{ParseError, ParseResult, Solution};
 
 fn main() {
-    if let Ok(lines) = read_lines("src/2/input.txt") {
-        let list = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap())
-            .collect();
-
-        println!("Part 1: {}", score_strategy(&list));
-        println!("Part 2: {}", score_strategy2(&list));
+    aoc_common::run_day::<Day>(2022, 2);
+}
+
+pub struct Day;
+
+impl Solution for Day
+{
+    type Parsed = Vec<(char, char)>;
+    type PartOne = u32;
+    type PartTwo = u32;
+
+    fn parse(input: &str) -> ParseResult<Vec<(char, char)>>
+    {
+        return parse(input);
+    }
+
+    fn part_one(input: &Vec<(char, char)>) -> u32
+    {
+        return score_strategy(input);
+    }
+
+    fn part_two(input: &Vec<(char, char)>) -> u32
+    {
+        return score_strategy2(input);
     }
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+/// The rounds of the strategy guide, such as `A Y`: their shape from `ABC`
+/// and the second column from `XYZ`.
+fn parse(input: &str) -> ParseResult<Vec<(char, char)>>
+{
+    return aoc_common::lines(input)
+        .map(|round| {
+            let chars: Vec<char> = round.chars().collect();
+            return match chars[..]
+            {
+                [theirs @ 'A'..='C', ' ', mine @ 'X'..='Z'] => Ok((theirs, mine)),
+                _ => Err(ParseError::at_slice(input, round, "expected a round such as 'A Y'"))
+            };
+        })
+        .collect();
 }
 
-pub fn score_strategy(strat: &Vec<String>) -> u32
+pub fn score_strategy(strat: &Vec<(char, char)>) -> u32
 {
     let mut score = 0;
     for round in strat
     {
-        let theirs = round.chars().nth(0).unwrap();
-        let mine = round.chars().nth(2).unwrap();
+        let (theirs, mine) = *round;
         score = score + score_mine(mine);
         score = score + score_game(theirs, mine);
     }
     return score;
 }
 
-pub fn score_strategy2(strat: &Vec<String>) -> u32
+pub fn score_strategy2(strat: &Vec<(char, char)>) -> u32
 {
     let mut score = 0;
     for round in strat
     {
-        let theirs = round.chars().nth(0).unwrap();
-        let result = round.chars().nth(2).unwrap();
+        let (theirs, result) = *round;
         let mine = pick_mine(theirs, result);
         score = score + score_mine(mine);
         score = score + score_game2(result);
@@ -127,22 +149,23 @@ mod tests {
     #[test]
     fn test_score_strategy() 
     {
-        let input = vec![
-            String::from("A Y"),
-            String::from("B X"),
-            String::from("C Z")
-        ];
+        let input = parse("A Y\nB X\nC Z\n").unwrap();
         assert_eq!(15, score_strategy(&input));
     }
 
     #[test]
     fn test_score_strategy2() 
     {
-        let input = vec![
-            String::from("A Y"),
-            String::from("B X"),
-            String::from("C Z")
-        ];
+        let input = parse("A Y\nB X\nC Z\n").unwrap();
         assert_eq!(12, score_strategy2(&input));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_errors()
+    {
+        assert_eq!(vec![('A', 'Y'), ('C', 'Z')], parse("A Y\nC Z\n").unwrap());
+        let err = parse("A Y\nD Z\n").unwrap_err();
+        assert_eq!((2, 1), (err.line, err.column));
+        assert!(parse("A Y\nAY\n").is_err());
+    }
+}
@@ -1,113 +1,96 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use aoc_common::{BitSet, ParseError, ParseResult, Solution};
 
 fn main() {
-    if let Ok(lines) = read_lines("src/3/input.txt") {
-        let list = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap())
-            .collect();
-
-        println!("Part 1: {}", part_one(&list));
-        println!("Part 2: {}", part_two(&list));
-    }
+    aoc_common::run_day::<Day>(2022, 3);
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
+pub struct Day;
 
-pub fn part_one(bags: &Vec<String>) -> usize
+impl Solution for Day
 {
-    return bags.iter()
-        .map(|x| score_bag(x))
-        .fold(0, |x,acc| x + acc);
-}
+    type Parsed = Vec<String>;
+    type PartOne = usize;
+    type PartTwo = usize;
 
-pub fn score_bag(bag: &String) -> usize
-{
-    let mut scores: [u8; 52] = [0; 52];
+    fn parse(input: &str) -> ParseResult<Vec<String>>
+    {
+        return parse(input);
+    }
 
-    let len = bag.chars().count() / 2;
-    let mut i = 0;
-    for c in bag.chars()
+    fn part_one(bags: &Vec<String>) -> usize
     {
-        let index = get_priority(c);
-        let score = scores[index];
+        return bags.iter()
+            .map(|x| score_bag(x))
+            .fold(0, |x,acc| x + acc);
+    }
 
-        if i >= len
-        {
-            if score > 0
-            {
-                return index + 1;
-            }
-        }
-        else
+    fn part_two(bags: &Vec<String>) -> usize
+    {
+        let mut it = bags.iter();
+        let mut score = 0;
+        while let Some(line) = it.next()
         {
-            scores[index] = score + 1;
-            i = i + 1;
+            score = score + sticker_score(line, it.next().unwrap(), it.next().unwrap());
         }
+        return score;
     }
-
-    panic!("ahhhh");
 }
 
-pub fn get_priority(c : char) -> usize
+/// The rucksacks, one per line.  Each holds letters split evenly between two
+/// compartments that share an item, and each group of three shares a badge.
+fn parse(input: &str) -> ParseResult<Vec<String>>
 {
-    let val = c as usize;
-    let a = 'a' as usize;
-    let b = 'A' as usize;
-    if val > a
+    let bags: Vec<&str> = aoc_common::lines(input).collect();
+    for bag in &bags
     {
-        return val - a;
+        if let Some(i) = bag.find(|c: char| !c.is_ascii_alphabetic())
+        {
+            return Err(ParseError::at_slice(input, &bag[i..], "expected an item from 'a' to 'z' or 'A' to 'Z'"));
+        }
+        let (first, second) = bag.split_at(bag.len() / 2);
+        if bag.len() % 2 != 0 || mask_bag(first).intersection(&mask_bag(second)).min().is_none()
+        {
+            return Err(ParseError::at_slice(input, bag, "expected two compartments of the same size sharing an item"));
+        }
     }
-    else
+    if bags.len() % 3 != 0
     {
-        return (val - b) + 26;
+        return Err(ParseError::at(input, input.len(), "expected the rucksacks in groups of three"));
     }
-}
-
-pub fn part_two(bags: &Vec<String>) -> usize
-{
-    let mut it = bags.iter();
-    let mut score = 0;
-    while let Some(line) = it.next()
+    for group in bags.chunks(3)
     {
-        score = score + sticker_score(line, it.next().unwrap(), it.next().unwrap());
+        let shared = mask_bag(group[0]).intersection(&mask_bag(group[1])).intersection(&mask_bag(group[2]));
+        if shared.min().is_none()
+        {
+            return Err(ParseError::at_slice(input, group[0], "expected the group of three to share an item"));
+        }
     }
-    return score;
+    return Ok(bags.iter().map(|b| b.to_string()).collect());
 }
 
-pub fn sticker_score(bag1: &String, bag2: &String, bag3: &String) -> usize
+pub fn score_bag(bag: &String) -> usize
 {
-    let mut mask:u64 = 0xffffffffffffffff;
-    mask = mask & mask_bag(bag1);
-    mask = mask & mask_bag(bag2);
-    mask = mask & mask_bag(bag3);
-
-    let mut i = 1;
-    while mask & 1 == 0
-    {
-        mask = mask >> 1;
-        i = i + 1;
-    }
-    return i;
+    let (first, second) = bag.split_at(bag.len() / 2);
+    let shared = mask_bag(first).intersection(&mask_bag(second));
+    return shared.min().expect("no item in both compartments") + 1;
 }
 
-pub fn mask_bag(bag: &String) -> u64
+pub fn get_priority(c : char) -> usize
 {
-    let mut mask:u64 = 0;
+    return BitSet::letter_index(c).unwrap();
+}
 
-    for c in bag.chars()
-    {
-        let index = get_priority(c);
-        mask = mask | (1 << index);
-    }
+pub fn sticker_score(bag1: &String, bag2: &String, bag3: &String) -> usize
+{
+    let mask = mask_bag(bag1)
+        .intersection(&mask_bag(bag2))
+        .intersection(&mask_bag(bag3));
+    return mask.min().expect("no item in all three bags") + 1;
+}
 
-    return mask;
+pub fn mask_bag(bag: &str) -> BitSet
+{
+    return BitSet::from_letters(bag);
 }
 
 #[cfg(test)]
@@ -148,7 +131,7 @@ mod tests {
             String::from("ttgJtRGJQctTZtZT"),
             String::from("CrZsJsPPZsGzwwsLwLmpwMDw")
         ];
-        assert_eq!(157, part_one(&input));
+        assert_eq!(157, Day::part_one(&Day::parse(&input.join("\n")).unwrap()));
     }
 
     #[test]
@@ -162,6 +145,19 @@ mod tests {
             String::from("ttgJtRGJQctTZtZT"),
             String::from("CrZsJsPPZsGzwwsLwLmpwMDw")
         ];
-        assert_eq!(70, part_two(&input));
+        assert_eq!(70, Day::part_two(&Day::parse(&input.join("\n")).unwrap()));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_errors()
+    {
+        assert!(Day::parse("abca\nbcdb\ncdec\n").is_ok());
+        let err = Day::parse("abca\nbc1b\ncdec\n").unwrap_err();
+        assert_eq!((2, 3), (err.line, err.column));
+        let err = Day::parse("abca\nbcde\ncdec\n").unwrap_err();
+        assert_eq!((2, 1), (err.line, err.column));
+        let err = Day::parse("abca\nbcdb\nxyzx\n").unwrap_err();
+        assert_eq!((1, 1), (err.line, err.column));
+        assert!(Day::parse("abca\nbcdb\n").is_err());
+    }
+}
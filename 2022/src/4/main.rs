@@ -1,77 +1,70 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use aoc_common::{IntervalSet, ParseError, ParseResult, Solution};
 
 fn main() {
-    if let Ok(lines) = read_lines("src/4/input.txt") {
-        let list = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap())
-            .map(|r| parse_input(&r))
-            .collect();
-
-        println!("Part 1: {}", part_one(&list));
-        println!("Part 2: {}", part_two(&list));
-    }
+    aoc_common::run_day::<Day>(2022, 4);
 }
 
-fn parse_input(input: &str) -> (Area, Area)
-{
-    let mut split = input.split(',');
-    return (parse_area(split.next().unwrap()), parse_area(split.next().unwrap()));
-}
+pub struct Day;
 
-fn parse_area(input: &str) -> Area
+impl Solution for Day
 {
-    let mut split = input.split('-');
-    return Area::of(split.next().unwrap().parse::<i32>().unwrap(), split.next().unwrap().parse::<i32>().unwrap());
-}
+    type Parsed = Vec<(IntervalSet, IntervalSet)>;
+    type PartOne = usize;
+    type PartTwo = usize;
 
-#[derive(Debug)]
-pub struct Area
-{
-    start : i32, 
-    end : i32
-}
-impl Area
-{
-    fn of(s: i32, e: i32) -> Area
+    fn parse(input: &str) -> ParseResult<Vec<(IntervalSet, IntervalSet)>>
     {
-        return  Area{
-            start: s,
-            end: e
-        };
+        return parse(input);
     }
 
-    fn contains(&self, b: &Area) -> bool
+    fn part_one(input: &Vec<(IntervalSet, IntervalSet)>) -> usize
     {
-        return self.start <= b.start && self.end >= b.end;
+        return count_contained(input);
     }
 
-    fn overlaps(&self, b: &Area) -> bool
+    fn part_two(input: &Vec<(IntervalSet, IntervalSet)>) -> usize
     {
-        return (b.start >= self.start && b.start <= self.end) ||
-            (b.end >= self.start && b.end <= self.end);
+        return count_overlapping(input);
     }
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+fn parse(input: &str) -> ParseResult<Vec<(IntervalSet, IntervalSet)>>
+{
+    return aoc_common::lines(input)
+        .map(|r| parse_input(input, r))
+        .collect();
 }
 
-pub fn part_one(areas: &Vec<(Area, Area)>) -> usize
+/// A pair of assignments such as `2-4,6-8`, from a line of `input`.
+fn parse_input(input: &str, line: &str) -> ParseResult<(IntervalSet, IntervalSet)>
+{
+    let (first, second) = line.split_once(',')
+        .ok_or_else(|| ParseError::at_slice(input, line, "expected a pair of assignments such as '2-4,6-8'"))?;
+    return Ok((parse_area(input, first)?, parse_area(input, second)?));
+}
+
+fn parse_area(input: &str, area: &str) -> ParseResult<IntervalSet>
+{
+    let range = area.split_once('-')
+        .and_then(|(start, end)| Some((start.parse::<u32>().ok()?, end.parse::<u32>().ok()?)));
+    return match range
+    {
+        Some((start, end)) if start <= end => Ok(IntervalSet::inclusive(start as i64, end as i64)),
+        _ => Err(ParseError::at_slice(input, area, "expected a range of sections such as '2-4'"))
+    };
+}
+
+fn count_contained(areas: &Vec<(IntervalSet, IntervalSet)>) -> usize
 {
     return areas.iter()
-        .filter(|a| a.0.contains(&a.1) || a.1.contains(&a.0))
+        .filter(|a| a.0.is_superset(&a.1) || a.1.is_superset(&a.0))
         .count();
 }
 
-pub fn part_two(areas: &Vec<(Area, Area)>) -> usize
+fn count_overlapping(areas: &Vec<(IntervalSet, IntervalSet)>) -> usize
 {
     return areas.iter()
-        .filter(|a| a.0.overlaps(&a.1) || a.1.overlaps(&a.0))
+        .filter(|a| a.0.overlaps(&a.1))
         .count();
 }
 
@@ -83,27 +76,37 @@ mod tests {
     fn test_part_one() 
     {
         let input = vec![
-            (Area::of(2,4),Area::of(6,8)),
-            (Area::of(2,3),Area::of(4,5)),
-            (Area::of(5,7),Area::of(7,9)),
-            (Area::of(2,8),Area::of(3,7)),
-            (Area::of(6,6),Area::of(4,6)),
-            (Area::of(2,6),Area::of(4,8))
+            (IntervalSet::inclusive(2,4),IntervalSet::inclusive(6,8)),
+            (IntervalSet::inclusive(2,3),IntervalSet::inclusive(4,5)),
+            (IntervalSet::inclusive(5,7),IntervalSet::inclusive(7,9)),
+            (IntervalSet::inclusive(2,8),IntervalSet::inclusive(3,7)),
+            (IntervalSet::inclusive(6,6),IntervalSet::inclusive(4,6)),
+            (IntervalSet::inclusive(2,6),IntervalSet::inclusive(4,8))
         ];
-        assert_eq!(2, part_one(&input));
+        assert_eq!(2, count_contained(&input));
     }
 
     #[test]
     fn test_part_two() 
     {
         let input = vec![
-            (Area::of(2,4),Area::of(6,8)),
-            (Area::of(2,3),Area::of(4,5)),
-            (Area::of(5,7),Area::of(7,9)),
-            (Area::of(2,8),Area::of(3,7)),
-            (Area::of(6,6),Area::of(4,6)),
-            (Area::of(2,6),Area::of(4,8))
+            (IntervalSet::inclusive(2,4),IntervalSet::inclusive(6,8)),
+            (IntervalSet::inclusive(2,3),IntervalSet::inclusive(4,5)),
+            (IntervalSet::inclusive(5,7),IntervalSet::inclusive(7,9)),
+            (IntervalSet::inclusive(2,8),IntervalSet::inclusive(3,7)),
+            (IntervalSet::inclusive(6,6),IntervalSet::inclusive(4,6)),
+            (IntervalSet::inclusive(2,6),IntervalSet::inclusive(4,8))
         ];
-        assert_eq!(4, part_two(&input));
+        assert_eq!(4, count_overlapping(&input));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_errors()
+    {
+        assert_eq!(vec![(IntervalSet::inclusive(2, 4), IntervalSet::inclusive(6, 8))], parse("2-4,6-8\n").unwrap());
+        let err = parse("2-4,6-8\n2-3,5-4\n").unwrap_err();
+        assert_eq!((2, 5), (err.line, err.column));
+        let err = parse("2-4,6-8\n2-3;4-5\n").unwrap_err();
+        assert_eq!((2, 1), (err.line, err.column));
+    }
+}
@@ -1,24 +1,116 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use aoc_common::{ParseError, ParseResult, Solution, Unsolved};
 
 fn main() {
-    if let Ok(lines) = read_lines("src/5/input.txt") {
-        let strings = lines
-            .filter(|r| r.is_ok())
-            .map(|r| r.unwrap())
-            .collect();
-        let input = parse_input(&strings);
-
-        println!("Part 1: {}", part_one(&input.0, &input.1));
+    aoc_common::run_day::<Day>(2022, 5);
+}
+
+pub struct Day;
+
+impl Solution for Day
+{
+    type Parsed = (Vec<Vec<char>>, Vec<Move>);
+    type PartOne = String;
+    type PartTwo = Unsolved;
+
+    const PARTS: u32 = 1;
+
+    fn parse(input: &str) -> ParseResult<(Vec<Vec<char>>, Vec<Move>)>
+    {
+        return parse_input(input);
+    }
+
+    fn part_one(input: &(Vec<Vec<char>>, Vec<Move>)) -> String
+    {
+        return top_crates(&input.0, &input.1);
+    }
+
+    fn part_two(_input: &(Vec<Vec<char>>, Vec<Move>)) -> Unsolved
+    {
+        return Unsolved;
     }
 }
 
-fn parse_input(input: &Vec<String>) -> (Vec<Vec<char>>, Vec<Move>)
+/// The stacks from bottom to top, and the moves with stacks counted from 0.
+fn parse_input(input: &str) -> ParseResult<(Vec<Vec<char>>, Vec<Move>)>
 {
-    let crates:Vec<Vec<char>> = Vec::new();
-    let moves:Vec<Move> = Vec::new();
-    return (vec![], vec![]);
+    let paragraphs = aoc_common::paragraphs(input);
+    if paragraphs.len() != 2
+    {
+        return Err(ParseError::at(input, 0, "expected the stacks, a blank line, then the moves"));
+    }
+    let (labels, rows) = paragraphs[0].split_last().unwrap();
+    let crates = parse_crates(input, labels, rows)?;
+
+    // Only the heights are needed to check every move has enough to take.
+    let mut heights:Vec<usize> = crates.iter().map(|x| x.len()).collect();
+    let mut moves = Vec::new();
+    for line in paragraphs[1].iter()
+    {
+        let m = parse_move(line, crates.len()).map_err(|e| ParseError::at_slice(input, line, e))?;
+        if heights[m.src] < m.qty
+        {
+            return Err(ParseError::at_slice(input, line, format!("stack {} only has {} crates", m.src + 1, heights[m.src])));
+        }
+        heights[m.src] = heights[m.src] - m.qty;
+        heights[m.dst] = heights[m.dst] + m.qty;
+        moves.push(m);
+    }
+    return Ok((crates, moves));
+}
+
+fn parse_crates(input: &str, labels: &str, rows: &[&str]) -> ParseResult<Vec<Vec<char>>>
+{
+    let count = labels.split_whitespace().count();
+    for (i, label) in labels.split_whitespace().enumerate()
+    {
+        if label != (i + 1).to_string()
+        {
+            return Err(ParseError::at_slice(input, label, format!("expected stack {}", i + 1)));
+        }
+    }
+
+    let mut crates = vec![Vec::new(); count];
+    for (height, row) in rows.iter().rev().enumerate()
+    {
+        if !row.is_ascii()
+        {
+            return Err(ParseError::at_slice(input, row, "expected a row of crates"));
+        }
+        let chars:Vec<char> = row.chars().collect();
+        for (x, cell) in chars.chunks(4).enumerate()
+        {
+            let at = |i: usize| ParseError::at_slice(input, &row[x * 4 + i..], "expected a crate such as '[A]' over each stack");
+            match cell
+            {
+                [' ', ' ', ' '] | [' ', ' ', ' ', ' '] => continue,
+                ['[', c, ']'] | ['[', c, ']', ' '] if c.is_ascii_uppercase() && x < count => {
+                    if crates[x].len() != height
+                    {
+                        return Err(ParseError::at_slice(input, &row[x * 4..], "the crate is floating in mid-air"));
+                    }
+                    crates[x].push(*c);
+                }
+                _ => return Err(at(cell.iter().position(|c| !"[] ".contains(*c)).unwrap_or(0)))
+            }
+        }
+    }
+    return Ok(crates);
+}
+
+/// A `move N from A to B` line with `count` stacks.
+fn parse_move(line: &str, count: usize) -> Result<Move, String>
+{
+    let words:Vec<&str> = line.split(' ').collect();
+    if words.len() != 6 || words[0] != "move" || words[2] != "from" || words[4] != "to"
+    {
+        return Err("expected 'move N from A to B'".to_string());
+    }
+    let number = |w: &str| w.parse::<usize>().map_err(|_| format!("expected a number, got '{}'", w));
+    let stack = |w: &str| number(w).and_then(|n| match n {
+        1..=9999 if n <= count => Ok(n - 1),
+        _ => Err(format!("there's no stack {}", n))
+    });
+    return Ok(Move::of(stack(words[3])?, stack(words[5])?, number(words[1])?));
 }
 
 #[derive(Debug)]
@@ -40,16 +132,19 @@ impl Move
     }
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
-
-pub fn part_one(crates: &Vec<Vec<char>>, moves: &Vec<Move>) -> String
+fn top_crates(crates: &Vec<Vec<char>>, moves: &Vec<Move>) -> String
 {
+    let mut crates = crates.clone();
+    for m in moves
+    {
+        for _ in 0..m.qty
+        {
+            let c = crates[m.src].pop().unwrap();
+            crates[m.dst].push(c);
+        }
+    }
     return crates.iter()
-        .map(|x| x[x.len() - 1])
+        .filter_map(|x| x.last())
         .collect();
 }
 
@@ -68,10 +163,41 @@ mod tests {
         let moves = vec![
             Move::of(1, 0, 1),
             Move::of(0, 2, 3),
-            Move::of(1, 0, 1),
-            Move::of(0, 1, 2),
+            Move::of(1, 0, 2),
+            Move::of(0, 1, 1),
         ];
-        assert_eq!("CMZ", part_one(&crates, &moves));
+        assert_eq!("CMZ", top_crates(&crates, &moves));
     }
 
+    const EXAMPLE: &str = "    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2
+";
+
+    #[test]
+    fn test_parse()
+    {
+        let (crates, moves) = Day::parse(EXAMPLE).unwrap();
+        assert_eq!(vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']], crates);
+        assert_eq!((1, 0, 1), (moves[0].src, moves[0].dst, moves[0].qty));
+        assert_eq!("CMZ", Day::part_one(&(crates, moves)));
+    }
+
+    #[test]
+    fn test_parse_errors()
+    {
+        let err = Day::parse(&EXAMPLE.replace("[M]", "[m]")).unwrap_err();
+        assert_eq!((3, 6), (err.line, err.column));
+        let err = Day::parse(&EXAMPLE.replace("move 3 from 1", "move 4 from 1")).unwrap_err();
+        assert_eq!((7, "stack 1 only has 3 crates"), (err.line, err.message.as_str()));
+        let err = Day::parse(&EXAMPLE.replace("to 3", "to 4")).unwrap_err();
+        assert_eq!("there's no stack 4", err.message);
+        assert!(Day::parse("garbage").is_err());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,18 @@
+//! Each day's solution as a module so the `aoc` runner can call into it.
+//! A day's `main` is only used by its own binary.
+#![allow(dead_code)]
+
+#[path = "1/main.rs"]
+pub mod day1;
+
+#[path = "2/main.rs"]
+pub mod day2;
+
+#[path = "3/main.rs"]
+pub mod day3;
+
+#[path = "4/main.rs"]
+pub mod day4;
+
+#[path = "5/main.rs"]
+pub mod day5;
@@ -1,21 +1,48 @@
+use aoc_common::{ParseError, ParseResult, Solution};
+
 fn main() {
-    let input = include_str!("input.txt");
-    let part_one: u32 = input.lines().map(|l| parse_calibration(l)).sum();
-    println!("Part 1: {}", part_one);
-    let part_two: u32 = input.lines().map(|l| parse_calibration2(l)).sum();
-    println!("Part 2: {}", part_two);
+    aoc_common::run_day::<Day>(2023, 1);
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    /// The digits on each line, without and then with the spelled-out ones.
+    type Parsed = Vec<(Vec<u32>, Vec<u32>)>;
+    type PartOne = u32;
+    type PartTwo = u32;
+
+    fn parse(input: &str) -> ParseResult<Vec<(Vec<u32>, Vec<u32>)>> {
+        aoc_common::lines(input)
+            .enumerate()
+            .map(|(y, line)| match digits2(line) {
+                spelled if spelled.is_empty() => {
+                    Err(ParseError::at_line(input, y, 0, "no digits on the line"))
+                }
+                spelled => Ok((digits(line), spelled)),
+            })
+            .collect()
+    }
+
+    /// Lines whose digits are all spelled out have no calibration value yet.
+    fn part_one(input: &Vec<(Vec<u32>, Vec<u32>)>) -> u32 {
+        input.iter().filter_map(|(d, _)| calibration(d)).sum()
+    }
+
+    fn part_two(input: &Vec<(Vec<u32>, Vec<u32>)>) -> u32 {
+        input.iter().filter_map(|(_, d)| calibration(d)).sum()
+    }
 }
 
-fn parse_calibration(line: &str) -> u32 {
-    let digits: Vec<u32> = line
-        .chars()
-        .filter(|c| c.is_numeric())
-        .filter_map(|c| c.to_digit(10))
-        .collect();
-    (digits[0] * 10) + digits[digits.len() - 1]
+fn calibration(digits: &[u32]) -> Option<u32> {
+    Some(digits.first()? * 10 + digits.last()?)
 }
 
-fn parse_calibration2(line: &str) -> u32 {
+fn digits(line: &str) -> Vec<u32> {
+    line.chars().filter_map(|c| c.to_digit(10)).collect()
+}
+
+fn digits2(line: &str) -> Vec<u32> {
     let modified_line = line
         .replace("one", "o1e")
         .replace("two", "t2o")
@@ -26,13 +53,21 @@ fn parse_calibration2(line: &str) -> u32 {
         .replace("seven", "s7n")
         .replace("eight", "e8t")
         .replace("nine", "n9e");
-    parse_calibration(&modified_line)
+    digits(&modified_line)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn parse_calibration(line: &str) -> u32 {
+        calibration(&digits(line)).unwrap()
+    }
+
+    fn parse_calibration2(line: &str) -> u32 {
+        calibration(&digits2(line)).unwrap()
+    }
+
     #[test]
     fn test_parse_calibration() {
         assert_eq!(12, parse_calibration("1abc2"));
@@ -57,4 +92,14 @@ mod tests {
         assert_eq!(79, parse_calibration2("sevenine"));
         assert_eq!(81, parse_calibration2("eight5fourone"));
     }
+
+    #[test]
+    fn test_parse() {
+        let input = Day::parse("1abc2\ntwo1nine\neightwothree\n").unwrap();
+        assert_eq!(12 + 11, Day::part_one(&input));
+        assert_eq!(12 + 29 + 83, Day::part_two(&input));
+
+        let err = Day::parse("1abc2\nabc\n").unwrap_err();
+        assert_eq!((2, 1), (err.line, err.column));
+    }
 }
@@ -6,6 +6,7 @@ use std::{
     rc::Rc,
 };
 
+use aoc_common::{finish, Direction, ParseError, ParseResult, Point, Solution};
 use nom::{
     character::complete::{multispace0, one_of},
     combinator::map,
@@ -14,25 +15,6 @@ use nom::{
     IResult,
 };
 
-#[derive(Debug, Clone, PartialEq)]
-enum Direction {
-    North,
-    East,
-    South,
-    West,
-}
-
-impl Direction {
-    pub fn inverse(direction: &Direction) -> Direction {
-        match direction {
-            Direction::North => Direction::South,
-            Direction::East => Direction::West,
-            Direction::South => Direction::North,
-            Direction::West => Direction::East,
-        }
-    }
-}
-
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum PipeKind {
     Vertical,
@@ -65,25 +47,10 @@ impl PipeKind {
     }
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
-struct Coords {
-    x: usize,
-    y: usize,
-}
-
-impl Coords {
-    pub fn explore(&mut self, direction: &Direction) {
-        match direction {
-            Direction::North => self.y = self.y - 1,
-            Direction::East => self.x = self.x + 1,
-            Direction::South => self.y = self.y + 1,
-            Direction::West => self.x = self.x - 1,
-        }
-    }
-}
+type Coords = Point<i64>;
 
-#[derive(Debug)]
-struct Map {
+#[derive(Debug, Clone)]
+pub struct Map {
     width: usize,
     start_idx: usize,
     grid: Vec<PipeKind>,
@@ -106,19 +73,17 @@ impl Map {
     }
 
     pub fn start_coords(&self) -> Coords {
-        Coords {
-            x: self.start_idx % self.width,
-            y: self.start_idx / self.width,
-        }
+        Coords::from_pos((self.start_idx % self.width, self.start_idx / self.width))
     }
 
     pub fn pipe_at(&self, coords: &Coords) -> PipeKind {
-        if coords.x >= self.width {
-            return PipeKind::Ground;
+        match coords.to_pos() {
+            Some((x, y)) if x < self.width => {
+                let idx = x + (y * self.width);
+                self.grid.get(idx).map_or(PipeKind::Ground, |x| *x)
+            }
+            _ => PipeKind::Ground,
         }
-
-        let idx = coords.x + (coords.y * self.width);
-        self.grid.get(idx).map_or(PipeKind::Ground, |x| *x)
     }
 }
 
@@ -143,22 +108,19 @@ impl Path {
     }
 
     pub fn explore(&mut self, direction: &Direction) {
-        self.coords.explore(direction);
+        self.coords = self.coords.step(*direction);
         self.explored.push(self.coords);
     }
 
     pub fn can_explore(&self, direction: &Direction) -> bool {
-        let mut new_coords = self.coords.clone();
-        new_coords.explore(direction);
+        let new_coords = self.coords.step(*direction);
 
         if self.explored.contains(&new_coords) {
             return false;
         }
 
         let new_tile = self.map.pipe_at(&new_coords);
-        new_tile
-            .directions()
-            .contains(&Direction::inverse(&direction))
+        new_tile.directions().contains(&direction.opposite())
     }
 
     pub fn enclosed_tiles(&self) -> usize {
@@ -224,8 +186,8 @@ impl Path {
 
     fn bounding_box(&self) -> (Coords, Coords) {
         let mut top_left = Coords {
-            x: usize::MAX,
-            y: usize::MAX,
+            x: i64::MAX,
+            y: i64::MAX,
         };
         let mut bottom_right = Coords { x: 0, y: 0 };
 
@@ -303,22 +265,40 @@ impl Search {
 }
 
 fn main() {
-    let input = include_str!("input.txt");
-    let (_, map) = parse_map(input).unwrap();
+    aoc_common::run_day::<Day>(2023, 10);
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = Map;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> ParseResult<Map> {
+        if !input.contains('S') {
+            return Err(ParseError::at(input, input.len(), "no start tile 'S'"));
+        }
+        finish(input, parse_map(input))
+    }
+
+    fn part_one(map: &Map) -> usize {
+        let (_, distance) = explore(map).find_furthest_point();
+        distance
+    }
+
+    fn part_two(map: &Map) -> usize {
+        explore(map).longest_path().enclosed_tiles()
+    }
+}
+
+fn explore(map: &Map) -> Search {
     let start = map.start_coords();
-    let map = Rc::new(map);
+    let map = Rc::new(map.clone());
 
     let mut search = Search::new(map, start);
     while search.explore() != 0 {}
-    let (coords, distance) = search.find_furthest_point();
-    println!("Part one: {} ({}, {})", distance, coords.x, coords.y);
-    let longest = search.longest_path();
-    let part_two = longest.enclosed_tiles();
-    println!(
-        "Part two: {} (in path {} long)",
-        part_two,
-        longest.explored.len()
-    );
+    search
 }
 
 fn parse_map(input: &str) -> IResult<&str, Map> {
@@ -1,5 +1,7 @@
 use std::collections::HashSet;
 
+use aoc_common::{ParseError, ParseResult, Solution};
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct Galaxy {
     x: usize,
@@ -13,7 +15,7 @@ impl Galaxy {
 }
 
 #[derive(Debug, Clone)]
-struct Map {
+pub struct Map {
     galaxies: Vec<Galaxy>,
 }
 
@@ -42,16 +44,31 @@ impl Map {
 }
 
 fn main() {
-    let input = include_str!("input.txt");
-    let mut map = parse(input);
-    let mut map2 = map.clone();
-    map.expand_universe(2);
-    map2.expand_universe(1000000);
-
-    let part_one: usize = distance_between_pairs(&map);
-    println!("Part one: {}", part_one);
-    let part_two: usize = distance_between_pairs(&map2);
-    println!("Part two: {}", part_two);
+    aoc_common::run_day::<Day>(2023, 11);
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = Map;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> ParseResult<Map> {
+        parse(input)
+    }
+
+    fn part_one(input: &Map) -> usize {
+        let mut map = input.clone();
+        map.expand_universe(2);
+        distance_between_pairs(&map)
+    }
+
+    fn part_two(input: &Map) -> usize {
+        let mut map = input.clone();
+        map.expand_universe(1000000);
+        distance_between_pairs(&map)
+    }
 }
 
 fn distance_between_pairs(map: &Map) -> usize {
@@ -68,22 +85,28 @@ fn distance_between_pairs(map: &Map) -> usize {
     result
 }
 
-fn parse(input: &str) -> Map {
-    let galaxies = input
-        .lines()
-        .enumerate()
-        .flat_map(|(y, line)| parse_galaxies(line, y))
-        .collect();
-    Map { galaxies }
+fn parse(input: &str) -> ParseResult<Map> {
+    let mut galaxies = Vec::new();
+    for (y, line) in aoc_common::lines(input).enumerate() {
+        galaxies.extend(parse_galaxies(input, line, y)?);
+    }
+    Ok(Map { galaxies })
 }
 
-fn parse_galaxies(input: &str, y: usize) -> Vec<Galaxy> {
-    input
+fn parse_galaxies(input: &str, line: &str, y: usize) -> ParseResult<Vec<Galaxy>> {
+    if let Some(x) = line.find(|c| c != '.' && c != '#') {
+        return Err(ParseError::at_slice(
+            input,
+            &line[x..],
+            "expected '.' or '#'",
+        ));
+    }
+    Ok(line
         .chars()
         .enumerate()
         .filter(|c| c.1 == '#')
         .map(|c| Galaxy { x: c.0, y })
-        .collect()
+        .collect())
 }
 
 #[cfg(test)]
@@ -102,7 +125,7 @@ mod tests {
 ..........
 .......#..
 #...#.....";
-        let mut map = parse(input);
+        let mut map = parse(input).unwrap();
         map.expand_universe(2);
 
         let part_one = distance_between_pairs(&map);
@@ -121,7 +144,7 @@ mod tests {
 ..........
 .......#..
 #...#.....";
-        let mut map = parse(input);
+        let mut map = parse(input).unwrap();
         map.expand_universe(10);
 
         let part_one = distance_between_pairs(&map);
@@ -140,10 +163,21 @@ mod tests {
 ..........
 .......#..
 #...#.....";
-        let mut map = parse(input);
+        let mut map = parse(input).unwrap();
         map.expand_universe(100);
 
         let part_one = distance_between_pairs(&map);
         assert_eq!(8410, part_one);
     }
+
+    #[test]
+    fn test_parse_errors() {
+        let map = parse("#..\n..#\n").unwrap();
+        assert_eq!(
+            vec![Galaxy { x: 0, y: 0 }, Galaxy { x: 2, y: 1 }],
+            map.galaxies
+        );
+        let err = parse("#..\n.*#\n").unwrap_err();
+        assert_eq!((2, 2), (err.line, err.column));
+    }
 }
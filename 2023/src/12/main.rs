@@ -1,28 +1,46 @@
 use std::collections::HashMap;
 
+use aoc_common::{finish, ParseResult, Solution};
 use nom::{
-    bytes::complete::{tag, take_until},
+    bytes::complete::{is_a, tag},
     character::complete::{digit1, multispace0, space1},
-    combinator::{map, opt, recognize},
-    multi::many1,
+    combinator::map_res,
+    multi::{many1, separated_list1},
     sequence::{separated_pair, terminated},
     IResult,
 };
 
 fn main() {
-    let input = include_str!("input.txt");
-    let (_, parsed) = parse(input).unwrap();
-    let part_one: usize = parsed
-        .iter()
-        .map(|t| create_valid_arrangements(t.0, &t.1))
-        .sum();
-    println!("Part one: {}", part_one);
-    let part_two: usize = parsed
-        .into_iter()
-        .map(|(input, layout)| duplicate(input, layout, 5))
-        .map(|t| create_valid_arrangements(t.0.as_str(), &t.1))
-        .sum();
-    println!("Part two: {}", part_two);
+    aoc_common::run_day::<Day>(2023, 12);
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = Vec<(String, Vec<usize>)>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> ParseResult<Vec<(String, Vec<usize>)>> {
+        let rows = finish(input, parse(input))?;
+        Ok(rows
+            .into_iter()
+            .map(|(row, layout)| (row.to_string(), layout))
+            .collect())
+    }
+
+    fn part_one(rows: &Vec<(String, Vec<usize>)>) -> usize {
+        rows.iter()
+            .map(|t| create_valid_arrangements(&t.0, &t.1))
+            .sum()
+    }
+
+    fn part_two(rows: &Vec<(String, Vec<usize>)>) -> usize {
+        rows.iter()
+            .map(|(input, layout)| duplicate(input, layout.clone(), 5))
+            .map(|t| create_valid_arrangements(t.0.as_str(), &t.1))
+            .sum()
+    }
 }
 
 fn duplicate(input: &str, layout: Vec<usize>, n: usize) -> (String, Vec<usize>) {
@@ -127,14 +145,10 @@ fn parse(input: &str) -> IResult<&str, Vec<(&str, Vec<usize>)>> {
 }
 
 fn parse_line(input: &str) -> IResult<&str, (&str, Vec<usize>)> {
-    separated_pair(
-        take_until(" "),
-        space1,
-        many1(terminated(parse_usize, opt(tag(",")))),
-    )(input)
+    separated_pair(is_a("?.#"), space1, separated_list1(tag(","), parse_usize))(input)
 }
 fn parse_usize(l: &str) -> IResult<&str, usize> {
-    map(recognize(digit1), |o: &str| o.parse::<usize>().unwrap())(l)
+    map_res(digit1, str::parse::<usize>)(l)
 }
 
 #[cfg(test)]
@@ -181,4 +195,15 @@ mod tests {
         let d = duplicate("?###????????", vec![3, 2, 1], 5);
         assert_eq!(506250, create_valid_arrangements(d.0.as_str(), &d.1));
     }
+
+    #[test]
+    fn test_parse_errors() {
+        let input = "???.### 1,1,3\n.??..??...?##. 1,1,3\n";
+        assert_eq!(2, Day::parse(input).unwrap().len());
+        let err = Day::parse(&format!("{}garbage\n", input)).unwrap_err();
+        assert_eq!((3, 1), (err.line, err.column));
+        let err = Day::parse("???.### 1,x,3\n").unwrap_err();
+        assert_eq!((1, 10), (err.line, err.column));
+        assert!(Day::parse("???.### 1,99999999999999999999999\n").is_err());
+    }
 }
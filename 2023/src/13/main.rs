@@ -1,26 +1,48 @@
+use aoc_common::{finish, ParseResult, Solution, Unsolved};
 use nom::{
     branch::alt,
     character::complete::{char, line_ending, newline},
-    combinator::{map, opt},
+    combinator::{all_consuming, map, opt},
     multi::many1,
     sequence::terminated,
     IResult,
 };
 
 #[derive(Debug, PartialEq, Eq)]
-enum ItemKind {
+pub enum ItemKind {
     Ash,
     Rock,
 }
 
 #[derive(Debug, PartialEq, Eq)]
-struct Pattern {
+pub struct Pattern {
     rows: Vec<Vec<ItemKind>>,
 }
 
 fn main() {
-    let input = include_str!("input.txt");
-    let (_, patterns) = parse(input).unwrap();
+    aoc_common::run_day::<Day>(2023, 13);
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = Vec<Pattern>;
+    type PartOne = Unsolved;
+    type PartTwo = Unsolved;
+
+    const PARTS: u32 = 0;
+
+    fn parse(input: &str) -> ParseResult<Vec<Pattern>> {
+        finish(input, all_consuming(parse)(input))
+    }
+
+    fn part_one(_input: &Vec<Pattern>) -> Unsolved {
+        Unsolved
+    }
+
+    fn part_two(_input: &Vec<Pattern>) -> Unsolved {
+        Unsolved
+    }
 }
 
 fn find_horizontal_symetry(pattern: &Pattern) -> Option<usize> {
@@ -30,9 +52,7 @@ fn find_horizontal_symetry(pattern: &Pattern) -> Option<usize> {
 
 fn find_vertical_symetry(pattern: &Pattern) -> Option<usize> {
     let height = pattern.rows.len();
-    for i in 1..height-1 {
-        
-    }
+    for i in 1..height - 1 {}
     None
 }
 fn parse(input: &str) -> IResult<&str, Vec<Pattern>> {
@@ -52,3 +72,18 @@ fn parse_items(input: &str) -> IResult<&str, Vec<ItemKind>> {
         _ => panic!("unexpected char {}", c),
     }))(input)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let patterns = Day::parse("#.#\n..#\n\n##\n.#\n").unwrap();
+        assert_eq!(2, patterns.len());
+        assert_eq!(vec![ItemKind::Rock, ItemKind::Rock], patterns[1].rows[0]);
+
+        let err = Day::parse("#.#\n.x#\n").unwrap_err();
+        assert_eq!((2, 2), (err.line, err.column));
+    }
+}
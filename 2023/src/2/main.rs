@@ -1,9 +1,11 @@
+use aoc_common::{finish, ParseResult, Solution};
 use nom::{
     branch::alt,
     bytes::complete::tag,
     character::complete::digit1,
-    combinator::{map, recognize},
-    sequence::{preceded, terminated, tuple},
+    combinator::map_res,
+    multi::separated_list1,
+    sequence::{delimited, separated_pair},
     IResult,
 };
 
@@ -33,32 +35,53 @@ impl Cubes {
 }
 
 #[derive(Debug, Clone)]
-struct Game {
+pub struct Game {
     game_id: i32,
     rounds: Vec<Cubes>,
 }
 
 fn main() {
-    let input = include_str!("input.txt");
-    let games: Vec<Game> = input.lines().map(|l| parse_game(l).unwrap().1).collect();
+    aoc_common::run_day::<Day>(2023, 2);
+}
 
-    let part_one: i32 = games
-        .iter()
-        .filter(|g| {
-            is_game_possible(
-                g,
-                Cubes {
-                    red: 12,
-                    green: 13,
-                    blue: 14,
-                },
-            )
-        })
-        .map(|g| g.game_id)
-        .sum();
-    println!("Part one: {}", part_one);
-    let part_two: usize = games.iter().map(|g| game_power(g)).sum();
-    println!("Part two: {}", part_two);
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = Vec<Game>;
+    type PartOne = i32;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> ParseResult<Vec<Game>> {
+        parse_games(input)
+    }
+
+    fn part_one(games: &Vec<Game>) -> i32 {
+        games
+            .iter()
+            .filter(|g| {
+                is_game_possible(
+                    g,
+                    Cubes {
+                        red: 12,
+                        green: 13,
+                        blue: 14,
+                    },
+                )
+            })
+            .map(|g| g.game_id)
+            .sum()
+    }
+
+    fn part_two(games: &Vec<Game>) -> usize {
+        games.iter().map(|g| game_power(g)).sum()
+    }
+}
+
+fn parse_games(input: &str) -> ParseResult<Vec<Game>> {
+    input
+        .lines()
+        .map(|l| finish(input, parse_game(l)))
+        .collect()
 }
 
 fn game_power(game: &Game) -> usize {
@@ -76,41 +99,66 @@ fn is_game_possible(game: &Game, total_cubes: Cubes) -> bool {
         .is_none()
 }
 
+fn parse_count(i: &str) -> IResult<&str, i32> {
+    map_res(digit1, str::parse::<i32>)(i)
+}
+
 fn parse_cubes(i: &str) -> IResult<&str, Cubes> {
-    let parts = i.split(", ");
-    let mut red = 0;
-    let mut green = 0;
-    let mut blue = 0;
-
-    for part in parts {
-        let (_, (amount, _, colour)) = tuple((
-            map(recognize(digit1), |o: &str| o.parse::<i32>().unwrap()),
+    let (i, parts) = separated_list1(
+        tag(", "),
+        separated_pair(
+            parse_count,
             tag(" "),
             alt((tag("red"), tag("green"), tag("blue"))),
-        ))(part)?;
+        ),
+    )(i)?;
 
-        match colour {
-            "red" => red = red + amount,
-            "green" => green = green + amount,
-            "blue" => blue = blue + amount,
-            _ => panic!("invalid branch"),
-        }
+    let mut cubes = Cubes {
+        red: 0,
+        green: 0,
+        blue: 0,
+    };
+    for (amount, colour) in parts {
+        let total = match colour {
+            "red" => &mut cubes.red,
+            "green" => &mut cubes.green,
+            _ => &mut cubes.blue,
+        };
+        *total = total.saturating_add(amount);
     }
-    Ok((i, Cubes { red, green, blue }))
+    Ok((i, cubes))
 }
 
 fn parse_game(i: &str) -> IResult<&str, Game> {
-    let (games, game_id) = terminated(
-        preceded(
-            tag("Game "),
-            map(recognize(digit1), |o: &str| o.parse::<i32>().unwrap()),
-        ),
-        tag(": "),
-    )(i)?;
+    let (i, game_id) = delimited(tag("Game "), parse_count, tag(": "))(i)?;
+    let (i, rounds) = separated_list1(tag("; "), parse_cubes)(i)?;
+    Ok((i, Game { game_id, rounds }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let mut rounds = Vec::new();
-    for r in games.split("; ") {
-        rounds.push(parse_cubes(r)?.1);
+    #[test]
+    fn test_parts() {
+        let input = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+";
+        let games = Day::parse(input).unwrap();
+        assert_eq!(8, Day::part_one(&games));
+        assert_eq!(2286, Day::part_two(&games));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = Day::parse("Game 1: 3 blue, 4 red\nGame 2: 1 blue junk\n").unwrap_err();
+        assert_eq!((2, 16), (err.line, err.column));
+        let err = Day::parse("Game 1: 3 blue, 4 red\nGame 2: 1 purple\n").unwrap_err();
+        assert_eq!((2, 11), (err.line, err.column));
+        let err = Day::parse("Game 99999999999: 3 blue\n").unwrap_err();
+        assert_eq!((1, 6), (err.line, err.column));
     }
-    Ok((i, Game { game_id, rounds }))
 }
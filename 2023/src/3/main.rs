@@ -1,11 +1,13 @@
+use aoc_common::{ParseError, ParseResult, Solution, Unsolved};
+
 #[derive(Debug, Clone)]
-enum Item {
+pub enum Item {
     Symbol(char),
     PartNumber(u32),
 }
 
 #[derive(Debug, Clone)]
-struct ItemCoord {
+pub struct ItemCoord {
     item: Item,
     start_x: i32,
     end_x: i32,
@@ -13,22 +15,41 @@ struct ItemCoord {
 }
 
 fn main() {
-    let input = include_str!("input.txt");
-    let items: Vec<ItemCoord> = input
-        .lines()
-        .enumerate()
-        .map(|line| process_line(line.1, line.0 as i32))
-        .flat_map(|v| v.into_iter())
-        .collect();
-    dbg(&filter_part_one(&items));
-    let part_one: u32 = filter_part_one(&items)
-        .iter()
-        .map(|i| match i.item {
-            Item::Symbol(_) => 0,
-            Item::PartNumber(num) => num,
-        })
-        .sum();
-    println!("Part one: {}", part_one);
+    aoc_common::run_day::<Day>(2023, 3);
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = Vec<ItemCoord>;
+    type PartOne = u32;
+    type PartTwo = Unsolved;
+
+    const PARTS: u32 = 1;
+
+    fn parse(input: &str) -> ParseResult<Vec<ItemCoord>> {
+        let mut items = Vec::new();
+        for (y, line) in aoc_common::lines(input).enumerate() {
+            let line_items = process_line(line, y as i32)
+                .map_err(|(x, message)| ParseError::at_line(input, y, x, message))?;
+            items.extend(line_items);
+        }
+        Ok(items)
+    }
+
+    fn part_one(items: &Vec<ItemCoord>) -> u32 {
+        filter_part_one(items)
+            .iter()
+            .map(|i| match i.item {
+                Item::Symbol(_) => 0,
+                Item::PartNumber(num) => num,
+            })
+            .sum()
+    }
+
+    fn part_two(_input: &Vec<ItemCoord>) -> Unsolved {
+        Unsolved
+    }
 }
 
 fn dbg(items: &Vec<ItemCoord>) {
@@ -90,14 +111,25 @@ fn find_item(items: &Vec<ItemCoord>, x: i32, y: i32) -> Option<ItemCoord> {
     idx.ok().map(|i| items[i].clone())
 }
 
-fn process_line(line: &str, y_coord: i32) -> Vec<ItemCoord> {
+/// The numbers and symbols on a line, or the column and reason it's invalid.
+fn process_line(line: &str, y_coord: i32) -> Result<Vec<ItemCoord>, (usize, String)> {
     let mut result = Vec::new();
     let mut current_part = None;
     let mut start_x = None;
     for (x_coord, c) in line.chars().enumerate() {
         if c.is_ascii_digit() {
-            current_part = Some(current_part.map_or(0, |x| x * 10) + c.to_digit(10).unwrap());
-            start_x.get_or_insert(x_coord as i32);
+            let start = *start_x.get_or_insert(x_coord as i32);
+            current_part = current_part
+                .map_or(Some(0), |x: u32| x.checked_mul(10))
+                .and_then(|x| x.checked_add(c.to_digit(10).unwrap()));
+            if current_part.is_none() {
+                return Err((start as usize, "the part number is too large".to_string()));
+            }
+        } else if c != '.' && !c.is_ascii_punctuation() {
+            return Err((
+                x_coord,
+                format!("expected a digit, '.' or a symbol, got '{}'", c),
+            ));
         } else {
             if current_part.is_some() {
                 result.push(ItemCoord {
@@ -117,7 +149,7 @@ fn process_line(line: &str, y_coord: i32) -> Vec<ItemCoord> {
             }
         }
     }
-    result
+    Ok(result)
 }
 
 #[cfg(test)]
@@ -127,12 +159,7 @@ mod tests {
     #[test]
     fn test_part_one() {
         let input = "467..114..\n...*......\n..35..633.\n......#...\n617*......\n.....+.58.\n..592.....\n......755.\n...$.*....\n.664.598..";
-        let items: Vec<ItemCoord> = input
-            .lines()
-            .enumerate()
-            .map(|line| process_line(line.1, line.0 as i32))
-            .flat_map(|v| v.into_iter())
-            .collect();
+        let items = Day::parse(input).unwrap();
         let part_one: u32 = filter_part_one(&items)
             .iter()
             .map(|i| match i.item {
@@ -142,4 +169,13 @@ mod tests {
             .sum();
         assert_eq!(4361, part_one)
     }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = Day::parse("467..114..\n...*.. ...\n").unwrap_err();
+        assert_eq!((2, 7), (err.line, err.column));
+        let err = Day::parse("..99999999999\n").unwrap_err();
+        assert_eq!((1, 3), (err.line, err.column));
+        assert_eq!("the part number is too large", err.message);
+    }
 }
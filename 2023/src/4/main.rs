@@ -1,31 +1,61 @@
-use std::process::id;
-
+use aoc_common::{finish, ParseError, ParseResult, Solution};
 use nom::{
     bytes::complete::tag,
-    character::complete::{digit1, multispace0, multispace1},
-    combinator::{map, recognize},
+    character::complete::{digit1, space0, space1},
+    combinator::map_res,
     multi::many1,
-    sequence::{delimited, preceded, terminated, tuple},
+    sequence::{delimited, preceded, separated_pair, terminated, tuple},
     IResult,
 };
 
-use nom::sequence::separated_pair;
-
 #[derive(Debug, Clone)]
-struct Game {
+pub struct Game {
     id: i32,
     winners: Vec<i32>,
     card: Vec<i32>,
 }
 
 fn main() {
-    let input = include_str!("input.txt");
-    let games: Vec<Game> = input.lines().map(|l| parse_game(l).unwrap().1).collect();
+    aoc_common::run_day::<Day>(2023, 4);
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = Vec<Game>;
+    type PartOne = i32;
+    type PartTwo = i32;
+
+    fn parse(input: &str) -> ParseResult<Vec<Game>> {
+        parse_games(input)
+    }
+
+    fn part_one(games: &Vec<Game>) -> i32 {
+        games.iter().map(|g| calculate_score(g)).sum()
+    }
+
+    fn part_two(games: &Vec<Game>) -> i32 {
+        play_game(games).iter().map(|g| g.1).sum()
+    }
+}
 
-    let part_one: i32 = games.iter().map(|g| calculate_score(g)).sum();
-    println!("Part one: {}", part_one);
-    let part_two: i32 = play_game(&games).iter().map(|g| g.1).sum();
-    println!("Part two: {}", part_two);
+/// The scratchcards, one per line.  A card can't win copies of cards past the
+/// end of the table.
+fn parse_games(input: &str) -> ParseResult<Vec<Game>> {
+    let lines: Vec<&str> = aoc_common::lines(input).collect();
+    let mut games = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        let game = finish(input, parse_game(line))?;
+        if num_matches(&game) > lines.len() - i - 1 {
+            return Err(ParseError::at_slice(
+                input,
+                line,
+                "expected the card to win copies of cards in the table",
+            ));
+        }
+        games.push(game);
+    }
+    Ok(games)
 }
 
 fn play_game(games: &Vec<Game>) -> Vec<(Game, i32)> {
@@ -59,26 +89,21 @@ fn calculate_score(game: &Game) -> i32 {
     }
 }
 
+fn parse_number(i: &str) -> IResult<&str, i32> {
+    map_res(digit1, str::parse::<i32>)(i)
+}
+
 fn parse_game(i: &str) -> IResult<&str, Game> {
-    let (games, id) = terminated(
-        preceded(
-            tuple((tag("Card"), multispace1)),
-            map(recognize(digit1), |o: &str| o.parse::<i32>().unwrap()),
-        ),
-        tuple((tag(":"), multispace1)),
+    let (i, id) = delimited(
+        tuple((tag("Card"), space1)),
+        parse_number,
+        tuple((tag(":"), space1)),
     )(i)?;
-    let (_, (winners, card)) = separated_pair(
-        many1(terminated(
-            map(recognize(digit1), |o: &str| o.parse::<i32>().unwrap()),
-            multispace1,
-        )),
+    let (i, (winners, card)) = separated_pair(
+        many1(terminated(parse_number, space1)),
         tag("|"),
-        many1(delimited(
-            multispace0,
-            map(recognize(digit1), |o: &str| o.parse::<i32>().unwrap()),
-            multispace0,
-        )),
-    )(games)?;
+        many1(preceded(space0, parse_number)),
+    )(i)?;
 
     Ok((i, Game { id, winners, card }))
 }
@@ -113,4 +138,16 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
         let part_two: i32 = play_game(&games).iter().map(|g| g.1).sum();
         assert_eq!(30, part_two)
     }
+
+    #[test]
+    fn test_parse_errors() {
+        let input = "Card 1: 41 48 | 83 86\nCard 2: 13 32 | 61 30 x\n";
+        assert_eq!(2, parse_games(&input.replace(" x", "")).unwrap().len());
+        let err = parse_games(input).unwrap_err();
+        assert_eq!((2, 23), (err.line, err.column));
+        let err = parse_games("Card 1: 41 48 | 41 48\nCard 2: 13 | 61\n").unwrap_err();
+        assert_eq!((1, 1), (err.line, err.column));
+        let err = parse_games("Card 1: 99999999999 | 1\n").unwrap_err();
+        assert_eq!((1, 9), (err.line, err.column));
+    }
 }
@@ -1,235 +1,155 @@
-use core::ops::Range;
+use std::convert::TryFrom;
 
+use aoc_common::{finish, IntervalSet, ParseError, ParseResult, PiecewiseMap, Solution};
 use nom::{
     bytes::complete::tag,
     character::complete::{digit1, line_ending, multispace0, multispace1},
-    combinator::{map, opt, recognize},
+    combinator::{consumed, map_res, opt},
     multi::many1,
     sequence::{preceded, terminated, tuple},
     IResult,
 };
 
+/// The almanac's maps composed into one, from seed to location.
 #[derive(Debug, Clone)]
-struct Mapping {
-    range_start: u64,
-    range_end: u64,
-    mapped_start: u64,
-    mapped_end: u64,
+pub struct Almanac {
+    seed_to_location: PiecewiseMap,
 }
 
-#[derive(Debug, Clone)]
-struct Mappings {
-    mappings: Vec<Mapping>,
-}
-
-#[derive(Debug, Clone)]
-struct Almanac {
-    seed_to_soil: Mappings,
-    soil_to_fertilizer: Mappings,
-    fertilizer_to_water: Mappings,
-    water_to_light: Mappings,
-    light_to_temp: Mappings,
-    temp_to_humidity: Mappings,
-    humidity_to_location: Mappings,
-}
+impl Almanac {
+    /// Composes the maps, given in order from seed-to-soil to
+    /// humidity-to-location.
+    pub fn new(maps: &[PiecewiseMap]) -> Almanac {
+        let seed_to_location = maps
+            .iter()
+            .fold(PiecewiseMap::new(), |composed, map| composed.then(map));
+        Almanac { seed_to_location }
+    }
 
-impl Mapping {
-    pub fn new(range_start: u64, mapped_start: u64, length: u64) -> Mapping {
-        Mapping {
-            range_start,
-            range_end: range_start + length,
-            mapped_start: mapped_start,
-            mapped_end: mapped_start + length,
-        }
+    pub fn get_location(&self, seed: u64) -> u64 {
+        self.seed_to_location.apply(seed as i64) as u64
     }
 
-    pub fn get_mapped(&self, input: u64) -> Option<u64> {
-        if input < self.range_start || input >= self.range_end {
-            None
-        } else {
-            Some(self.mapped_start + (input - self.range_start))
-        }
+    pub fn get_min_location(&self, seeds: &IntervalSet) -> u64 {
+        self.seed_to_location.apply_set(seeds).min().unwrap() as u64
     }
 }
 
-impl Mappings {
-    pub fn get_mapped(&self, input: u64) -> u64 {
-        self.mappings
-            .iter()
-            .filter_map(|r| r.get_mapped(input))
-            .next()
-            .unwrap_or(input)
-    }
+fn main() {
+    aoc_common::run_day::<Day>(2023, 5);
+}
 
-    pub fn map_range(&self, range: Range<u64>) -> Vec<Range<u64>> {
-        let mut submappings = Vec::new();
-
-        let mut last = range.start;
-        for m in &self.mappings {
-            let start = m.range_start.max(range.start);
-            let end = m.range_end.min(range.end);
-            if end > start {
-                if last < start {
-                    submappings.push(last..start);
-                }
-
-                let start_diff = start - m.range_start;
-                let end_diff = m.range_end - end;
-                last = end + 1;
-                submappings.push(m.mapped_start + start_diff..m.mapped_end - end_diff)
-            }
-        }
-
-        if last < range.end {
-            submappings.push(last..range.end);
-        }
-
-        submappings
-    }
+pub struct Day;
 
-    pub fn map_ranges(&self, ranges: Vec<Range<u64>>) -> Vec<Range<u64>> {
-        ranges.into_iter().flat_map(|r| self.map_range(r)).collect()
-    }
-}
+impl Solution for Day {
+    type Parsed = (Vec<u64>, Almanac);
+    type PartOne = u64;
+    type PartTwo = u64;
 
-impl Almanac {
-    pub fn new(
-        seed_to_soil: Mappings,
-        soil_to_fertilizer: Mappings,
-        fertilizer_to_water: Mappings,
-        water_to_light: Mappings,
-        light_to_temp: Mappings,
-        temp_to_humidity: Mappings,
-        humidity_to_location: Mappings,
-    ) -> Almanac {
-        Almanac {
-            seed_to_soil,
-            soil_to_fertilizer,
-            fertilizer_to_water,
-            water_to_light,
-            light_to_temp,
-            temp_to_humidity,
-            humidity_to_location,
-        }
+    fn parse(input: &str) -> ParseResult<(Vec<u64>, Almanac)> {
+        parse(input)
     }
 
-    pub fn get_location(&self, seed: u64) -> u64 {
-        self.humidity_to_location.get_mapped(
-            self.temp_to_humidity.get_mapped(
-                self.light_to_temp.get_mapped(
-                    self.water_to_light.get_mapped(
-                        self.fertilizer_to_water.get_mapped(
-                            self.soil_to_fertilizer
-                                .get_mapped(self.seed_to_soil.get_mapped(seed)),
-                        ),
-                    ),
-                ),
-            ),
-        )
+    fn part_one(input: &(Vec<u64>, Almanac)) -> u64 {
+        let (seeds, almanac) = input;
+        seeds
+            .iter()
+            .map(|s| almanac.get_location(s.clone()))
+            .min()
+            .unwrap()
     }
 
-    pub fn get_min_location(&self, range: Range<u64>) -> u64 {
-        let mut ranges = vec![range];
-
-        let mappings = vec![
-            &self.seed_to_soil,
-            &self.soil_to_fertilizer,
-            &self.fertilizer_to_water,
-            &self.water_to_light,
-            &self.light_to_temp,
-            &self.temp_to_humidity,
-            &self.humidity_to_location,
-        ];
-        for m in mappings {
-            ranges = m.map_ranges(ranges);
-        }
-
-        ranges.iter().map(|r| r.start).min().unwrap()
+    fn part_two(input: &(Vec<u64>, Almanac)) -> u64 {
+        let (seeds, almanac) = input;
+        calc_part_two(seeds, almanac).unwrap()
     }
 }
 
-fn main() {
-    let input = include_str!("input.txt");
-    let (_, (seeds, almanac)) = parse(input).unwrap();
-
-    let part_one = seeds.iter().map(|s| almanac.get_location(s.clone())).min();
-    println!("Part one: {}", part_one.unwrap());
-    println!("Part two: {}", calc_part_two(&seeds, &almanac).unwrap());
-}
-
 fn calc_part_two(seeds: &Vec<u64>, almanac: &Almanac) -> Option<u64> {
-    (0..seeds.len())
-        .step_by(2)
-        .map(|i| (seeds[i], seeds[i + 1]))
-        .map(|r| almanac.get_min_location(r.0..r.0 + r.1))
-        .min()
+    let seeds: IntervalSet = seeds
+        .chunks(2)
+        .map(|pair| pair[0] as i64..(pair[0] + pair[1]) as i64)
+        .collect();
+    (!seeds.is_empty()).then(|| almanac.get_min_location(&seeds))
 }
 
-fn parse(input: &str) -> IResult<&str, (Vec<u64>, Almanac)> {
-    tuple((parse_seeds, parse_almanac))(input)
+/// A `destination source length` line, still borrowed from the input.
+type RangeLine<'a> = (&'a str, (u64, u64, u64));
+
+const MAP_NAMES: [&str; 7] = [
+    "seed-to-soil",
+    "soil-to-fertilizer",
+    "fertilizer-to-water",
+    "water-to-light",
+    "light-to-temperature",
+    "temperature-to-humidity",
+    "humidity-to-location",
+];
+
+fn parse(input: &str) -> ParseResult<(Vec<u64>, Almanac)> {
+    let (seeds, maps) = finish(input, tuple((parse_seeds, parse_almanac))(input))?;
+    let maps = maps
+        .into_iter()
+        .map(|lines| piecewise_map(input, lines))
+        .collect::<ParseResult<Vec<_>>>()?;
+    Ok((seeds, Almanac::new(&maps)))
 }
 
 fn parse_int(l: &str) -> IResult<&str, u64> {
-    map(recognize(digit1), |o: &str| o.parse::<u64>().unwrap())(l)
+    map_res(digit1, str::parse::<u64>)(l)
 }
 
 fn parse_seeds(l: &str) -> IResult<&str, Vec<u64>> {
     preceded(tag("seeds: "), many1(terminated(parse_int, multispace0)))(l)
 }
 
-fn parse_almanac(l: &str) -> IResult<&str, Almanac> {
-    map(
-        tuple((
-            preceded(tuple((tag("seed-to-soil map:"), line_ending)), parse_ranges),
-            preceded(
-                tuple((tag("soil-to-fertilizer map:"), line_ending)),
-                parse_ranges,
-            ),
-            preceded(
-                tuple((tag("fertilizer-to-water map:"), line_ending)),
-                parse_ranges,
-            ),
-            preceded(
-                tuple((tag("water-to-light map:"), line_ending)),
-                parse_ranges,
-            ),
-            preceded(
-                tuple((tag("light-to-temperature map:"), line_ending)),
-                parse_ranges,
-            ),
-            preceded(
-                tuple((tag("temperature-to-humidity map:"), line_ending)),
-                parse_ranges,
-            ),
-            preceded(
-                tuple((tag("humidity-to-location map:"), line_ending)),
-                parse_ranges,
-            ),
-        )),
-        |p| Almanac::new(p.0, p.1, p.2, p.3, p.4, p.5, p.6),
-    )(l)
+/// The lines of each map, in the order of [`MAP_NAMES`].
+fn parse_almanac(mut l: &str) -> IResult<&str, Vec<Vec<RangeLine<'_>>>> {
+    let mut maps = vec![];
+    for name in MAP_NAMES {
+        let (rest, lines) = preceded(
+            tuple((tag(name), tag(" map:"), line_ending)),
+            many1(terminated(parse_range, opt(line_ending))),
+        )(l)?;
+        maps.push(lines);
+        l = rest;
+    }
+    Ok((l, maps))
 }
 
-fn parse_ranges(l: &str) -> IResult<&str, Mappings> {
-    map(
-        many1(terminated(parse_range, opt(line_ending))),
-        |mut mappings| {
-            mappings.sort_by_key(|i| i.range_start);
-            Mappings { mappings }
-        },
-    )(l)
+fn parse_range(l: &str) -> IResult<&str, RangeLine<'_>> {
+    consumed(tuple((
+        terminated(parse_int, multispace1),
+        terminated(parse_int, multispace1),
+        terminated(parse_int, multispace0),
+    )))(l)
 }
 
-fn parse_range(l: &str) -> IResult<&str, Mapping> {
-    map(
-        tuple((
-            terminated(parse_int, multispace1),
-            terminated(parse_int, multispace1),
-            terminated(parse_int, multispace0),
-        )),
-        |p| Mapping::new(p.1, p.0, p.2),
-    )(l)
+/// The map made of `lines`, which must each fit in an `i64` and not overlap.
+fn piecewise_map(input: &str, lines: Vec<RangeLine<'_>>) -> ParseResult<PiecewiseMap> {
+    let mut mappings = PiecewiseMap::new();
+    for (line, (target, source, length)) in lines {
+        let range = || {
+            let source = i64::try_from(source).ok()?;
+            let target = i64::try_from(target).ok()?;
+            let length = i64::try_from(length).ok()?;
+            source.checked_add(length)?;
+            target.checked_add(length)?;
+            Some((source..source + length, target))
+        };
+        let (source, target) =
+            range().ok_or_else(|| ParseError::at_slice(input, line, "the range is too large"))?;
+        mappings.try_insert(source, target).map_err(|existing| {
+            let message = format!(
+                "the source range overlaps {}..{} earlier in the map",
+                existing.start, existing.end
+            );
+            ParseError::at_slice(input, line, message)
+        })?;
+    }
+    Ok(mappings)
 }
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -269,7 +189,7 @@ temperature-to-humidity map:
 humidity-to-location map:
 60 56 37
 56 93 4";
-        let (_, (seeds, almanac)) = parse(input).unwrap();
+        let (seeds, almanac) = parse(input).unwrap();
         let part_one = seeds.into_iter().map(|s| almanac.get_location(s)).min();
         assert_eq!(35, part_one.unwrap());
     }
@@ -309,8 +229,32 @@ temperature-to-humidity map:
 humidity-to-location map:
 60 56 37
 56 93 4";
-        let (_, (seeds, almanac)) = parse(input).unwrap();
+        let (seeds, almanac) = parse(input).unwrap();
         let part_two = calc_part_two(&seeds, &almanac);
         assert_eq!(46, part_two.unwrap());
     }
+
+    #[test]
+    fn test_parse_errors() {
+        let maps: String = MAP_NAMES
+            .iter()
+            .map(|name| format!("{} map:\n0 10 5\n\n", name))
+            .collect();
+        let input = format!("seeds: 1 2\n\n{}", maps);
+        assert!(parse(&input).is_ok());
+
+        let input = input.replacen("0 10 5\n", "0 10 5\n20 12 2\n", 1);
+        let err = parse(&input).unwrap_err();
+        assert_eq!((5, 1), (err.line, err.column));
+        assert_eq!(
+            "the source range overlaps 10..15 earlier in the map",
+            err.message
+        );
+
+        let input = format!(
+            "seeds: 1 2\n\n{}",
+            maps.replacen("10 5", "10 99999999999999999999", 1)
+        );
+        assert!(parse(&input).is_err());
+    }
 }
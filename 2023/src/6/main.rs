@@ -1,27 +1,57 @@
-use itertools::Itertools;
+use aoc_common::{finish, ParseError, ParseResult, Solution};
 use nom::{
     bytes::complete::tag,
-    character::complete::{digit1, line_ending, multispace0, multispace1},
-    combinator::{map, opt, recognize},
+    character::complete::{digit1, line_ending, space0, space1},
+    combinator::map_res,
     multi::{fold_many1, many1},
-    sequence::{delimited, preceded, terminated, tuple},
+    sequence::{preceded, separated_pair},
     IResult,
 };
 
 #[derive(Debug, Clone)]
-struct Race {
+pub struct Race {
     duration: u64,
     record: u64,
 }
 
 fn main() {
-    let input = include_str!("input.txt");
-    let (_, races) = parse(input).unwrap();
-    let part_one: usize = races.iter().map(|r| num_winning_combinations(r)).product();
-    println!("Part one: {}", part_one);
-    let (_, race2) = parse2(input).unwrap();
-    let part_two = num_winning_combinations(&race2);
-    println!("Part two: {}", part_two);
+    aoc_common::run_day::<Day>(2023, 6);
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = (Vec<Race>, Race);
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> ParseResult<(Vec<Race>, Race)> {
+        let (durations, records) = finish(input, parse(input))?;
+        if durations.len() != records.len() {
+            return Err(ParseError::at_line(
+                input,
+                1,
+                0,
+                format!("expected {} distances, one for each race", durations.len()),
+            ));
+        }
+        let races = durations
+            .into_iter()
+            .zip(records)
+            .map(|(duration, record)| Race { duration, record })
+            .collect();
+        Ok((races, finish(input, parse2(input))?))
+    }
+
+    fn part_one(input: &(Vec<Race>, Race)) -> usize {
+        let (races, _) = input;
+        races.iter().map(|r| num_winning_combinations(r)).product()
+    }
+
+    fn part_two(input: &(Vec<Race>, Race)) -> usize {
+        let (_, race2) = input;
+        num_winning_combinations(race2)
+    }
 }
 
 fn num_winning_combinations(race: &Race) -> usize {
@@ -35,51 +65,52 @@ fn calculate_distance(hold_time: u64, total_time: u64) -> u64 {
     hold_time * (total_time - hold_time)
 }
 
-fn parse(input: &str) -> IResult<&str, Vec<Race>> {
-    let (_, (durations, records)) = tuple((
-        preceded(
-            tag("Time:"),
-            many1(delimited(multispace0, parse_int, multispace0)),
-        ),
-        preceded(
-            tag("Distance:"),
-            many1(delimited(multispace0, parse_int, multispace0)),
-        ),
-    ))(input)?;
-
-    Ok((
-        input,
-        durations
-            .iter()
-            .zip_eq(records)
-            .map(|pair| Race {
-                duration: *pair.0,
-                record: pair.1,
-            })
-            .collect(),
-    ))
+fn parse(input: &str) -> IResult<&str, (Vec<u64>, Vec<u64>)> {
+    separated_pair(
+        preceded(tag("Time:"), many1(preceded(space1, parse_int))),
+        line_ending,
+        preceded(tag("Distance:"), many1(preceded(space1, parse_int))),
+    )(input)
 }
 
 fn parse2(input: &str) -> IResult<&str, Race> {
-    let (_, (duration, record)) = tuple((
+    let (rem, (duration, record)) = separated_pair(
         preceded(tag("Time:"), parse_spaced_int),
+        line_ending,
         preceded(tag("Distance:"), parse_spaced_int),
-    ))(input)?;
+    )(input)?;
 
-    Ok((input, Race { duration, record }))
+    Ok((rem, Race { duration, record }))
 }
 
 fn parse_spaced_int(l: &str) -> IResult<&str, u64> {
-    map(
-        fold_many1(
-            delimited(multispace0, recognize(digit1), multispace0),
-            || String::new(),
-            |a, i| a + i,
-        ),
-        |o: String| o.parse::<u64>().unwrap(),
+    map_res(
+        fold_many1(preceded(space0, digit1), String::new, |a, i| a + i),
+        |o: String| o.parse::<u64>(),
     )(l)
 }
 
 fn parse_int(l: &str) -> IResult<&str, u64> {
-    map(recognize(digit1), |o: &str| o.parse::<u64>().unwrap())(l)
+    map_res(digit1, str::parse::<u64>)(l)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parts() {
+        let input = Day::parse("Time:      7  15   30\nDistance:  9  40  200\n").unwrap();
+        assert_eq!(288, Day::part_one(&input));
+        assert_eq!(71503, Day::part_two(&input));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = Day::parse("Time:      7  15   30\nDistance:  9  40\n").unwrap_err();
+        assert_eq!((2, 1), (err.line, err.column));
+        let err = Day::parse("Time:      7  15   30\nDistance:  9  40  200\njunk\n").unwrap_err();
+        assert_eq!((3, 1), (err.line, err.column));
+        assert!(Day::parse("Time: 99999999999 99999999999\nDistance: 1 1\n").is_err());
+    }
 }
@@ -1,11 +1,12 @@
 use std::cmp::Ordering;
 
+use aoc_common::{finish, ParseResult, Solution};
 use itertools::Itertools;
 use nom::{
-    character::complete::{anychar, digit1, multispace0, multispace1},
-    combinator::{map, recognize},
+    character::complete::{digit1, multispace0, one_of, space1},
+    combinator::map_res,
     multi::{count, many1},
-    sequence::{delimited, tuple},
+    sequence::{preceded, terminated, tuple},
     IResult,
 };
 
@@ -21,32 +22,46 @@ enum HandKind {
 }
 
 #[derive(Debug, Clone)]
-struct Hand {
+pub struct Hand {
     kind: HandKind,
     cards: [u8; 5],
     bid: i32,
 }
 
 fn main() {
-    let input = include_str!("input.txt");
-    let (_, mut hands) = parse(input).unwrap();
-    hands.sort_by(compare_hands);
+    aoc_common::run_day::<Day>(2023, 7);
+}
 
-    let part_one: usize = hands
-        .iter()
-        .enumerate()
-        .map(|t| (t.0 + 1) * t.1.bid as usize)
-        .sum();
-    println!("Part one: {}", part_one);
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = Vec<Hand>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> ParseResult<Vec<Hand>> {
+        finish(input, parse(input))
+    }
+
+    fn part_one(hands: &Vec<Hand>) -> usize {
+        let mut hands = hands.clone();
+        hands.sort_by(compare_hands);
+        total_winnings(&hands)
+    }
 
-    let mut hands2 = convert_jokers_in_hands(&hands);
-    hands2.sort_by(compare_hands);
-    let part_two: usize = hands2
+    fn part_two(hands: &Vec<Hand>) -> usize {
+        let mut hands = convert_jokers_in_hands(hands);
+        hands.sort_by(compare_hands);
+        total_winnings(&hands)
+    }
+}
+
+fn total_winnings(hands: &[Hand]) -> usize {
+    hands
         .iter()
         .enumerate()
         .map(|t| (t.0 + 1) * t.1.bid as usize)
-        .sum();
-    println!("Part two: {}", part_two);
+        .sum()
 }
 
 fn convert_jokers_in_hands(hands: &[Hand]) -> Vec<Hand> {
@@ -98,13 +113,16 @@ fn compare_cards(ahand: &[u8; 5], bhand: &[u8; 5]) -> Ordering {
 }
 
 fn parse(input: &str) -> IResult<&str, Vec<Hand>> {
-    let (_, hand_input) = many1(tuple((
-        count(anychar, 5),
-        delimited(multispace1, parse_int, multispace0),
-    )))(input)?;
+    let (rem, hand_input) = many1(terminated(
+        tuple((
+            count(one_of("AKQJT98765432"), 5),
+            preceded(space1, parse_int),
+        )),
+        multispace0,
+    ))(input)?;
 
     Ok((
-        input,
+        rem,
         hand_input
             .into_iter()
             .map(|t| (convert_to_card_ids(t.0), t.1))
@@ -210,5 +228,25 @@ fn card_id(card: char) -> u8 {
 }
 
 fn parse_int(l: &str) -> IResult<&str, i32> {
-    map(recognize(digit1), |o: &str| o.parse::<i32>().unwrap())(l)
+    map_res(digit1, str::parse::<i32>)(l)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parts() {
+        let input = "32T3K 765\nT55J5 684\nKK677 28\nKTJJT 220\nQQQJA 483\n";
+        let hands = Day::parse(input).unwrap();
+        assert_eq!(6440, Day::part_one(&hands));
+        assert_eq!(5905, Day::part_two(&hands));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = Day::parse("32T3K 765\nT55X5 684\n").unwrap_err();
+        assert_eq!((2, 1), (err.line, err.column));
+        assert!(Day::parse("32T3K 99999999999\n").is_err());
+    }
 }
@@ -7,6 +7,7 @@ use std::{
     ptr::NonNull,
 };
 
+use aoc_common::{finish, ParseResult, Solution};
 use nom::{
     branch::alt,
     bytes::complete::tag,
@@ -18,7 +19,7 @@ use nom::{
 };
 
 #[derive(Debug, Clone)]
-enum Instruction {
+pub enum Instruction {
     Left,
     Right,
 }
@@ -43,7 +44,7 @@ impl Node {
 }
 
 #[derive(Debug, Clone)]
-struct Map {
+pub struct Map {
     begin: Link,
     all_begins: Vec<Link>,
     _phantom: PhantomData<Node>,
@@ -107,12 +108,29 @@ impl Follow {
 }
 
 fn main() {
-    let input = include_str!("input.txt");
-    let (_, (instructions, map)) = parse(input).unwrap();
-    let part_one = follow_instructions(&instructions, &map);
-    println!("Part one: {}", part_one);
-    let part_two = follow_all_paths(&instructions, &map);
-    println!("Part two: {}", part_two);
+    aoc_common::run_day::<Day>(2023, 8);
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = (Vec<Instruction>, Map);
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> ParseResult<(Vec<Instruction>, Map)> {
+        finish(input, parse(input))
+    }
+
+    fn part_one(input: &(Vec<Instruction>, Map)) -> usize {
+        let (instructions, map) = input;
+        follow_instructions(instructions, map)
+    }
+
+    fn part_two(input: &(Vec<Instruction>, Map)) -> usize {
+        let (instructions, map) = input;
+        follow_all_paths(instructions, map)
+    }
 }
 
 fn follow_instructions(instructions: &[Instruction], map: &Map) -> usize {
@@ -144,23 +162,7 @@ fn follow_all_paths(instructions: &[Instruction], map: &Map) -> usize {
         .iter()
         .map(|b| follow_path_until(instructions, *b, predicate))
         .collect();
-    lcm(&steps)
-}
-
-pub fn lcm(nums: &[usize]) -> usize {
-    if nums.len() == 1 {
-        return nums[0];
-    }
-    let a = nums[0];
-    let b = lcm(&nums[1..]);
-    a * b / gcd_of_two_numbers(a, b)
-}
-
-fn gcd_of_two_numbers(a: usize, b: usize) -> usize {
-    if b == 0 {
-        return a;
-    }
-    gcd_of_two_numbers(b, a % b)
+    aoc_common::lcm_all(steps.iter().map(|&s| s as i128)).expect("lcm overflowed") as usize
 }
 
 fn parse(input: &str) -> IResult<&str, (Vec<Instruction>, Map)> {
@@ -228,3 +230,26 @@ fn parse_instructions(input: &str) -> IResult<&str, Vec<Instruction>> {
         _ => panic!("Invalid character {}", c),
     }))(input)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part_two() {
+        // AAA is required by part one and reaches ZZZ in one step.
+        let input = "LR
+
+QQA = (QQB, XXX)
+QQB = (XXX, QQZ)
+QQZ = (QQB, XXX)
+RRA = (RRB, XXX)
+RRB = (RRC, RRC)
+RRC = (RRZ, RRZ)
+RRZ = (RRB, RRB)
+XXX = (XXX, XXX)
+AAA = (ZZZ, ZZZ)
+ZZZ = (ZZZ, ZZZ)";
+        assert_eq!(Day::part_two(&Day::parse(input).unwrap()), 6);
+    }
+}
@@ -1,23 +1,39 @@
+use aoc_common::{finish, ParseResult, Solution};
 use nom::{
     character::complete::{digit1, i64, multispace0, space0},
-    combinator::{map, recognize},
+    combinator::{all_consuming, map, recognize},
     multi::many1,
     sequence::{preceded, terminated},
     IResult,
 };
 
 #[derive(Debug, Clone)]
-struct History {
+pub struct History {
     values: Vec<i64>,
 }
 
 fn main() {
-    let input = include_str!("input.txt");
-    let (_, histories) = parse(input).unwrap();
-    let part_one: i64 = histories.iter().map(predict_forward).sum();
-    println!("Part one: {}", part_one);
-    let part_two: i64 = histories.iter().map(predict_back).sum();
-    println!("Part two: {}", part_two);
+    aoc_common::run_day::<Day>(2023, 9);
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = Vec<History>;
+    type PartOne = i64;
+    type PartTwo = i64;
+
+    fn parse(input: &str) -> ParseResult<Vec<History>> {
+        finish(input, all_consuming(parse)(input))
+    }
+
+    fn part_one(histories: &Vec<History>) -> i64 {
+        histories.iter().map(predict_forward).sum()
+    }
+
+    fn part_two(histories: &Vec<History>) -> i64 {
+        histories.iter().map(predict_back).sum()
+    }
 }
 
 fn predict_forward(history: &History) -> i64 {
@@ -36,7 +52,7 @@ fn predict(values: &Vec<i64>) -> i64 {
 
     while !previous_row.iter().all(|d| *d == 0) {
         let differences = calculate_differences(&previous_row);
-        sum = sum + differences.last().unwrap();
+        sum = sum + differences.last().copied().unwrap_or(0);
         previous_row = differences;
     }
 
@@ -58,3 +74,21 @@ fn parse(input: &str) -> IResult<&str, Vec<History>> {
 fn parse_history(input: &str) -> IResult<&str, History> {
     map(many1(preceded(space0, i64)), |values| History { values })(input)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parts() {
+        let input = Day::parse("0 3 6 9 12 15\n1 3 6 10 15 21\n10 13 16 21 30 45\n").unwrap();
+        assert_eq!(114, Day::part_one(&input));
+        assert_eq!(2, Day::part_two(&input));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = Day::parse("0 3 6\n1 x 6\n").unwrap_err();
+        assert_eq!((2, 3), (err.line, err.column));
+    }
+}
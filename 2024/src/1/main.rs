@@ -1,26 +1,57 @@
+use aoc_common::{ParseError, ParseResult, Solution};
 use itertools::Itertools;
 
 fn main() {
-    let input = include_str!("input.txt");
+    aoc_common::run_day::<Day>(2024, 1);
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = (Vec<i32>, Vec<i32>);
+    type PartOne = i32;
+    type PartTwo = i32;
+
+    fn parse(input: &str) -> ParseResult<(Vec<i32>, Vec<i32>)> {
+        parse_lists(input)
+    }
+
+    fn part_one((list_one, list_two): &(Vec<i32>, Vec<i32>)) -> i32 {
+        sum_distances(list_one, list_two)
+    }
+
+    fn part_two((list_one, list_two): &(Vec<i32>, Vec<i32>)) -> i32 {
+        similarity_score(list_one, list_two)
+    }
+}
+
+fn parse_lists(input: &str) -> ParseResult<(Vec<i32>, Vec<i32>)> {
     let mut list_one = Vec::new();
     let mut list_two = Vec::new();
-    input.lines().map(parse_line).for_each(|(a, b)| {
+    for line in aoc_common::lines(input) {
+        let (a, b) = parse_line(input, line)?;
         list_one.push(a);
         list_two.push(b);
-    });
-    println!("Part 1: {}", sum_distances(&list_one, &list_two));
-    println!("Part 2: {}", similarity_score(&list_one, &list_two));
+    }
+    Ok((list_one, list_two))
 }
 
-fn parse_line(line: &str) -> (i32, i32) {
-    let mut parts = line.split("   ");
-    let a = parse_location_id(parts.next().unwrap());
-    let b = parse_location_id(parts.next().unwrap());
-    (a, b)
+fn parse_line(input: &str, line: &str) -> ParseResult<(i32, i32)> {
+    let ids: Vec<&str> = line.split_whitespace().collect();
+    match ids[..] {
+        [a, b] => Ok((parse_location_id(input, a)?, parse_location_id(input, b)?)),
+        _ => Err(ParseError::at_slice(
+            input,
+            line,
+            "expected two location ids",
+        )),
+    }
 }
 
-fn parse_location_id(location: &str) -> i32 {
-    location.parse().unwrap()
+fn parse_location_id(input: &str, location: &str) -> ParseResult<i32> {
+    location
+        .parse()
+        .map_err(|_| ParseError::at_slice(input, location, "expected a location id"))
 }
 
 fn sum_distances(list_one: &Vec<i32>, list_two: &Vec<i32>) -> i32 {
@@ -70,4 +101,15 @@ mod tests {
         let b = vec![4, 3, 5, 3, 9, 3];
         assert_eq!(similarity_score(&a, &b), 31);
     }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(Ok((vec![3, 4], vec![4, 3])), Day::parse("3   4\n4   3\n"));
+        let err = Day::parse("3   4\n4   x\n").unwrap_err();
+        assert_eq!((2, 5), (err.line, err.column));
+        assert_eq!(
+            "expected two location ids",
+            Day::parse("3\n").unwrap_err().message
+        );
+    }
 }
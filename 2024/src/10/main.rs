@@ -1,73 +1,38 @@
-fn main() {
-    let input = include_str!("input.txt");
-    println!("Part one: {}", part_one(input));
-    println!("Part two: {}", part_two(input));
-}
+use aoc_common::{Grid, ParseResult, Pos, Solution};
 
-struct Map<'a> {
-    data: &'a str,
-    line_len: usize,
+fn main() {
+    aoc_common::run_day::<Day>(2024, 10);
 }
 
-impl<'a> Map<'a> {
-    fn new(data: &'a str) -> Self {
-        Self {
-            data,
-            line_len: data.lines().next().unwrap().len() + 1,
-        }
-    }
+pub struct Day;
 
-    fn index_of(&self, c: char, start_idx: usize) -> Option<usize> {
-        self.data[start_idx..].find(c).map(|idx| idx + start_idx)
-    }
+impl Solution for Day {
+    type Parsed = Grid<char>;
+    type PartOne = i64;
+    type PartTwo = i64;
 
-    fn coords(&self, idx: usize) -> (u8, u8) {
-        ((idx % self.line_len) as u8, (idx / self.line_len) as u8)
+    fn parse(input: &str) -> ParseResult<Grid<char>> {
+        Grid::parse(input, |c| c)
     }
 
-    fn idx(&self, coords: (u8, u8)) -> usize {
-        (coords.1 as usize * self.line_len) + coords.0 as usize
+    fn part_one(map: &Grid<char>) -> i64 {
+        trail_starts(map)
+            .map(|start| find_unique_trail_heads(map, start).len() as i64)
+            .sum()
     }
 
-    fn get(&self, coords: (u8, u8)) -> Option<char> {
-        let idx = self.idx(coords);
-        self.data.chars().nth(idx)
-    }
-}
-
-fn part_one(input: &str) -> i64 {
-    let map = Map::new(input);
-    let mut start_idx = 0;
-    let mut total = 0;
-
-    while let Some(idx) = map.index_of('0', start_idx) {
-        let coords = map.coords(idx);
-
-        let trailheads = find_unique_trail_heads(&map, coords);
-        total += trailheads.len() as i64;
-
-        start_idx = idx + 1;
+    fn part_two(map: &Grid<char>) -> i64 {
+        trail_starts(map)
+            .map(|start| find_all_trail_heads(map, start).len() as i64)
+            .sum()
     }
-    total
 }
 
-fn part_two(input: &str) -> i64 {
-    let map = Map::new(input);
-    let mut start_idx = 0;
-    let mut total = 0;
-
-    while let Some(idx) = map.index_of('0', start_idx) {
-        let coords = map.coords(idx);
-
-        let trailheads = find_all_trail_heads(&map, coords);
-        total += trailheads.len() as i64;
-
-        start_idx = idx + 1;
-    }
-    total
+fn trail_starts(map: &Grid<char>) -> impl Iterator<Item = Pos> + '_ {
+    map.iter().filter(|(_, &c)| c == '0').map(|(pos, _)| pos)
 }
 
-fn find_unique_trail_heads(map: &Map, start: (u8, u8)) -> Vec<(u8, u8)> {
+fn find_unique_trail_heads(map: &Grid<char>, start: Pos) -> Vec<Pos> {
     let mut trail_heads = Vec::new();
     find_trail_heads(map, start, &mut |coords| {
         if trail_heads.contains(&coords) {
@@ -78,7 +43,7 @@ fn find_unique_trail_heads(map: &Map, start: (u8, u8)) -> Vec<(u8, u8)> {
     trail_heads
 }
 
-fn find_all_trail_heads(map: &Map, start: (u8, u8)) -> Vec<(u8, u8)> {
+fn find_all_trail_heads(map: &Grid<char>, start: Pos) -> Vec<Pos> {
     let mut trail_heads = Vec::new();
     find_trail_heads(map, start, &mut |coords| {
         trail_heads.push(coords);
@@ -86,33 +51,19 @@ fn find_all_trail_heads(map: &Map, start: (u8, u8)) -> Vec<(u8, u8)> {
     trail_heads
 }
 
-fn find_trail_heads<F>(map: &Map, start: (u8, u8), consumer: &mut F)
+fn find_trail_heads<F>(map: &Grid<char>, start: Pos, consumer: &mut F)
 where
-    F: FnMut((u8, u8)),
+    F: FnMut(Pos),
 {
-    let current = map.get(start).unwrap();
+    let current = map[start];
     if current == '9' {
         consumer(start);
         return;
     }
 
-    try_to_explore_trail(map, (start.0 + 1, start.1), current, consumer);
-    if start.0 > 0 {
-        try_to_explore_trail(map, (start.0 - 1, start.1), current, consumer);
-    }
-    try_to_explore_trail(map, (start.0, start.1 + 1), current, consumer);
-    if start.1 > 0 {
-        try_to_explore_trail(map, (start.0, start.1 - 1), current, consumer);
-    }
-}
-
-fn try_to_explore_trail<F>(map: &Map, possible: (u8, u8), current: char, consumer: &mut F)
-where
-    F: FnMut((u8, u8)),
-{
-    if let Some(next) = map.get(possible) {
-        if next as u8 - 1 == current as u8 {
-            find_trail_heads(map, possible, consumer);
+    for next in map.neighbours4(start) {
+        if map[next] as u8 == current as u8 + 1 {
+            find_trail_heads(map, next, consumer);
         }
     }
 }
@@ -131,7 +82,7 @@ mod tests {
 32019012
 01329801
 10456732";
-        assert_eq!(part_one(map), 36);
+        assert_eq!(Day::part_one(&Day::parse(map).unwrap()), 36);
     }
 
     #[test]
@@ -143,7 +94,7 @@ mod tests {
 7777777
 8777778
 9777779";
-        assert_eq!(part_one(map), 2);
+        assert_eq!(Day::part_one(&Day::parse(map).unwrap()), 2);
     }
 
     #[test]
@@ -152,7 +103,7 @@ mod tests {
 1234
 8765
 9876";
-        assert_eq!(part_one(map), 1);
+        assert_eq!(Day::part_one(&Day::parse(map).unwrap()), 1);
     }
 
     #[test]
@@ -165,6 +116,6 @@ mod tests {
 32019012
 01329801
 10456732";
-        assert_eq!(part_two(map), 81);
+        assert_eq!(Day::part_two(&Day::parse(map).unwrap()), 81);
     }
 }
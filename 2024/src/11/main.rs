@@ -1,62 +1,66 @@
-use std::collections::HashMap;
-
-use itertools::Itertools;
+use aoc_common::{memoize, ParseError, ParseResult, Solution};
 
 fn main() {
-    let input = include_str!("input.txt");
-    println!("Part one: {}", part_one(input, 25));
-    println!("Part two: {}", part_one(input, 75));
+    aoc_common::run_day::<Day>(2024, 11);
 }
 
-fn part_one(input: &str, blinks: usize) -> usize {
-    let input: Vec<i64> = input
-        .split_whitespace()
-        .map(|i| i.parse().unwrap())
-        .collect_vec();
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = Vec<i64>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> ParseResult<Vec<i64>> {
+        input
+            .split_whitespace()
+            .map(|i| {
+                Some(i)
+                    .filter(|i| i.chars().all(|c| c.is_ascii_digit()))
+                    .and_then(|i| i.parse().ok())
+                    .ok_or_else(|| ParseError::at_slice(input, i, "expected a stone's number"))
+            })
+            .collect()
+    }
 
-    let mut result = 0;
-    for stone in input {
-        result += count_blinks(stone, blinks, &mut HashMap::new());
+    fn part_one(input: &Vec<i64>) -> usize {
+        count_stones(input, 25)
+    }
+
+    fn part_two(input: &Vec<i64>) -> usize {
+        count_stones(input, 75)
     }
-    result
 }
 
+fn count_stones(input: &[i64], blinks: usize) -> usize {
+    let mut count_blinks = memoize(count_blinks);
+    input
+        .iter()
+        .map(|&stone| count_blinks.call((stone, blinks)))
+        .sum()
+}
+
+/// How many stones `stone` becomes after `blinks` blinks.
 fn count_blinks(
-    mut stone: i64,
-    mut blinks: usize,
-    memory: &mut HashMap<(i64, usize), usize>,
+    count: &mut dyn FnMut((i64, usize)) -> usize,
+    (stone, blinks): (i64, usize),
 ) -> usize {
     if blinks == 0 {
         return 1;
     }
 
-    let m = memory.get(&(stone, blinks));
-    if let Some(&value) = m {
-        return value;
+    if stone == 0 {
+        return count((1, blinks - 1));
     }
-
-    for _ in 0..blinks {
-        if stone == 0 {
-            stone = 1;
-            blinks -= 1;
-        } else {
-            let num_digits = digits(stone);
-            if num_digits % 2 == 0 {
-                let half = num_digits / 2;
-                let ten_pow = 10_i64.pow(half as u32);
-                let a = stone / ten_pow;
-                let b = stone - (a * ten_pow);
-                let result =
-                    count_blinks(a, blinks - 1, memory) + count_blinks(b, blinks - 1, memory);
-                memory.insert((stone, blinks), result);
-                return result;
-            } else {
-                stone *= 2024;
-                blinks -= 1;
-            }
-        }
+    let num_digits = digits(stone);
+    if num_digits % 2 == 0 {
+        let ten_pow = 10_i64.pow(num_digits as u32 / 2);
+        let a = stone / ten_pow;
+        let b = stone - (a * ten_pow);
+        count((a, blinks - 1)) + count((b, blinks - 1))
+    } else {
+        count((stone * 2024, blinks - 1))
     }
-    1
 }
 
 fn digits(n: i64) -> usize {
@@ -73,7 +77,14 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        assert_eq!(part_one("125 17", 6), 22);
-        assert_eq!(part_one("125 17", 25), 55312);
+        assert_eq!(count_stones(&[125, 17], 6), 22);
+        assert_eq!(count_stones(&[125, 17], 25), 55312);
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Ok(vec![125, 17]), Day::parse("125 17\n"));
+        let err = Day::parse("125 -17\n").unwrap_err();
+        assert_eq!((1, 5), (err.line, err.column));
     }
 }
@@ -1,10 +1,48 @@
 use core::str;
 use std::collections::{HashMap, HashSet};
 
+use aoc_common::{ParseError, ParseResult, Solution};
+
 fn main() {
-    let input = include_str!("input.txt");
-    println!("Part one: {}", part_one(input));
-    println!("Part two: {}", part_two(input));
+    aoc_common::run_day::<Day>(2024, 12);
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = Map;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> ParseResult<Map> {
+        parse(input)
+    }
+
+    fn part_one(map: &Map) -> usize {
+        let mut result = 0;
+        for (_, flowers) in &map.map {
+            let groups = group(&flowers);
+            for group in groups {
+                let area = group.area();
+                let perimeter = group.perimeter();
+                result += area * perimeter;
+            }
+        }
+        result
+    }
+
+    fn part_two(map: &Map) -> usize {
+        let mut result = 0;
+        for (_, flowers) in &map.map {
+            let groups = group(&flowers);
+            for group in groups {
+                let area = group.area();
+                let sides = group.sides();
+                result += area * sides;
+            }
+        }
+        result
+    }
 }
 
 struct FlowerGroup {
@@ -97,49 +135,38 @@ impl FlowerGroup {
     }
 }
 
-struct Map {
+#[derive(Debug)]
+pub struct Map {
     map: HashMap<char, Vec<(usize, usize)>>,
 }
 
-fn part_one(input: &str) -> usize {
-    let map = parse(input);
-    let mut result = 0;
-    for (_, flowers) in map.map {
-        let groups = group(&flowers);
-        for group in groups {
-            let area = group.area();
-            let perimeter = group.perimeter();
-            result += area * perimeter;
-        }
+/// The garden, a rectangle of plots each marked with the letter of its plant.
+fn parse(input: &str) -> ParseResult<Map> {
+    let mut map = HashMap::new();
+    let lines: Vec<&str> = aoc_common::lines(input).collect();
+    let width = lines.first().map_or(0, |line| line.len());
+    if width == 0 {
+        return Err(ParseError::at(input, 0, "expected a map of the garden"));
     }
-    result
-}
 
-fn part_two(input: &str) -> usize {
-    let map = parse(input);
-    let mut result = 0;
-    for (_, flowers) in map.map {
-        let groups = group(&flowers);
-        for group in groups {
-            let area = group.area();
-            let sides = group.sides();
-            result += area * sides;
+    for (y, line) in lines.iter().enumerate() {
+        if let Some(x) = line.find(|c: char| !c.is_ascii_alphabetic()) {
+            return Err(ParseError::at_slice(
+                input,
+                &line[x..],
+                "expected a plant letter",
+            ));
         }
-    }
-    result
-}
-
-fn parse(input: &str) -> Map {
-    let mut map = HashMap::new();
-    let line_len = input.lines().next().unwrap().len() + 1;
-
-    for (i, c) in input.chars().enumerate() {
-        if c.is_alphabetic() {
+        if line.len() != width {
+            let message = format!("expected {} plots in the row", width);
+            return Err(ParseError::at_slice(input, line, message));
+        }
+        for (x, c) in line.chars().enumerate() {
             let flowers: &mut Vec<(usize, usize)> = map.entry(c).or_default();
-            flowers.push((i % line_len, i / line_len));
+            flowers.push((x, y));
         }
     }
-    Map { map }
+    Ok(Map { map })
 }
 
 fn group(flowers: &Vec<(usize, usize)>) -> Vec<FlowerGroup> {
@@ -197,7 +224,7 @@ VVIIICJJEE
 MIIIIIJJEE
 MIIISIJEEE
 MMMISSJEEE";
-        assert_eq!(part_one(map), 1930);
+        assert_eq!(Day::part_one(&Day::parse(map).unwrap()), 1930);
     }
 
     #[test]
@@ -207,7 +234,7 @@ OXOXO
 OOOOO
 OXOXO
 OOOOO";
-        assert_eq!(part_one(map), 772);
+        assert_eq!(Day::part_one(&Day::parse(map).unwrap()), 772);
     }
 
     #[test]
@@ -222,7 +249,7 @@ VVIIICJJEE
 MIIIIIJJEE
 MIIISIJEEE
 MMMISSJEEE";
-        assert_eq!(part_two(map), 1206);
+        assert_eq!(Day::part_two(&Day::parse(map).unwrap()), 1206);
     }
 
     #[test]
@@ -231,7 +258,7 @@ MMMISSJEEE";
 BBCD
 BBCC
 EEEC";
-        assert_eq!(part_two(map), 80);
+        assert_eq!(Day::part_two(&Day::parse(map).unwrap()), 80);
     }
 
     #[test]
@@ -241,7 +268,7 @@ OXOXO
 OOOOO
 OXOXO
 OOOOO";
-        assert_eq!(part_two(map), 436);
+        assert_eq!(Day::part_two(&Day::parse(map).unwrap()), 436);
     }
 
     #[test]
@@ -251,7 +278,7 @@ EXXXX
 EEEEE
 EXXXX
 EEEEE";
-        assert_eq!(part_two(map), 236);
+        assert_eq!(Day::part_two(&Day::parse(map).unwrap()), 236);
     }
 
     #[test]
@@ -262,6 +289,19 @@ AAABBA
 ABBAAA
 ABBAAA
 AAAAAA";
-        assert_eq!(part_two(map), 368);
+        assert_eq!(Day::part_two(&Day::parse(map).unwrap()), 368);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Day::part_one(&parse("AAAA\r\nBBCD\r\nBBCC\r\nEEEC\r\n").unwrap()),
+            140
+        );
+        let err = parse("AAAA\nBB.D\n").unwrap_err();
+        assert_eq!((2, 3), (err.line, err.column));
+        let err = parse("AAAA\nBBC\n").unwrap_err();
+        assert_eq!((2, 1), (err.line, err.column));
+        assert!(parse("").is_err());
     }
 }
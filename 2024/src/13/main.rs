@@ -1,51 +1,62 @@
+use aoc_common::{ParseError, ParseResult, Solution};
+
 fn main() {
-    let input = include_str!("input.txt");
-    println!("Part one: {}", part_one(input));
-    println!("Part two: {}", part_two(input));
+    aoc_common::run_day::<Day>(2024, 13);
 }
 
-#[derive(Debug)]
-struct Button {
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = Vec<ClawMachine>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> ParseResult<Vec<ClawMachine>> {
+        aoc_common::paragraphs(input)
+            .iter()
+            .map(|lines| parse_claw_machine(input, lines))
+            .collect()
+    }
+
+    fn part_one(input: &Vec<ClawMachine>) -> usize {
+        input.iter().filter_map(cheapest_way_to_win).sum()
+    }
+
+    fn part_two(input: &Vec<ClawMachine>) -> usize {
+        input
+            .iter()
+            .map(|machines| ClawMachine {
+                button_a: machines.button_a.clone(),
+                button_b: machines.button_b.clone(),
+                prize: Prize {
+                    x: machines.prize.x + 10000000000000_usize,
+                    y: machines.prize.y + 10000000000000_usize,
+                },
+            })
+            .filter_map(|machine| cheapest_way_to_win(&machine))
+            .sum()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Button {
     x: usize,
     y: usize,
 }
 
-#[derive(Debug)]
-struct Prize {
+#[derive(Debug, Clone)]
+pub struct Prize {
     x: usize,
     y: usize,
 }
 
-struct ClawMachine {
+#[derive(Debug)]
+pub struct ClawMachine {
     button_a: Button,
     button_b: Button,
     prize: Prize,
 }
 
-fn part_one(input: &str) -> usize {
-    input
-        .split("\n\n")
-        .map(parse_claw_machine)
-        .filter_map(|machine| cheapest_way_to_win(&machine))
-        .sum()
-}
-
-fn part_two(input: &str) -> usize {
-    input
-        .split("\n\n")
-        .map(parse_claw_machine)
-        .map(|machines| ClawMachine {
-            button_a: machines.button_a,
-            button_b: machines.button_b,
-            prize: Prize {
-                x: machines.prize.x + 10000000000000_usize,
-                y: machines.prize.y + 10000000000000_usize,
-            },
-        })
-        .filter_map(|machine| cheapest_way_to_win(&machine))
-        .sum()
-}
-
 fn cheapest_way_to_win(machine: &ClawMachine) -> Option<usize> {
     // solve a system of linear equations
     let xa = machine.button_a.x as i128;
@@ -70,28 +81,50 @@ fn cheapest_way_to_win(machine: &ClawMachine) -> Option<usize> {
     Some((num_a_presses as usize * 3) + num_b_presses as usize)
 }
 
-fn parse_claw_machine(input: &str) -> ClawMachine {
-    let mut lines = input.lines();
-    let button_a = parse_button(lines.next().unwrap());
-    let button_b = parse_button(lines.next().unwrap());
-    let prize = parse_prize(lines.next().unwrap());
-    ClawMachine {
+/// A machine from its three lines of `input`.
+fn parse_claw_machine(input: &str, lines: &[&str]) -> ParseResult<ClawMachine> {
+    let [a, b, prize] = match lines {
+        [a, b, prize] => [a, b, prize],
+        _ => {
+            return Err(ParseError::at_slice(
+                input,
+                lines[0],
+                "expected two buttons and a prize",
+            ))
+        }
+    };
+    let (x, y) = parse_pair(input, a, "Button A: ", '+')?;
+    let button_a = Button { x, y };
+    let (x, y) = parse_pair(input, b, "Button B: ", '+')?;
+    let button_b = Button { x, y };
+    let (x, y) = parse_pair(input, prize, "Prize: ", '=')?;
+    let prize = Prize { x, y };
+    Ok(ClawMachine {
         button_a,
         button_b,
         prize,
-    }
+    })
 }
 
-fn parse_button(input: &str) -> Button {
-    let x = input.split(',').next().unwrap()[11..].parse().unwrap();
-    let y = input.split(',').nth(1).unwrap()[2..].parse().unwrap();
-    Button { x, y }
-}
-
-fn parse_prize(input: &str) -> Prize {
-    let x = input.split(',').next().unwrap()[9..].parse().unwrap();
-    let y = input.split(',').nth(1).unwrap()[3..].parse().unwrap();
-    Prize { x, y }
+/// The numbers in a line such as `Button A: X+94, Y+34`.
+fn parse_pair(input: &str, line: &str, prefix: &str, sign: char) -> ParseResult<(usize, usize)> {
+    let expected = || format!("expected '{}X{}.., Y{}..'", prefix, sign, sign);
+    let (x, y) = line
+        .strip_prefix(prefix)
+        .and_then(|rest| rest.split_once(", "))
+        .ok_or_else(|| ParseError::at_slice(input, line, expected()))?;
+    let number = |text: &str, axis: char| {
+        let digits = text
+            .strip_prefix(axis)
+            .and_then(|t| t.strip_prefix(sign))
+            .ok_or_else(|| ParseError::at_slice(input, text, expected()))?;
+        // Small enough that the determinants can't overflow.
+        digits
+            .parse::<u32>()
+            .map(|n| n as usize)
+            .map_err(|_| ParseError::at_slice(input, digits, "expected a number"))
+    };
+    Ok((number(x, 'X')?, number(y, 'Y')?))
 }
 
 #[cfg(test)]
@@ -115,7 +148,7 @@ Prize: X=7870, Y=6450
 Button A: X+69, Y+23
 Button B: X+27, Y+71
 Prize: X=18641, Y=10279";
-        assert_eq!(part_one(input), 480);
+        assert_eq!(Day::part_one(&Day::parse(input).unwrap()), 480);
     }
 
     #[test]
@@ -135,6 +168,18 @@ Prize: X=7870, Y=6450
 Button A: X+69, Y+23
 Button B: X+27, Y+71
 Prize: X=18641, Y=10279";
-        assert_eq!(part_two(input), 875318608908);
+        assert_eq!(Day::part_two(&Day::parse(input).unwrap()), 875318608908);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = Day::parse("Button A: X+94, Y+34\nButton B: X+22, Y+x\nPrize: X=8400, Y=5400\n")
+            .unwrap_err();
+        assert_eq!((2, 19), (err.line, err.column));
+        let err = Day::parse("Button A: X+94, Y+34\nButton B: X+22, Y+67\n").unwrap_err();
+        assert_eq!("expected two buttons and a prize", err.message);
+        let err = Day::parse("Button A: X+94, Y+34\nButton B: X+22, Y+67\nPrize: X+8400, Y=5400\n")
+            .unwrap_err();
+        assert_eq!((3, 8), (err.line, err.column));
     }
 }
@@ -1,22 +1,41 @@
 use std::collections::HashSet;
+use std::convert::TryFrom;
 
-use itertools::Itertools;
+use aoc_common::{brent, ParseError, ParseResult, Solution};
 
 fn main() {
-    let input = include_str!("input.txt");
-    println!("Part one: {}", part_one(input, (101, 103)));
-    println!("Part two: {}", part_two(input, (101, 103)));
+    aoc_common::run_day::<Day>(2024, 14);
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = Vec<Robot>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> ParseResult<Vec<Robot>> {
+        parse(input)
+    }
+
+    fn part_one(input: &Vec<Robot>) -> usize {
+        safety_factor(input, (101, 103))
+    }
+
+    fn part_two(input: &Vec<Robot>) -> usize {
+        find_tree(input, (101, 103))
+    }
 }
 
 #[derive(Debug)]
-struct Robot {
+pub struct Robot {
     position: (usize, usize),
     velocity: (isize, isize),
 }
 
-fn part_one(input: &str, max_size: (usize, usize)) -> usize {
+fn safety_factor(robots: &[Robot], max_size: (usize, usize)) -> usize {
     let quadrants = generate_quadrants(
-        parse(input)
+        robots
             .iter()
             .map(|robot| solve_robot_position(robot, max_size, 100)),
         max_size,
@@ -49,35 +68,37 @@ fn generate_quadrants(
         })
 }
 
-fn part_two(input: &str, max_size: (usize, usize)) -> usize {
-    let robots = parse(input);
-    for i in 0..100000 {
+fn find_tree(robots: &[Robot], max_size: (usize, usize)) -> usize {
+    // The robots come back to where they started eventually, so only one
+    // cycle needs searching.
+    let start: Vec<(usize, usize)> = robots.iter().map(|robot| robot.position).collect();
+    let cycle = brent(start, |positions| {
+        positions
+            .iter()
+            .zip(robots)
+            .map(|(&position, robot)| {
+                let moved = Robot {
+                    position,
+                    velocity: robot.velocity,
+                };
+                solve_robot_position(&moved, max_size, 1)
+            })
+            .collect()
+    });
+    for i in 0..(cycle.start + cycle.length) as isize {
         let positions: HashSet<(usize, usize)> = robots
             .iter()
             .map(|robot| solve_robot_position(robot, max_size, i))
             .collect();
 
         if possible_solve(&positions, max_size) {
-            // println!("Iteration: {}", i);
-            // debug_print_tree(max_size, positions);
             return i as usize;
         }
     }
-    panic!("No solution found");
-}
-
-fn debug_print_tree(max_size: (usize, usize), positions: HashSet<(usize, usize)>) {
-    for y in 0..max_size.1 {
-        for x in 0..max_size.0 {
-            if positions.contains(&(x, y)) {
-                print!("1");
-            } else {
-                print!(".");
-            }
-        }
-        println!();
-    }
-    println!();
+    panic!(
+        "No solution found in {} seconds",
+        cycle.start + cycle.length
+    );
 }
 
 fn possible_solve(positions: &HashSet<(usize, usize)>, max_size: (usize, usize)) -> bool {
@@ -124,23 +145,46 @@ fn wrap_position(pos: isize, max: usize) -> usize {
     }
 }
 
-fn parse(input: &str) -> Vec<Robot> {
-    input.lines().map(parse_robot).collect()
+fn parse(input: &str) -> ParseResult<Vec<Robot>> {
+    aoc_common::lines(input)
+        .map(|line| parse_robot(input, line))
+        .collect()
+}
+
+/// A robot from a line such as `p=0,4 v=3,-3`, which is part of `input`.
+fn parse_robot(input: &str, line: &str) -> ParseResult<Robot> {
+    let (position, velocity) = line
+        .split_once(' ')
+        .ok_or_else(|| ParseError::at_slice(input, line, "expected 'p=x,y v=x,y'"))?;
+    let (x, y) = parse_pair(input, position, "p=")?;
+    let position = match (usize::try_from(x), usize::try_from(y)) {
+        (Ok(x), Ok(y)) => (x, y),
+        _ => {
+            return Err(ParseError::at_slice(
+                input,
+                position,
+                "positions can't be negative",
+            ))
+        }
+    };
+    let (x, y) = parse_pair(input, velocity, "v=")?;
+    Ok(Robot {
+        position,
+        velocity: (x as isize, y as isize),
+    })
 }
 
-fn parse_robot(line: &str) -> Robot {
-    let mut parts = line.split_whitespace();
-    let position = parts.next().unwrap()[2..]
-        .split(',')
-        .map(|n| n.parse().unwrap())
-        .collect_tuple()
-        .unwrap();
-    let velocity = parts.next().unwrap()[2..]
-        .split(',')
-        .map(|n| n.parse().unwrap())
-        .collect_tuple()
-        .unwrap();
-    Robot { position, velocity }
+/// Small enough numbers that a robot can be moved on many steps at once.
+fn parse_pair(input: &str, text: &str, prefix: &str) -> ParseResult<(i32, i32)> {
+    let (x, y) = text
+        .strip_prefix(prefix)
+        .and_then(|pair| pair.split_once(','))
+        .ok_or_else(|| ParseError::at_slice(input, text, format!("expected '{}x,y'", prefix)))?;
+    let number = |n: &str| {
+        n.parse()
+            .map_err(|_| ParseError::at_slice(input, n, "expected a number"))
+    };
+    Ok((number(x)?, number(y)?))
 }
 
 #[cfg(test)]
@@ -171,23 +215,33 @@ p=9,3 v=2,3
 p=7,3 v=-1,2
 p=2,4 v=2,-3
 p=9,5 v=-3,-3";
-        assert_eq!(part_one(input, (11, 7)), 12);
+        assert_eq!(safety_factor(&parse(input).unwrap(), (11, 7)), 12);
     }
 
     #[test]
     fn test_part_two() {
-        let input = "p=0,4 v=3,-3
-p=6,3 v=-1,-3
-p=10,3 v=-1,2
-p=2,0 v=2,-1
-p=0,0 v=1,3
-p=3,0 v=-2,-2
-p=7,6 v=-1,-3
-p=3,0 v=-1,-2
-p=9,3 v=2,3
-p=7,3 v=-1,2
-p=2,4 v=2,-3
-p=9,5 v=-3,-3";
-        assert_eq!(part_two(input, (11, 7)), 12);
+        // The example never lines ten robots up, so these only do once they
+        // all reach the top row after three seconds.
+        let input = "p=0,4 v=0,1
+p=1,1 v=0,2
+p=2,5 v=0,3
+p=3,4 v=0,1
+p=4,1 v=0,2
+p=5,5 v=0,3
+p=6,4 v=0,1
+p=7,1 v=0,2
+p=8,5 v=0,3
+p=9,4 v=0,1
+p=10,2 v=1,1";
+        assert_eq!(find_tree(&parse(input).unwrap(), (11, 7)), 3);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = parse("p=0,4 v=3,-3\np=6,3 v=-1,x\n").unwrap_err();
+        assert_eq!((2, 12), (err.line, err.column));
+        let err = parse("p=-1,4 v=3,-3\n").unwrap_err();
+        assert_eq!("positions can't be negative", err.message);
+        assert!(parse("p=0,4\n").is_err());
     }
 }
@@ -1,19 +1,47 @@
 use std::collections::HashMap;
 
+use aoc_common::{Direction, ParseError, ParseResult, Point, Solution};
+
 fn main() {
-    let input = include_str!("input.txt");
-    println!("Part one: {}", part_one(input));
-    println!("Part two: {}", part_two(input));
+    aoc_common::run_day::<Day>(2024, 15);
 }
 
-#[derive(Debug, Hash, Eq, PartialEq, Clone)]
-struct Vec2 {
-    x: i16,
-    y: i16,
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = (Map, Vec<Direction>);
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> ParseResult<(Map, Vec<Direction>)> {
+        parse(input)
+    }
+
+    fn part_one((map, instructions): &(Map, Vec<Direction>)) -> usize {
+        let mut map = map.clone();
+        process_instructions(&mut map, instructions);
+        map.items
+            .iter()
+            .filter(|(_, item)| **item == Item::Box)
+            .map(|(pos, _)| gps_coord(pos))
+            .sum()
+    }
+
+    fn part_two((map, instructions): &(Map, Vec<Direction>)) -> usize {
+        let mut map = scale_up_map(map);
+        process_instructions(&mut map, instructions);
+        map.items
+            .iter()
+            .filter(|(_, item)| **item == Item::LeftBox)
+            .map(|(pos, _)| gps_coord(pos))
+            .sum()
+    }
 }
 
-#[derive(Debug, Hash, Eq, PartialEq)]
-enum Item {
+type Vec2 = Point<i16>;
+
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+pub enum Item {
     Wall,
     Box,
     LeftBox,
@@ -21,43 +49,16 @@ enum Item {
     Robot,
 }
 
-struct Map {
+#[derive(Clone, Debug)]
+pub struct Map {
     items: HashMap<Vec2, Item>,
 }
 
-#[derive(Debug, PartialEq, Eq, Hash)]
-enum Instruction {
-    Left,
-    Right,
-    Up,
-    Down,
-}
-
-fn part_one(input: &str) -> usize {
-    let (mut map, instructions) = parse(input);
-    process_instructions(&mut map, &instructions);
-    map.items
-        .iter()
-        .filter(|(_, item)| **item == Item::Box)
-        .map(|(pos, _)| gps_coord(pos))
-        .sum()
-}
-
-fn part_two(input: &str) -> usize {
-    let (mut map, instructions) = parse(&scale_up_map(input));
-    process_instructions(&mut map, &instructions);
-    map.items
-        .iter()
-        .filter(|(_, item)| **item == Item::LeftBox)
-        .map(|(pos, _)| gps_coord(pos))
-        .sum()
-}
-
 fn gps_coord(pos: &Vec2) -> usize {
     pos.x as usize + (100 * pos.y as usize)
 }
 
-fn process_instructions(map: &mut Map, instructions: &[Instruction]) {
+fn process_instructions(map: &mut Map, instructions: &[Direction]) {
     for instruction in instructions {
         let robot_pos = map
             .items
@@ -69,10 +70,10 @@ fn process_instructions(map: &mut Map, instructions: &[Instruction]) {
                     None
                 }
             })
-            .unwrap()
-            .clone();
+            .copied()
+            .unwrap();
 
-        try_to_move_item(map, &robot_pos, instruction, false);
+        try_to_move_item(map, &robot_pos, *instruction, false);
 
         // dbg_print_map(map);
     }
@@ -86,7 +87,7 @@ fn dbg_print_map(map: &Map) {
 
     for y in min_y..=max_y {
         for x in min_x..=max_x {
-            let pos = Vec2 { x, y };
+            let pos = Vec2::new(x, y);
             let item = map.items.get(&pos);
             let c = match item {
                 Some(Item::Wall) => '#',
@@ -103,19 +104,19 @@ fn dbg_print_map(map: &Map) {
     println!();
 }
 
-fn try_to_move_item(map: &mut Map, pos: &Vec2, instruction: &Instruction, dry_run: bool) -> bool {
-    let new_pos = fun_name(pos, instruction);
+fn try_to_move_item(map: &mut Map, pos: &Vec2, instruction: Direction, dry_run: bool) -> bool {
+    let new_pos = pos.step(instruction);
 
     if let Some(item) = map.items.get(&new_pos) {
         if *item == Item::Wall {
             return false;
         }
         if (*item == Item::LeftBox || *item == Item::RightBox)
-            && (*instruction == Instruction::Up || *instruction == Instruction::Down)
+            && (instruction == Direction::North || instruction == Direction::South)
         {
             let linked_pos = match *item {
-                Item::LeftBox => fun_name(&new_pos, &Instruction::Right),
-                Item::RightBox => fun_name(&new_pos, &Instruction::Left),
+                Item::LeftBox => new_pos.step(Direction::East),
+                Item::RightBox => new_pos.step(Direction::West),
                 _ => panic!("Invalid instruction"),
             };
             let possible = try_to_move_item(map, &new_pos, instruction, true)
@@ -137,80 +138,80 @@ fn try_to_move_item(map: &mut Map, pos: &Vec2, instruction: &Instruction, dry_ru
     true
 }
 
-fn fun_name(pos: &Vec2, instruction: &Instruction) -> Vec2 {
-    match instruction {
-        Instruction::Left => Vec2 {
-            x: pos.x - 1,
-            y: pos.y,
-        },
-        Instruction::Right => Vec2 {
-            x: pos.x + 1,
-            y: pos.y,
-        },
-        Instruction::Up => Vec2 {
-            x: pos.x,
-            y: pos.y - 1,
-        },
-        Instruction::Down => Vec2 {
-            x: pos.x,
-            y: pos.y + 1,
-        },
-    }
-}
-
-fn scale_up_map(input: &str) -> String {
-    let mut output = String::new();
-    for c in input.chars() {
-        match c {
-            '#' => output.push_str("##"),
-            'O' => output.push_str("[]"),
-            '.' => output.push_str(".."),
-            '@' => output.push_str("@."),
-            _ => output.push(c),
+/// The same warehouse with everything except the robot twice as wide.
+fn scale_up_map(map: &Map) -> Map {
+    let mut items = HashMap::new();
+    for (pos, item) in &map.items {
+        let left = Vec2::new(pos.x * 2, pos.y);
+        let right = Vec2::new(pos.x * 2 + 1, pos.y);
+        match item {
+            Item::Wall => {
+                items.insert(left, Item::Wall);
+                items.insert(right, Item::Wall);
+            }
+            Item::Box => {
+                items.insert(left, Item::LeftBox);
+                items.insert(right, Item::RightBox);
+            }
+            Item::Robot => {
+                items.insert(left, Item::Robot);
+            }
+            Item::LeftBox | Item::RightBox => unreachable!("the map is already scaled up"),
         }
     }
-    output
+    Map { items }
 }
 
-fn parse(input: &str) -> (Map, Vec<Instruction>) {
-    let mut split = input.split("\n\n");
-    let map = parse_map(split.next().unwrap());
-    let instructions = parse_instructions(split.next().unwrap());
-    (map, instructions)
+fn parse(input: &str) -> ParseResult<(Map, Vec<Direction>)> {
+    let (map, instructions) = input
+        .split_once("\n\n")
+        .ok_or_else(|| ParseError::at(input, input.len(), "expected a blank line after the map"))?;
+    Ok((
+        parse_map(input, map)?,
+        parse_instructions(input, instructions)?,
+    ))
 }
 
-fn parse_map(input: &str) -> Map {
+/// The map from `text`, which starts `input`. Everything stays within half
+/// the range of a coordinate so it can be scaled up for part two.
+fn parse_map(input: &str, text: &str) -> ParseResult<Map> {
     let mut items = HashMap::new();
-    input.lines().enumerate().for_each(|(y, line)| {
-        line.chars().enumerate().for_each(|(x, c)| {
-            let pos = Vec2 {
-                x: x as i16,
-                y: y as i16,
-            };
+    for (y, line) in text.lines().enumerate() {
+        for (x, c) in line.chars().enumerate() {
+            if x >= i16::MAX as usize / 2 || y >= i16::MAX as usize {
+                return Err(ParseError::at_line(input, y, x, "the map is too large"));
+            }
             let item = match c {
                 '#' => Item::Wall,
                 'O' => Item::Box,
-                '[' => Item::LeftBox,
-                ']' => Item::RightBox,
                 '@' => Item::Robot,
-                _ => return,
+                '.' => continue,
+                _ => {
+                    let message = format!("unexpected character '{}'", c);
+                    return Err(ParseError::at_line(input, y, x, message));
+                }
             };
-            items.insert(pos, item);
-        });
-    });
-    Map { items }
+            items.insert(Vec2::from_pos((x, y)), item);
+        }
+    }
+    match items.values().filter(|item| **item == Item::Robot).count() {
+        1 => Ok(Map { items }),
+        n => Err(ParseError::at_slice(
+            input,
+            text,
+            format!("expected one robot, found {}", n),
+        )),
+    }
 }
 
-fn parse_instructions(input: &str) -> Vec<Instruction> {
-    input
-        .chars()
-        .filter(|&c| c == '^' || c == 'v' || c == '<' || c == '>')
-        .map(|c| match c {
-            '^' => Instruction::Up,
-            'v' => Instruction::Down,
-            '<' => Instruction::Left,
-            '>' => Instruction::Right,
-            _ => panic!("Invalid instruction"),
+fn parse_instructions(input: &str, text: &str) -> ParseResult<Vec<Direction>> {
+    text.char_indices()
+        .filter(|(_, c)| *c != '\n')
+        .map(|(i, c)| {
+            Direction::from_arrow(c).ok_or_else(|| {
+                let offset = text.as_ptr() as usize - input.as_ptr() as usize + i;
+                ParseError::at(input, offset, format!("unexpected character '{}'", c))
+            })
         })
         .collect()
 }
@@ -242,7 +243,7 @@ vvv<<^>^v^^><<>>><>^<<><^vv^^<>vvv<>><^^v>^>vv<>v<<<<v<^v>^<^^>>>^<v<v
 <><^^>^^^<><vvvvv^v<v<<>^v<v>v<<^><<><<><<<^^<<<^<<>><<><^^^>^^<>^>v<>
 ^^>vv<^v^v<vv>^<><v<^v>^^^>>>^^vvv^>vvv<>>>^<^>>>>>^<<^v>^vvv<>^<><<v>
 v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
-        assert_eq!(part_one(input), 10092);
+        assert_eq!(Day::part_one(&Day::parse(input).unwrap()), 10092);
     }
 
     #[test]
@@ -257,7 +258,7 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
 ########
 
 <^^>>>vv<v>>v<<";
-        assert_eq!(part_one(input), 2028);
+        assert_eq!(Day::part_one(&Day::parse(input).unwrap()), 2028);
     }
 
     #[test]
@@ -283,7 +284,7 @@ vvv<<^>^v^^><<>>><>^<<><^vv^^<>vvv<>><^^v>^>vv<>v<<<<v<^v>^<^^>>>^<v<v
 <><^^>^^^<><vvvvv^v<v<<>^v<v>v<<^><<><<><<<^^<<<^<<>><<><^^^>^^<>^>v<>
 ^^>vv<^v^v<vv>^<><v<^v>^^^>>>^^vvv^>vvv<>>>^<^>>>>>^<<^v>^vvv<>^<><<v>
 v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
-        assert_eq!(part_two(input), 9021);
+        assert_eq!(Day::part_two(&Day::parse(input).unwrap()), 9021);
     }
 
     #[test]
@@ -297,6 +298,17 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
 #######
 
 <vv<<^^<<^^";
-        assert_eq!(part_two(input), 618);
+        assert_eq!(Day::part_two(&Day::parse(input).unwrap()), 618);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = Day::parse("####\n#@x#\n####\n\n<>").unwrap_err();
+        assert_eq!((2, 3), (err.line, err.column));
+        let err = Day::parse("####\n#@.#\n####\n\n<x>").unwrap_err();
+        assert_eq!((5, 2), (err.line, err.column));
+        let err = Day::parse("####\n#..#\n####\n\n<>").unwrap_err();
+        assert_eq!("expected one robot, found 0", err.message);
+        assert!(Day::parse("####\n#@.#\n####\n").is_err());
     }
 }
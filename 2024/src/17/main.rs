@@ -1,135 +1,646 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::num::{IntErrorKind, ParseIntError};
+
+use aoc_common::{
+    Debuggable, Exit, Flow, InstructionSet, Machine, ParseError, ParseResult, Registers, Solution,
+    VmError,
+};
 use itertools::Itertools;
 
 fn main() {
-    let input = include_str!("input.txt");
-    println!("Part one: {}", part_one(input));
-    println!("Part two: {}", part_two(input));
+    aoc_common::run_debuggable::<Day, _>(2024, 17, |(computer, program), part| {
+        let mut computer = computer.clone();
+        if part == 2 {
+            match find_quine(&computer, program) {
+                Ok(a) => computer.registers[A] = a,
+                Err(e) => eprintln!("{}, so starting with A unchanged", e),
+            }
+        }
+        Machine::new(ThreeBit::default(), program.clone(), computer)
+    });
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = (Computer, Vec<i32>);
+    type PartOne = String;
+    type PartTwo = u64;
+
+    fn parse(input: &str) -> ParseResult<(Computer, Vec<i32>)> {
+        parse(input)
+    }
+
+    fn part_one(input: &(Computer, Vec<i32>)) -> String {
+        let (computer, program) = input;
+        run_program(computer, program)
+            .unwrap_or_else(|e| panic!("{}", e))
+            .iter()
+            .join(",")
+    }
+
+    fn part_two(input: &(Computer, Vec<i32>)) -> u64 {
+        let (computer, program) = input;
+        find_quine(computer, program).unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+const A: usize = 0;
+const B: usize = 1;
+const C: usize = 2;
+
+/// How many steps a candidate A gets to output the program and halt, like the
+/// debugger's limit on a resume.
+const STEP_LIMIT: usize = 1_000_000;
+
+/// The 3-bit computer, where each instruction is an opcode followed by its
+/// operand.
+///
+/// Registers are 64 bits wide. The division instructions divide by a power of
+/// two, so can only shrink a register, and dividing by 2 to the power of 64 or
+/// more gives zero. A [`checked`](ThreeBit::checked) computer instead reports
+/// dividing a non-zero register that way as an overflow, since a divisor that
+/// wide doesn't fit in a register. Solving runs unchecked; in the debugger,
+/// `set checked` switches checking on.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ThreeBit {
+    checked: bool,
 }
 
-#[derive(Debug, Clone)]
-struct Computer {
-    a: i32,
-    b: i32,
-    c: i32,
-    pc: i32,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Opcode {
+    Adv,
+    Bxl,
+    Bst,
+    Jnz,
+    Bxc,
+    Out,
+    Bdv,
+    Cdv,
+}
+
+impl Opcode {
+    /// Every opcode, in numeric order.
+    const ALL: [Opcode; 8] = [
+        Opcode::Adv,
+        Opcode::Bxl,
+        Opcode::Bst,
+        Opcode::Jnz,
+        Opcode::Bxc,
+        Opcode::Out,
+        Opcode::Bdv,
+        Opcode::Cdv,
+    ];
+
+    fn mnemonic(self) -> &'static str {
+        match self {
+            Opcode::Adv => "adv",
+            Opcode::Bxl => "bxl",
+            Opcode::Bst => "bst",
+            Opcode::Jnz => "jnz",
+            Opcode::Bxc => "bxc",
+            Opcode::Out => "out",
+            Opcode::Bdv => "bdv",
+            Opcode::Cdv => "cdv",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Instruction {
+    opcode: Opcode,
+    operand: i32,
+}
+
+impl Instruction {
+    /// The operand as a listing shows it: a combo operand names the register
+    /// it reads, and `bxc` ignores its operand altogether.
+    fn operand_text(&self) -> String {
+        match self.opcode {
+            Opcode::Bxc => String::new(),
+            Opcode::Adv | Opcode::Bst | Opcode::Out | Opcode::Bdv | Opcode::Cdv => combo_expr(
+                &[Expr::Reg('A'), Expr::Reg('B'), Expr::Reg('C')],
+                self.operand,
+            )
+            .to_string(),
+            Opcode::Bxl | Opcode::Jnz => self.operand.to_string(),
+        }
+    }
+
+    /// What the instruction does, as a statement.
+    fn effect(&self) -> String {
+        let x = self.operand_text();
+        match self.opcode {
+            Opcode::Adv => format!("A = A >> {}", x),
+            Opcode::Bxl => format!("B = B ^ {}", x),
+            Opcode::Bst => format!("B = {} % 8", x),
+            Opcode::Jnz => format!("if A != 0 goto {}", x),
+            Opcode::Bxc => "B = B ^ C".to_string(),
+            Opcode::Out => format!("out({} % 8)", x),
+            Opcode::Bdv => format!("B = A >> {}", x),
+            Opcode::Cdv => format!("C = A >> {}", x),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Computer {
+    registers: Registers<u64, 3>,
     output: Vec<i32>,
 }
 
-fn part_one(input: &str) -> String {
-    let (mut computer, program) = parse(input);
-    run_program(&mut computer, &program, usize::MAX);
-    computer.output.iter().join(",")
+impl Computer {
+    fn new(a: u64, b: u64, c: u64) -> Computer {
+        Computer {
+            registers: Registers::with_values(["A", "B", "C"], [a, b, c]),
+            output: vec![],
+        }
+    }
 }
 
-fn part_two(input: &str) -> usize {
-    let (_, program) = parse(input);
+impl InstructionSet for ThreeBit {
+    type Code = i32;
+    type Instruction = Instruction;
+    type State = Computer;
+    /// The registers and how much had been output.
+    type Undo = (Registers<u64, 3>, usize);
 
-    let computer = &mut Computer {
-        a: 0,
-        b: 0,
-        c: 0,
-        pc: program.len() as i32 - 2,
-        output: vec![],
-    };
-    while program[computer.pc as usize] != 5 {
-        run_program(computer, &program, 1);
+    fn decode(&self, code: &[i32], ip: usize) -> Result<(Instruction, usize), String> {
+        let opcode = usize::try_from(code[ip])
+            .ok()
+            .and_then(|op| Opcode::ALL.get(op))
+            .ok_or_else(|| format!("unknown opcode {}", code[ip]))?;
+        let operand = *code.get(ip + 1).ok_or("missing operand")?;
+        if !(0..8).contains(&operand) {
+            return Err(format!("operand {} isn't a 3-bit number", operand));
+        }
+        Ok((
+            Instruction {
+                opcode: *opcode,
+                operand,
+            },
+            2,
+        ))
+    }
+
+    fn execute(&self, instruction: &Instruction, computer: &mut Computer) -> Result<Flow, String> {
+        let operand = instruction.operand;
+        let registers = &mut computer.registers;
+        match instruction.opcode {
+            Opcode::Adv => registers[A] = self.divide(registers[A], combo(registers, operand)?)?,
+            Opcode::Bxl => registers[B] ^= operand as u64,
+            Opcode::Bst => registers[B] = combo(registers, operand)? % 8,
+            Opcode::Jnz => {
+                if registers[A] != 0 {
+                    return Ok(Flow::Goto(operand as usize));
+                }
+            }
+            Opcode::Bxc => registers[B] ^= registers[C],
+            Opcode::Out => computer
+                .output
+                .push((combo(registers, operand)? % 8) as i32),
+            Opcode::Bdv => registers[B] = self.divide(registers[A], combo(registers, operand)?)?,
+            Opcode::Cdv => registers[C] = self.divide(registers[A], combo(registers, operand)?)?,
+        }
+        Ok(Flow::Next)
     }
 
-    let instruction = program[computer.pc as usize];
-    let operand = program[computer.pc as usize + 1];
-    dbg!(instruction, operand);
+    fn save(&self, _instruction: &Instruction, computer: &Computer) -> (Registers<u64, 3>, usize) {
+        (computer.registers, computer.output.len())
+    }
 
-    0
+    fn restore(&self, (registers, output): (Registers<u64, 3>, usize), computer: &mut Computer) {
+        computer.registers = registers;
+        computer.output.truncate(output);
+    }
 }
 
-fn run_program(computer: &mut Computer, program: &[i32], max_instructions: usize) {
-    for _ in 0..max_instructions {
-        if computer.pc >= program.len() as i32 {
-            return;
+impl ThreeBit {
+    /// A computer that reports over-wide divisions instead of giving zero.
+    pub fn checked() -> ThreeBit {
+        ThreeBit { checked: true }
+    }
+
+    /// `value` divided by 2 to the power `by`.
+    fn divide(&self, value: u64, by: u64) -> Result<u64, String> {
+        match u32::try_from(by).ok().and_then(|by| value.checked_shr(by)) {
+            Some(quotient) => Ok(quotient),
+            None if self.checked && value != 0 => Err(format!(
+                "dividing {} by 2^{} overflows the 64-bit registers",
+                value, by
+            )),
+            None => Ok(0),
+        }
+    }
+}
+
+impl Debuggable for ThreeBit {
+    fn mnemonic(&self, instruction: &Instruction) -> String {
+        instruction.opcode.mnemonic().to_string()
+    }
+
+    fn describe(&self, instruction: &Instruction) -> String {
+        format!(
+            "{} {}",
+            instruction.opcode.mnemonic(),
+            instruction.operand_text()
+        )
+        .trim_end()
+        .to_string()
+    }
+
+    fn comment(&self, instruction: &Instruction) -> Option<String> {
+        Some(instruction.effect())
+    }
+
+    fn decompile(&self, program: &[i32]) -> Option<String> {
+        Some(decompile(program))
+    }
+
+    fn registers(&self, computer: &Computer) -> Vec<(String, String)> {
+        computer
+            .registers
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    fn memory(&self, computer: &Computer) -> Vec<(String, String)> {
+        vec![("output".to_string(), computer.output.iter().join(","))]
+    }
+
+    fn set_option(&mut self, name: &str, on: bool) -> Result<(), String> {
+        match name {
+            "checked" => self.checked = on,
+            _ => return Err(format!("no option called {}", name)),
         }
-        let instruction = program[computer.pc as usize];
-        let operand = program[computer.pc as usize + 1];
-        match instruction {
-            0 => {
-                // adv
-                let op = combo(computer, operand);
-                computer.a /= 1 << op;
+        Ok(())
+    }
+}
+
+/// Runs `program` from the start on a copy of `computer`, returning what it
+/// outputs.
+fn run_program(computer: &Computer, program: &[i32]) -> Result<Vec<i32>, VmError> {
+    let mut machine = Machine::new(ThreeBit::default(), program.to_vec(), computer.clone());
+    machine.run()?;
+    Ok(machine.into_state().output)
+}
+
+/// The lowest initial A for which `program` outputs itself.
+///
+/// Each time round its loop the program outputs a digit worked out from the
+/// low bits of A and then shifts A right by three, so the last digit depends
+/// only on A's top three bits, the last two on its top six and so on. Working
+/// back from the end, each candidate so far is extended by every value of the
+/// next three bits and kept if the program then outputs the end of itself.
+///
+/// Candidates too big for a register are dropped, as are those that run for
+/// [`STEP_LIMIT`] steps without halting, and either is only reported if nothing
+/// else works.
+fn find_quine(computer: &Computer, program: &[i32]) -> Result<u64, String> {
+    let mut candidates: Vec<u64> = vec![0];
+    let mut overflowed = false;
+    let mut runaway = false;
+    for len in 1..=program.len() {
+        let wanted = &program[program.len() - len..];
+        let mut next = vec![];
+        for a in candidates {
+            let a = match a.checked_mul(8) {
+                Some(a) => a,
+                None => {
+                    overflowed = true;
+                    continue;
+                }
+            };
+            for bits in 0..8 {
+                match outputs(computer, program, a + bits, wanted)? {
+                    Some(true) => next.push(a + bits),
+                    Some(false) => {}
+                    None => runaway = true,
+                }
             }
-            1 => {
-                // bxl
-                computer.b ^= operand;
+        }
+        // Extending each candidate in order keeps them sorted.
+        candidates = next;
+    }
+    match candidates.first() {
+        Some(&a) => Ok(a),
+        None if overflowed => Err("the quine needs an A wider than 64 bits".to_string()),
+        None if runaway => Err(format!(
+            "no initial A makes the program output itself and halt within {} steps",
+            STEP_LIMIT
+        )),
+        None => Err("no initial A makes the program output itself".to_string()),
+    }
+}
+
+/// Whether the program outputs exactly `wanted` and halts starting with `a` in
+/// A, stopping early once the output goes wrong. `None` if it's still going
+/// after [`STEP_LIMIT`] steps.
+fn outputs(
+    computer: &Computer,
+    program: &[i32],
+    a: u64,
+    wanted: &[i32],
+) -> Result<Option<bool>, String> {
+    let mut computer = computer.clone();
+    computer.registers[A] = a;
+    let mut machine = Machine::new(ThreeBit::default(), program.to_vec(), computer);
+    let exit = machine
+        .run_until(|machine, _| {
+            machine.steps() >= STEP_LIMIT || !wanted.starts_with(&machine.state().output)
+        })
+        .map_err(|e| e.to_string())?;
+    let output = &machine.state().output;
+    if exit == Exit::Stopped && wanted.starts_with(output) {
+        return Ok(None);
+    }
+    Ok(Some(exit == Exit::Halted && output == wanted))
+}
+
+/// Every instruction in order with its address, or where decoding failed.
+fn decode_all(program: &[i32]) -> Result<Vec<(usize, Instruction)>, String> {
+    let mut decoded = vec![];
+    let mut ip = 0;
+    while ip < program.len() {
+        let (instruction, width) = ThreeBit::default()
+            .decode(program, ip)
+            .map_err(|e| format!("can't decode address {}: {}", ip, e))?;
+        decoded.push((ip, instruction));
+        ip += width;
+    }
+    Ok(decoded)
+}
+
+/// The program as pseudo-code. A program ending in its only jump, back to an
+/// earlier instruction, is a `do`/`while` loop whose body is worked through
+/// symbolically, so each output and the new A are written in terms of the
+/// registers at the top of the loop. Anything else is listed statement by
+/// statement with labels on the jump targets.
+fn decompile(program: &[i32]) -> String {
+    let instructions = match decode_all(program) {
+        Ok(instructions) => instructions,
+        Err(e) => return format!("{}\n", e),
+    };
+    let jumps: Vec<usize> = (0..instructions.len())
+        .filter(|&i| instructions[i].1.opcode == Opcode::Jnz)
+        .collect();
+    let loop_start = match jumps[..] {
+        [last] if last + 1 == instructions.len() => {
+            let target = instructions[last].1.operand;
+            instructions
+                .iter()
+                .position(|(ip, _)| *ip as i32 == target)
+                .filter(|&start| start < last)
+        }
+        _ => None,
+    };
+
+    let mut code = String::new();
+    match loop_start {
+        Some(start) => {
+            for (_, instruction) in &instructions[..start] {
+                code += &format!("{}\n", instruction.effect());
             }
-            2 => {
-                // bst
-                computer.b = combo(computer, operand) % 8;
+            code += "do {\n";
+            let body: Vec<Instruction> = instructions[start..instructions.len() - 1]
+                .iter()
+                .map(|(_, instruction)| *instruction)
+                .collect();
+            for statement in loop_body(&body) {
+                code += &format!("    {}\n", statement);
             }
-            3 => {
-                // jnz
-                if computer.a != 0 {
-                    computer.pc = operand - 2;
+            code += "} while A != 0\n";
+        }
+        None => {
+            let targets: Vec<i32> = jumps.iter().map(|&i| instructions[i].1.operand).collect();
+            for (ip, instruction) in &instructions {
+                if targets.contains(&(*ip as i32)) {
+                    code += &format!("{}:\n", ip);
                 }
+                code += &format!("    {}\n", instruction.effect());
             }
-            4 => {
-                // bxc
-                computer.b ^= computer.c;
-            }
-            5 => {
-                // out
-                computer.output.push(combo(computer, operand) % 8);
+        }
+    }
+    code
+}
+
+/// The statements of a loop body without its closing jump, after working out
+/// each register as an expression of the registers at the top of the loop.
+/// B and C are only assigned at the end if the next time round reads them,
+/// and the assignments are written as one parallel assignment if any of them
+/// reads a register assigned before it.
+fn loop_body(body: &[Instruction]) -> Vec<String> {
+    let mut registers = [Expr::Reg('A'), Expr::Reg('B'), Expr::Reg('C')];
+    let mut statements = vec![];
+    let mut outputs = vec![];
+    for instruction in body {
+        let x = combo_expr(&registers, instruction.operand);
+        match instruction.opcode {
+            Opcode::Adv => registers[A] = Expr::shr(registers[A].clone(), x),
+            Opcode::Bxl => {
+                registers[B] = Expr::xor(registers[B].clone(), Expr::Lit(instruction.operand))
             }
-            6 => {
-                // bdv
-                let op = combo(computer, operand);
-                computer.b = computer.a / (1 << op);
+            Opcode::Bst => registers[B] = Expr::mod8(x),
+            Opcode::Jnz => unreachable!("the loop body has no jumps"),
+            Opcode::Bxc => registers[B] = Expr::xor(registers[B].clone(), registers[C].clone()),
+            Opcode::Out => {
+                let value = Expr::mod8(x);
+                statements.push(format!("out({})", value));
+                outputs.push(value);
             }
-            7 => {
-                // cdv
-                let op = combo(computer, operand);
-                computer.c = computer.a / (1 << op);
+            Opcode::Bdv => registers[B] = Expr::shr(registers[A].clone(), x),
+            Opcode::Cdv => registers[C] = Expr::shr(registers[A].clone(), x),
+        }
+    }
+
+    let read = |name: char| {
+        outputs
+            .iter()
+            .chain(&registers)
+            .any(|expr| expr.mentions(name))
+    };
+    let assigned: Vec<(char, &Expr)> = ['A', 'B', 'C']
+        .iter()
+        .copied()
+        .zip(&registers)
+        .filter(|&(name, expr)| *expr != Expr::Reg(name) && (name == 'A' || read(name)))
+        .collect();
+    let sequential = assigned
+        .iter()
+        .enumerate()
+        .all(|(i, (_, expr))| assigned[..i].iter().all(|(name, _)| !expr.mentions(*name)));
+    if sequential {
+        for (name, expr) in assigned {
+            statements.push(format!("{} = {}", name, expr));
+        }
+    } else {
+        statements.push(format!(
+            "{} = {}",
+            assigned.iter().map(|(name, _)| name).join(", "),
+            assigned.iter().map(|(_, expr)| expr).join(", ")
+        ));
+    }
+    statements
+}
+
+/// A value worked out by a loop body, in terms of the registers at its top.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Expr {
+    Lit(i32),
+    Reg(char),
+    /// The reserved combo operand 7.
+    Invalid,
+    Shr(Box<Expr>, Box<Expr>),
+    Mod8(Box<Expr>),
+    /// Terms xored together, any literal folded into one at the end.
+    Xor(Vec<Expr>),
+}
+
+impl Expr {
+    fn shr(value: Expr, by: Expr) -> Expr {
+        match (value, by) {
+            (value, Expr::Lit(0)) => value,
+            (Expr::Lit(v), Expr::Lit(by)) if by < 32 => Expr::Lit(v >> by),
+            (value, by) => Expr::Shr(Box::new(value), Box::new(by)),
+        }
+    }
+
+    fn mod8(value: Expr) -> Expr {
+        match value {
+            Expr::Lit(v) => Expr::Lit(v % 8),
+            Expr::Mod8(inner) => Expr::Mod8(inner),
+            value => Expr::Mod8(Box::new(value)),
+        }
+    }
+
+    fn xor(a: Expr, b: Expr) -> Expr {
+        let mut literal = 0;
+        let mut terms = vec![];
+        for term in [a, b] {
+            let parts = match term {
+                Expr::Xor(parts) => parts,
+                term => vec![term],
+            };
+            for part in parts {
+                match part {
+                    Expr::Lit(v) => literal ^= v,
+                    part => terms.push(part),
+                }
             }
-            _ => panic!("Unknown instruction: {}", instruction),
         }
-        computer.pc += 2;
+        if literal != 0 || terms.is_empty() {
+            terms.push(Expr::Lit(literal));
+        }
+        if terms.len() == 1 {
+            terms.pop().unwrap()
+        } else {
+            Expr::Xor(terms)
+        }
+    }
+
+    fn mentions(&self, name: char) -> bool {
+        match self {
+            Expr::Reg(reg) => *reg == name,
+            Expr::Lit(_) | Expr::Invalid => false,
+            Expr::Shr(a, b) => a.mentions(name) || b.mentions(name),
+            Expr::Mod8(a) => a.mentions(name),
+            Expr::Xor(terms) => terms.iter().any(|t| t.mentions(name)),
+        }
+    }
+
+    /// Written as an operand of another expression.
+    fn operand(&self) -> String {
+        match self {
+            Expr::Shr(..) | Expr::Mod8(_) | Expr::Xor(_) => format!("({})", self),
+            _ => self.to_string(),
+        }
     }
 }
 
-fn combo(computer: &Computer, op: i32) -> i32 {
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expr::Lit(v) => write!(f, "{}", v),
+            Expr::Reg(name) => write!(f, "{}", name),
+            Expr::Invalid => write!(f, "<invalid>"),
+            Expr::Shr(a, b) => write!(f, "{} >> {}", a.operand(), b.operand()),
+            Expr::Mod8(a) => write!(f, "{} % 8", a.operand()),
+            Expr::Xor(terms) => write!(f, "{}", terms.iter().map(Expr::operand).join(" ^ ")),
+        }
+    }
+}
+
+/// A combo operand as an expression of `registers`.
+fn combo_expr(registers: &[Expr; 3], op: i32) -> Expr {
+    match op {
+        0..=3 => Expr::Lit(op),
+        4..=6 => registers[op as usize - 4].clone(),
+        _ => Expr::Invalid,
+    }
+}
+
+fn combo(registers: &Registers<u64, 3>, op: i32) -> Result<u64, String> {
     match op {
-        0..=3 => op,
-        4 => computer.a,
-        5 => computer.b,
-        6 => computer.c,
-        _ => panic!("Invalid combo operand: {}", op),
+        0..=3 => Ok(op as u64),
+        4 => Ok(registers[A]),
+        5 => Ok(registers[B]),
+        6 => Ok(registers[C]),
+        _ => Err(format!("invalid combo operand {}", op)),
     }
 }
 
-fn parse(input: &str) -> (Computer, Vec<i32>) {
-    let mut lines = input.lines();
-    let a = lines.next().unwrap()[12..].parse().unwrap();
-    let b = lines.next().unwrap()[12..].parse().unwrap();
-    let c = lines.next().unwrap()[12..].parse().unwrap();
+fn parse(input: &str) -> ParseResult<(Computer, Vec<i32>)> {
+    let lines: Vec<&str> = aoc_common::lines(input).collect();
+    let register = |y: usize, name: &str| -> ParseResult<u64> {
+        let prefix = format!("Register {}: ", name);
+        let value = field(input, &lines, y, &prefix)?;
+        value.parse().map_err(|e: ParseIntError| {
+            let message = match e.kind() {
+                IntErrorKind::PosOverflow => "doesn't fit in a 64-bit register",
+                _ => "expected a number",
+            };
+            ParseError::at_line(input, y, prefix.len(), message)
+        })
+    };
+    let a = register(0, "A")?;
+    let b = register(1, "B")?;
+    let c = register(2, "C")?;
 
-    let program = parse_program(&lines.nth(1).unwrap()[9..]);
+    let program = parse_program(input, 4, field(input, &lines, 4, "Program: ")?)?;
 
-    (
-        Computer {
-            a,
-            b,
-            c,
-            pc: 0,
-            output: vec![],
-        },
-        program,
-    )
+    Ok((Computer::new(a, b, c), program))
+}
+
+/// The rest of line `y` after `prefix`, which it must start with.
+fn field<'a>(input: &str, lines: &[&'a str], y: usize, prefix: &str) -> ParseResult<&'a str> {
+    lines
+        .get(y)
+        .and_then(|line| line.strip_prefix(prefix))
+        .ok_or_else(|| {
+            ParseError::at_line(input, y, 0, format!("expected '{}'", prefix.trim_end()))
+        })
 }
 
-fn parse_program(input: &str) -> Vec<i32> {
-    input.split(',').map(|x| x.parse().unwrap()).collect()
+fn parse_program(input: &str, y: usize, program: &str) -> ParseResult<Vec<i32>> {
+    let mut column = "Program: ".len();
+    program
+        .split(',')
+        .map(|x| {
+            let value = x
+                .parse()
+                .map_err(|_| ParseError::at_line(input, y, column, "expected a number"));
+            column += x.len() + 1;
+            value
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::Debugger;
 
     #[test]
     fn test_part_one() {
@@ -138,16 +649,266 @@ Register B: 0
 Register C: 0
 
 Program: 0,1,5,4,3,0";
-        assert_eq!(part_one(input), "4,6,3,5,6,3,5,2,1,0");
+        assert_eq!(
+            Day::part_one(&Day::parse(input).unwrap()),
+            "4,6,3,5,6,3,5,2,1,0"
+        );
     }
 
     #[test]
     fn test_part_two() {
-        let input = "Register A: 729
+        let input = "Register A: 2024
 Register B: 0
 Register C: 0
 
-Program: 0,1,5,4,3,0";
-        assert_eq!(part_two(input), 117440);
+Program: 0,3,5,4,3,0";
+        assert_eq!(Day::part_two(&Day::parse(input).unwrap()), 117440);
+    }
+
+    #[test]
+    fn test_no_quine() {
+        // Halving A each time round, the first two outputs share a bit and
+        // can't be 0 then 1.
+        let (computer, program) = Day::parse(
+            "Register A: 729
+Register B: 0
+Register C: 0
+
+Program: 0,1,5,4,3,0",
+        )
+        .unwrap();
+        assert_eq!(
+            Err("no initial A makes the program output itself".to_string()),
+            find_quine(&computer, &program)
+        );
+    }
+
+    #[test]
+    fn test_large_registers() {
+        // Forty-eight bits of A, three at a time.
+        let computer = Computer::new((1 << 48) - 1, 0, 0);
+        let mut expected = vec![7; 15];
+        expected.push(0);
+        assert_eq!(Ok(expected), run_program(&computer, &[0, 3, 5, 4, 3, 0]));
+
+        // bdv A, out B: shifting 100 bits out of A leaves nothing, which is
+        // an overflow when checked.
+        let program = vec![6, 4, 5, 5];
+        assert_eq!(
+            Ok(vec![0]),
+            run_program(&Computer::new(100, 0, 0), &program)
+        );
+        let checked = |program: &[i32], computer| {
+            let mut machine = Machine::new(ThreeBit { checked: true }, program.to_vec(), computer);
+            machine.run().map(|_| machine.into_state().output)
+        };
+        let err = checked(&program, Computer::new(100, 0, 0)).unwrap_err();
+        assert_eq!((0, 0), (err.ip, err.step));
+        assert_eq!(
+            "dividing 100 by 2^100 overflows the 64-bit registers",
+            err.message
+        );
+        // bdv B, out B: zero stays zero however far it's shifted, checked or
+        // not.
+        let program = vec![6, 5, 5, 5];
+        assert_eq!(
+            Ok(vec![0]),
+            run_program(&Computer::new(0, 100, 0), &program)
+        );
+        assert_eq!(Ok(vec![0]), checked(&program, Computer::new(0, 100, 0)));
+    }
+
+    #[test]
+    fn test_large_quine() {
+        // Padding the program with no-op bxl 0s needs three more bits of A
+        // for each output.
+        let padded = |k: usize| {
+            let mut program = [1, 0].repeat(k);
+            program.extend([0, 3, 5, 4, 3, 0].iter());
+            program
+        };
+        let computer = Computer::new(0, 0, 0);
+        let program = padded(5);
+        let a = find_quine(&computer, &program).unwrap();
+        assert!(a >= 1 << 45);
+        assert_eq!(
+            Ok(program),
+            run_program(&Computer::new(a, 0, 0), &padded(5))
+        );
+        assert_eq!(
+            Err("the quine needs an A wider than 64 bits".to_string()),
+            find_quine(&computer, &padded(9))
+        );
+    }
+
+    #[test]
+    fn test_runaway_quine() {
+        // jnz 0 spins forever with any A but zero, which halts without
+        // output.
+        let computer = Computer::new(0, 0, 0);
+        assert_eq!(Ok(None), outputs(&computer, &[3, 0], 1, &[3, 0]));
+        assert_eq!(
+            Err(format!(
+                "no initial A makes the program output itself and halt within {} steps",
+                STEP_LIMIT
+            )),
+            find_quine(&computer, &[3, 0])
+        );
+    }
+
+    #[test]
+    fn test_debugger() {
+        let (computer, program) = Day::parse(
+            "Register A: 729
+Register B: 0
+Register C: 0
+
+Program: 0,1,5,4,3,0",
+        )
+        .unwrap();
+        let mut debugger = Debugger::new(Machine::new(ThreeBit::default(), program, computer));
+        let mut out = Vec::new();
+        for command in ["break out", "c", "c", "memory", "bs", "registers"] {
+            debugger.command(command, &mut out).unwrap();
+        }
+        assert_eq!(
+            "[1]     2: out A            A=364 B=0 C=0
+[4]     2: out A            A=182 B=0 C=0
+output = 4
+[3]     0: adv 1            A=364 B=0 C=0
+A = 364
+B = 0
+C = 0
+",
+            String::from_utf8(out).unwrap().replace("breakpoint\n", "")
+        );
+    }
+
+    #[test]
+    fn test_debugger_checked() {
+        // bdv A, out B, as in test_large_registers.
+        let computer = Computer::new(100, 0, 0);
+        let mut debugger = Debugger::new(Machine::new(
+            ThreeBit::default(),
+            vec![6, 4, 5, 5],
+            computer,
+        ));
+        let mut out = Vec::new();
+        for command in ["set checked", "c", "set checked off", "c", "set fast"] {
+            debugger.command(command, &mut out).unwrap();
+        }
+        assert_eq!(
+            "error at address 0 after 0 steps: dividing 100 by 2^100 overflows the 64-bit registers
+[2] halted  A=100 B=0 C=0
+no option called fast
+",
+            String::from_utf8(out).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_disassemble() {
+        let listing = |program: Vec<i32>| {
+            let computer = Computer::new(0, 0, 0);
+            let mut debugger = Debugger::new(Machine::new(ThreeBit::default(), program, computer));
+            let mut out = Vec::new();
+            debugger.command("disassemble", &mut out).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+        assert_eq!(
+            "    0: adv 1        ; A = A >> 1
+    2: out A        ; out(A % 8)
+    4: jnz 0        ; if A != 0 goto 0
+",
+            listing(vec![0, 1, 5, 4, 3, 0])
+        );
+        assert_eq!(
+            "    0: bxc          ; B = B ^ C\n    2: missing operand\n",
+            listing(vec![4, 7, 2])
+        );
+    }
+
+    #[test]
+    fn test_decompile() {
+        assert_eq!(
+            "do {
+    out((A >> 1) % 8)
+    A = A >> 1
+} while A != 0
+",
+            decompile(&[0, 1, 5, 4, 3, 0])
+        );
+        // The shape of a typical puzzle input.
+        assert_eq!(
+            "do {
+    out(((A % 8) ^ (A >> ((A % 8) ^ 1)) ^ 4) % 8)
+    A = A >> 3
+} while A != 0
+",
+            decompile(&[2, 4, 1, 1, 7, 5, 1, 5, 4, 0, 0, 3, 5, 5, 3, 0])
+        );
+        // B carries over from one time round to the next.
+        assert_eq!(
+            "B = 3 % 8
+do {
+    out((B ^ 2) % 8)
+    A = A >> 1
+    B = B ^ 2
+} while A != 0
+",
+            decompile(&[2, 3, 1, 2, 5, 5, 0, 1, 3, 2])
+        );
+        // The new B is worked out from A before it's shifted.
+        assert_eq!(
+            "do {
+    out(B % 8)
+    A, B = A >> 1, A % 8
+} while A != 0
+",
+            decompile(&[5, 5, 2, 4, 0, 1, 3, 0])
+        );
+        assert_eq!(
+            "    A = A >> 1
+2:
+    out(A % 8)
+    if A != 0 goto 2
+    out(B % 8)
+",
+            decompile(&[0, 1, 5, 4, 3, 2, 5, 5])
+        );
+        assert_eq!(
+            "can't decode address 2: missing operand\n",
+            decompile(&[4, 7, 2])
+        );
+    }
+
+    #[test]
+    fn test_run_errors() {
+        let computer = Computer::new(0, 0, 0);
+        let err = run_program(&computer, &[1, 2, 5, 7]).unwrap_err();
+        assert_eq!((2, 1), (err.ip, err.step));
+        assert_eq!("invalid combo operand 7", err.message);
+        assert_eq!(
+            "missing operand",
+            run_program(&computer, &[4]).unwrap_err().message
+        );
+        assert_eq!(
+            "operand 9 isn't a 3-bit number",
+            run_program(&computer, &[1, 9]).unwrap_err().message
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = Day::parse("Register A: 729\nRegister B: x\n").unwrap_err();
+        assert_eq!((2, 13), (err.line, err.column));
+
+        let err = Day::parse("Register A: 729\nRegister B: 0\nRegister C: 0\n").unwrap_err();
+        assert_eq!((4, 1), (err.line, err.column));
+        assert_eq!("expected 'Program:'", err.message);
+
+        let err = Day::parse("Register A: 18446744073709551616\n").unwrap_err();
+        assert_eq!((1, 13), (err.line, err.column));
+        assert_eq!("doesn't fit in a 64-bit register", err.message);
     }
 }
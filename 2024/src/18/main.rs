@@ -1,22 +1,36 @@
-use std::collections::{HashMap, HashSet};
-
-use itertools::Itertools;
+use aoc_common::{Grid, ParseError, ParseResult, Solution};
 
 fn main() {
-    let input = include_str!("input.txt");
-    println!("Part one: {}", part_one(input, 1024));
-    let two = part_two(input);
-    println!("Part two: {},{}", two.0, two.1);
+    aoc_common::run_day::<Day>(2024, 18);
 }
 
-fn part_one(input: &str, num_bytes: usize) -> i64 {
-    let map = parse(input);
-    dijstra(&map, num_bytes).unwrap()
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = Map;
+    type PartOne = i64;
+    type PartTwo = String;
+
+    fn parse(input: &str) -> ParseResult<Map> {
+        parse(input)
+    }
+
+    fn part_one(map: &Map) -> i64 {
+        shortest_path(map, 1024)
+    }
+
+    fn part_two(map: &Map) -> String {
+        let (x, y) = first_blocking_byte(map);
+        format!("{},{}", x, y)
+    }
 }
 
-fn part_two(input: &str) -> (i64, i64) {
-    let map = parse(input);
-    binary_search(&map, 0, map.corruptions.len() - 1)
+fn shortest_path(map: &Map, num_bytes: usize) -> i64 {
+    escape(map, num_bytes).expect("the exit is already cut off")
+}
+
+fn first_blocking_byte(map: &Map) -> (i64, i64) {
+    binary_search(map, 0, map.corruptions.len() - 1)
 }
 
 fn binary_search(map: &Map, low: usize, high: usize) -> (i64, i64) {
@@ -25,7 +39,7 @@ fn binary_search(map: &Map, low: usize, high: usize) -> (i64, i64) {
     }
 
     let mid = (low + high) / 2;
-    let result = dijstra(map, mid);
+    let result = escape(map, mid);
     if result.is_none() {
         binary_search(map, low, mid)
     } else {
@@ -33,82 +47,54 @@ fn binary_search(map: &Map, low: usize, high: usize) -> (i64, i64) {
     }
 }
 
-struct Map {
+#[derive(Debug)]
+pub struct Map {
     max: (i64, i64),
     corruptions: Vec<(i64, i64)>,
 }
 
-fn parse(input: &str) -> Map {
-    let corruptions: Vec<(i64, i64)> = input
-        .lines()
-        .map(|line| line.split(',').collect_tuple().unwrap())
-        .map(|(x, y)| (x.parse().unwrap(), y.parse().unwrap()))
-        .collect();
+fn parse(input: &str) -> ParseResult<Map> {
+    let corruptions = aoc_common::lines(input)
+        .map(|line| parse_byte(input, line))
+        .collect::<ParseResult<Vec<_>>>()?;
+    if corruptions.is_empty() {
+        return Err(ParseError::at(input, 0, "no bytes fall"));
+    }
     let max = corruptions
         .iter()
         .fold((0, 0), |acc, (x, y)| (acc.0.max(*x), acc.1.max(*y)));
-    Map { max, corruptions }
+    Ok(Map { max, corruptions })
 }
 
-struct PathNode {
-    distance: i64,
+/// The position `x,y` a byte falls at, from a line of `input`.
+fn parse_byte(input: &str, line: &str) -> ParseResult<(i64, i64)> {
+    let (x, y) = line
+        .split_once(',')
+        .ok_or_else(|| ParseError::at_slice(input, line, "expected 'x,y'"))?;
+    let coordinate = |n: &str| {
+        n.parse::<u16>()
+            .map(i64::from)
+            .map_err(|_| ParseError::at_slice(input, n, "expected a coordinate"))
+    };
+    Ok((coordinate(x)?, coordinate(y)?))
 }
 
-impl PathNode {
-    fn new() -> PathNode {
-        PathNode { distance: i64::MAX }
+/// The fewest steps from the top left to the bottom right once the first
+/// `num_bytes` have fallen.
+fn escape(map: &Map, num_bytes: usize) -> Option<i64> {
+    let mut corrupted = Grid::filled(map.max.0 as usize + 1, map.max.1 as usize + 1, false);
+    for &(x, y) in map.corruptions.iter().take(num_bytes) {
+        corrupted[(x as usize, y as usize)] = true;
     }
-}
-
-fn dijstra(map: &Map, num_bytes: usize) -> Option<i64> {
-    let mut unvisited = all_locations(&map.max, &map.corruptions[..num_bytes]);
-    let mut nodes = all_nodes(&unvisited);
-    nodes.get_mut(&(0, 0)).unwrap().distance = 0;
-
-    loop {
-        let closest_unvisited = unvisited.iter().min_by_key(|&x| &nodes[x].distance);
-        closest_unvisited?;
-
-        let current = *closest_unvisited.unwrap();
-        let current_distance = nodes[&current].distance;
-        if current == map.max {
-            if current_distance == i64::MAX {
-                return None;
-            }
-            return Some(current_distance);
-        }
-
-        for offset in [(1, 0), (0, 1), (-1, 0), (0, -1)] {
-            let neighbor = (current.0 + offset.0, current.1 + offset.1);
-            let distance = current_distance.checked_add(1).unwrap_or(i64::MAX);
-            if let Some(node) = nodes.get_mut(&neighbor) {
-                if distance < node.distance {
-                    node.distance = distance;
-                }
-            }
-        }
-
-        unvisited.remove(&current);
-    }
-}
 
-fn all_locations(max: &(i64, i64), corrupted: &[(i64, i64)]) -> HashSet<(i64, i64)> {
-    let mut locations = HashSet::new();
-    for x in 0..=max.0 {
-        for y in 0..=max.1 {
-            if !corrupted.contains(&(x, y)) {
-                locations.insert((x, y));
-            }
-        }
-    }
-    locations
-}
-
-fn all_nodes(locations: &HashSet<(i64, i64)>) -> HashMap<(i64, i64), PathNode> {
-    locations
-        .iter()
-        .map(|&loc| (loc, PathNode::new()))
-        .collect()
+    let search = aoc_common::bfs((0, 0), |&pos| {
+        corrupted
+            .neighbours4(pos)
+            .filter(|&n| !corrupted[n])
+            .collect::<Vec<_>>()
+    });
+    let end = (map.max.0 as usize, map.max.1 as usize);
+    search.distance(&end).map(|d| d as i64)
 }
 
 #[cfg(test)]
@@ -142,7 +128,7 @@ mod tests {
 0,5
 1,6
 2,0";
-        assert_eq!(part_one(input, 12), 22);
+        assert_eq!(shortest_path(&parse(input).unwrap(), 12), 22);
     }
 
     #[test]
@@ -172,6 +158,14 @@ mod tests {
 0,5
 1,6
 2,0";
-        assert_eq!(part_two(input), (6, 1));
+        assert_eq!(first_blocking_byte(&parse(input).unwrap()), (6, 1));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = parse("5,4\n4,-2\n").unwrap_err();
+        assert_eq!((2, 3), (err.line, err.column));
+        assert!(parse("5,4\n4\n").is_err());
+        assert!(parse("").is_err());
     }
 }
@@ -1,70 +1,85 @@
-use std::collections::HashMap;
-
+use aoc_common::{memoize, ParseError, ParseResult, Solution};
 use itertools::Itertools;
 
 fn main() {
-    let input = include_str!("input.txt");
-    println!("Part one: {}", part_one(input));
-    println!("Part two: {}", part_two(input));
+    aoc_common::run_day::<Day>(2024, 19);
 }
 
-fn part_one(input: &str) -> usize {
-    let (towels, required) = parse(input);
-    required
-        .iter()
-        .filter(|f| num_ways_to_create(f, &towels) > 0)
-        .count()
-}
+pub struct Day;
 
-fn part_two(input: &str) -> usize {
-    let (towels, required) = parse(input);
-    required
-        .iter()
-        .map(|f| num_ways_to_create(f, &towels))
-        .sum()
-}
-
-fn num_ways_to_create(required: &str, towels: &Vec<&str>) -> usize {
-    num_ways_to_create_memoized(required, towels, &mut HashMap::new())
-}
+impl Solution for Day {
+    type Parsed = (Vec<String>, Vec<String>);
+    type PartOne = usize;
+    type PartTwo = usize;
 
-fn num_ways_to_create_memoized(
-    required: &str,
-    towels: &Vec<&str>,
-    mem: &mut HashMap<String, usize>,
-) -> usize {
-    if required.is_empty() {
-        return 1;
+    fn parse(input: &str) -> ParseResult<(Vec<String>, Vec<String>)> {
+        parse(input)
     }
 
-    if let Some(&ways) = mem.get(required) {
-        return ways;
+    fn part_one((towels, required): &(Vec<String>, Vec<String>)) -> usize {
+        required
+            .iter()
+            .filter(|f| num_ways_to_create(f, towels) > 0)
+            .count()
     }
 
-    let mut ways = 0;
-    for t in towels {
-        if required.starts_with(t) {
-            ways += num_ways_to_create_memoized(&required[t.len()..], towels, mem);
-        }
+    fn part_two((towels, required): &(Vec<String>, Vec<String>)) -> usize {
+        required.iter().map(|f| num_ways_to_create(f, towels)).sum()
     }
+}
 
-    if required.len() > 5 {
-        mem.insert(required.to_string(), ways);
-    }
-    ways
+fn num_ways_to_create(required: &str, towels: &[String]) -> usize {
+    let mut ways = memoize(|ways, required: &str| {
+        if required.is_empty() {
+            return 1;
+        }
+        towels
+            .iter()
+            .filter_map(|t| required.strip_prefix(t))
+            .map(|rest| ways(rest))
+            .sum()
+    });
+    ways.call(required)
 }
 
-fn parse(input: &str) -> (Vec<&str>, Vec<&str>) {
+fn parse(input: &str) -> ParseResult<(Vec<String>, Vec<String>)> {
     let mut lines = input.lines();
-    let towels = lines
-        .next()
-        .unwrap()
+    let towels = lines.next().unwrap_or("");
+    let towels: Vec<String> = towels
         .split(", ")
+        .map(|towel| stripes(input, towel))
+        .collect::<ParseResult<Vec<_>>>()?
+        .into_iter()
         .sorted_by_key(|x| 100000 - x.len())
         .collect();
-    lines.next().unwrap();
-    let required = lines.collect();
-    (towels, required)
+    if lines.next() != Some("") {
+        return Err(ParseError::at_line(
+            input,
+            1,
+            0,
+            "expected a blank line after the towels",
+        ));
+    }
+    let required = lines
+        .map(|design| stripes(input, design))
+        .collect::<ParseResult<_>>()?;
+    Ok((towels, required))
+}
+
+/// A towel or design of `w`, `u`, `b`, `r` and `g` stripes, from a part of
+/// `input`.
+fn stripes(input: &str, text: &str) -> ParseResult<String> {
+    if text.is_empty() {
+        return Err(ParseError::at_slice(input, text, "expected some stripes"));
+    }
+    match text.find(|c| !"wubrg".contains(c)) {
+        Some(i) => Err(ParseError::at_slice(
+            input,
+            &text[i..],
+            format!("unexpected stripe '{}'", text[i..].chars().next().unwrap()),
+        )),
+        None => Ok(text.to_string()),
+    }
 }
 
 #[cfg(test)]
@@ -83,7 +98,7 @@ ubwu
 bwurrg
 brgr
 bbrgwb";
-        assert_eq!(part_one(input), 6);
+        assert_eq!(Day::part_one(&Day::parse(input).unwrap()), 6);
     }
 
     #[test]
@@ -98,6 +113,16 @@ ubwu
 bwurrg
 brgr
 bbrgwb";
-        assert_eq!(part_two(input), 16);
+        assert_eq!(Day::part_two(&Day::parse(input).unwrap()), 16);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = parse("r, wr, bx\n\nbrwrr\n").unwrap_err();
+        assert_eq!((1, 9), (err.line, err.column));
+        let err = parse("r, wr\n\nbrwrr\nbr r\n").unwrap_err();
+        assert_eq!((4, 3), (err.line, err.column));
+        assert!(parse("r, wr\nbrwrr\n").is_err());
+        assert!(parse("r, , wr\n\nbrwrr\n").is_err());
     }
 }
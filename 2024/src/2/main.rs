@@ -1,15 +1,40 @@
+use aoc_common::{ParseError, ParseResult, Solution};
 use itertools::Itertools;
 
 fn main() {
-    let input = include_str!("input.txt");
-    let count = input.lines().filter(|line| safe(line)).count();
-    println!("Part 1: {}", count);
-    let count_damp = input.lines().filter(|line| safe_damp(line)).count();
-    println!("Part 2: {}", count_damp);
+    aoc_common::run_day::<Day>(2024, 2);
 }
 
-fn parse(line: &str) -> Vec<i32> {
-    line.split(' ').map(|n| n.parse::<i32>().unwrap()).collect()
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = Vec<Vec<i32>>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> ParseResult<Vec<Vec<i32>>> {
+        aoc_common::lines(input)
+            .map(|line| parse(input, line))
+            .collect()
+    }
+
+    fn part_one(input: &Vec<Vec<i32>>) -> usize {
+        input.iter().filter(|report| safe(report)).count()
+    }
+
+    fn part_two(input: &Vec<Vec<i32>>) -> usize {
+        input.iter().filter(|report| safe_damp(report)).count()
+    }
+}
+
+/// The levels of a report, `line` being one of the lines of `input`.
+fn parse(input: &str, line: &str) -> ParseResult<Vec<i32>> {
+    line.split(' ')
+        .map(|n| {
+            n.parse::<i32>()
+                .map_err(|_| ParseError::at_slice(input, n, "expected a level"))
+        })
+        .collect()
 }
 
 fn check_rules(numbers: &Vec<i32>) -> bool {
@@ -20,13 +45,12 @@ fn check_rules(numbers: &Vec<i32>) -> bool {
     (all_positive || all_negative) && all_valid
 }
 
-fn safe(line: &str) -> bool {
-    check_rules(&parse(line))
+fn safe(report: &Vec<i32>) -> bool {
+    check_rules(report)
 }
 
-fn safe_damp(line: &str) -> bool {
-    let parsed = parse(line);
-    if check_rules(&parsed) {
+fn safe_damp(parsed: &Vec<i32>) -> bool {
+    if check_rules(parsed) {
         return true;
     }
 
@@ -44,23 +68,34 @@ fn safe_damp(line: &str) -> bool {
 mod tests {
     use super::*;
 
+    fn report(line: &str) -> Vec<i32> {
+        parse(line, line).unwrap()
+    }
+
     #[test]
     fn test_safe() {
-        assert!(safe("7 6 4 2 1"));
-        assert!(!safe("1 2 7 8 9"));
-        assert!(!safe("9 7 6 2 1"));
-        assert!(!safe("1 3 2 4 5"));
-        assert!(!safe("8 6 4 4 1"));
-        assert!(safe("1 3 6 7 9"));
+        assert!(safe(&report("7 6 4 2 1")));
+        assert!(!safe(&report("1 2 7 8 9")));
+        assert!(!safe(&report("9 7 6 2 1")));
+        assert!(!safe(&report("1 3 2 4 5")));
+        assert!(!safe(&report("8 6 4 4 1")));
+        assert!(safe(&report("1 3 6 7 9")));
     }
 
     #[test]
     fn test_safe_damp() {
-        assert!(safe_damp("7 6 4 2 1"));
-        assert!(!safe_damp("1 2 7 8 9"));
-        assert!(!safe_damp("9 7 6 2 1"));
-        assert!(safe_damp("1 3 2 4 5"));
-        assert!(safe_damp("8 6 4 4 1"));
-        assert!(safe_damp("1 3 6 7 9"));
+        assert!(safe_damp(&report("7 6 4 2 1")));
+        assert!(!safe_damp(&report("1 2 7 8 9")));
+        assert!(!safe_damp(&report("9 7 6 2 1")));
+        assert!(safe_damp(&report("1 3 2 4 5")));
+        assert!(safe_damp(&report("8 6 4 4 1")));
+        assert!(safe_damp(&report("1 3 6 7 9")));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = Day::parse("7 6 4 2 1\n1 2 x 8 9\n").unwrap_err();
+        assert_eq!((2, 5), (err.line, err.column));
+        assert!(Day::parse("1  2\n").is_err());
     }
 }
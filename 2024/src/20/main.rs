@@ -1,52 +1,77 @@
 use std::collections::{HashMap, HashSet};
 
+use aoc_common::{Grid, ParseError, ParseResult, Pos, Solution};
+
 fn main() {
-    let input = include_str!("input.txt");
-    println!("Part one: {}", part_one(input, 100));
-    println!("Part two: {}", part_two(input, 100));
+    aoc_common::run_day::<Day>(2024, 20);
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = Map;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> ParseResult<Map> {
+        parse(input)
+    }
+
+    fn part_one(map: &Map) -> usize {
+        count_cheats(map, 100)
+    }
+
+    fn part_two(map: &Map) -> usize {
+        count_long_cheats(map, 100)
+    }
 }
 
 #[derive(Debug)]
-struct Map {
-    start: (i16, i16),
-    end: (i16, i16),
-    spaces: Vec<(i16, i16)>,
+pub struct Map {
+    start: Pos,
+    end: Pos,
+    walls: Grid<bool>,
 }
 
-fn parse(input: &str) -> Map {
-    let mut spaces = Vec::new();
-    let mut start = (0, 0);
-    let mut end = (0, 0);
-    for (y, line) in input.lines().enumerate() {
-        for (x, c) in line.chars().enumerate() {
-            match c {
-                'S' => start = (x as i16, y as i16),
-                'E' => end = (x as i16, y as i16),
-                '.' => spaces.push((x as i16, y as i16)),
-                _ => {}
-            }
-        }
-    }
-    Map { start, end, spaces }
+fn parse(input: &str) -> ParseResult<Map> {
+    let cells = Grid::try_parse(input, |c| Some(c).filter(|c| "#.SE".contains(*c)))?;
+    let find = |tile: char| {
+        cells.find(|&c| c == tile).ok_or_else(|| {
+            ParseError::at(input, input.len(), format!("the track has no '{}'", tile))
+        })
+    };
+    Ok(Map {
+        start: find('S')?,
+        end: find('E')?,
+        walls: cells.map(|&c| c == '#'),
+    })
+}
+
+fn count_cheats(map: &Map, threshold: usize) -> usize {
+    count_cheats_of_length(map, 2, threshold)
 }
 
-fn part_one(input: &str, threshold: usize) -> usize {
-    let map = parse(input);
-    let from_start = dijstra(&map, map.start, map.end).expect("No route from start");
-    let from_end = dijstra(&map, map.end, map.start).expect("No route from end");
-    let distance = from_start[&map.end].distance;
-    discover_cheats(&from_start, &from_end, 2, distance - threshold + 1)
+fn count_long_cheats(map: &Map, threshold: usize) -> usize {
+    count_cheats_of_length(map, 20, threshold)
 }
 
-fn part_two(input: &str, threshold: usize) -> usize {
-    let map = parse(input);
-    let from_start = dijstra(&map, map.start, map.end).expect("No route from start");
-    let from_end = dijstra(&map, map.end, map.start).expect("No route from end");
-    let distance = from_start[&map.end].distance;
-    discover_cheats(&from_start, &from_end, 20, distance - threshold + 1)
+/// The number of cheats of up to `cheat_length` steps which save at least
+/// `threshold` steps.
+fn count_cheats_of_length(map: &Map, cheat_length: isize, threshold: usize) -> usize {
+    let from_start = distances(map, map.start);
+    let from_end = distances(map, map.end);
+    let distance = *from_start
+        .get(&map.end)
+        .expect("the end can't be reached from the start");
+    match (distance + 1).checked_sub(threshold) {
+        Some(max_distance) => {
+            discover_cheats(map, &from_start, &from_end, cheat_length, max_distance)
+        }
+        None => 0,
+    }
 }
 
-fn generate_offsets(cheat_length: i16) -> Vec<(i16, i16)> {
+fn generate_offsets(cheat_length: isize) -> Vec<(isize, isize)> {
     let mut offsets = Vec::new();
     for x in 0..=cheat_length {
         for y in 0..=cheat_length {
@@ -66,24 +91,20 @@ fn generate_offsets(cheat_length: i16) -> Vec<(i16, i16)> {
 }
 
 fn discover_cheats(
-    from_start: &HashMap<(i16, i16), PathNode>,
-    from_end: &HashMap<(i16, i16), PathNode>,
-    cheat_length: i16,
+    map: &Map,
+    from_start: &HashMap<Pos, usize>,
+    from_end: &HashMap<Pos, usize>,
+    cheat_length: isize,
     max_distance: usize,
 ) -> usize {
     let mut cheats = HashSet::new();
-    let offsets: Vec<(i16, i16)> = generate_offsets(cheat_length);
-    for (location, node) in from_start.iter() {
-        if node.distance == usize::MAX {
-            continue;
-        }
-
-        for offset in &offsets {
-            let neighbor = (location.0 + offset.0, location.1 + offset.1);
-            if let Some(other_node) = from_end.get(&neighbor) {
+    let offsets = generate_offsets(cheat_length);
+    for (&location, &distance) in from_start.iter() {
+        for &offset in &offsets {
+            let neighbor = map.walls.offset(location, offset);
+            if let Some(other_distance) = neighbor.and_then(|n| from_end.get(&n)) {
                 let duration_of_cheat = offset.0.abs() + offset.1.abs();
-                let cheat_distance =
-                    node.distance + other_node.distance + duration_of_cheat as usize;
+                let cheat_distance = distance + other_distance + duration_of_cheat as usize;
                 if cheat_distance < max_distance {
                     cheats.insert((location, neighbor));
                 }
@@ -93,76 +114,15 @@ fn discover_cheats(
     cheats.len()
 }
 
-struct PathNode {
-    distance: usize,
-}
-
-impl PathNode {
-    fn new() -> Self {
-        PathNode {
-            distance: usize::MAX - 30000,
-        }
-    }
-}
-
-fn dijstra(map: &Map, start: (i16, i16), end: (i16, i16)) -> Option<HashMap<(i16, i16), PathNode>> {
-    let mut unvisited = all_locations(map);
-    let mut nodes = all_nodes(&unvisited, start, end);
-
-    loop {
-        let closest_unvisited = unvisited.iter().min_by_key(|&x| nodes[x].distance);
-        if closest_unvisited.is_none() {
-            break;
-        }
-
-        let current = *closest_unvisited.unwrap();
-        let current_distance = nodes[&current].distance;
-        if current == end {
-            if current_distance == usize::MAX {
-                return None;
-            }
-            return Some(nodes);
-        }
-
-        for offset in [(1, 0), (0, 1), (-1, 0), (0, -1)] {
-            let neighbor = (current.0 + offset.0, current.1 + offset.1);
-            let distance = current_distance.checked_add(1).unwrap_or(usize::MAX);
-            if let Some(node) = nodes.get_mut(&neighbor) {
-                if distance < node.distance {
-                    node.distance = distance;
-                }
-            }
-        }
-
-        unvisited.remove(&current);
-    }
-
-    None
-}
-
-fn all_nodes(
-    unvisited: &HashSet<(i16, i16)>,
-    start: (i16, i16),
-    end: (i16, i16),
-) -> HashMap<(i16, i16), PathNode> {
-    let mut nodes: HashMap<(i16, i16), PathNode> =
-        unvisited.iter().map(|&x| (x, PathNode::new())).collect();
-    nodes.insert(
-        start,
-        PathNode {
-            distance: 0,
-            ..PathNode::new()
-        },
-    );
-    nodes.insert(end, PathNode::new());
-    nodes
-}
-
-fn all_locations(map: &Map) -> HashSet<(i16, i16)> {
-    let mut unvisited: HashSet<(i16, i16)> = map.spaces.iter().copied().collect();
-    unvisited.insert(map.start);
-    unvisited.insert(map.end);
-    unvisited
+/// The number of steps from `from` to every open space on the track.
+fn distances(map: &Map, from: Pos) -> HashMap<Pos, usize> {
+    aoc_common::bfs(from, |&pos| {
+        map.walls
+            .neighbours4(pos)
+            .filter(|&n| !map.walls[n])
+            .collect::<Vec<_>>()
+    })
+    .into_distances()
 }
 
 #[cfg(test)]
@@ -186,18 +146,19 @@ mod tests {
 #.#.#.#.#.#.###
 #...#...#...###
 ###############";
-
-        assert_eq!(part_one(input, 64), 1);
-        assert_eq!(part_one(input, 40), 2);
-        assert_eq!(part_one(input, 38), 3);
-        assert_eq!(part_one(input, 36), 4);
-        assert_eq!(part_one(input, 20), 5);
-        assert_eq!(part_one(input, 12), 8);
-        assert_eq!(part_one(input, 10), 10);
-        assert_eq!(part_one(input, 8), 14);
-        assert_eq!(part_one(input, 6), 16);
-        assert_eq!(part_one(input, 4), 30);
-        assert_eq!(part_one(input, 2), 44);
+        let map = parse(input).unwrap();
+
+        assert_eq!(count_cheats(&map, 64), 1);
+        assert_eq!(count_cheats(&map, 40), 2);
+        assert_eq!(count_cheats(&map, 38), 3);
+        assert_eq!(count_cheats(&map, 36), 4);
+        assert_eq!(count_cheats(&map, 20), 5);
+        assert_eq!(count_cheats(&map, 12), 8);
+        assert_eq!(count_cheats(&map, 10), 10);
+        assert_eq!(count_cheats(&map, 8), 14);
+        assert_eq!(count_cheats(&map, 6), 16);
+        assert_eq!(count_cheats(&map, 4), 30);
+        assert_eq!(count_cheats(&map, 2), 44);
     }
 
     #[test]
@@ -217,20 +178,30 @@ mod tests {
 #.#.#.#.#.#.###
 #...#...#...###
 ###############";
+        let map = parse(input).unwrap();
+
+        assert_eq!(count_long_cheats(&map, 76), 3);
+        assert_eq!(count_long_cheats(&map, 74), 7);
+        assert_eq!(count_long_cheats(&map, 72), 29);
+        assert_eq!(count_long_cheats(&map, 70), 41);
+        assert_eq!(count_long_cheats(&map, 68), 55);
+        assert_eq!(count_long_cheats(&map, 66), 67);
+        assert_eq!(count_long_cheats(&map, 64), 86);
+        assert_eq!(count_long_cheats(&map, 62), 106);
+        assert_eq!(count_long_cheats(&map, 60), 129);
+        assert_eq!(count_long_cheats(&map, 58), 154);
+        assert_eq!(count_long_cheats(&map, 56), 193);
+        assert_eq!(count_long_cheats(&map, 54), 222);
+        assert_eq!(count_long_cheats(&map, 52), 253);
+        assert_eq!(count_long_cheats(&map, 50), 285);
+    }
 
-        assert_eq!(part_two(input, 76), 3);
-        assert_eq!(part_two(input, 74), 7);
-        assert_eq!(part_two(input, 72), 29);
-        assert_eq!(part_two(input, 70), 41);
-        assert_eq!(part_two(input, 68), 55);
-        assert_eq!(part_two(input, 66), 67);
-        assert_eq!(part_two(input, 64), 86);
-        assert_eq!(part_two(input, 62), 106);
-        assert_eq!(part_two(input, 60), 129);
-        assert_eq!(part_two(input, 58), 154);
-        assert_eq!(part_two(input, 56), 193);
-        assert_eq!(part_two(input, 54), 222);
-        assert_eq!(part_two(input, 52), 253);
-        assert_eq!(part_two(input, 50), 285);
+    #[test]
+    fn test_parse_errors() {
+        let err = parse("#####\n#S.E#\n##x##\n").unwrap_err();
+        assert_eq!((3, 3), (err.line, err.column));
+        let err = parse("#####\n#S..#\n#####\n").unwrap_err();
+        assert_eq!("the track has no 'E'", err.message);
+        assert!(parse("#####\n#..E#\n#####\n").is_err());
     }
 }
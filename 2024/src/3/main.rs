@@ -1,72 +1,84 @@
+use aoc_common::{ParseError, ParseResult, Solution};
 use regex::Regex;
 
 #[derive(Debug, PartialEq)]
-enum Op {
+pub enum Op {
     Mul(i64, i64),
     Do,
     Dont,
 }
 
 fn main() {
-    let input = include_str!("input.txt");
-    let parsed = parse(input);
+    aoc_common::run_day::<Day>(2024, 3);
+}
+
+pub struct Day;
 
-    let part_one = parsed
-        .iter()
-        .map(|op| if let Op::Mul(a, b) = op { a * b } else { 0 })
-        .sum::<i64>();
-    println!("Part one {}", part_one);
+impl Solution for Day {
+    type Parsed = Vec<Op>;
+    type PartOne = i64;
+    type PartTwo = i64;
 
-    let mut enabled = true;
-    let mut part_two = 0;
-    for op in parsed {
-        match op {
-            Op::Mul(a, b) => {
-                if enabled {
-                    part_two += a * b;
+    fn parse(input: &str) -> ParseResult<Vec<Op>> {
+        parse(input)
+    }
+
+    fn part_one(input: &Vec<Op>) -> i64 {
+        input
+            .iter()
+            .map(|op| if let Op::Mul(a, b) = op { a * b } else { 0 })
+            .sum::<i64>()
+    }
+
+    fn part_two(input: &Vec<Op>) -> i64 {
+        let mut enabled = true;
+        let mut part_two = 0;
+        for op in input {
+            match op {
+                Op::Mul(a, b) => {
+                    if enabled {
+                        part_two += a * b;
+                    }
+                }
+                Op::Do => {
+                    enabled = true;
+                }
+                Op::Dont => {
+                    enabled = false;
                 }
-            }
-            Op::Do => {
-                enabled = true;
-            }
-            Op::Dont => {
-                enabled = false;
             }
         }
+        part_two
     }
-    println!("Part two {}", part_two);
 }
 
-fn parse(input: &str) -> Vec<Op> {
+/// The instructions among the corrupted memory.  Anything else is noise, but
+/// memory without a single instruction is probably the wrong input.
+fn parse(input: &str) -> ParseResult<Vec<Op>> {
     let mut result = Vec::new();
-    let re = Regex::new(r"(mul\(\d{1,3},\d{1,3}\))|(do\(\))|(don't\(\))").unwrap();
-    for cap in re.find_iter(input) {
-        if cap.as_str().starts_with("mul(") {
-            let mut parts = cap.as_str().split(',');
-            let a = parts
-                .next()
-                .unwrap()
-                .split("mul(")
-                .nth(1)
-                .unwrap()
-                .parse()
-                .unwrap();
-            let b = parts
-                .next()
-                .unwrap()
-                .split(')')
-                .next()
-                .unwrap()
-                .parse()
-                .unwrap();
-            result.push(Op::Mul(a, b));
-        } else if cap.as_str().starts_with("do()") {
-            result.push(Op::Do);
-        } else if cap.as_str().starts_with("don't()") {
-            result.push(Op::Dont);
-        }
+    let re = Regex::new(r"mul\((\d{1,3}),(\d{1,3})\)|do\(\)|don't\(\)").unwrap();
+    for cap in re.captures_iter(input) {
+        let op = match (cap.get(1), cap.get(2)) {
+            (Some(a), Some(b)) => Op::Mul(number(input, a.as_str())?, number(input, b.as_str())?),
+            _ if &cap[0] == "do()" => Op::Do,
+            _ => Op::Dont,
+        };
+        result.push(op);
     }
-    result
+    if result.is_empty() {
+        return Err(ParseError::at(
+            input,
+            0,
+            "expected some instructions in the memory",
+        ));
+    }
+    Ok(result)
+}
+
+fn number(input: &str, digits: &str) -> ParseResult<i64> {
+    digits
+        .parse()
+        .map_err(|_| ParseError::at_slice(input, digits, "expected a number"))
 }
 
 #[cfg(test)]
@@ -77,7 +89,8 @@ mod tests {
     fn test_parse() {
         let expected = vec![Op::Mul(2, 4), Op::Mul(5, 5), Op::Mul(11, 8), Op::Mul(8, 5)];
         assert_eq!(
-            parse("xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))"),
+            parse("xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))")
+                .unwrap(),
             expected
         );
     }
@@ -92,8 +105,15 @@ mod tests {
             Op::Mul(8, 5),
         ];
         assert_eq!(
-            parse("xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))"),
+            parse("xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))")
+                .unwrap(),
             expected
         );
     }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse("mul[3,7]!@^do_not_mul(5,5").is_err());
+        assert!(parse("").is_err());
+    }
 }
@@ -1,92 +1,65 @@
+use aoc_common::{Grid, ParseResult, Pos, Solution, NEIGHBOURS_8};
+
 fn main() {
-    let input = include_str!("input.txt");
-    let part_one = occurrances(input);
-    println!("Part one {}", part_one);
-    let part_two = x_mas(input);
-    println!("Part two {}", part_two);
+    aoc_common::run_day::<Day>(2024, 4);
 }
 
-fn occurrances(input: &str) -> usize {
-    let mut idx = input.find('X').unwrap_or(input.len());
-    let mut count = 0;
-    while idx < input.len() {
-        count += occurrances_from(input, idx);
-        let base = idx + 1;
-        idx = input[base..].find('X').unwrap_or(input.len()) + base;
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = Grid<char>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> ParseResult<Grid<char>> {
+        Grid::try_parse(input, |c| Some(c).filter(char::is_ascii_uppercase))
     }
-    count
-}
 
-fn occurrances_from(input: &str, start: usize) -> usize {
-    let line_len = input.find('\n').unwrap() as i64 + 1;
-    is_occurance(input, start, 1)
-        + is_occurance(input, start, -1)
-        + is_occurance(input, start, line_len)
-        + is_occurance(input, start, -line_len)
-        + is_occurance(input, start, line_len + 1)
-        + is_occurance(input, start, line_len - 1)
-        + is_occurance(input, start, -line_len + 1)
-        + is_occurance(input, start, -line_len - 1)
-}
+    fn part_one(input: &Grid<char>) -> usize {
+        occurrances(input)
+    }
 
-fn is_occurance(input: &str, start: usize, step: i64) -> usize {
-    let mut idx = start as i64;
-    let tgt = "XMAS";
-    for i in 0..tgt.len() {
-        if idx < 0
-            || idx >= input.len() as i64
-            || input[idx as usize..idx as usize + 1] != tgt[i..i + 1]
-        {
-            return 0;
-        }
-        idx += step;
+    fn part_two(input: &Grid<char>) -> usize {
+        x_mas(input)
     }
-    1
 }
 
-fn x_mas(input: &str) -> usize {
-    let mut idx = input.find('A').unwrap_or(input.len());
-    let mut count = 0;
-    while idx < input.len() {
-        count += x_mas_from(input, idx);
-        let base = idx + 1;
-        idx = input[base..].find('A').unwrap_or(input.len()) + base;
-    }
-    count
+fn occurrances(grid: &Grid<char>) -> usize {
+    grid.iter()
+        .filter(|(_, &c)| c == 'X')
+        .map(|(pos, _)| {
+            NEIGHBOURS_8
+                .iter()
+                .filter(|&&step| is_occurance(grid, pos, step))
+                .count()
+        })
+        .sum()
 }
 
-fn x_mas_from(input: &str, start: usize) -> usize {
-    let line_len = input.find('\n').unwrap() as i64 + 1;
-    let upper_left = start as i64 - line_len - 1;
-    let upper_right = start as i64 - line_len + 1;
-    let lower_left = start as i64 + line_len - 1;
-    let lower_right = start as i64 + line_len + 1;
-    if is_mas(input, upper_left, lower_right) && is_mas(input, upper_right, lower_left) {
-        1
-    } else {
-        0
-    }
+/// Whether "MAS" follows the X at `start` going in direction `step`.
+fn is_occurance(grid: &Grid<char>, start: Pos, step: (isize, isize)) -> bool {
+    grid.ray(start, step)
+        .take(3)
+        .map(|pos| grid[pos])
+        .eq("MAS".chars())
 }
 
-fn is_mas(input: &str, start: i64, end: i64) -> bool {
-    if let Some(start_idx) = valid_idx(input, start) {
-        if let Some(end_idx) = valid_idx(input, end) {
-            let s = &input[start_idx..start_idx + 1];
-            let e = &input[end_idx..end_idx + 1];
-            s == "M" && e == "S" || s == "S" && e == "M"
-        } else {
-            false
-        }
-    } else {
-        false
-    }
+fn x_mas(grid: &Grid<char>) -> usize {
+    grid.iter()
+        .filter(|&(pos, &c)| c == 'A' && x_mas_from(grid, pos))
+        .count()
+}
+
+fn x_mas_from(grid: &Grid<char>, start: Pos) -> bool {
+    is_mas(grid, start, (-1, -1), (1, 1)) && is_mas(grid, start, (1, -1), (-1, 1))
 }
 
-fn valid_idx(input: &str, idx: i64) -> Option<usize> {
-    if idx < 0 || idx >= input.len() as i64 {
-        None
-    } else {
-        Some(idx as usize)
+/// Whether the cells either side of the A at `start` are an M and an S.
+fn is_mas(grid: &Grid<char>, start: Pos, before: (isize, isize), after: (isize, isize)) -> bool {
+    let cell = |step| grid.offset(start, step).map(|pos| grid[pos]);
+    match (cell(before), cell(after)) {
+        (Some('M'), Some('S')) | (Some('S'), Some('M')) => true,
+        _ => false,
     }
 }
 
@@ -106,7 +79,7 @@ SMSMSASXSS
 SAXAMASAAA
 MAMMMXMMMM
 MXMXAXMASX";
-        assert_eq!(18, occurrances(input));
+        assert_eq!(18, occurrances(&Day::parse(input).unwrap()));
     }
 
     #[test]
@@ -121,6 +94,14 @@ SMSMSASXSS
 SAXAMASAAA
 MAMMMXMMMM
 MXMXAXMASX";
-        assert_eq!(9, x_mas(input));
+        assert_eq!(9, x_mas(&Day::parse(input).unwrap()));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = Day::parse("XMAS\nXMa\n").unwrap_err();
+        assert_eq!((2, 3), (err.line, err.column));
+        let err = Day::parse("XMAS\nXMA\n").unwrap_err();
+        assert_eq!((2, 4), (err.line, err.column));
     }
 }
@@ -1,8 +1,9 @@
 use std::collections::{HashMap, HashSet};
 
-use itertools::Itertools;
+use aoc_common::{ParseError, ParseResult, Solution};
 
-struct Rules {
+#[derive(Debug)]
+pub struct Rules {
     rules: HashMap<i64, Vec<i64>>,
     vals: HashMap<i64, Vec<i64>>,
 }
@@ -32,33 +33,70 @@ impl Rules {
 }
 
 fn main() {
-    let input = include_str!("input.txt");
-    let divide = input.find("\n\n").unwrap();
-    let rules = parse_rules(&input[..divide]);
-    let messages = parse_messages(&input[divide + 2..]);
-    println!("Part one: {}", part_one(&rules, &messages));
-    println!("Part two: {}", part_two(&rules, &messages));
+    aoc_common::run_day::<Day>(2024, 5);
 }
 
-fn parse_rules(input: &str) -> Rules {
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = (Rules, Vec<Vec<i64>>);
+    type PartOne = i64;
+    type PartTwo = i64;
+
+    fn parse(input: &str) -> ParseResult<(Rules, Vec<Vec<i64>>)> {
+        parse(input)
+    }
+
+    fn part_one(input: &(Rules, Vec<Vec<i64>>)) -> i64 {
+        let (rules, messages) = input;
+        sum_ordered_middles(&rules, &messages)
+    }
+
+    fn part_two(input: &(Rules, Vec<Vec<i64>>)) -> i64 {
+        let (rules, messages) = input;
+        sum_reordered_middles(&rules, &messages)
+    }
+}
+
+fn parse(input: &str) -> ParseResult<(Rules, Vec<Vec<i64>>)> {
+    let divide = input.find("\n\n").ok_or_else(|| {
+        ParseError::at(input, input.len(), "expected a blank line after the rules")
+    })?;
+    Ok((
+        parse_rules(input, &input[..divide])?,
+        parse_messages(input, &input[divide + 2..])?,
+    ))
+}
+
+/// The ordering rules such as `47|53` in `section`, a slice of `input`.
+fn parse_rules(input: &str, section: &str) -> ParseResult<Rules> {
     let mut rules = Rules::new();
 
-    for line in input.lines() {
-        let (left, right) = line.split('|').collect_tuple().unwrap();
-        rules.add_mapping(left.parse().unwrap(), right.parse().unwrap());
+    for line in section.lines() {
+        let (left, right) = line
+            .split_once('|')
+            .ok_or_else(|| ParseError::at_slice(input, line, "expected a rule such as '47|53'"))?;
+        rules.add_mapping(page(input, left)?, page(input, right)?);
     }
 
-    rules
+    Ok(rules)
 }
 
-fn parse_messages(input: &str) -> Vec<Vec<i64>> {
-    input
+/// The updates in `section`, a slice of `input`, each a list of pages.
+fn parse_messages(input: &str, section: &str) -> ParseResult<Vec<Vec<i64>>> {
+    section
         .lines()
-        .map(|line| line.split(',').map(|n| n.parse().unwrap()).collect())
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.split(',').map(|n| page(input, n)).collect())
         .collect()
 }
 
-fn part_one(rules: &Rules, messages: &[Vec<i64>]) -> i64 {
+fn page(input: &str, page: &str) -> ParseResult<i64> {
+    page.parse()
+        .map_err(|_| ParseError::at_slice(input, page, "expected a page number"))
+}
+
+fn sum_ordered_middles(rules: &Rules, messages: &[Vec<i64>]) -> i64 {
     let mut result = 0;
     for msg in messages {
         if is_valid_message(rules, msg) {
@@ -84,7 +122,7 @@ fn is_valid_message(rules: &Rules, message: &[i64]) -> bool {
     true
 }
 
-fn part_two(rules: &Rules, messages: &[Vec<i64>]) -> i64 {
+fn sum_reordered_middles(rules: &Rules, messages: &[Vec<i64>]) -> i64 {
     let mut result = 0;
     for msg in messages {
         if !is_valid_message(rules, msg) {
@@ -134,8 +172,7 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let rules = parse_rules(
-            "47|53
+        let rules = "47|53
 97|13
 97|61
 97|47
@@ -155,24 +192,22 @@ mod tests {
 75|61
 47|29
 75|13
-53|13",
-        );
-        let messages = parse_messages(
-            "75,47,61,53,29
+53|13";
+        let rules = parse_rules(rules, rules).unwrap();
+        let messages = "75,47,61,53,29
 97,61,53,29,13
 75,29,13
 75,97,47,61,53
 61,13,29
-97,13,75,29,47",
-        );
+97,13,75,29,47";
+        let messages = parse_messages(messages, messages).unwrap();
 
-        assert_eq!(part_one(&rules, &messages), 143);
+        assert_eq!(sum_ordered_middles(&rules, &messages), 143);
     }
 
     #[test]
     fn test_part_two() {
-        let rules = parse_rules(
-            "47|53
+        let rules = "47|53
 97|13
 97|61
 97|47
@@ -192,17 +227,30 @@ mod tests {
 75|61
 47|29
 75|13
-53|13",
-        );
-        let messages = parse_messages(
-            "75,47,61,53,29
+53|13";
+        let rules = parse_rules(rules, rules).unwrap();
+        let messages = "75,47,61,53,29
 97,61,53,29,13
 75,29,13
 75,97,47,61,53
 61,13,29
-97,13,75,29,47",
-        );
+97,13,75,29,47";
+        let messages = parse_messages(messages, messages).unwrap();
+
+        assert_eq!(sum_reordered_middles(&rules, &messages), 123);
+    }
 
-        assert_eq!(part_two(&rules, &messages), 123);
+    #[test]
+    fn test_parse_errors() {
+        let input = "47|53\n97|13\n\n75,47,53\n97,x,13\n";
+        assert_eq!(
+            vec![vec![75, 47, 53], vec![97, 13]],
+            parse(&input.replace("x,", "")).unwrap().1
+        );
+        let err = parse(input).unwrap_err();
+        assert_eq!((5, 4), (err.line, err.column));
+        let err = parse(&input.replace("97|13", "97-13")).unwrap_err();
+        assert_eq!((2, 1), (err.line, err.column));
+        assert!(parse("47|53\n97|13\n").is_err());
     }
 }
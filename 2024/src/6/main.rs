@@ -1,52 +1,46 @@
-use std::collections::HashSet;
-
+use aoc_common::{Direction, Grid, ParseError, ParseResult, Point, Solution};
 use itertools::Itertools;
 
 fn main() {
-    let input = include_str!("input.txt");
-    let map = parse_map(input);
-    let start_coord = find_start(input);
-    println!("Part one: {}", part_one(&map, start_coord));
-    println!("Part two: {}", part_two(&map, start_coord));
+    aoc_common::run_day::<Day>(2024, 6);
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Coord(i32);
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = (Grid<bool>, Coord);
+    type PartOne = usize;
+    type PartTwo = usize;
 
-impl Coord {
-    fn new<T: Into<i32>>(x: T, y: T) -> Self {
-        Self(y.into() << 16 | x.into())
+    fn parse(input: &str) -> ParseResult<(Grid<bool>, Coord)> {
+        parse(input)
     }
 
-    fn x(&self) -> i32 {
-        self.0 & 0xFFFF
+    fn part_one((map, start): &(Grid<bool>, Coord)) -> usize {
+        count_visited(map, *start)
     }
 
-    fn y(&self) -> i32 {
-        self.0 >> 16
+    fn part_two((map, start): &(Grid<bool>, Coord)) -> usize {
+        count_loop_obstructions(map, *start)
     }
 }
 
+pub type Coord = Point<i32>;
+
 trait Map {
     fn in_bounds(&self, coord: Coord) -> bool;
 
     fn contains_obstacle(&self, coord: &Coord) -> bool;
 }
 
-struct ArryBackedMap {
-    size: (usize, usize),
-    obstacles: HashSet<Coord>,
-}
-
-impl Map for ArryBackedMap {
+/// Each cell is true where there's an obstacle.
+impl Map for Grid<bool> {
     fn in_bounds(&self, coord: Coord) -> bool {
-        let x = coord.x();
-        let y = coord.y();
-        x >= 0 && x < self.size.0 as i32 && y >= 0 && y < self.size.1 as i32
+        self.pos_of(coord).is_some()
     }
 
     fn contains_obstacle(&self, coord: &Coord) -> bool {
-        self.obstacles.contains(coord)
+        self.pos_of(*coord).is_some_and(|pos| self[pos])
     }
 }
 
@@ -65,49 +59,27 @@ impl<'a> Map for ExtendedMap<'a> {
     }
 }
 
-fn parse_map(input: &str) -> ArryBackedMap {
-    let obstacles = input
-        .lines()
-        .enumerate()
-        .flat_map(|(y, line)| {
-            line.chars()
-                .enumerate()
-                .filter(|(_, c)| *c == '#')
-                .map(move |(x, _)| Coord::new(x as i32, y as i32))
-        })
-        .collect();
-    ArryBackedMap {
-        size: (input.lines().next().unwrap().len(), input.lines().count()),
-        obstacles,
-    }
-}
-
-fn find_start(input: &str) -> Coord {
-    input
-        .lines()
-        .enumerate()
-        .find_map(|(y, line)| {
-            line.chars()
-                .enumerate()
-                .find(|(_, c)| *c == '^')
-                .map(|(x, _)| Coord::new(x as i32, y as i32))
-        })
-        .unwrap()
+fn parse(input: &str) -> ParseResult<(Grid<bool>, Coord)> {
+    let cells = Grid::parse(input, |c| c)?;
+    let start = cells
+        .find(|&c| c == '^')
+        .ok_or_else(|| ParseError::at(input, input.len(), "no guard '^' on the map"))?;
+    Ok((cells.map(|&c| c == '#'), Coord::from_pos(start)))
 }
 
-fn part_one(map: &dyn Map, start: Coord) -> usize {
+fn count_visited(map: &dyn Map, start: Coord) -> usize {
     follow_route(map, start).into_iter().unique().count()
 }
 
 fn follow_route(map: &dyn Map, start: Coord) -> Vec<Coord> {
     let mut current = start;
     let mut route = vec![current];
-    let mut direction = (0, -1);
+    let mut direction = Direction::North;
 
     while map.in_bounds(current) {
-        let next = Coord::new(current.x() + direction.0, current.y() + direction.1);
+        let next = current.step(direction);
         if map.contains_obstacle(&next) {
-            direction = rot_90(direction);
+            direction = direction.turn_right();
         } else {
             current = next;
             if map.in_bounds(next) {
@@ -119,7 +91,7 @@ fn follow_route(map: &dyn Map, start: Coord) -> Vec<Coord> {
     route
 }
 
-fn part_two(map: &dyn Map, start: Coord) -> usize {
+fn count_loop_obstructions(map: &dyn Map, start: Coord) -> usize {
     let candidate_locations: Vec<Coord> = follow_route(map, start).into_iter().unique().collect();
     let mut count = 0;
 
@@ -138,16 +110,16 @@ fn part_two(map: &dyn Map, start: Coord) -> usize {
 
 fn is_loop(map: &dyn Map, start: Coord) -> bool {
     let mut current = start;
-    let mut route = vec![(current, 0, -1)];
-    let mut direction = (0, -1);
+    let mut route = vec![(current, Direction::North)];
+    let mut direction = Direction::North;
 
     while map.in_bounds(current) {
-        let next = Coord::new(current.x() + direction.0, current.y() + direction.1);
+        let next = current.step(direction);
         if map.contains_obstacle(&next) {
-            direction = rot_90(direction);
+            direction = direction.turn_right();
         } else {
             current = next;
-            let key = (next, direction.0, direction.1);
+            let key = (next, direction);
             if route.contains(&key) {
                 return true;
             }
@@ -160,22 +132,17 @@ fn is_loop(map: &dyn Map, start: Coord) -> bool {
     false
 }
 
-fn rot_90(current: (i32, i32)) -> (i32, i32) {
-    let x = -current.1;
-    let y = current.0;
-    (x, y)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_rot_90() {
-        assert_eq!(rot_90((0, 1)), (-1, 0));
-        assert_eq!(rot_90((-1, 0)), (0, -1));
-        assert_eq!(rot_90((0, -1)), (1, 0));
-        assert_eq!(rot_90((1, 0)), (0, 1));
+        assert_eq!(Coord::new(0, 1).rotate_clockwise(), Coord::new(-1, 0));
+        assert_eq!(Coord::new(-1, 0).rotate_clockwise(), Coord::new(0, -1));
+        assert_eq!(Coord::new(0, -1).rotate_clockwise(), Coord::new(1, 0));
+        assert_eq!(Coord::new(1, 0).rotate_clockwise(), Coord::new(0, 1));
+        assert_eq!(Direction::North.turn_right().offset(), Coord::new(1, 0));
     }
 
     #[test]
@@ -190,9 +157,8 @@ mod tests {
 ........#.
 #.........
 ......#...";
-        let map = parse_map(input);
-        let start_coord = find_start(input);
-        assert_eq!(part_one(&map, start_coord), 41);
+        let (map, start_coord) = parse(input).unwrap();
+        assert_eq!(count_visited(&map, start_coord), 41);
     }
 
     #[test]
@@ -207,8 +173,7 @@ mod tests {
 ........#.
 #.........
 ......#...";
-        let map = parse_map(input);
-        let start_coord = find_start(input);
-        assert_eq!(part_two(&map, start_coord), 6);
+        let (map, start_coord) = parse(input).unwrap();
+        assert_eq!(count_loop_obstructions(&map, start_coord), 6);
     }
 }
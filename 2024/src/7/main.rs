@@ -1,25 +1,53 @@
-use itertools::Itertools;
+use aoc_common::{ParseError, ParseResult, Solution};
 
 fn main() {
-    let input = include_str!("input.txt");
-    println!("Part one: {}", part_one(input));
-    println!("Part two: {}", part_two(input));
+    aoc_common::run_day::<Day>(2024, 7);
 }
 
-fn part_one(input: &str) -> i64 {
-    input
-        .lines()
-        .map(parse_line)
-        .filter(|(answer, inputs)| is_valid(answer, inputs))
-        .map(|(ans, _)| ans)
-        .sum()
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = Vec<(i64, Vec<i64>)>;
+    type PartOne = i64;
+    type PartTwo = i64;
+
+    fn parse(input: &str) -> ParseResult<Vec<(i64, Vec<i64>)>> {
+        aoc_common::lines(input)
+            .map(|line| parse_line(input, line))
+            .collect()
+    }
+
+    fn part_one(input: &Vec<(i64, Vec<i64>)>) -> i64 {
+        input
+            .iter()
+            .filter(|(answer, inputs)| is_valid(answer, inputs))
+            .map(|(ans, _)| ans)
+            .sum()
+    }
+
+    fn part_two(input: &Vec<(i64, Vec<i64>)>) -> i64 {
+        input
+            .iter()
+            .filter(|(answer, inputs)| is_valid2(answer, inputs))
+            .map(|(ans, _)| ans)
+            .sum()
+    }
 }
 
-fn parse_line(line: &str) -> (i64, Vec<i64>) {
-    let (answer, inputs) = line.split(": ").collect_tuple().unwrap();
-    let answer = answer.parse().unwrap();
-    let inputs = inputs.split(' ').map(|n| n.parse().unwrap()).collect();
-    (answer, inputs)
+/// An equation, `line` being one of the lines of `input`.
+fn parse_line(input: &str, line: &str) -> ParseResult<(i64, Vec<i64>)> {
+    let (answer, inputs) = line
+        .split_once(": ")
+        .ok_or_else(|| ParseError::at_slice(input, line, "expected 'answer: inputs'"))?;
+    let number = |n: &str| {
+        Some(n)
+            .filter(|n| n.chars().all(|c| c.is_ascii_digit()))
+            .and_then(|n| n.parse().ok())
+            .ok_or_else(|| ParseError::at_slice(input, n, "expected a number"))
+    };
+    let answer = number(answer)?;
+    let inputs = inputs.split(' ').map(number).collect::<ParseResult<_>>()?;
+    Ok((answer, inputs))
 }
 
 fn is_valid(answer: &i64, inputs: &[i64]) -> bool {
@@ -33,17 +61,12 @@ fn is_valid_recursive(answer: &i64, current: i64, inputs: &[i64]) -> bool {
     if current > *answer {
         return false;
     }
-    is_valid_recursive(answer, current * inputs[0], &inputs[1..])
-        || is_valid_recursive(answer, current + inputs[0], &inputs[1..])
-}
-
-fn part_two(input: &str) -> i64 {
-    input
-        .lines()
-        .map(parse_line)
-        .filter(|(answer, inputs)| is_valid2(answer, inputs))
-        .map(|(ans, _)| ans)
-        .sum()
+    let next = |value: Option<i64>| {
+        value.map_or(false, |value| {
+            is_valid_recursive(answer, value, &inputs[1..])
+        })
+    };
+    next(current.checked_mul(inputs[0])) || next(current.checked_add(inputs[0]))
 }
 
 fn is_valid2(answer: &i64, inputs: &[i64]) -> bool {
@@ -57,13 +80,19 @@ fn is_valid_recursive2(answer: &i64, current: i64, inputs: &[i64]) -> bool {
     if current > *answer {
         return false;
     }
-    is_valid_recursive2(answer, current * inputs[0], &inputs[1..])
-        || is_valid_recursive2(answer, current + inputs[0], &inputs[1..])
-        || is_valid_recursive2(answer, concat(current, inputs[0]), &inputs[1..])
+    // Anything too large for an i64 is certainly larger than the answer.
+    let next = |value: Option<i64>| {
+        value.map_or(false, |value| {
+            is_valid_recursive2(answer, value, &inputs[1..])
+        })
+    };
+    next(current.checked_mul(inputs[0]))
+        || next(current.checked_add(inputs[0]))
+        || next(concat(current, inputs[0]))
 }
 
-fn concat(a: i64, b: i64) -> i64 {
-    format!("{}{}", a, b).parse().unwrap()
+fn concat(a: i64, b: i64) -> Option<i64> {
+    format!("{}{}", a, b).parse().ok()
 }
 
 #[cfg(test)]
@@ -85,8 +114,9 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(
-            "190: 10 19
+        let result = Day::part_one(
+            &Day::parse(
+                "190: 10 19
 3267: 81 40 27
 83: 17 5
 156: 15 6
@@ -95,6 +125,8 @@ mod tests {
 192: 17 8 14
 21037: 9 7 18 13
 292: 11 6 16 20",
+            )
+            .unwrap(),
         );
 
         assert_eq!(result, 3749);
@@ -102,8 +134,9 @@ mod tests {
 
     #[test]
     fn test_part_two() {
-        let result = part_two(
-            "190: 10 19
+        let result = Day::part_two(
+            &Day::parse(
+                "190: 10 19
 3267: 81 40 27
 83: 17 5
 156: 15 6
@@ -112,8 +145,19 @@ mod tests {
 192: 17 8 14
 21037: 9 7 18 13
 292: 11 6 16 20",
+            )
+            .unwrap(),
         );
 
         assert_eq!(result, 11387);
     }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = Day::parse("190: 10 19\n3267: 81 -40 27\n").unwrap_err();
+        assert_eq!((2, 10), (err.line, err.column));
+        assert!(Day::parse("190 10 19\n").is_err());
+        assert!(Day::parse("190: \n").is_err());
+        assert!(!is_valid2(&i64::MAX, &[i64::MAX, 2, 9]));
+    }
 }
@@ -1,78 +1,56 @@
-use std::{collections::HashMap, convert::TryInto, ops::Sub};
+use std::collections::HashMap;
+
+use aoc_common::{Grid, ParseResult, Point, Solution};
 
 fn main() {
-    let input = include_str!("input.txt");
-    println!("Part one: {}", part_one(input));
-    println!("Part two: {}", part_two(input));
+    aoc_common::run_day::<Day>(2024, 8);
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Coord(i16, i16);
+pub struct Day;
 
-impl Coord {
-    fn new<T>(x: T, y: T) -> Self
-    where
-        i16: From<T>,
-    {
-        Self(i16::from(x), i16::from(y))
-    }
+impl Solution for Day {
+    type Parsed = Map;
+    type PartOne = usize;
+    type PartTwo = usize;
 
-    fn try_new<T: TryInto<i16>>(x: T, y: T) -> Result<Self, T::Error> {
-        Ok(Self(x.try_into()?, y.try_into()?))
+    fn parse(input: &str) -> ParseResult<Map> {
+        parse_map(input)
     }
 
-    fn x(&self) -> i16 {
-        self.0
-    }
+    fn part_one(map: &Map) -> usize {
+        let mut result = 0;
 
-    fn y(&self) -> i16 {
-        self.1
+        for x in 0..map.size.0 {
+            for y in 0..map.size.1 {
+                if is_valid_antinode(&map, Coord::from_pos((x, y)), true) {
+                    result += 1;
+                }
+            }
+        }
+        result
     }
 
-    fn mag_manhatten(&self) -> i32 {
-        self.x().abs() as i32 + self.y().abs() as i32
-    }
-}
+    fn part_two(map: &Map) -> usize {
+        let mut result = 0;
 
-impl Sub for Coord {
-    type Output = Self;
-
-    fn sub(self, other: Coord) -> Coord {
-        Coord::new(self.x() - other.x(), self.y() - other.y())
-    }
-}
-
-struct Map {
-    size: (usize, usize),
-    antenna: HashMap<char, Vec<Coord>>,
-}
-
-fn part_one(input: &str) -> usize {
-    let map = parse_map(input);
-    let mut result = 0;
-
-    for x in 0..map.size.0 {
-        for y in 0..map.size.1 {
-            if is_valid_antinode(&map, Coord::try_new(x, y).unwrap(), true) {
-                result += 1;
+        for x in 0..map.size.0 {
+            for y in 0..map.size.1 {
+                if is_valid_antinode(&map, Coord::from_pos((x, y)), false) {
+                    result += 1;
+                }
             }
         }
+        result
     }
-    result
 }
 
-fn part_two(input: &str) -> usize {
-    let map = parse_map(input);
-    let mut result = 0;
+// Wide enough that the cross products in `are_parallel` can't overflow.
+type Coord = Point<i64>;
 
-    for x in 0..map.size.0 {
-        for y in 0..map.size.1 {
-            if is_valid_antinode(&map, Coord::try_new(x, y).unwrap(), false) {
-                result += 1;
-            }
-        }
-    }
-    result
+#[derive(Debug)]
+pub struct Map {
+    size: (usize, usize),
+    antenna: HashMap<char, Vec<Coord>>,
 }
 
 fn is_valid_antinode(map: &Map, coord: Coord, check_distances: bool) -> bool {
@@ -101,30 +79,29 @@ fn check_distance_between_antennas(vec_a: Coord, vec_b: Coord, check_distances:
     if !are_parallel(vec_a, vec_b) {
         return false;
     }
-    let dist_a = vec_a.mag_manhatten();
-    let dist_b = vec_b.mag_manhatten();
+    let dist_a = vec_a.manhattan();
+    let dist_b = vec_b.manhattan();
     !check_distances || dist_a == dist_b * 2 || dist_b == dist_a * 2
 }
 
 fn are_parallel(vec_a: Coord, vec_b: Coord) -> bool {
-    vec_a.x() * vec_b.y() == vec_a.y() * vec_b.x()
+    vec_a.x * vec_b.y == vec_a.y * vec_b.x
 }
 
-fn parse_map(input: &str) -> Map {
+/// Antennas are letters or digits, with `.` for empty space.
+fn parse_map(input: &str) -> ParseResult<Map> {
+    let grid = Grid::try_parse(input, |c| {
+        Some(c).filter(|c| *c == '.' || c.is_ascii_alphanumeric())
+    })?;
     let mut antenna = HashMap::new();
-    for (y, line) in input.lines().enumerate() {
-        for (x, c) in line.chars().enumerate() {
-            if c != '.' {
-                let entry = antenna.entry(c).or_insert(vec![]);
-                entry.push(Coord::try_new(x, y).unwrap());
-            }
+    for (pos, &c) in grid.iter() {
+        if c != '.' {
+            let entry = antenna.entry(c).or_insert(vec![]);
+            entry.push(Coord::from_pos(pos));
         }
     }
-    let size = (
-        input.lines().next().map_or(0, |line| line.len()),
-        input.lines().count(),
-    );
-    Map { size, antenna }
+    let size = (grid.width(), grid.height());
+    Ok(Map { size, antenna })
 }
 
 #[cfg(test)]
@@ -145,7 +122,7 @@ mod tests {
 .........A..
 ............
 ............";
-        assert_eq!(part_one(input), 14);
+        assert_eq!(Day::part_one(&Day::parse(input).unwrap()), 14);
     }
 
     #[test]
@@ -160,7 +137,7 @@ mod tests {
 ..........
 ..........
 ..........";
-        assert_eq!(part_one(input), 2);
+        assert_eq!(Day::part_one(&Day::parse(input).unwrap()), 2);
     }
 
     #[test]
@@ -175,7 +152,7 @@ mod tests {
 ..........
 ..........
 ..........";
-        assert_eq!(part_one(input), 4);
+        assert_eq!(Day::part_one(&Day::parse(input).unwrap()), 4);
     }
 
     #[test]
@@ -192,6 +169,14 @@ mod tests {
 .........A..
 ............
 ............";
-        assert_eq!(part_two(input), 34);
+        assert_eq!(Day::part_two(&Day::parse(input).unwrap()), 34);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = Day::parse("..a.\n.#..\n").unwrap_err();
+        assert_eq!((2, 2), (err.line, err.column));
+        let err = Day::parse("..a.\n...\n").unwrap_err();
+        assert_eq!((2, 4), (err.line, err.column));
     }
 }
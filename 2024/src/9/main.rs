@@ -1,34 +1,48 @@
 use core::panic;
 
+use aoc_common::{ParseError, ParseResult, Solution};
+
 fn main() {
-    let input = include_str!("input.txt");
-    println!("Part one: {}", part_one(input));
-    println!("Part two: {}", part_two(input));
+    aoc_common::run_day::<Day>(2024, 9);
 }
 
-#[derive(Clone, Debug)]
-enum Node {
-    File(i64, i64),
-    Space(i64),
-}
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = Vec<Node>;
+    type PartOne = i64;
+    type PartTwo = i64;
+
+    fn parse(input: &str) -> ParseResult<Vec<Node>> {
+        parse(input)
+    }
+
+    fn part_one(nodes: &Vec<Node>) -> i64 {
+        score(&defragment(&nodes))
+    }
 
-fn part_one(input: &str) -> i64 {
-    let nodes = parse(input);
-    score(&defragment(&nodes))
+    fn part_two(nodes: &Vec<Node>) -> i64 {
+        score(&defragment2(&nodes))
+    }
 }
 
-fn part_two(input: &str) -> i64 {
-    let nodes = parse(input);
-    score(&defragment2(&nodes))
+#[derive(Clone, Debug)]
+pub enum Node {
+    File(i64, i64),
+    Space(i64),
 }
 
-fn parse(input: &str) -> Vec<Node> {
+fn parse(input: &str) -> ParseResult<Vec<Node>> {
     let mut file = true;
     let mut id = 0;
     let mut result = Vec::new();
 
-    for c in input.chars() {
-        let size = c.to_digit(10).unwrap() as i64;
+    let disk_map = input.trim_end();
+    for (i, c) in disk_map.char_indices() {
+        let size = c
+            .to_digit(10)
+            .ok_or_else(|| ParseError::at_slice(input, &disk_map[i..], "expected a digit"))?
+            as i64;
         if file {
             result.push(Node::File(size, id));
             id += 1;
@@ -37,7 +51,10 @@ fn parse(input: &str) -> Vec<Node> {
         }
         file = !file;
     }
-    result
+    if result.is_empty() {
+        return Err(ParseError::at(input, 0, "expected a disk map"));
+    }
+    Ok(result)
 }
 
 fn defragment(nodes: &Vec<Node>) -> Vec<Node> {
@@ -170,11 +187,25 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        assert_eq!(part_one("2333133121414131402"), 1928);
+        assert_eq!(
+            Day::part_one(&Day::parse("2333133121414131402").unwrap()),
+            1928
+        );
     }
 
     #[test]
     fn test_part_two() {
-        assert_eq!(part_two("2333133121414131402"), 2858);
+        assert_eq!(
+            Day::part_two(&Day::parse("2333133121414131402").unwrap()),
+            2858
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(3, parse("123\n").unwrap().len());
+        let err = parse("12x3\n").unwrap_err();
+        assert_eq!((1, 3), (err.line, err.column));
+        assert!(parse("").is_err());
     }
 }
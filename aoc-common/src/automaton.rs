@@ -0,0 +1,278 @@
+//! A sparse cellular automaton: only live cells are stored, so boards can grow
+//! without bound in any number of dimensions.
+
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use crate::{Grid, HexCoord, NEIGHBOURS_8};
+
+/// Which cells count as neighbours of a cell.
+pub trait Neighbourhood<C> {
+    fn neighbours(&self, cell: &C) -> Vec<C>;
+}
+
+/// Every cell touching this one, including diagonally: 8 in 2D, 26 in 3D and
+/// 80 in 4D.
+#[derive(Debug, Clone, Copy)]
+pub struct Moore;
+
+/// Only the cells sharing a face with this one: 4 in 2D, 6 in 3D.
+#[derive(Debug, Clone, Copy)]
+pub struct VonNeumann;
+
+/// The six tiles around a hexagon.
+#[derive(Debug, Clone, Copy)]
+pub struct Hex;
+
+/// The first cell visible in each of the eight directions across a grid,
+/// looking past cells that can never be live.
+#[derive(Debug, Clone)]
+pub struct LineOfSight {
+    visible: HashMap<[i64; 2], Vec<[i64; 2]>>,
+}
+
+impl<const D: usize> Neighbourhood<[i64; D]> for Moore {
+    fn neighbours(&self, cell: &[i64; D]) -> Vec<[i64; D]> {
+        let mut cells = vec![*cell];
+        for d in 0..D {
+            cells = cells
+                .into_iter()
+                .flat_map(|c| {
+                    (-1..=1).map(move |delta| {
+                        let mut next = c;
+                        next[d] += delta;
+                        next
+                    })
+                })
+                .collect();
+        }
+        cells.retain(|c| c != cell);
+        cells
+    }
+}
+
+impl<const D: usize> Neighbourhood<[i64; D]> for VonNeumann {
+    fn neighbours(&self, cell: &[i64; D]) -> Vec<[i64; D]> {
+        (0..D)
+            .flat_map(|d| {
+                [-1, 1].into_iter().map(move |delta| {
+                    let mut next = *cell;
+                    next[d] += delta;
+                    next
+                })
+            })
+            .collect()
+    }
+}
+
+impl Neighbourhood<HexCoord> for Hex {
+    fn neighbours(&self, tile: &HexCoord) -> Vec<HexCoord> {
+        tile.neighbours().to_vec()
+    }
+}
+
+impl LineOfSight {
+    /// Looks across `cells`, where true marks a cell that can be live and
+    /// blocks the view beyond it.
+    pub fn new(cells: &Grid<bool>) -> LineOfSight {
+        let visible = cells
+            .iter()
+            .filter(|(_, &solid)| solid)
+            .map(|(pos, _)| {
+                let seen = NEIGHBOURS_8
+                    .iter()
+                    .filter_map(|&step| cells.ray(pos, step).find(|&p| cells[p]))
+                    .map(cell)
+                    .collect();
+                (cell(pos), seen)
+            })
+            .collect();
+        LineOfSight { visible }
+    }
+}
+
+impl Neighbourhood<[i64; 2]> for LineOfSight {
+    fn neighbours(&self, cell: &[i64; 2]) -> Vec<[i64; 2]> {
+        self.visible.get(cell).cloned().unwrap_or_default()
+    }
+}
+
+fn cell((x, y): (usize, usize)) -> [i64; 2] {
+    [x as i64, y as i64]
+}
+
+/// How many live neighbours bring a dead cell to life, and how many keep a
+/// live one alive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    birth: Vec<usize>,
+    survival: Vec<usize>,
+}
+
+impl Rule {
+    pub fn new(birth: &[usize], survival: &[usize]) -> Rule {
+        Rule {
+            birth: birth.to_vec(),
+            survival: survival.to_vec(),
+        }
+    }
+
+    /// Conway's Game of Life: born with three neighbours, surviving with two
+    /// or three.
+    pub fn life() -> Rule {
+        Rule::new(&[3], &[2, 3])
+    }
+
+    pub fn next(&self, alive: bool, live_neighbours: usize) -> bool {
+        let counts = if alive { &self.survival } else { &self.birth };
+        counts.contains(&live_neighbours)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Automaton<C, N> {
+    live: HashSet<C>,
+    neighbourhood: N,
+    rule: Rule,
+    /// The only cells that may ever be live, if the board is bounded.
+    domain: Option<HashSet<C>>,
+}
+
+impl<C, N> Automaton<C, N>
+where
+    C: Clone + Eq + Hash,
+    N: Neighbourhood<C>,
+{
+    pub fn new<I>(live: I, neighbourhood: N, rule: Rule) -> Automaton<C, N>
+    where
+        I: IntoIterator<Item = C>,
+    {
+        Automaton {
+            live: live.into_iter().collect(),
+            neighbourhood,
+            rule,
+            domain: None,
+        }
+    }
+
+    /// Restricts the board to `domain`. On an unbounded board a cell with no
+    /// live neighbours is never born, so rules that allow that need one.
+    pub fn within<I>(mut self, domain: I) -> Automaton<C, N>
+    where
+        I: IntoIterator<Item = C>,
+    {
+        let domain: HashSet<C> = domain.into_iter().collect();
+        self.live.retain(|c| domain.contains(c));
+        self.domain = Some(domain);
+        self
+    }
+
+    pub fn live(&self) -> &HashSet<C> {
+        &self.live
+    }
+
+    pub fn is_live(&self, cell: &C) -> bool {
+        self.live.contains(cell)
+    }
+
+    pub fn count(&self) -> usize {
+        self.live.len()
+    }
+
+    pub fn live_neighbours(&self, cell: &C) -> usize {
+        self.neighbourhood
+            .neighbours(cell)
+            .iter()
+            .filter(|n| self.live.contains(n))
+            .count()
+    }
+
+    /// Advances one generation, returning whether any cell changed.
+    pub fn step(&mut self) -> bool {
+        let mut counts: HashMap<C, usize> = HashMap::new();
+        for cell in &self.live {
+            for n in self.neighbourhood.neighbours(cell) {
+                *counts.entry(n).or_default() += 1;
+            }
+        }
+
+        let count = |c: &C| counts.get(c).copied().unwrap_or(0);
+        let next: HashSet<C> = match &self.domain {
+            Some(domain) => domain
+                .iter()
+                .filter(|c| self.rule.next(self.live.contains(c), count(c)))
+                .cloned()
+                .collect(),
+            None => self
+                .live
+                .iter()
+                .chain(counts.keys())
+                .filter(|c| self.rule.next(self.live.contains(c), count(c)))
+                .cloned()
+                .collect(),
+        };
+
+        let changed = next != self.live;
+        self.live = next;
+        changed
+    }
+
+    pub fn run(&mut self, generations: usize) {
+        for _ in 0..generations {
+            self.step();
+        }
+    }
+
+    /// Steps until a generation changes nothing, returning how many
+    /// generations that took. Never returns if the board doesn't settle.
+    pub fn run_until_stable(&mut self) -> usize {
+        let mut generations = 0;
+        while self.step() {
+            generations += 1;
+        }
+        generations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_neighbourhoods() {
+        assert_eq!(8, Moore.neighbours(&[0, 0]).len());
+        assert_eq!(26, Moore.neighbours(&[0, 0, 0]).len());
+        assert_eq!(80, Moore.neighbours(&[0, 0, 0, 0]).len());
+        assert_eq!(6, VonNeumann.neighbours(&[0, 0, 0]).len());
+        assert!(Hex
+            .neighbours(&HexCoord::ORIGIN)
+            .contains(&HexCoord::new(-1, 1)));
+
+        let seats = Grid::parse("#.#\n...\n#..", |c| c == '#').unwrap();
+        let sight = LineOfSight::new(&seats);
+        let mut seen = sight.neighbours(&[0, 0]);
+        seen.sort();
+        assert_eq!(vec![[0, 2], [2, 0]], seen);
+    }
+
+    #[test]
+    fn test_blinker() {
+        let mut life = Automaton::new([[0, -1], [0, 0], [0, 1]], Moore, Rule::life());
+        assert_eq!(3, life.live_neighbours(&[1, 0]));
+        assert!(life.step());
+        assert_eq!(HashSet::from([[-1, 0], [0, 0], [1, 0]]), *life.live());
+        life.run(3);
+        assert!(life.is_live(&[0, 1]));
+        assert_eq!(3, life.count());
+    }
+
+    #[test]
+    fn test_domain() {
+        // Every cell with no live neighbours is born, so the board fills up
+        // but only within the domain.
+        let mut board = Automaton::new(Vec::new(), VonNeumann, Rule::new(&[0], &[0, 1, 2]))
+            .within([[0, 0], [1, 0], [5, 5]]);
+        assert_eq!(1, board.run_until_stable());
+        assert_eq!(3, board.count());
+    }
+}
@@ -0,0 +1,256 @@
+//! Sets of small integers packed into machine words, for puzzles whose sets
+//! are drawn from a handful of letters or bit positions.
+
+use std::fmt;
+
+/// A set of integers below `64 * WORDS`, one bit each.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BitSet<const WORDS: usize = 1> {
+    words: [u64; WORDS],
+}
+
+impl<const WORDS: usize> BitSet<WORDS> {
+    /// How many integers the set has room for, from 0.
+    pub const CAPACITY: usize = 64 * WORDS;
+
+    pub fn new() -> BitSet<WORDS> {
+        BitSet { words: [0; WORDS] }
+    }
+
+    /// Every integer the set can hold.
+    pub fn full() -> BitSet<WORDS> {
+        BitSet {
+            words: [u64::MAX; WORDS],
+        }
+    }
+
+    /// Adds `value`, returning whether it was new. Panics if `value` is past
+    /// the set's capacity.
+    pub fn insert(&mut self, value: usize) -> bool {
+        assert!(
+            value < Self::CAPACITY,
+            "{} doesn't fit in a set of {}",
+            value,
+            Self::CAPACITY
+        );
+        let (word, bit) = (value / 64, 1 << (value % 64));
+        let new = self.words[word] & bit == 0;
+        self.words[word] |= bit;
+        new
+    }
+
+    /// Removes `value`, returning whether it was there.
+    pub fn remove(&mut self, value: usize) -> bool {
+        let present = self.contains(value);
+        if present {
+            self.words[value / 64] &= !(1 << (value % 64));
+        }
+        present
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        value < Self::CAPACITY && self.words[value / 64] & (1 << (value % 64)) != 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    fn zip_with(&self, other: &BitSet<WORDS>, f: impl Fn(u64, u64) -> u64) -> BitSet<WORDS> {
+        let mut words = self.words;
+        for (w, o) in words.iter_mut().zip(other.words) {
+            *w = f(*w, o);
+        }
+        BitSet { words }
+    }
+
+    pub fn union(&self, other: &BitSet<WORDS>) -> BitSet<WORDS> {
+        self.zip_with(other, |a, b| a | b)
+    }
+
+    pub fn intersection(&self, other: &BitSet<WORDS>) -> BitSet<WORDS> {
+        self.zip_with(other, |a, b| a & b)
+    }
+
+    /// The values in this set but not in `other`.
+    pub fn difference(&self, other: &BitSet<WORDS>) -> BitSet<WORDS> {
+        self.zip_with(other, |a, b| a & !b)
+    }
+
+    /// The values in exactly one of the sets.
+    pub fn symmetric_difference(&self, other: &BitSet<WORDS>) -> BitSet<WORDS> {
+        self.zip_with(other, |a, b| a ^ b)
+    }
+
+    pub fn is_subset(&self, other: &BitSet<WORDS>) -> bool {
+        self.difference(other).is_empty()
+    }
+
+    pub fn is_superset(&self, other: &BitSet<WORDS>) -> bool {
+        other.is_subset(self)
+    }
+
+    pub fn is_disjoint(&self, other: &BitSet<WORDS>) -> bool {
+        self.intersection(other).is_empty()
+    }
+
+    /// The values in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &word)| {
+            let mut rest = word;
+            std::iter::from_fn(move || {
+                if rest == 0 {
+                    return None;
+                }
+                let bit = rest.trailing_zeros() as usize;
+                rest &= rest - 1;
+                Some(i * 64 + bit)
+            })
+        })
+    }
+
+    pub fn min(&self) -> Option<usize> {
+        self.iter().next()
+    }
+
+    pub fn max(&self) -> Option<usize> {
+        self.words
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, &w)| w != 0)
+            .map(|(i, w)| i * 64 + 63 - w.leading_zeros() as usize)
+    }
+
+    /// Every subset of this set, starting with the empty one. Panics if the
+    /// set has 64 or more values, as there'd be too many to count.
+    pub fn subsets(&self) -> impl Iterator<Item = BitSet<WORDS>> {
+        let values: Vec<usize> = self.iter().collect();
+        assert!(values.len() < 64, "too many subsets to enumerate");
+        (0..1u64 << values.len()).map(move |choice| {
+            values
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| choice & (1 << i) != 0)
+                .map(|(_, &v)| v)
+                .collect()
+        })
+    }
+}
+
+impl BitSet<1> {
+    /// The set whose values are the positions of the set bits of `bits`.
+    pub fn from_bits(bits: u64) -> BitSet<1> {
+        BitSet { words: [bits] }
+    }
+
+    pub fn bits(&self) -> u64 {
+        self.words[0]
+    }
+
+    /// Where a letter goes in a set of letters: `a` to `z` are 0 to 25 and
+    /// `A` to `Z` 26 to 51.
+    pub fn letter_index(c: char) -> Option<usize> {
+        match c {
+            'a'..='z' => Some(c as usize - 'a' as usize),
+            'A'..='Z' => Some(c as usize - 'A' as usize + 26),
+            _ => None,
+        }
+    }
+
+    /// The letters in `text`, ignoring anything else.
+    pub fn from_letters(text: &str) -> BitSet<1> {
+        text.chars().filter_map(BitSet::letter_index).collect()
+    }
+
+    /// The letters in the set, lower case first.
+    pub fn letters(&self) -> impl Iterator<Item = char> + '_ {
+        self.iter().filter_map(|i| match i {
+            0..=25 => Some((b'a' + i as u8) as char),
+            26..=51 => Some((b'A' + (i - 26) as u8) as char),
+            _ => None,
+        })
+    }
+}
+
+impl<const WORDS: usize> Default for BitSet<WORDS> {
+    fn default() -> BitSet<WORDS> {
+        BitSet::new()
+    }
+}
+
+impl<const WORDS: usize> FromIterator<usize> for BitSet<WORDS> {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> BitSet<WORDS> {
+        let mut set = BitSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<const WORDS: usize> Extend<usize> for BitSet<WORDS> {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl<const WORDS: usize> fmt::Debug for BitSet<WORDS> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_remove() {
+        let mut set: BitSet<2> = BitSet::new();
+        assert!(set.insert(3));
+        assert!(set.insert(100));
+        assert!(!set.insert(3));
+        assert_eq!(vec![3, 100], set.iter().collect::<Vec<_>>());
+        assert_eq!((Some(3), Some(100)), (set.min(), set.max()));
+        assert!(set.remove(3) && !set.remove(3));
+        assert_eq!(1, set.len());
+        assert!(!set.contains(500));
+        assert_eq!(128, BitSet::<2>::full().len());
+    }
+
+    #[test]
+    fn test_set_algebra() {
+        let a: BitSet = [1, 2, 3].into_iter().collect();
+        let b: BitSet = [3, 4].into_iter().collect();
+        assert_eq!(BitSet::from_bits(0b11110), a.union(&b));
+        assert_eq!(BitSet::from_bits(0b1000), a.intersection(&b));
+        assert_eq!(BitSet::from_bits(0b110), a.difference(&b));
+        assert_eq!(BitSet::from_bits(0b10110), a.symmetric_difference(&b));
+        assert!(a.intersection(&b).is_subset(&a));
+        assert!(a.is_superset(&BitSet::from_bits(0b10)));
+        assert!(a.difference(&b).is_disjoint(&b));
+    }
+
+    #[test]
+    fn test_letters() {
+        let set = BitSet::from_letters("vJrwpW twJgWr");
+        assert_eq!(Some(15), BitSet::letter_index('p'));
+        assert_eq!(Some(37), BitSet::letter_index('L'));
+        assert_eq!("gprtvwJW", set.letters().collect::<String>());
+    }
+
+    #[test]
+    fn test_subsets() {
+        let subsets: Vec<u64> = BitSet::from_bits(0b1010)
+            .subsets()
+            .map(|s| s.bits())
+            .collect();
+        assert_eq!(vec![0b0, 0b10, 0b1000, 0b1010], subsets);
+        assert_eq!(1, BitSet::<1>::new().subsets().count());
+    }
+}
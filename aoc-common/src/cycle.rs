@@ -0,0 +1,235 @@
+//! Finding where a deterministic simulation starts repeating itself, so the
+//! state at a step far in the future can be read off from one trip round the
+//! cycle.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// The steps from `start` repeat every `length` steps forever.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The earliest step with the same state as step `n`.
+    pub fn equivalent(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+}
+
+/// Brent's algorithm, which only ever holds two states at once. `step` is
+/// called several times for each state, so it must be pure.
+pub fn brent<S, F>(start: S, mut step: F) -> Cycle
+where
+    S: Clone + Eq,
+    F: FnMut(&S) -> S,
+{
+    // Teleport the tortoise to the hare at each power of two until the hare
+    // catches it, at which point they are a whole cycle apart.
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    // With the hare a cycle ahead, they first meet where the cycle begins.
+    let mut tortoise = start.clone();
+    let mut hare = start;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    Cycle { start, length }
+}
+
+/// Floyd's tortoise and hare, which like [`brent`] holds only two states but
+/// usually calls `step` more often.
+pub fn floyd<S, F>(start: S, mut step: F) -> Cycle
+where
+    S: Clone + Eq,
+    F: FnMut(&S) -> S,
+{
+    let mut tortoise = step(&start);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        let once = step(&hare);
+        hare = step(&once);
+    }
+
+    let mut tortoise = start;
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    let mut length = 1;
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+    Cycle { start, length }
+}
+
+/// Every state reached so far in order, indexed so that a repeat is spotted as
+/// soon as it happens.
+#[derive(Debug, Clone)]
+pub struct History<S> {
+    states: Vec<S>,
+    seen: HashMap<S, usize>,
+    cycle: Option<Cycle>,
+}
+
+impl<S: Clone + Eq + Hash> History<S> {
+    pub fn new() -> History<S> {
+        History {
+            states: Vec::new(),
+            seen: HashMap::new(),
+            cycle: None,
+        }
+    }
+
+    /// Records the state at the next step, returning the cycle if the state
+    /// has been seen before. Once a cycle is found the history is complete
+    /// and later states are ignored.
+    pub fn record(&mut self, state: S) -> Option<Cycle> {
+        if self.cycle.is_some() {
+            return self.cycle;
+        }
+        if let Some(&start) = self.seen.get(&state) {
+            self.cycle = Some(Cycle {
+                start,
+                length: self.states.len() - start,
+            });
+            return self.cycle;
+        }
+        self.seen.insert(state.clone(), self.states.len());
+        self.states.push(state);
+        None
+    }
+
+    /// The states up to the first repeat, or all of them if none has repeated.
+    pub fn states(&self) -> &[S] {
+        &self.states
+    }
+
+    pub fn cycle(&self) -> Option<Cycle> {
+        self.cycle
+    }
+
+    /// The state at step `n`, going round the cycle to reach it if it's past
+    /// the end of the history.
+    pub fn state_at(&self, n: usize) -> Option<&S> {
+        match self.cycle {
+            Some(cycle) => self.states.get(cycle.equivalent(n)),
+            None => self.states.get(n),
+        }
+    }
+}
+
+impl<S: Clone + Eq + Hash> Default for History<S> {
+    fn default() -> History<S> {
+        History::new()
+    }
+}
+
+/// Steps on from `start` until a state repeats, keeping every state on the
+/// way. Never returns if no state ever does.
+pub fn find_cycle<S, F>(start: S, mut step: F) -> History<S>
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> S,
+{
+    let mut history = History::new();
+    let mut state = start;
+    loop {
+        let next = step(&state);
+        if history.record(state).is_some() {
+            return history;
+        }
+        state = next;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 0, 1, 2 then round 3..10 forever.
+    fn rho(n: &u32) -> u32 {
+        if *n == 9 {
+            3
+        } else {
+            n + 1
+        }
+    }
+
+    #[test]
+    fn test_detectors_agree() {
+        let expected = Cycle {
+            start: 3,
+            length: 7,
+        };
+        assert_eq!(expected, brent(0, rho));
+        assert_eq!(expected, floyd(0, rho));
+        assert_eq!(Some(expected), find_cycle(0, rho).cycle());
+        // A fixed point is a cycle of length one.
+        assert_eq!(
+            Cycle {
+                start: 0,
+                length: 1
+            },
+            brent(5, |&n| n)
+        );
+    }
+
+    #[test]
+    fn test_extrapolation() {
+        let history = find_cycle(0, rho);
+        assert_eq!(10, history.states().len());
+        assert_eq!(Some(&2), history.state_at(2));
+        assert_eq!(Some(&3), history.state_at(10));
+        assert_eq!(
+            Some(&(3 + (1_000_000_000 - 3) % 7)),
+            history.state_at(1_000_000_000)
+        );
+    }
+
+    #[test]
+    fn test_record() {
+        let mut history = History::new();
+        for state in ["a", "b", "c"] {
+            assert_eq!(None, history.record(state));
+        }
+        let cycle = Some(Cycle {
+            start: 1,
+            length: 2,
+        });
+        assert_eq!(cycle, history.record("b"));
+        assert_eq!(cycle, history.record("z"));
+        assert_eq!(3, history.states().len());
+        assert_eq!(Some(&"c"), history.state_at(4));
+    }
+}
@@ -0,0 +1,572 @@
+//! An interactive debugger for programs running on a [`Machine`], driven by
+//! commands read a line at a time so it works in a plain terminal.
+
+use std::env;
+use std::io::{self, BufRead, Write};
+use std::process;
+use std::str::FromStr;
+
+use crate::{day_args, day_input, run_day, InstructionSet, Machine, Solution};
+
+/// What the debugger needs to know about an instruction set to show and
+/// watch a running program.
+pub trait Debuggable: InstructionSet {
+    /// The name of an instruction's operation, which opcode breakpoints match.
+    fn mnemonic(&self, instruction: &Self::Instruction) -> String;
+
+    /// An instruction as it appears in listings.
+    fn describe(&self, instruction: &Self::Instruction) -> String {
+        format!("{:?}", instruction)
+    }
+
+    /// What an instruction does, shown beside it in listings.
+    fn comment(&self, _instruction: &Self::Instruction) -> Option<String> {
+        None
+    }
+
+    /// Higher-level pseudo-code for a whole program, if the instruction set
+    /// can work it out.
+    fn decompile(&self, _code: &[Self::Code]) -> Option<String> {
+        None
+    }
+
+    /// Each register's name and value, which watchpoints compare.
+    fn registers(&self, state: &Self::State) -> Vec<(String, String)>;
+
+    /// Anything else worth dumping, such as memory or output.
+    fn memory(&self, _state: &Self::State) -> Vec<(String, String)> {
+        Vec::new()
+    }
+
+    /// Turns one of the instruction set's named options on or off, for the
+    /// `set` command.
+    fn set_option(&mut self, name: &str, _on: bool) -> Result<(), String> {
+        Err(format!("no option called {}", name))
+    }
+}
+
+/// Where `continue` stops.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Breakpoint {
+    /// Before the instruction at this address.
+    Address(usize),
+    /// Before any instruction with this mnemonic.
+    Opcode(String),
+}
+
+impl FromStr for Breakpoint {
+    type Err = String;
+
+    /// A number is an address and anything else a mnemonic.
+    fn from_str(s: &str) -> Result<Breakpoint, String> {
+        match s.parse() {
+            Ok(ip) => Ok(Breakpoint::Address(ip)),
+            Err(_) if !s.is_empty() => Ok(Breakpoint::Opcode(s.to_string())),
+            Err(_) => Err("expected an address or an opcode".to_string()),
+        }
+    }
+}
+
+const HELP: &str = "\
+step [n]        run n instructions (s)
+back [n]        undo n instructions (bs)
+continue        run to a breakpoint, a watched change, the end or the step limit (c)
+break [where]   break at an address or opcode, or list breakpoints (b)
+delete <where>  remove a breakpoint (d)
+watch <reg>     stop when a register changes (w)
+registers       dump the registers (r)
+memory          dump memory and output (m)
+list [n]        show the next n instructions, 5 by default (l)
+disassemble     list the whole program (dis)
+decompile       show the program as pseudo-code (dc)
+set <opt> [off] turn an instruction set option on, or off
+quit            leave the debugger (q)";
+
+/// How many instructions `continue` runs before giving up, so a program that
+/// never halts hands control back.
+const STEP_LIMIT: usize = 1_000_000;
+
+/// A machine being stepped through, with its breakpoints and watchpoints.
+pub struct Debugger<I: Debuggable> {
+    machine: Machine<I>,
+    breakpoints: Vec<Breakpoint>,
+    watches: Vec<String>,
+    step_limit: usize,
+}
+
+impl<I: Debuggable> Debugger<I> {
+    /// Takes over `machine`, tracing it so steps can be undone.
+    pub fn new(machine: Machine<I>) -> Debugger<I> {
+        Debugger {
+            machine: machine.with_trace(),
+            breakpoints: Vec::new(),
+            watches: Vec::new(),
+            step_limit: STEP_LIMIT,
+        }
+    }
+
+    /// Stops `continue` after `steps` instructions instead of a million.
+    pub fn with_step_limit(mut self, steps: usize) -> Debugger<I> {
+        self.step_limit = steps;
+        self
+    }
+
+    pub fn machine(&self) -> &Machine<I> {
+        &self.machine
+    }
+
+    /// Reads commands from `input` until it ends or says `quit`, writing a
+    /// prompt before each.
+    pub fn repl<R: BufRead, W: Write>(&mut self, input: R, mut out: W) -> io::Result<()> {
+        writeln!(out, "Type 'help' for commands.")?;
+        self.show_position(&mut out)?;
+        write!(out, "(debug) ")?;
+        out.flush()?;
+        for line in input.lines() {
+            if !self.command(&line?, &mut out)? {
+                break;
+            }
+            write!(out, "(debug) ")?;
+            out.flush()?;
+        }
+        writeln!(out)
+    }
+
+    /// Runs one command, returning false if it was `quit`.
+    pub fn command<W: Write>(&mut self, line: &str, out: &mut W) -> io::Result<bool> {
+        let mut words = line.split_whitespace();
+        let (command, arg) = match words.next() {
+            Some(command) => (command, words.next()),
+            None => return Ok(true),
+        };
+        let count = |arg: Option<&str>| arg.map_or(Ok(1), str::parse::<usize>);
+
+        match (command, arg) {
+            ("step" | "s", _) => match count(arg) {
+                Ok(n) => self.step(n, out)?,
+                Err(_) => writeln!(out, "expected a number of steps")?,
+            },
+            ("back" | "bs", _) => match count(arg) {
+                Ok(n) => self.back(n, out)?,
+                Err(_) => writeln!(out, "expected a number of steps")?,
+            },
+            ("continue" | "c", None) => self.resume(out)?,
+            ("break" | "b", None) => self.show_breakpoints(out)?,
+            ("break" | "b", Some(place)) => match place.parse() {
+                Ok(breakpoint) if self.breakpoints.contains(&breakpoint) => {}
+                Ok(breakpoint) => self.breakpoints.push(breakpoint),
+                Err(e) => writeln!(out, "{}", e)?,
+            },
+            ("delete" | "d", Some(place)) => {
+                let before = self.breakpoints.len();
+                if let Ok(breakpoint) = place.parse::<Breakpoint>() {
+                    self.breakpoints.retain(|b| *b != breakpoint);
+                }
+                if self.breakpoints.len() == before {
+                    writeln!(out, "no breakpoint at {}", place)?;
+                }
+            }
+            ("watch" | "w", Some(name)) => {
+                if self.register(name).is_none() {
+                    writeln!(out, "no register called {}", name)?;
+                } else if !self.watches.iter().any(|w| w == name) {
+                    self.watches.push(name.to_string());
+                }
+            }
+            ("registers" | "r", None) => {
+                for (name, value) in self.machine.isa().registers(self.machine.state()) {
+                    writeln!(out, "{} = {}", name, value)?;
+                }
+            }
+            ("memory" | "m", None) => {
+                for (name, value) in self.machine.isa().memory(self.machine.state()) {
+                    writeln!(out, "{} = {}", name, value)?;
+                }
+            }
+            ("list" | "l", _) => match arg.map_or(Ok(5), str::parse::<usize>) {
+                Ok(n) => self.list(n, out)?,
+                Err(_) => writeln!(out, "expected a number of instructions")?,
+            },
+            ("disassemble" | "dis", None) => self.list_from(0, usize::MAX, out)?,
+            ("decompile" | "dc", None) => match self.machine.isa().decompile(self.machine.code()) {
+                Some(code) => writeln!(out, "{}", code.trim_end())?,
+                None => writeln!(out, "no decompiler for this instruction set")?,
+            },
+            ("set", Some(name)) => {
+                let set = match words.next() {
+                    None | Some("on") => self.machine.isa_mut().set_option(name, true),
+                    Some("off") => self.machine.isa_mut().set_option(name, false),
+                    Some(_) => Err("expected on or off".to_string()),
+                };
+                if let Err(e) = set {
+                    writeln!(out, "{}", e)?;
+                }
+            }
+            ("help" | "h", None) => writeln!(out, "{}", HELP)?,
+            ("quit" | "q", None) => return Ok(false),
+            _ => writeln!(out, "unknown command '{}', try 'help'", line.trim())?,
+        }
+        Ok(true)
+    }
+
+    fn step<W: Write>(&mut self, n: usize, out: &mut W) -> io::Result<()> {
+        for _ in 0..n {
+            match self.machine.step() {
+                Ok(true) => {}
+                Ok(false) => break,
+                Err(e) => return writeln!(out, "error {}", e),
+            }
+        }
+        self.show_position(out)
+    }
+
+    fn back<W: Write>(&mut self, n: usize, out: &mut W) -> io::Result<()> {
+        for _ in 0..n {
+            if !self.machine.step_back() {
+                writeln!(out, "at the start of the recorded history")?;
+                break;
+            }
+        }
+        self.show_position(out)
+    }
+
+    /// Runs until a breakpoint other than the one already stopped at, a
+    /// watched register changing, the end, an error or the step limit.
+    fn resume<W: Write>(&mut self, out: &mut W) -> io::Result<()> {
+        let watched = self.watched();
+        let mut first = true;
+        for steps in 0.. {
+            if steps == self.step_limit {
+                writeln!(
+                    out,
+                    "stopped after {} steps without reaching a breakpoint",
+                    steps
+                )?;
+                break;
+            }
+            let instruction = match self.machine.next_instruction() {
+                Ok(Some(instruction)) => instruction,
+                Ok(None) => break,
+                Err(e) => return writeln!(out, "error {}", e),
+            };
+            if !first && self.is_breakpoint(&instruction) {
+                writeln!(out, "breakpoint")?;
+                break;
+            }
+            first = false;
+            if let Err(e) = self.machine.step() {
+                return writeln!(out, "error {}", e);
+            }
+
+            let now = self.watched();
+            if now != watched {
+                for ((name, old), (_, new)) in watched.iter().zip(&now) {
+                    if old != new {
+                        writeln!(out, "{} changed from {} to {}", name, old, new)?;
+                    }
+                }
+                break;
+            }
+        }
+        self.show_position(out)
+    }
+
+    fn is_breakpoint(&self, instruction: &I::Instruction) -> bool {
+        let mnemonic = self.machine.isa().mnemonic(instruction);
+        self.breakpoints.iter().any(|b| match b {
+            Breakpoint::Address(ip) => *ip == self.machine.ip(),
+            Breakpoint::Opcode(op) => *op == mnemonic,
+        })
+    }
+
+    fn register(&self, name: &str) -> Option<String> {
+        self.machine
+            .isa()
+            .registers(self.machine.state())
+            .into_iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value)
+    }
+
+    fn watched(&self) -> Vec<(String, String)> {
+        self.watches
+            .iter()
+            .map(|name| (name.clone(), self.register(name).unwrap_or_default()))
+            .collect()
+    }
+
+    fn show_breakpoints<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        for breakpoint in &self.breakpoints {
+            match breakpoint {
+                Breakpoint::Address(ip) => writeln!(out, "break at address {}", ip)?,
+                Breakpoint::Opcode(op) => writeln!(out, "break on {}", op)?,
+            }
+        }
+        for name in &self.watches {
+            writeln!(out, "watch {}", name)?;
+        }
+        Ok(())
+    }
+
+    /// Decodes onwards from the instruction pointer.
+    fn list<W: Write>(&self, n: usize, out: &mut W) -> io::Result<()> {
+        self.list_from(self.machine.ip(), n, out)
+    }
+
+    fn list_from<W: Write>(&self, mut ip: usize, n: usize, out: &mut W) -> io::Result<()> {
+        let isa = self.machine.isa();
+        let code = self.machine.code();
+        for _ in 0..n {
+            if ip >= code.len() {
+                break;
+            }
+            match isa.decode(code, ip) {
+                Ok((instruction, width)) => {
+                    let text = isa.describe(&instruction);
+                    match isa.comment(&instruction) {
+                        Some(comment) => writeln!(out, "{:>5}: {:<12} ; {}", ip, text, comment)?,
+                        None => writeln!(out, "{:>5}: {}", ip, text)?,
+                    }
+                    ip += width;
+                }
+                Err(e) => return writeln!(out, "{:>5}: {}", ip, e),
+            }
+        }
+        Ok(())
+    }
+
+    /// The next instruction and the registers, or how the machine halted.
+    fn show_position<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        let machine = &self.machine;
+        let registers = machine
+            .isa()
+            .registers(machine.state())
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>()
+            .join(" ");
+        match machine.next_instruction() {
+            Ok(Some(instruction)) => writeln!(
+                out,
+                "[{}] {:>5}: {:<16} {}",
+                machine.steps(),
+                machine.ip(),
+                machine.isa().describe(&instruction),
+                registers
+            ),
+            Ok(None) => writeln!(out, "[{}] halted  {}", machine.steps(), registers),
+            Err(e) => writeln!(out, "[{}] error {}", machine.steps(), e),
+        }
+    }
+}
+
+/// The `main` of a day whose program runs on a [`Machine`]: as [`run_day`],
+/// except that `--debug <part>` steps through the machine `machine` builds
+/// for that part, taking commands from stdin.
+pub fn run_debuggable<S, I>(year: u32, day: u32, machine: fn(&S::Parsed, u32) -> Machine<I>)
+where
+    S: Solution,
+    I: Debuggable,
+{
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (input, part) = match day_args(&args) {
+        Ok(args) => match args.debug {
+            Some(part) => (args.input, part),
+            None => return run_day::<S>(year, day),
+        },
+        Err(_) => return run_day::<S>(year, day),
+    };
+
+    let debugged = debug::<S, I>(year, day, input, part, machine);
+    if let Err(e) = debugged {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
+
+fn debug<S, I>(
+    year: u32,
+    day: u32,
+    input: Option<&str>,
+    part: u32,
+    machine: fn(&S::Parsed, u32) -> Machine<I>,
+) -> Result<(), String>
+where
+    S: Solution,
+    I: Debuggable,
+{
+    if input == Some("-") {
+        return Err("--debug reads commands from stdin, so needs the input from a file".into());
+    }
+    let input = day_input(year, day, input)?;
+    let parsed = S::parse(&input).map_err(|e| format!("failed to parse input: {}", e))?;
+    let mut debugger = Debugger::new(machine(&parsed, part));
+    debugger
+        .repl(io::stdin().lock(), io::stdout())
+        .map_err(|e| format!("debugger failed: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Flow, Registers};
+
+    /// `add n` and `jnz n` on one register, `x`.
+    #[derive(Debug, Clone)]
+    struct Adder;
+
+    impl InstructionSet for Adder {
+        type Code = (&'static str, i64);
+        type Instruction = (&'static str, i64);
+        type State = Registers<i64, 1>;
+        type Undo = Registers<i64, 1>;
+
+        fn decode(
+            &self,
+            code: &[(&'static str, i64)],
+            ip: usize,
+        ) -> Result<((&'static str, i64), usize), String> {
+            Ok((code[ip], 1))
+        }
+
+        fn execute(
+            &self,
+            &(op, n): &(&'static str, i64),
+            registers: &mut Registers<i64, 1>,
+        ) -> Result<Flow, String> {
+            match op {
+                "add" => registers[0] += n,
+                "jnz" if registers[0] != 0 => return Ok(Flow::Jump(n)),
+                "jnz" => {}
+                _ => return Err(format!("bad op {}", op)),
+            }
+            Ok(Flow::Next)
+        }
+
+        fn save(
+            &self,
+            _instruction: &(&'static str, i64),
+            registers: &Registers<i64, 1>,
+        ) -> Registers<i64, 1> {
+            *registers
+        }
+
+        fn restore(&self, undo: Registers<i64, 1>, registers: &mut Registers<i64, 1>) {
+            *registers = undo;
+        }
+    }
+
+    impl Debuggable for Adder {
+        fn mnemonic(&self, instruction: &(&'static str, i64)) -> String {
+            instruction.0.to_string()
+        }
+
+        fn describe(&self, &(op, n): &(&'static str, i64)) -> String {
+            format!("{} {}", op, n)
+        }
+
+        fn registers(&self, registers: &Registers<i64, 1>) -> Vec<(String, String)> {
+            registers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect()
+        }
+    }
+
+    /// Counts `x` down from 3 to 0.
+    fn debugger() -> Debugger<Adder> {
+        let code = vec![("add", 3), ("add", -1), ("jnz", -1), ("add", 10)];
+        Debugger::new(Machine::new(Adder, code, Registers::new(["x"])))
+    }
+
+    fn run(debugger: &mut Debugger<Adder>, commands: &str) -> String {
+        let mut out = Vec::new();
+        for line in commands.lines() {
+            debugger.command(line, &mut out).unwrap();
+        }
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_step_and_back() {
+        let mut debugger = debugger();
+        let out = run(&mut debugger, "step 2\nback\nregisters");
+        assert_eq!(
+            "[2]     2: jnz -1           x=2\n[1]     1: add -1           x=3\nx = 3\n",
+            out
+        );
+        let out = run(&mut debugger, "bs 5");
+        assert!(out.starts_with("at the start of the recorded history\n[0]"));
+    }
+
+    #[test]
+    fn test_breakpoints() {
+        let mut debugger = debugger();
+        run(&mut debugger, "break jnz\nb 3");
+        assert_eq!(
+            "break on jnz\nbreak at address 3\n",
+            run(&mut debugger, "b")
+        );
+        assert!(run(&mut debugger, "c").contains("[2]     2: jnz"));
+        assert!(run(&mut debugger, "c").contains("[4]     2: jnz"));
+        run(&mut debugger, "delete jnz");
+        assert!(run(&mut debugger, "c").contains("[7]     3: add 10"));
+        assert!(run(&mut debugger, "c").contains("halted  x=10"));
+        assert!(run(&mut debugger, "d jnz").contains("no breakpoint"));
+    }
+
+    #[test]
+    fn test_step_limit() {
+        let code = vec![("add", 1), ("jnz", -1)];
+        let mut debugger =
+            Debugger::new(Machine::new(Adder, code, Registers::new(["x"]))).with_step_limit(100);
+        let out = run(&mut debugger, "c");
+        assert!(out.starts_with(
+            "stopped after 100 steps without reaching a breakpoint
+[100]"
+        ));
+        assert!(run(&mut debugger, "c").contains("[200]"));
+    }
+
+    #[test]
+    fn test_watch() {
+        let mut debugger = debugger();
+        assert!(run(&mut debugger, "watch y").contains("no register called y"));
+        let out = run(&mut debugger, "step\nwatch x\ncontinue");
+        assert!(out.contains("x changed from 3 to 2"));
+        assert_eq!(2, debugger.machine().steps());
+    }
+
+    #[test]
+    fn test_set() {
+        let mut debugger = debugger();
+        assert_eq!("no option called fast\n", run(&mut debugger, "set fast"));
+        assert_eq!("expected on or off\n", run(&mut debugger, "set fast maybe"));
+    }
+
+    #[test]
+    fn test_repl() {
+        let mut out = Vec::new();
+        debugger()
+            .repl("list 2\nfrobnicate\nquit\nstep\n".as_bytes(), &mut out)
+            .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("    0: add 3\n    1: add -1\n(debug) "));
+        assert!(out.contains("unknown command 'frobnicate'"));
+        assert!(!out.contains("[1]"));
+    }
+
+    #[test]
+    fn test_whole_program() {
+        let mut debugger = debugger();
+        let out = run(
+            &mut debugger,
+            "step 3
+dis
+dc",
+        );
+        assert!(out.ends_with(
+            "    0: add 3\n    1: add -1\n    2: jnz -1\n    3: add 10\nno decompiler for this instruction set\n"
+        ));
+    }
+}
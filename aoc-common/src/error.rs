@@ -0,0 +1,190 @@
+//! Errors for malformed puzzle input, pointing at where parsing went wrong.
+
+use std::error::Error;
+use std::fmt;
+
+use nom::IResult;
+
+/// How much of a long line to show either side of the error.
+const CONTEXT: usize = 30;
+
+/// A parse failure at a line and column of the input, both counted from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    /// The text of the offending line.
+    pub snippet: String,
+    pub message: String,
+}
+
+pub type ParseResult<T> = Result<T, ParseError>;
+
+impl ParseError {
+    /// An error at byte `offset` into `input`.
+    pub fn at(input: &str, offset: usize, message: impl Into<String>) -> ParseError {
+        let offset = offset.min(input.len());
+        let start = input[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |i| offset + i);
+        ParseError {
+            line: input[..start].matches('\n').count() + 1,
+            column: input[start..offset].chars().count() + 1,
+            snippet: input[start..end].to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// An error where `part` begins, `part` being a slice borrowed from `input`
+    /// such as the remainder a parser stopped at.
+    pub fn at_slice(input: &str, part: &str, message: impl Into<String>) -> ParseError {
+        let offset = (part.as_ptr() as usize)
+            .checked_sub(input.as_ptr() as usize)
+            .filter(|&offset| offset <= input.len())
+            .unwrap_or(input.len());
+        ParseError::at(input, offset, message)
+    }
+
+    /// An error at `column` (counted in characters from 0) of the line with
+    /// index `line`.
+    pub fn at_line(
+        input: &str,
+        line: usize,
+        column: usize,
+        message: impl Into<String>,
+    ) -> ParseError {
+        let start: usize = input.split_inclusive('\n').take(line).map(str::len).sum();
+        let offset = input[start..]
+            .char_indices()
+            .nth(column)
+            .map_or(input.len(), |(i, _)| start + i);
+        ParseError::at(input, offset, message)
+    }
+
+    /// Converts a failure from a nom parser that was given `input`.
+    pub fn from_nom(input: &str, err: nom::Err<nom::error::Error<&str>>) -> ParseError {
+        match err {
+            nom::Err::Incomplete(_) => {
+                ParseError::at(input, input.len(), "unexpected end of input")
+            }
+            nom::Err::Error(e) | nom::Err::Failure(e) if e.input.is_empty() => {
+                ParseError::at(input, input.len(), "unexpected end of input")
+            }
+            nom::Err::Error(e) | nom::Err::Failure(e) => ParseError::at_slice(
+                input,
+                e.input,
+                format!("unexpected input ({} failed)", e.code.description()),
+            ),
+        }
+    }
+}
+
+/// Takes the value from a nom parser run over `input`, turning a failure into a
+/// [`ParseError`]. Anything left unparsed other than trailing whitespace is an
+/// error too.
+pub fn finish<'a, T>(input: &'a str, result: IResult<&'a str, T>) -> ParseResult<T> {
+    let (rest, value) = result.map_err(|e| ParseError::from_nom(input, e))?;
+    let rest = rest.trim_start();
+    if !rest.is_empty() {
+        return Err(ParseError::at_slice(
+            input,
+            rest,
+            "unexpected trailing input",
+        ));
+    }
+    Ok(value)
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )?;
+
+        // Only show the part of a long line around the error.
+        let skip = (self.column - 1).saturating_sub(CONTEXT);
+        let snippet: String = self.snippet.chars().skip(skip).take(CONTEXT * 2).collect();
+        let ellipsis = if skip > 0 { "..." } else { "" };
+        writeln!(f, "    {}{}", ellipsis, snippet)?;
+        write!(
+            f,
+            "    {}^",
+            " ".repeat(ellipsis.len() + self.column - 1 - skip)
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nom::bytes::complete::tag;
+    use nom::character::complete::digit1;
+    use nom::sequence::preceded;
+
+    #[test]
+    fn test_at() {
+        let input = "Register A: 729\nRegister B: x\n";
+        let err = ParseError::at(input, 28, "expected a number");
+        assert_eq!((2, 13), (err.line, err.column));
+        assert_eq!("Register B: x", err.snippet);
+        assert_eq!(
+            "line 2, column 13: expected a number\n    Register B: x\n                ^",
+            err.to_string()
+        );
+        assert_eq!(err, ParseError::at_line(input, 1, 12, "expected a number"));
+        assert_eq!(
+            err,
+            ParseError::at_slice(input, &input[28..], "expected a number")
+        );
+    }
+
+    #[test]
+    fn test_at_end() {
+        let err = ParseError::at("ab\ncd", 5, "truncated");
+        assert_eq!((2, 3, "cd"), (err.line, err.column, err.snippet.as_str()));
+        let err = ParseError::at_line("ab\n", 3, 0, "truncated");
+        assert_eq!((2, 1, ""), (err.line, err.column, err.snippet.as_str()));
+    }
+
+    #[test]
+    fn test_long_line() {
+        let input = format!("{}x{}", ".".repeat(100), ".".repeat(100));
+        let err = ParseError::at(&input, 100, "bad pixel");
+        let shown = err.to_string();
+        let lines: Vec<&str> = shown.lines().collect();
+        assert_eq!(lines[1].find('x'), lines[2].find('^'));
+        assert!(lines[1].starts_with("    ..."));
+    }
+
+    #[test]
+    fn test_finish() {
+        let input = "Register A: x";
+        let parse = |i| preceded(tag("Register A: "), digit1)(i);
+        assert_eq!(Ok("12"), finish("Register A: 12", parse("Register A: 12")));
+
+        let err = finish(input, parse(input)).unwrap_err();
+        assert_eq!((1, 13), (err.line, err.column));
+
+        let err = finish("Register A: ", parse("Register A: ")).unwrap_err();
+        assert_eq!("unexpected end of input", err.message);
+    }
+
+    #[test]
+    fn test_finish_leftovers() {
+        let parse = |i| preceded(tag("Register A: "), digit1)(i);
+        assert_eq!(
+            Ok("12"),
+            finish("Register A: 12\n\n", parse("Register A: 12\n\n"))
+        );
+
+        let input = "Register A: 12\njunk\n";
+        let err = finish(input, parse(input)).unwrap_err();
+        assert_eq!((2, 1), (err.line, err.column));
+        assert_eq!("unexpected trailing input", err.message);
+    }
+}
@@ -0,0 +1,439 @@
+//! A rectangular grid of cells, the shape most puzzle inputs come in.
+
+use std::fmt;
+use std::iter;
+use std::ops::{Index, IndexMut};
+
+use crate::{lines, Coordinate, Direction, ParseError, ParseResult, Point};
+
+/// A cell's position as `(x, y)`, with `(0, 0)` in the top left.
+pub type Pos = (usize, usize);
+
+/// Up, right, down, left.
+pub const NEIGHBOURS_4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Every neighbour including diagonals, row by row.
+pub const NEIGHBOURS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from its cells in row order.
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Grid<T> {
+        assert_eq!(
+            width * height,
+            cells.len(),
+            "a {}x{} grid needs {} cells",
+            width,
+            height,
+            width * height
+        );
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn from_fn<F>(width: usize, height: usize, f: F) -> Grid<T>
+    where
+        F: FnMut(Pos) -> T,
+    {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(f)
+            .collect();
+        Grid::new(width, height, cells)
+    }
+
+    /// Builds a grid from rows, which must all be the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Grid<T> {
+        let width = rows.first().map_or(0, Vec::len);
+        assert!(
+            rows.iter().all(|r| r.len() == width),
+            "every row must have {} cells",
+            width
+        );
+        let height = rows.len();
+        Grid::new(width, height, rows.into_iter().flatten().collect())
+    }
+
+    /// Parses a block of text, mapping each character to a cell.
+    pub fn parse<F>(input: &str, f: F) -> ParseResult<Grid<T>>
+    where
+        F: Fn(char) -> T,
+    {
+        Grid::try_parse(input, |c| Some(f(c)))
+    }
+
+    /// Parses a block of text, failing at the first character `f` rejects or
+    /// at a row whose length differs from the first.
+    pub fn try_parse<F>(input: &str, f: F) -> ParseResult<Grid<T>>
+    where
+        F: Fn(char) -> Option<T>,
+    {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::new();
+        for (y, line) in lines(input).enumerate() {
+            let mut len = 0;
+            for (x, c) in line.chars().enumerate() {
+                let cell = f(c).ok_or_else(|| {
+                    ParseError::at_line(input, y, x, format!("unexpected character '{}'", c))
+                })?;
+                cells.push(cell);
+                len += 1;
+            }
+            let width = *width.get_or_insert(len);
+            if len != width {
+                return Err(ParseError::at_line(
+                    input,
+                    y,
+                    len.min(width),
+                    format!("expected {} cells in the row, found {}", width, len),
+                ));
+            }
+            height += 1;
+        }
+        Ok(Grid::new(width.unwrap_or(0), height, cells))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (x, y): Pos) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.index_of(pos).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.index_of(pos).map(move |i| &mut self.cells[i])
+    }
+
+    /// Replaces the cell at `pos`, returning false if it is off the grid.
+    pub fn set(&mut self, pos: Pos, value: T) -> bool {
+        match self.get_mut(pos) {
+            Some(cell) => {
+                *cell = value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn index_of(&self, pos: Pos) -> Option<usize> {
+        self.contains(pos).then(|| pos.1 * self.width + pos.0)
+    }
+
+    /// The position `(dx, dy)` away from `pos`, if it is on the grid.
+    pub fn offset(&self, (x, y): Pos, (dx, dy): (isize, isize)) -> Option<Pos> {
+        let pos = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+        self.contains(pos).then_some(pos)
+    }
+
+    /// The neighbouring position in `direction`, if it is on the grid.
+    pub fn step(&self, pos: Pos, direction: Direction) -> Option<Pos> {
+        let Point { x, y } = direction.offset();
+        self.offset(pos, (x, y))
+    }
+
+    /// The position of `point`, if it is on the grid.
+    pub fn pos_of<C: Coordinate>(&self, point: Point<C>) -> Option<Pos> {
+        point.to_pos().filter(|&pos| self.contains(pos))
+    }
+
+    /// The up to four cells sharing an edge with `pos`.
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        NEIGHBOURS_4
+            .iter()
+            .filter_map(move |&d| self.offset(pos, d))
+    }
+
+    /// The up to eight cells touching `pos`, including diagonally.
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        NEIGHBOURS_8
+            .iter()
+            .filter_map(move |&d| self.offset(pos, d))
+    }
+
+    /// Every position from `pos` (not included) stepping by `step` until the
+    /// edge of the grid.
+    pub fn ray(&self, pos: Pos, step: (isize, isize)) -> impl Iterator<Item = Pos> + '_ {
+        iter::successors(self.offset(pos, step), move |&p| self.offset(p, step))
+    }
+
+    /// Every position in row order.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// Every cell with its position, in row order.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    /// The position of the first cell, in row order, matching `f`.
+    pub fn find<F>(&self, f: F) -> Option<Pos>
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.iter().find(|(_, cell)| f(cell)).map(|(pos, _)| pos)
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(move |y| self.row(y))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {} is off the grid", x);
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    /// Each diagonal running down and to the right, starting from the bottom
+    /// left corner.
+    pub fn diagonals(&self) -> impl Iterator<Item = Vec<Pos>> + '_ {
+        let starts = (0..self.height)
+            .rev()
+            .map(|y| (0, y))
+            .chain((1..self.width).map(|x| (x, 0)));
+        starts.map(move |start| self.line_from(start, (1, 1)))
+    }
+
+    /// Each diagonal running down and to the left, starting from the top left
+    /// corner.
+    pub fn anti_diagonals(&self) -> impl Iterator<Item = Vec<Pos>> + '_ {
+        let last = self.width.saturating_sub(1);
+        let starts = (0..self.width)
+            .map(|x| (x, 0))
+            .chain((1..self.height).map(move |y| (last, y)));
+        starts.map(move |start| self.line_from(start, (-1, 1)))
+    }
+
+    fn line_from(&self, start: Pos, step: (isize, isize)) -> Vec<Pos> {
+        iter::once(start).chain(self.ray(start, step)).collect()
+    }
+
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: Fn(&T) -> U,
+    {
+        Grid::new(self.width, self.height, self.cells.iter().map(f).collect())
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn filled(width: usize, height: usize, value: T) -> Grid<T> {
+        Grid::new(width, height, vec![value; width * height])
+    }
+
+    /// Swaps rows and columns.
+    pub fn transpose(&self) -> Grid<T> {
+        Grid::from_fn(self.height, self.width, |(x, y)| self[(y, x)].clone())
+    }
+
+    pub fn rotate_clockwise(&self) -> Grid<T> {
+        Grid::from_fn(self.height, self.width, |(x, y)| {
+            self[(y, self.height - 1 - x)].clone()
+        })
+    }
+
+    pub fn rotate_anticlockwise(&self) -> Grid<T> {
+        Grid::from_fn(self.height, self.width, |(x, y)| {
+            self[(self.width - 1 - y, x)].clone()
+        })
+    }
+
+    /// Mirrors left to right.
+    pub fn flip_horizontal(&self) -> Grid<T> {
+        Grid::from_fn(self.width, self.height, |(x, y)| {
+            self[(self.width - 1 - x, y)].clone()
+        })
+    }
+
+    /// Mirrors top to bottom.
+    pub fn flip_vertical(&self) -> Grid<T> {
+        Grid::from_fn(self.width, self.height, |(x, y)| {
+            self[(x, self.height - 1 - y)].clone()
+        })
+    }
+}
+
+impl Grid<u8> {
+    /// Parses a grid of single digits such as `"123\n456"`.
+    pub fn digits(input: &str) -> ParseResult<Grid<u8>> {
+        Grid::try_parse(input, |c| c.to_digit(10).map(|d| d as u8))
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{:?} is off the {}x{} grid", pos, self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{:?} is off the {}x{} grid", pos, width, height))
+    }
+}
+
+/// Prints each row on its own line with no separator between cells.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Grid<char> {
+        Grid::parse("abc\ndef\n", |c| c).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = sample();
+        assert_eq!((3, 2), (grid.width(), grid.height()));
+        assert_eq!(Some(&'f'), grid.get((2, 1)));
+        assert_eq!(None, grid.get((3, 0)));
+        assert_eq!("abc\ndef", grid.to_string());
+
+        assert_eq!(
+            vec![1, 2, 3, 4],
+            Grid::digits("12\r\n34\n")
+                .unwrap()
+                .cells()
+                .copied()
+                .collect::<Vec<_>>()
+        );
+        let err = Grid::digits("12\n3x\n").unwrap_err();
+        assert_eq!((2, 2), (err.line, err.column));
+        let err = Grid::parse("abc\nde\n", |c| c).unwrap_err();
+        assert_eq!((2, 3), (err.line, err.column));
+    }
+
+    #[test]
+    fn test_set() {
+        let mut grid = sample();
+        assert!(grid.set((0, 1), 'x'));
+        assert!(!grid.set((0, 2), 'x'));
+        grid[(1, 1)] = 'y';
+        assert_eq!("abc\nxyf", grid.to_string());
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = sample();
+        assert_eq!(
+            vec![(1, 0), (0, 1)],
+            grid.neighbours4((0, 0)).collect::<Vec<_>>()
+        );
+        assert_eq!(5, grid.neighbours8((1, 0)).count());
+        assert_eq!(
+            vec![(1, 1), (0, 1)],
+            grid.ray((2, 1), (-1, 0)).collect::<Vec<_>>()
+        );
+        assert_eq!(None, grid.offset((0, 0), (-1, 0)));
+        assert_eq!(Some((0, 1)), grid.step((0, 0), Direction::South));
+        assert_eq!(None, grid.step((0, 0), Direction::West));
+        assert_eq!(Some((1, 1)), grid.pos_of(Point::new(1, 1)));
+        assert_eq!(None, grid.pos_of(Point::new(-1, 1)));
+    }
+
+    #[test]
+    fn test_lines() {
+        let grid = sample();
+        assert_eq!(&['d', 'e', 'f'], grid.row(1));
+        assert_eq!(vec!['b', 'e'], grid.column(1).copied().collect::<Vec<_>>());
+        assert_eq!(3, grid.columns().count());
+        let text = |lines: Vec<Vec<Pos>>| {
+            lines
+                .iter()
+                .map(|l| l.iter().map(|&p| grid[p]).collect::<String>())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(vec!["d", "ae", "bf", "c"], text(grid.diagonals().collect()));
+        assert_eq!(
+            vec!["a", "bd", "ce", "f"],
+            text(grid.anti_diagonals().collect())
+        );
+    }
+
+    #[test]
+    fn test_transforms() {
+        let grid = sample();
+        assert_eq!("ad\nbe\ncf", grid.transpose().to_string());
+        assert_eq!("da\neb\nfc", grid.rotate_clockwise().to_string());
+        assert_eq!("cf\nbe\nad", grid.rotate_anticlockwise().to_string());
+        assert_eq!("cba\nfed", grid.flip_horizontal().to_string());
+        assert_eq!("def\nabc", grid.flip_vertical().to_string());
+        assert_eq!(grid, grid.rotate_clockwise().rotate_anticlockwise());
+
+        let empty: Grid<char> = Grid::new(0, 2, vec![]);
+        let rotated = empty.rotate_clockwise();
+        assert_eq!((2, 0), (rotated.width(), rotated.height()));
+        assert_eq!(empty, empty.rotate_anticlockwise().rotate_clockwise());
+        assert_eq!(empty, empty.flip_horizontal().flip_vertical());
+    }
+
+    #[test]
+    fn test_find_and_map() {
+        let grid = sample();
+        assert_eq!(Some((1, 1)), grid.find(|&c| c == 'e'));
+        assert_eq!(None, grid.find(|&c| c == 'z'));
+        assert_eq!(
+            Grid::from_rows(vec![vec![true, false, false], vec![false; 3]]),
+            grid.map(|&c| c == 'a')
+        );
+    }
+}
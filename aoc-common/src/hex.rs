@@ -0,0 +1,198 @@
+//! Coordinates on a grid of pointy-topped hexagons, where each tile has
+//! neighbours to the east and west and on the four diagonals.
+
+use std::fmt;
+use std::ops::{Add, Mul};
+use std::str::FromStr;
+
+use crate::{ParseError, ParseResult};
+
+/// A tile in axial coordinates: `q` grows to the east and `r` to the south
+/// east. The third cube coordinate is `-q - r`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct HexCoord {
+    pub q: i64,
+    pub r: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HexDirection {
+    East,
+    NorthEast,
+    NorthWest,
+    West,
+    SouthWest,
+    SouthEast,
+}
+
+impl HexDirection {
+    /// Every direction, anticlockwise from east.
+    pub const ALL: [HexDirection; 6] = [
+        HexDirection::East,
+        HexDirection::NorthEast,
+        HexDirection::NorthWest,
+        HexDirection::West,
+        HexDirection::SouthWest,
+        HexDirection::SouthEast,
+    ];
+
+    /// The step to the neighbouring tile this way.
+    pub fn offset(self) -> HexCoord {
+        let (q, r) = match self {
+            HexDirection::East => (1, 0),
+            HexDirection::NorthEast => (1, -1),
+            HexDirection::NorthWest => (0, -1),
+            HexDirection::West => (-1, 0),
+            HexDirection::SouthWest => (-1, 1),
+            HexDirection::SouthEast => (0, 1),
+        };
+        HexCoord::new(q, r)
+    }
+
+    /// Parses an unseparated run of directions such as `"esenee"`.
+    pub fn parse_steps(text: &str) -> ParseResult<Vec<HexDirection>> {
+        let mut steps = Vec::new();
+        let mut offset = 0;
+        while offset < text.len() {
+            let len = if text[offset..].starts_with(['n', 's']) {
+                2
+            } else {
+                1
+            };
+            let step = text
+                .get(offset..offset + len)
+                .and_then(|s| s.parse().ok())
+                .ok_or_else(|| ParseError::at(text, offset, "expected a hex direction"))?;
+            steps.push(step);
+            offset += len;
+        }
+        Ok(steps)
+    }
+}
+
+impl FromStr for HexDirection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<HexDirection, String> {
+        match s {
+            "e" => Ok(HexDirection::East),
+            "ne" => Ok(HexDirection::NorthEast),
+            "nw" => Ok(HexDirection::NorthWest),
+            "w" => Ok(HexDirection::West),
+            "sw" => Ok(HexDirection::SouthWest),
+            "se" => Ok(HexDirection::SouthEast),
+            _ => Err(format!("unknown hex direction '{}'", s)),
+        }
+    }
+}
+
+impl HexCoord {
+    pub const ORIGIN: HexCoord = HexCoord { q: 0, r: 0 };
+
+    pub fn new(q: i64, r: i64) -> HexCoord {
+        HexCoord { q, r }
+    }
+
+    /// The cube coordinates `(q, r, s)`, which always sum to zero.
+    pub fn cube(self) -> (i64, i64, i64) {
+        (self.q, self.r, -self.q - self.r)
+    }
+
+    pub fn step(self, direction: HexDirection) -> HexCoord {
+        self + direction.offset()
+    }
+
+    /// Where following every step in turn ends up.
+    pub fn walk<I: IntoIterator<Item = HexDirection>>(self, steps: I) -> HexCoord {
+        steps.into_iter().fold(self, HexCoord::step)
+    }
+
+    pub fn neighbours(self) -> [HexCoord; 6] {
+        HexDirection::ALL.map(|d| self.step(d))
+    }
+
+    /// The fewest steps between the two tiles.
+    pub fn distance(self, other: HexCoord) -> i64 {
+        let (q, r, s) = (other + self * -1).cube();
+        (q.abs() + r.abs() + s.abs()) / 2
+    }
+
+    /// Every tile exactly `radius` steps away, going anticlockwise.
+    pub fn ring(self, radius: i64) -> Vec<HexCoord> {
+        if radius == 0 {
+            return vec![self];
+        }
+        let mut tile = self + HexDirection::SouthWest.offset() * radius;
+        let mut ring = Vec::new();
+        for direction in HexDirection::ALL {
+            for _ in 0..radius {
+                ring.push(tile);
+                tile = tile.step(direction);
+            }
+        }
+        ring
+    }
+}
+
+impl Add for HexCoord {
+    type Output = HexCoord;
+
+    fn add(self, other: HexCoord) -> HexCoord {
+        HexCoord::new(self.q + other.q, self.r + other.r)
+    }
+}
+
+impl Mul<i64> for HexCoord {
+    type Output = HexCoord;
+
+    fn mul(self, n: i64) -> HexCoord {
+        HexCoord::new(self.q * n, self.r * n)
+    }
+}
+
+impl fmt::Display for HexCoord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}, {})", self.q, self.r)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use HexDirection::*;
+
+    #[test]
+    fn test_parse_steps() {
+        assert_eq!(
+            vec![East, SouthEast, NorthEast, East],
+            HexDirection::parse_steps("esenee").unwrap()
+        );
+        let err = HexDirection::parse_steps("enx").unwrap_err();
+        assert_eq!(2, err.column);
+        assert!(HexDirection::parse_steps("es").is_err());
+    }
+
+    #[test]
+    fn test_walk() {
+        assert_eq!(
+            HexCoord::ORIGIN,
+            HexCoord::ORIGIN.walk(HexDirection::parse_steps("nwwswee").unwrap())
+        );
+        assert_eq!(
+            HexCoord::new(0, 1),
+            HexCoord::ORIGIN.walk(vec![East, SouthEast, West])
+        );
+    }
+
+    #[test]
+    fn test_distance_and_rings() {
+        let origin = HexCoord::ORIGIN;
+        assert_eq!(3, origin.distance(HexCoord::new(3, -3)));
+        assert_eq!(2, HexCoord::new(-1, 2).distance(HexCoord::new(1, 1)));
+        assert_eq!(vec![origin], origin.ring(0));
+        assert_eq!(6, origin.neighbours().len());
+        let ring = origin.ring(3);
+        assert_eq!(18, ring.len());
+        assert!(ring.iter().all(|t| origin.distance(*t) == 3));
+    }
+}
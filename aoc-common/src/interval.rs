@@ -0,0 +1,309 @@
+//! Sets of integers kept as sorted ranges, and maps that shift whole ranges at
+//! once, for puzzles whose numbers are too many to handle one at a time.
+
+use std::ops::Range;
+
+/// A set of integers stored as sorted, disjoint half-open ranges. Ranges that
+/// overlap or touch are coalesced, so two sets holding the same numbers are
+/// always equal.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct IntervalSet {
+    ranges: Vec<Range<i64>>,
+}
+
+impl IntervalSet {
+    pub fn new() -> IntervalSet {
+        IntervalSet::default()
+    }
+
+    /// The set `start..=end`, as puzzles usually write their ranges.
+    pub fn inclusive(start: i64, end: i64) -> IntervalSet {
+        IntervalSet::from(start..end + 1)
+    }
+
+    pub fn ranges(&self) -> &[Range<i64>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// How many integers the set holds.
+    pub fn len(&self) -> u64 {
+        self.ranges.iter().map(|r| (r.end - r.start) as u64).sum()
+    }
+
+    pub fn min(&self) -> Option<i64> {
+        self.ranges.first().map(|r| r.start)
+    }
+
+    pub fn max(&self) -> Option<i64> {
+        self.ranges.last().map(|r| r.end - 1)
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        let i = self.ranges.partition_point(|r| r.end <= value);
+        self.ranges.get(i).is_some_and(|r| r.start <= value)
+    }
+
+    pub fn insert(&mut self, range: Range<i64>) {
+        if range.is_empty() {
+            return;
+        }
+        // Every range touching the new one is merged into it.
+        let first = self.ranges.partition_point(|r| r.end < range.start);
+        let last = self.ranges.partition_point(|r| r.start <= range.end);
+        let merged = self.ranges[first..last]
+            .iter()
+            .fold(range, |acc, r| acc.start.min(r.start)..acc.end.max(r.end));
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        let mut result = self.clone();
+        for r in &other.ranges {
+            result.insert(r.clone());
+        }
+        result
+    }
+
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (&self.ranges[i], &other.ranges[j]);
+            let overlap = a.start.max(b.start)..a.end.min(b.end);
+            if !overlap.is_empty() {
+                ranges.push(overlap);
+            }
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        IntervalSet { ranges }
+    }
+
+    /// The numbers in this set but not in `other`.
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        let mut ranges = Vec::new();
+        let mut j = 0;
+        for r in &self.ranges {
+            let mut start = r.start;
+            while j < other.ranges.len() && other.ranges[j].end <= start {
+                j += 1;
+            }
+            let mut k = j;
+            while k < other.ranges.len() && other.ranges[k].start < r.end {
+                if other.ranges[k].start > start {
+                    ranges.push(start..other.ranges[k].start);
+                }
+                start = start.max(other.ranges[k].end);
+                k += 1;
+            }
+            if start < r.end {
+                ranges.push(start..r.end);
+            }
+        }
+        IntervalSet { ranges }
+    }
+
+    pub fn overlaps(&self, other: &IntervalSet) -> bool {
+        !self.intersection(other).is_empty()
+    }
+
+    /// Whether every number in `other` is also in this set.
+    pub fn is_superset(&self, other: &IntervalSet) -> bool {
+        other.difference(self).is_empty()
+    }
+}
+
+impl From<Range<i64>> for IntervalSet {
+    fn from(range: Range<i64>) -> IntervalSet {
+        let mut set = IntervalSet::new();
+        set.insert(range);
+        set
+    }
+}
+
+impl FromIterator<Range<i64>> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Range<i64>>>(iter: I) -> IntervalSet {
+        let mut set = IntervalSet::new();
+        for range in iter {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+/// A function on integers that shifts each of a number of disjoint source
+/// ranges by its own offset, leaving every other number where it is.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PiecewiseMap {
+    /// Sorted by start, never overlapping, never with a zero offset.
+    pieces: Vec<(Range<i64>, i64)>,
+}
+
+impl PiecewiseMap {
+    pub fn new() -> PiecewiseMap {
+        PiecewiseMap::default()
+    }
+
+    /// Maps `source` onto the range of the same length starting at `target`.
+    /// Panics if `source` overlaps a range already in the map.
+    pub fn insert(&mut self, source: Range<i64>, target: i64) {
+        if let Err(existing) = self.try_insert(source.clone(), target) {
+            panic!("{:?} overlaps {:?} already in the map", source, existing);
+        }
+    }
+
+    /// Like [`PiecewiseMap::insert`], but returns the range already in the
+    /// map that `source` overlaps instead of panicking, leaving the map as
+    /// it was.
+    pub fn try_insert(&mut self, source: Range<i64>, target: i64) -> Result<(), Range<i64>> {
+        let offset = target - source.start;
+        let i = self.pieces.partition_point(|(r, _)| r.end <= source.start);
+        if let Some((r, _)) = self.pieces.get(i) {
+            if r.start < source.end {
+                return Err(r.clone());
+            }
+        }
+        if !source.is_empty() && offset != 0 {
+            self.pieces.insert(i, (source, offset));
+        }
+        Ok(())
+    }
+
+    pub fn apply(&self, value: i64) -> i64 {
+        let i = self.pieces.partition_point(|(r, _)| r.end <= value);
+        match self.pieces.get(i) {
+            Some((r, offset)) if r.start <= value => value + offset,
+            _ => value,
+        }
+    }
+
+    /// Where every number in `set` ends up.
+    pub fn apply_set(&self, set: &IntervalSet) -> IntervalSet {
+        self.segments()
+            .iter()
+            .flat_map(|(source, offset)| {
+                set.intersection(&IntervalSet::from(source.clone()))
+                    .ranges
+                    .into_iter()
+                    .map(move |r| shift(&r, *offset))
+            })
+            .collect()
+    }
+
+    /// The map applying this one and then `next`.
+    pub fn then(&self, next: &PiecewiseMap) -> PiecewiseMap {
+        let mut pieces: Vec<(Range<i64>, i64)> = Vec::new();
+        let later = next.segments();
+        for (source, offset) in self.segments() {
+            let image = shift(&source, offset);
+            for (next_source, next_offset) in &later {
+                let overlap = image.start.max(next_source.start)..image.end.min(next_source.end);
+                if overlap.is_empty() {
+                    continue;
+                }
+                let piece = (shift(&overlap, -offset), offset + next_offset);
+                match pieces.last_mut() {
+                    Some((r, o)) if r.end == piece.0.start && *o == piece.1 => r.end = piece.0.end,
+                    _ => pieces.push(piece),
+                }
+            }
+        }
+        pieces.retain(|(_, offset)| *offset != 0);
+        PiecewiseMap { pieces }
+    }
+
+    /// The pieces plus the unmoved gaps between them, covering every `i64`
+    /// bar `i64::MAX`.
+    fn segments(&self) -> Vec<(Range<i64>, i64)> {
+        let mut segments = Vec::new();
+        let mut next = i64::MIN;
+        for (r, offset) in &self.pieces {
+            if next < r.start {
+                segments.push((next..r.start, 0));
+            }
+            segments.push((r.clone(), *offset));
+            next = r.end;
+        }
+        if next < i64::MAX {
+            segments.push((next..i64::MAX, 0));
+        }
+        segments
+    }
+}
+
+fn shift(range: &Range<i64>, offset: i64) -> Range<i64> {
+    range.start + offset..range.end + offset
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(ranges: &[Range<i64>]) -> IntervalSet {
+        ranges.iter().cloned().collect()
+    }
+
+    #[test]
+    fn test_coalescing() {
+        let s = set(&[5..8, 0..2, 2..3, 7..10, 20..20]);
+        assert_eq!(&[0..3, 5..10], s.ranges());
+        assert_eq!(8, s.len());
+        assert!(s.contains(9) && !s.contains(3) && !s.contains(10));
+        assert_eq!((Some(0), Some(9)), (s.min(), s.max()));
+        assert_eq!(IntervalSet::from(2..5), IntervalSet::inclusive(2, 4));
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = set(&[0..10, 20..30]);
+        let b = set(&[5..25, 40..50]);
+        assert_eq!(set(&[0..30, 40..50]), a.union(&b));
+        assert_eq!(set(&[5..10, 20..25]), a.intersection(&b));
+        assert_eq!(set(&[0..5, 25..30]), a.difference(&b));
+        assert_eq!(set(&[10..20, 40..50]), b.difference(&a));
+        assert!(a.overlaps(&b));
+        assert!(a.is_superset(&set(&[1..3, 22..30])));
+        assert!(!a.is_superset(&b));
+    }
+
+    #[test]
+    fn test_piecewise_map() {
+        let mut m = PiecewiseMap::new();
+        m.insert(98..100, 50);
+        m.insert(50..98, 52);
+        assert_eq!(81, m.apply(79));
+        assert_eq!(51, m.apply(99));
+        assert_eq!(10, m.apply(10));
+        assert_eq!(
+            set(&[45..51, 99..100]),
+            m.apply_set(&set(&[45..50, 97..99]))
+        );
+
+        let before = m.clone();
+        assert_eq!(Err(50..98), m.try_insert(90..99, 0));
+        assert_eq!(before, m);
+        assert_eq!(Ok(()), m.try_insert(0..50, 1));
+    }
+
+    #[test]
+    fn test_then() {
+        let mut first = PiecewiseMap::new();
+        first.insert(0..10, 100);
+        let mut second = PiecewiseMap::new();
+        second.insert(105..200, 5);
+        second.insert(0..5, 10);
+
+        let both = first.then(&second);
+        for x in -5..220 {
+            assert_eq!(second.apply(first.apply(x)), both.apply(x), "at {}", x);
+        }
+        assert_eq!(first, first.then(&PiecewiseMap::new()));
+    }
+}
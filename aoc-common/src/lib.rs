@@ -0,0 +1,196 @@
+//! Everything shared by every year's solutions: finding and splitting the
+//! input, the `Solution` trait with its typed answers and parse errors, and
+//! the building blocks puzzles keep reusing — grids, points and hex
+//! coordinates, path searches, cellular automata, interval maps, bit sets,
+//! cycle detection, memoization, number theory, and an instruction VM with a
+//! step debugger.
+//!
+//! Puzzle inputs are normalised on the way in (Windows line endings become
+//! `\n`, trailing blank lines are dropped) so individual days never need to
+//! care where the file came from.
+
+mod automaton;
+mod bitset;
+mod cycle;
+mod debugger;
+mod error;
+mod grid;
+mod hex;
+mod interval;
+mod maths;
+mod memo;
+mod output;
+mod point;
+mod search;
+mod solution;
+mod source;
+mod vm;
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+pub use automaton::{Automaton, Hex, LineOfSight, Moore, Neighbourhood, Rule, VonNeumann};
+pub use bitset::BitSet;
+pub use cycle::{brent, find_cycle, floyd, Cycle, History};
+pub use debugger::{run_debuggable, Breakpoint, Debuggable, Debugger};
+pub use error::{finish, ParseError, ParseResult};
+pub use grid::{Grid, Pos, NEIGHBOURS_4, NEIGHBOURS_8};
+pub use hex::{HexCoord, HexDirection};
+pub use interval::{IntervalSet, PiecewiseMap};
+pub use maths::{
+    binomial, crt, extended_gcd, gcd, lcm, lcm_all, mod_inverse, mod_mul, mod_pow, CrtError,
+};
+pub use memo::{memoize, CacheStats, Memo, Memoized};
+pub use output::{Format, Record};
+pub use point::{Coordinate, Direction, Point};
+pub use search::{astar, bfs, dijkstra, shortest_path, Found, Search};
+pub use solution::{run_day, solve, Answer, PartResult, Solution, Solved, Unsolved};
+pub use source::{day_args, day_input, DayArgs, Source, INPUT_DIR_VAR};
+pub use vm::{Exit, Flow, InstructionSet, Machine, Registers, Traced, VmError};
+
+/// Reads a puzzle input from disk, normalising line endings.
+pub fn read_input<P: AsRef<Path>>(path: P) -> io::Result<String> {
+    fs::read_to_string(path).map(|s| normalise(&s))
+}
+
+/// Converts CRLF line endings to LF and strips trailing blank lines, leaving a
+/// single final newline if the input had any content.
+pub fn normalise(input: &str) -> String {
+    let trimmed = input.replace("\r\n", "\n");
+    let trimmed = trimmed.trim_end_matches(['\n', '\r']);
+    if trimmed.is_empty() {
+        String::new()
+    } else {
+        format!("{}\n", trimmed)
+    }
+}
+
+/// Splits the input into lines, ignoring carriage returns and any blank lines
+/// at the end of the input.
+pub fn lines(input: &str) -> std::vec::IntoIter<&str> {
+    let mut lines: Vec<&str> = input
+        .lines()
+        .map(|l| l.strip_suffix('\r').unwrap_or(l))
+        .collect();
+    while lines.last().is_some_and(|l| l.trim().is_empty()) {
+        lines.pop();
+    }
+    lines.into_iter()
+}
+
+/// As [`lines`], but owning each line for solutions that work with `String`s.
+pub fn owned_lines(input: &str) -> Vec<String> {
+    lines(input).map(String::from).collect()
+}
+
+/// Splits the input into blank-line separated groups of lines.
+pub fn paragraphs(input: &str) -> Vec<Vec<&str>> {
+    let mut groups = Vec::new();
+    let mut current = Vec::new();
+    for line in lines(input) {
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                groups.push(current);
+                current = Vec::new();
+            }
+        } else {
+            current.push(line);
+        }
+    }
+    if !current.is_empty() {
+        groups.push(current);
+    }
+    groups
+}
+
+/// Extracts every integer in the text, in order, regardless of what separates
+/// them. A `-` directly before a number is treated as its sign. A number that
+/// doesn't fit in `T` is reported where it starts.
+pub fn numbers<T>(input: &str) -> ParseResult<Vec<T>>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    let mut numbers = Vec::new();
+    let mut start = None;
+    for (i, c) in input.char_indices() {
+        let in_number = c.is_ascii_digit()
+            || (c == '-'
+                && start.is_none()
+                && input[i + 1..].starts_with(|n: char| n.is_ascii_digit()));
+        match (in_number, start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                numbers.push(parse_number(input, s, &input[s..i])?);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        numbers.push(parse_number(input, s, &input[s..])?);
+    }
+    Ok(numbers)
+}
+
+/// Parses `text`, which starts at byte `offset` of `input`.
+pub fn parse_number<T>(input: &str, offset: usize, text: &str) -> ParseResult<T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    text.parse()
+        .map_err(|e| ParseError::at(input, offset, format!("invalid number '{}': {}", text, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalise() {
+        assert_eq!("a\nb\n", normalise("a\r\nb\r\n\r\n\n"));
+        assert_eq!("a\n", normalise("a"));
+        assert_eq!("", normalise("\n\n"));
+    }
+
+    #[test]
+    fn test_lines() {
+        assert_eq!(
+            vec!["a", "", "b"],
+            lines("a\r\n\r\nb\r\n\n\n").collect::<Vec<_>>()
+        );
+        assert_eq!(0, lines("").count());
+    }
+
+    #[test]
+    fn test_paragraphs() {
+        assert_eq!(
+            vec![vec!["a", "b"], vec!["c"]],
+            paragraphs("a\nb\n\n\nc\n\n")
+        );
+    }
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(Ok(vec![0, 4, 3, -3]), numbers::<i32>("p=0,4 v=3,-3"));
+        assert_eq!(Ok(vec![17, 1, 3]), numbers::<usize>("17,1,3\n"));
+        assert_eq!(Ok(vec![5, 10]), numbers::<u32>("5-10"));
+    }
+
+    #[test]
+    fn test_number_errors() {
+        let err = numbers::<u8>("1,2\n3,300").unwrap_err();
+        assert_eq!((2, 3), (err.line, err.column));
+        assert_eq!(
+            "invalid number '300': number too large to fit in target type",
+            err.message
+        );
+        let err = numbers::<u32>("x=-4").unwrap_err();
+        assert_eq!((1, 3), (err.line, err.column));
+        assert!(parse_number::<i32>("ab", 1, "b").is_err());
+    }
+}
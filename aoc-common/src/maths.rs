@@ -0,0 +1,196 @@
+//! Number theory on `i128`, with overflow reported rather than wrapped.
+
+use std::fmt;
+
+pub fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// The least common multiple, or `None` if it doesn't fit in an `i128`.
+pub fn lcm(a: i128, b: i128) -> Option<i128> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b).map(i128::abs)
+}
+
+/// The least common multiple of every number, 1 if there are none.
+pub fn lcm_all<I: IntoIterator<Item = i128>>(numbers: I) -> Option<i128> {
+    numbers.into_iter().try_fold(1, lcm)
+}
+
+/// Returns `(g, x, y)` where `g = gcd(a, b)` and `a * x + b * y = g`.
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// The `x` in `0..m` with `a * x ≡ 1 (mod m)`, if `a` and `m` are coprime.
+pub fn mod_inverse(a: i128, m: i128) -> Option<i128> {
+    let (g, x, _) = extended_gcd(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+/// `a * b mod m`, without overflowing however large the operands.
+pub fn mod_mul(a: i128, b: i128, m: i128) -> i128 {
+    let (a, mut b) = (a.rem_euclid(m), b.rem_euclid(m));
+    if let Some(product) = a.checked_mul(b) {
+        return product % m;
+    }
+    // Double and add, which only ever needs twice the modulus.
+    let mut result = 0;
+    let mut doubled = a;
+    while b > 0 {
+        if b & 1 == 1 {
+            result = add_mod(result, doubled, m);
+        }
+        doubled = add_mod(doubled, doubled, m);
+        b >>= 1;
+    }
+    result
+}
+
+fn add_mod(a: i128, b: i128, m: i128) -> i128 {
+    // Both are below m, so a - (m - b) can't overflow.
+    if a >= m - b {
+        a - (m - b)
+    } else {
+        a + b
+    }
+}
+
+/// `base` to the power `exp`, mod `m`.
+pub fn mod_pow(base: i128, mut exp: u128, m: i128) -> i128 {
+    let mut result = 1 % m;
+    let mut base = base.rem_euclid(m);
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mod_mul(result, base, m);
+        }
+        base = mod_mul(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
+
+/// The number of ways to choose `k` items from `n`, or `None` on overflow.
+pub fn binomial(n: i128, k: i128) -> Option<i128> {
+    if k < 0 || k > n {
+        return Some(0);
+    }
+    let k = k.min(n - k);
+    (0..k).try_fold(1i128, |acc, i| {
+        // acc * (n - i) is always a multiple of i + 1, so cancelling the
+        // common factor first keeps the division exact and the product small.
+        let g = gcd(acc, i + 1);
+        (acc / g).checked_mul((n - i) / ((i + 1) / g))
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrtError {
+    /// The congruences contradict each other.
+    NoSolution,
+    /// The combined modulus doesn't fit in an `i128`.
+    Overflow,
+}
+
+impl fmt::Display for CrtError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CrtError::NoSolution => write!(f, "the congruences have no common solution"),
+            CrtError::Overflow => write!(f, "the combined modulus overflows i128"),
+        }
+    }
+}
+
+impl std::error::Error for CrtError {}
+
+/// Solves `x ≡ residue (mod modulus)` for every `(residue, modulus)` pair at
+/// once, returning the smallest non-negative `x` and the modulus of every
+/// solution (the lcm of the moduli). The moduli needn't be coprime.
+pub fn crt(congruences: &[(i128, i128)]) -> Result<(i128, i128), CrtError> {
+    congruences
+        .iter()
+        .try_fold((0, 1), |(x, m), &(residue, modulus)| {
+            // Find t with x + m * t ≡ residue (mod modulus).
+            let (g, inverse, _) = extended_gcd(m, modulus);
+            let diff = residue - x;
+            if diff % g != 0 {
+                return Err(CrtError::NoSolution);
+            }
+            let step = modulus / g;
+            let combined = m.checked_mul(step).ok_or(CrtError::Overflow)?.abs();
+            let t = mod_mul(diff / g, inverse, step);
+            let x = (x + mod_mul(m, t, combined)).rem_euclid(combined);
+            Ok((x, combined))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(6, gcd(48, -18));
+        assert_eq!(Some(36), lcm(12, 18));
+        assert_eq!(Some(2520), lcm_all(1..=10));
+        assert_eq!(None, lcm(i128::MAX, i128::MAX - 1));
+    }
+
+    #[test]
+    fn test_extended_gcd() {
+        let (g, x, y) = extended_gcd(240, 46);
+        assert_eq!(2, g);
+        assert_eq!(g, 240 * x + 46 * y);
+    }
+
+    #[test]
+    fn test_modular() {
+        assert_eq!(Some(4), mod_inverse(3, 11));
+        assert_eq!(None, mod_inverse(6, 9));
+        assert_eq!(445, mod_pow(4, 13, 497));
+        let big = i128::MAX - 1;
+        assert_eq!(1, mod_mul(big - 1, big - 1, big));
+        assert_eq!(mod_mul(3, 5, 7), mod_mul(-4, 5, 7));
+    }
+
+    #[test]
+    fn test_binomial() {
+        assert_eq!(Some(10), binomial(5, 2));
+        assert_eq!(Some(0), binomial(3, 5));
+        assert_eq!(
+            Some(100_891_344_545_564_193_334_812_497_256),
+            binomial(100, 50)
+        );
+        assert_eq!(None, binomial(200, 100));
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(Ok((23, 105)), crt(&[(2, 3), (3, 5), (2, 7)]));
+        // Moduli sharing a factor.
+        assert_eq!(Ok((10, 12)), crt(&[(4, 6), (2, 4)]));
+        assert_eq!(Err(CrtError::NoSolution), crt(&[(1, 6), (2, 4)]));
+        assert_eq!(Ok((0, 1)), crt(&[]));
+        let huge = i128::MAX / 3;
+        assert_eq!(Err(CrtError::Overflow), crt(&[(0, huge), (0, huge - 1)]));
+    }
+}
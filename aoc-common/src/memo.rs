@@ -0,0 +1,162 @@
+//! Caches for recursive counting solutions, so each distinct argument is only
+//! ever computed once.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+
+/// How often a cache could answer a lookup itself.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+impl fmt::Display for CacheStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} hits, {} misses", self.hits, self.misses)
+    }
+}
+
+/// A map from arguments to results, filled in as they are computed.
+#[derive(Debug, Clone)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    stats: CacheStats,
+}
+
+impl<K: Eq + Hash, V: Clone> Memo<K, V> {
+    pub fn new() -> Memo<K, V> {
+        Memo {
+            cache: HashMap::new(),
+            stats: CacheStats::default(),
+        }
+    }
+
+    /// The cached value for `key`, computing it with `f` on a miss. `f` is
+    /// handed the memo so it can look up smaller cases recursively.
+    pub fn get_or_insert_with<F>(&mut self, key: K, f: F) -> V
+    where
+        F: FnOnce(&mut Memo<K, V>) -> V,
+    {
+        if let Some(value) = self.cache.get(&key) {
+            self.stats.hits += 1;
+            return value.clone();
+        }
+        self.stats.misses += 1;
+        let value = f(self);
+        self.cache.insert(key, value.clone());
+        value
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+}
+
+impl<K: Eq + Hash, V: Clone> Default for Memo<K, V> {
+    fn default() -> Memo<K, V> {
+        Memo::new()
+    }
+}
+
+/// A recursive function with its results cached by argument. See [`memoize`].
+pub struct Memoized<K, V, F> {
+    memo: Memo<K, V>,
+    f: F,
+}
+
+/// Wraps `f` so each argument is only computed once. `f` takes a function to
+/// recurse through, which goes via the cache, and the argument:
+///
+/// ```
+/// let mut fib = aoc_common::memoize(|fib, n: u64| {
+///     if n < 2 {
+///         n
+///     } else {
+///         fib(n - 1) + fib(n - 2)
+///     }
+/// });
+/// assert_eq!(12_586_269_025, fib.call(50));
+/// ```
+pub fn memoize<K, V, F>(f: F) -> Memoized<K, V, F>
+where
+    K: Clone + Eq + Hash,
+    V: Clone,
+    F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+{
+    Memoized {
+        memo: Memo::new(),
+        f,
+    }
+}
+
+impl<K, V, F> Memoized<K, V, F>
+where
+    K: Clone + Eq + Hash,
+    V: Clone,
+    F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+{
+    pub fn call(&mut self, key: K) -> V {
+        recurse(&mut self.memo, &self.f, key)
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.memo.stats()
+    }
+}
+
+fn recurse<K, V, F>(memo: &mut Memo<K, V>, f: &F, key: K) -> V
+where
+    K: Clone + Eq + Hash,
+    V: Clone,
+    F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+{
+    memo.get_or_insert_with(key.clone(), |memo| {
+        f(&mut |next| recurse(memo, f, next), key)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(memo: &mut Memo<(u32, u32), u64>, x: u32, y: u32) -> u64 {
+        if x == 0 || y == 0 {
+            return 1;
+        }
+        memo.get_or_insert_with((x, y), |memo| paths(memo, x - 1, y) + paths(memo, x, y - 1))
+    }
+
+    #[test]
+    fn test_memo() {
+        let mut memo = Memo::new();
+        assert_eq!(184_756, paths(&mut memo, 10, 10));
+        assert_eq!(100, memo.len());
+        assert_eq!(
+            CacheStats {
+                hits: 81,
+                misses: 100
+            },
+            memo.stats()
+        );
+    }
+
+    #[test]
+    fn test_memoize() {
+        let mut fib = memoize(|fib, n: u128| if n < 2 { n } else { fib(n - 1) + fib(n - 2) });
+        assert_eq!(354_224_848_179_261_915_075, fib.call(100));
+        assert_eq!(101, fib.stats().misses);
+        assert_eq!(98, fib.stats().hits);
+        fib.call(100);
+        assert_eq!(99, fib.stats().hits);
+    }
+}
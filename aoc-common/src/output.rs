@@ -0,0 +1,82 @@
+//! How results are printed: readable text by default, or one JSON record per
+//! part for scripts.
+
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use crate::PartResult;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Format {
+    #[default]
+    Human,
+    /// One JSON object per line, see [`Record`].
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Format, String> {
+        match s {
+            "human" => Ok(Format::Human),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("unknown format '{}', expected human or json", s)),
+        }
+    }
+}
+
+/// The machine-readable form of one part's result. Answers are always
+/// strings so numbers too large for a JSON double survive intact.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Record {
+    pub year: u32,
+    pub day: u32,
+    pub part: u32,
+    pub answer: String,
+    pub elapsed_ms: f64,
+}
+
+impl Record {
+    pub fn new(year: u32, day: u32, result: &PartResult) -> Record {
+        Record {
+            year,
+            day,
+            part: result.part,
+            answer: result.answer.to_string(),
+            elapsed_ms: result.elapsed.as_secs_f64() * 1000.0,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("records always serialise")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Answer;
+    use std::time::Duration;
+
+    #[test]
+    fn test_format() {
+        assert_eq!(Ok(Format::Json), "json".parse());
+        assert_eq!(Ok(Format::Human), "human".parse());
+        assert!("xml".parse::<Format>().is_err());
+    }
+
+    #[test]
+    fn test_record() {
+        let result = PartResult {
+            part: 2,
+            answer: Answer::Art("#.\n.#".to_string()),
+            elapsed: Duration::from_micros(1500),
+        };
+        assert_eq!(
+            r##"{"year":2021,"day":13,"part":2,"answer":"#.\n.#","elapsed_ms":1.5}"##,
+            Record::new(2021, 13, &result).to_json()
+        );
+    }
+}
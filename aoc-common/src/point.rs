@@ -0,0 +1,276 @@
+//! Points and vectors on an unbounded plane, for days that step beyond a grid
+//! or need signed arithmetic on positions.
+//!
+//! As on a [`Grid`](crate::Grid), `x` grows to the right and `y` downwards, so
+//! north is negative `y` and rotations are as they appear on screen.
+
+use std::fmt;
+use std::hash::Hash;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+use crate::Pos;
+
+/// The signed integers a [`Point`] can be made of.
+pub trait Coordinate:
+    Copy
+    + Ord
+    + Hash
+    + Default
+    + fmt::Debug
+    + fmt::Display
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Neg<Output = Self>
+    + TryFrom<usize>
+    + TryInto<usize>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn abs(self) -> Self;
+}
+
+macro_rules! coordinate {
+    ($($t:ty),*) => {
+        $(
+            impl Coordinate for $t {
+                const ZERO: $t = 0;
+                const ONE: $t = 1;
+
+                fn abs(self) -> $t {
+                    <$t>::abs(self)
+                }
+            }
+        )*
+    };
+}
+
+coordinate!(i8, i16, i32, i64, i128, isize);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point<T> {
+    pub x: T,
+    pub y: T,
+}
+
+/// A compass direction, north being up the screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    /// Every direction, clockwise from north.
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    /// The unit step this way.
+    pub fn offset<T: Coordinate>(self) -> Point<T> {
+        match self {
+            Direction::North => Point::new(T::ZERO, -T::ONE),
+            Direction::East => Point::new(T::ONE, T::ZERO),
+            Direction::South => Point::new(T::ZERO, T::ONE),
+            Direction::West => Point::new(-T::ONE, T::ZERO),
+        }
+    }
+
+    pub fn turn_right(self) -> Direction {
+        Direction::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn turn_left(self) -> Direction {
+        Direction::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn opposite(self) -> Direction {
+        Direction::ALL[(self as usize + 2) % 4]
+    }
+
+    /// Reads one of `^`, `>`, `v` and `<`.
+    pub fn from_arrow(c: char) -> Option<Direction> {
+        match c {
+            '^' => Some(Direction::North),
+            '>' => Some(Direction::East),
+            'v' => Some(Direction::South),
+            '<' => Some(Direction::West),
+            _ => None,
+        }
+    }
+}
+
+impl<T: Coordinate> Point<T> {
+    pub const ZERO: Point<T> = Point {
+        x: T::ZERO,
+        y: T::ZERO,
+    };
+
+    pub fn new(x: T, y: T) -> Point<T> {
+        Point { x, y }
+    }
+
+    /// The point at a grid position. Panics if it doesn't fit in `T`.
+    pub fn from_pos((x, y): Pos) -> Point<T> {
+        match (T::try_from(x), T::try_from(y)) {
+            (Ok(x), Ok(y)) => Point::new(x, y),
+            _ => panic!("({}, {}) doesn't fit in the point type", x, y),
+        }
+    }
+
+    /// The grid position at this point, if neither coordinate is negative.
+    pub fn to_pos(self) -> Option<Pos> {
+        Some((self.x.try_into().ok()?, self.y.try_into().ok()?))
+    }
+
+    pub fn step(self, direction: Direction) -> Point<T> {
+        self + direction.offset()
+    }
+
+    pub fn neighbours4(self) -> [Point<T>; 4] {
+        Direction::ALL.map(|d| self.step(d))
+    }
+
+    pub fn rotate_clockwise(self) -> Point<T> {
+        Point::new(-self.y, self.x)
+    }
+
+    pub fn rotate_anticlockwise(self) -> Point<T> {
+        Point::new(self.y, -self.x)
+    }
+
+    /// Rotates clockwise by a number of quarter turns, anticlockwise if
+    /// negative.
+    pub fn rotate(self, quarter_turns: i32) -> Point<T> {
+        match quarter_turns.rem_euclid(4) {
+            0 => self,
+            1 => self.rotate_clockwise(),
+            2 => -self,
+            _ => self.rotate_anticlockwise(),
+        }
+    }
+
+    /// The length of this vector moving only along the axes.
+    pub fn manhattan(self) -> T {
+        self.x.abs() + self.y.abs()
+    }
+
+    /// The length of this vector when diagonal moves count as one.
+    pub fn chebyshev(self) -> T {
+        self.x.abs().max(self.y.abs())
+    }
+
+    pub fn manhattan_distance(self, other: Point<T>) -> T {
+        (other - self).manhattan()
+    }
+
+    pub fn chebyshev_distance(self, other: Point<T>) -> T {
+        (other - self).chebyshev()
+    }
+}
+
+impl<T> From<(T, T)> for Point<T> {
+    fn from((x, y): (T, T)) -> Point<T> {
+        Point { x, y }
+    }
+}
+
+impl<T: Coordinate> Add for Point<T> {
+    type Output = Point<T>;
+
+    fn add(self, other: Point<T>) -> Point<T> {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: Coordinate> Sub for Point<T> {
+    type Output = Point<T>;
+
+    fn sub(self, other: Point<T>) -> Point<T> {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<T: Coordinate> AddAssign for Point<T> {
+    fn add_assign(&mut self, other: Point<T>) {
+        *self = *self + other;
+    }
+}
+
+impl<T: Coordinate> SubAssign for Point<T> {
+    fn sub_assign(&mut self, other: Point<T>) {
+        *self = *self - other;
+    }
+}
+
+impl<T: Coordinate> Mul<T> for Point<T> {
+    type Output = Point<T>;
+
+    fn mul(self, n: T) -> Point<T> {
+        Point::new(self.x * n, self.y * n)
+    }
+}
+
+impl<T: Coordinate> Neg for Point<T> {
+    type Output = Point<T>;
+
+    fn neg(self) -> Point<T> {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_directions() {
+        assert_eq!(Direction::East, Direction::North.turn_right());
+        assert_eq!(Direction::West, Direction::North.turn_left());
+        assert_eq!(Direction::North, Direction::South.opposite());
+        assert_eq!(Some(Direction::South), Direction::from_arrow('v'));
+        assert_eq!(Point::new(0, -1), Point::<i32>::ZERO.step(Direction::North));
+    }
+
+    #[test]
+    fn test_rotation() {
+        let p = Point::new(3, 1);
+        assert_eq!(Point::new(-1, 3), p.rotate_clockwise());
+        assert_eq!(p, p.rotate_clockwise().rotate_anticlockwise());
+        assert_eq!(p.rotate_anticlockwise(), p.rotate(-1));
+        assert_eq!(p.rotate(2), p.rotate(-2));
+        assert_eq!(p, p.rotate(4));
+        for d in Direction::ALL {
+            assert_eq!(d.turn_right().offset::<i8>(), d.offset().rotate_clockwise());
+        }
+    }
+
+    #[test]
+    fn test_distances() {
+        let a = Point::new(1i64, -2);
+        let b = Point::new(-3, 4);
+        assert_eq!(10, a.manhattan_distance(b));
+        assert_eq!(6, a.chebyshev_distance(b));
+        assert_eq!(Point::new(-4, 6), b - a);
+        assert_eq!(Point::new(2, -4), a * 2);
+    }
+
+    #[test]
+    fn test_pos_conversion() {
+        assert_eq!(Point::new(2i16, 5), Point::from_pos((2, 5)));
+        assert_eq!(Some((2, 5)), Point::new(2i16, 5).to_pos());
+        assert_eq!(None, Point::new(-1, 5).to_pos());
+    }
+}
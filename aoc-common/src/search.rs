@@ -0,0 +1,350 @@
+//! Shortest-path searches over any state type, driven by a closure giving each
+//! state's neighbours.
+//!
+//! Every search records all the predecessors a state can be reached from at
+//! its best cost, so puzzles asking about every optimal route (rather than
+//! just one) can walk back through them.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+/// The states reached by a search, with their cost from the start.
+#[derive(Debug, Clone)]
+pub struct Search<S> {
+    distances: HashMap<S, usize>,
+    predecessors: HashMap<S, Vec<S>>,
+}
+
+/// The result of a search that stopped at a goal.
+#[derive(Debug, Clone)]
+pub struct Found<S> {
+    pub cost: usize,
+    /// Every goal state reachable at `cost`, in the order they were found.
+    pub goals: Vec<S>,
+    pub search: Search<S>,
+}
+
+impl<S: Clone + Eq + Hash> Search<S> {
+    fn new(start: S) -> Search<S> {
+        let mut distances = HashMap::new();
+        distances.insert(start, 0);
+        Search {
+            distances,
+            predecessors: HashMap::new(),
+        }
+    }
+
+    /// Records reaching `next` from `from` at `cost`, returning true if that
+    /// is the best route found so far.
+    fn reach(&mut self, from: &S, next: S, cost: usize) -> bool {
+        match self.distances.get(&next) {
+            Some(&best) if best < cost => false,
+            Some(&best) if best == cost => {
+                let preds = self.predecessors.entry(next).or_default();
+                if !preds.contains(from) {
+                    preds.push(from.clone());
+                }
+                false
+            }
+            _ => {
+                self.distances.insert(next.clone(), cost);
+                self.predecessors.insert(next, vec![from.clone()]);
+                true
+            }
+        }
+    }
+
+    pub fn distance(&self, state: &S) -> Option<usize> {
+        self.distances.get(state).copied()
+    }
+
+    /// The cost of every state reached.
+    pub fn distances(&self) -> &HashMap<S, usize> {
+        &self.distances
+    }
+
+    pub fn into_distances(self) -> HashMap<S, usize> {
+        self.distances
+    }
+
+    /// The states `state` is reached from on its cheapest routes.
+    pub fn predecessors(&self, state: &S) -> &[S] {
+        self.predecessors.get(state).map_or(&[], Vec::as_slice)
+    }
+
+    /// One cheapest route from the start to `goal`, both included.
+    pub fn path_to(&self, goal: &S) -> Option<Vec<S>> {
+        self.distances.get(goal)?;
+        let mut path = vec![goal.clone()];
+        while let Some(prev) = self.predecessors(path.last().unwrap()).first() {
+            path.push(prev.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Every state lying on some cheapest route from the start to any of
+    /// `goals`, including the start and the goals themselves.
+    pub fn on_paths_to<'a, I>(&self, goals: I) -> HashSet<S>
+    where
+        I: IntoIterator<Item = &'a S>,
+        S: 'a,
+    {
+        let mut seen = HashSet::new();
+        let mut to_visit: Vec<S> = goals
+            .into_iter()
+            .filter(|g| self.distances.contains_key(g))
+            .cloned()
+            .collect();
+        while let Some(state) = to_visit.pop() {
+            if !seen.contains(&state) {
+                to_visit.extend(self.predecessors(&state).iter().cloned());
+                seen.insert(state);
+            }
+        }
+        seen
+    }
+}
+
+impl<S: Clone + Eq + Hash> Found<S> {
+    /// One cheapest route from the start to the first goal found.
+    pub fn path(&self) -> Vec<S> {
+        self.search
+            .path_to(&self.goals[0])
+            .expect("goals are always reached")
+    }
+
+    /// Every state on any cheapest route to any of the goals.
+    pub fn on_paths(&self) -> HashSet<S> {
+        self.search.on_paths_to(&self.goals)
+    }
+}
+
+/// A heap entry ordered so the lowest priority pops first.
+struct Entry<S> {
+    priority: usize,
+    cost: usize,
+    state: S,
+}
+
+impl<S> PartialEq for Entry<S> {
+    fn eq(&self, other: &Entry<S>) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<S> Eq for Entry<S> {}
+
+impl<S> PartialOrd for Entry<S> {
+    fn partial_cmp(&self, other: &Entry<S>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S> Ord for Entry<S> {
+    fn cmp(&self, other: &Entry<S>) -> Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+/// The cost of reaching every state reachable from `start`, where
+/// `neighbours` gives each state's successors and the cost of moving to them.
+pub fn dijkstra<S, N, I>(start: S, neighbours: N) -> Search<S>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    explore(start, neighbours, |_| 0, |_| false).0
+}
+
+/// The cheapest route from `start` to any state satisfying `goal`, or `None`
+/// if no goal can be reached.
+pub fn shortest_path<S, N, I, G>(start: S, neighbours: N, goal: G) -> Option<Found<S>>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, usize)>,
+    G: FnMut(&S) -> bool,
+{
+    astar(start, neighbours, |_| 0, goal)
+}
+
+/// As [`shortest_path`], guided by `heuristic`: an estimate of the remaining
+/// cost to a goal which must never overestimate it, nor drop by more than the
+/// cost of a step between neighbours.
+pub fn astar<S, N, I, H, G>(start: S, neighbours: N, heuristic: H, goal: G) -> Option<Found<S>>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, usize)>,
+    H: FnMut(&S) -> usize,
+    G: FnMut(&S) -> bool,
+{
+    let (search, found) = explore(start, neighbours, heuristic, goal);
+    found.map(|(cost, goals)| Found {
+        cost,
+        goals,
+        search,
+    })
+}
+
+fn explore<S, N, I, H, G>(
+    start: S,
+    mut neighbours: N,
+    mut heuristic: H,
+    mut goal: G,
+) -> (Search<S>, Option<(usize, Vec<S>)>)
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, usize)>,
+    H: FnMut(&S) -> usize,
+    G: FnMut(&S) -> bool,
+{
+    let mut search = Search::new(start.clone());
+    let mut found: Option<(usize, Vec<S>)> = None;
+    let mut heap = BinaryHeap::new();
+    heap.push(Entry {
+        priority: heuristic(&start),
+        cost: 0,
+        state: start,
+    });
+
+    while let Some(Entry {
+        priority,
+        cost,
+        state,
+    }) = heap.pop()
+    {
+        // Keep going past the first goal until nothing else could reach one
+        // as cheaply, so every optimal route is recorded.
+        if found.as_ref().is_some_and(|(best, _)| priority > *best) {
+            break;
+        }
+        if cost > search.distances[&state] {
+            continue;
+        }
+        if goal(&state) {
+            found
+                .get_or_insert_with(|| (cost, Vec::new()))
+                .1
+                .push(state);
+            continue;
+        }
+
+        for (next, step) in neighbours(&state) {
+            let next_cost = cost + step;
+            if search.reach(&state, next.clone(), next_cost) {
+                heap.push(Entry {
+                    priority: next_cost + heuristic(&next),
+                    cost: next_cost,
+                    state: next,
+                });
+            }
+        }
+    }
+
+    (search, found)
+}
+
+/// The number of steps to every state reachable from `start`, where each
+/// move to one of `neighbours` costs one.
+pub fn bfs<S, N, I>(start: S, mut neighbours: N) -> Search<S>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    let mut search = Search::new(start.clone());
+    let mut queue = VecDeque::from([start]);
+    while let Some(state) = queue.pop_front() {
+        let cost = search.distances[&state] + 1;
+        for next in neighbours(&state) {
+            if search.reach(&state, next.clone(), cost) {
+                queue.push_back(next);
+            }
+        }
+    }
+    search
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Grid;
+
+    /// A diamond with two equally cheap routes from 0 to 3, and a dead end.
+    fn diamond(n: &u32) -> Vec<(u32, usize)> {
+        match n {
+            0 => vec![(1, 1), (2, 2), (4, 1)],
+            1 => vec![(3, 2)],
+            2 => vec![(3, 1)],
+            4 => vec![(3, 5)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let search = dijkstra(0, diamond);
+        assert_eq!(Some(3), search.distance(&3));
+        assert_eq!(Some(1), search.distance(&4));
+        assert_eq!(None, search.distance(&5));
+        assert_eq!(&[1, 2], search.predecessors(&3));
+        assert_eq!(HashSet::from([0, 1, 2, 3]), search.on_paths_to([&3]));
+    }
+
+    #[test]
+    fn test_shortest_path() {
+        let found = shortest_path(0, diamond, |&n| n == 3).unwrap();
+        assert_eq!(3, found.cost);
+        assert_eq!(vec![3], found.goals);
+        assert_eq!(vec![0, 1, 3], found.path());
+        assert_eq!(4, found.on_paths().len());
+        assert!(shortest_path(0, diamond, |&n| n == 5).is_none());
+    }
+
+    #[test]
+    fn test_several_goals() {
+        // Both 1 and 2 are goals at cost 1 from 0, 3 is a goal but further.
+        let neighbours = |n: &u32| match n {
+            0 => vec![(1, 1), (2, 1), (3, 2)],
+            _ => vec![],
+        };
+        let found = shortest_path(0, neighbours, |&n| n > 0).unwrap();
+        assert_eq!(1, found.cost);
+        assert_eq!(vec![1, 2], {
+            let mut goals = found.goals.clone();
+            goals.sort();
+            goals
+        });
+    }
+
+    #[test]
+    fn test_astar_and_bfs() {
+        let grid = Grid::parse("..#\n.##\n...", |c| c == '#').unwrap();
+        let end = (2, 2);
+        let open = |&p: &(usize, usize)| {
+            grid.neighbours4(p)
+                .filter(|&n| !grid[n])
+                .collect::<Vec<_>>()
+        };
+        let manhattan = |&(x, y): &(usize, usize)| end.0 - x + end.1 - y;
+        let found = astar(
+            (0, 0),
+            |p| open(p).into_iter().map(|n| (n, 1)),
+            manhattan,
+            |&p| p == end,
+        )
+        .unwrap();
+        assert_eq!(4, found.cost);
+        assert_eq!(vec![(0, 0), (0, 1), (0, 2), (1, 2), (2, 2)], found.path());
+
+        let search = bfs((0, 0), open);
+        assert_eq!(Some(4), search.distance(&end));
+        assert_eq!(Some(1), search.distance(&(1, 0)));
+        assert_eq!(None, search.distance(&(2, 0)));
+    }
+}
@@ -0,0 +1,218 @@
+//! The interface every day's solution implements, so tooling can run any day
+//! without knowing its input or answer types.
+
+use std::env;
+use std::fmt;
+use std::process;
+use std::time::{Duration, Instant};
+
+use crate::{day_args, day_input, Format, ParseResult, Record};
+
+/// A puzzle answer, whatever shape the day produces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Number(i128),
+    Text(String),
+    /// Multi-line output, such as letters drawn in `#` and `.`.
+    Art(String),
+    /// The part hasn't been solved yet.
+    Unsolved,
+}
+
+impl Answer {
+    pub fn is_multiline(&self) -> bool {
+        matches!(self, Answer::Art(_))
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{}", n),
+            Answer::Text(s) | Answer::Art(s) => write!(f, "{}", s),
+            Answer::Unsolved => write!(f, "unsolved"),
+        }
+    }
+}
+
+macro_rules! from_number {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(n: $t) -> Answer {
+                Answer::Number(n as i128)
+            }
+        })*
+    };
+}
+
+from_number!(u8, u16, u32, u64, usize, i8, i16, i32, i64, i128);
+
+/// Text containing newlines is treated as [`Answer::Art`].
+impl From<String> for Answer {
+    fn from(s: String) -> Answer {
+        if s.contains('\n') {
+            Answer::Art(s)
+        } else {
+            Answer::Text(s)
+        }
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Answer {
+        Answer::from(s.to_string())
+    }
+}
+
+/// Stands in for a part that hasn't been solved yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Unsolved;
+
+impl From<Unsolved> for Answer {
+    fn from(_: Unsolved) -> Answer {
+        Answer::Unsolved
+    }
+}
+
+/// A day's puzzle: the input is parsed once and shared by both parts. Malformed
+/// input is reported by `parse` rather than panicking.
+pub trait Solution {
+    type Parsed;
+    type PartOne: Into<Answer>;
+    type PartTwo: Into<Answer>;
+
+    /// How many parts are solved; days still missing a part lower this.
+    const PARTS: u32 = 2;
+
+    fn parse(input: &str) -> ParseResult<Self::Parsed>;
+    fn part_one(parsed: &Self::Parsed) -> Self::PartOne;
+    fn part_two(parsed: &Self::Parsed) -> Self::PartTwo;
+}
+
+/// The answer to one part along with how long it took.
+#[derive(Debug, Clone)]
+pub struct PartResult {
+    pub part: u32,
+    pub answer: Answer,
+    pub elapsed: Duration,
+}
+
+/// The outcome of running a day, with parsing timed separately from each part.
+#[derive(Debug, Clone)]
+pub struct Solved {
+    pub parse: Duration,
+    pub parts: Vec<PartResult>,
+}
+
+/// Parses the input and runs the requested part, or every solved part if
+/// `part` is `None`.
+pub fn solve<S: Solution>(input: &str, part: Option<u32>) -> ParseResult<Solved> {
+    let start = Instant::now();
+    let parsed = S::parse(input)?;
+    let parse = start.elapsed();
+
+    let parts = (1..=S::PARTS)
+        .filter(|p| part.is_none_or(|part| part == *p))
+        .map(|p| {
+            let start = Instant::now();
+            let answer = match p {
+                1 => S::part_one(&parsed).into(),
+                _ => S::part_two(&parsed).into(),
+            };
+            PartResult {
+                part: p,
+                answer,
+                elapsed: start.elapsed(),
+            }
+        })
+        .collect();
+
+    Ok(Solved { parse, parts })
+}
+
+/// The `main` of a day's own binary: loads its input and prints each answer in
+/// the format asked for, exiting with a diagnostic if the input can't be read
+/// or parsed.
+pub fn run_day<S: Solution>(year: u32, day: u32) {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let solved = day_args(&args).and_then(|args| {
+        if args.debug.is_some() {
+            return Err(format!("{} day {} has no machine to debug", year, day));
+        }
+        let input = day_input(year, day, args.input)?;
+        let solved =
+            solve::<S>(&input, None).map_err(|e| format!("failed to parse input: {}", e))?;
+        Ok((args.format, solved))
+    });
+    let (format, solved) = solved.unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(1);
+    });
+
+    for result in solved.parts {
+        match format {
+            Format::Json => println!("{}", Record::new(year, day, &result).to_json()),
+            Format::Human if result.answer.is_multiline() => {
+                println!("Part {}:\n{}", result.part, result.answer)
+            }
+            Format::Human => println!("Part {}: {}", result.part, result.answer),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Sum;
+
+    impl Solution for Sum {
+        type Parsed = Vec<i64>;
+        type PartOne = i64;
+        type PartTwo = Unsolved;
+
+        const PARTS: u32 = 1;
+
+        fn parse(input: &str) -> ParseResult<Vec<i64>> {
+            crate::lines(input)
+                .enumerate()
+                .map(|(y, line)| {
+                    line.parse()
+                        .map_err(|_| crate::ParseError::at_line(input, y, 0, "expected a number"))
+                })
+                .collect()
+        }
+
+        fn part_one(numbers: &Vec<i64>) -> i64 {
+            numbers.iter().sum()
+        }
+
+        fn part_two(_: &Vec<i64>) -> Unsolved {
+            Unsolved
+        }
+    }
+
+    #[test]
+    fn test_answer_from() {
+        assert_eq!(Answer::Number(-3), Answer::from(-3i32));
+        assert_eq!(Answer::Number(7), Answer::from(7usize));
+        assert_eq!(Answer::Text("4,6,3".to_string()), Answer::from("4,6,3"));
+        assert_eq!(Answer::Art("#.\n.#".to_string()), Answer::from("#.\n.#"));
+        assert_eq!("unsolved", Answer::from(Unsolved).to_string());
+    }
+
+    #[test]
+    fn test_solve() {
+        let solved = solve::<Sum>("1\n2\n3\n", None).unwrap();
+        assert_eq!(1, solved.parts.len());
+        assert_eq!(1, solved.parts[0].part);
+        assert_eq!(Answer::Number(6), solved.parts[0].answer);
+        assert!(solve::<Sum>("1", Some(2)).unwrap().parts.is_empty());
+    }
+
+    #[test]
+    fn test_solve_parse_error() {
+        let err = solve::<Sum>("1\n2\nthree\n", None).unwrap_err();
+        assert_eq!((3, 1), (err.line, err.column));
+    }
+}
@@ -0,0 +1,176 @@
+//! Choosing where a day's puzzle input is read from.
+
+use std::env;
+use std::fmt;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use crate::{normalise, read_input, Format};
+
+/// Environment variable naming a directory of inputs laid out as `<year>/<day>.txt`.
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    File(PathBuf),
+    Stdin,
+}
+
+impl Source {
+    /// Picks the input for a day: an explicit `--input` argument wins (`-` meaning
+    /// stdin), then `$AOC_INPUT_DIR/<year>/<day>.txt`, then `default`.
+    pub fn resolve(arg: Option<&str>, year: u32, day: u32, default: PathBuf) -> Source {
+        let dir = env::var_os(INPUT_DIR_VAR).map(PathBuf::from);
+        resolve_with(arg, dir.as_deref(), year, day, default)
+    }
+
+    /// Reads the whole input, failing with a readable message if it is missing or empty.
+    pub fn read(&self) -> Result<String, String> {
+        let input = match self {
+            Source::File(path) => read_input(path)
+                .map_err(|e| format!("failed to read input from {}: {}", self, e))?,
+            Source::Stdin => {
+                let mut input = String::new();
+                io::stdin()
+                    .read_to_string(&mut input)
+                    .map_err(|e| format!("failed to read input from {}: {}", self, e))?;
+                normalise(&input)
+            }
+        };
+
+        if input.is_empty() {
+            return Err(format!("input from {} is empty", self));
+        }
+        Ok(input)
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Source::File(path) => write!(f, "{}", path.display()),
+            Source::Stdin => write!(f, "stdin"),
+        }
+    }
+}
+
+fn resolve_with(
+    arg: Option<&str>,
+    input_dir: Option<&Path>,
+    year: u32,
+    day: u32,
+    default: PathBuf,
+) -> Source {
+    match (arg, input_dir) {
+        (Some("-"), _) => Source::Stdin,
+        (Some(path), _) => Source::File(PathBuf::from(path)),
+        (None, Some(dir)) => Source::File(dir.join(year.to_string()).join(format!("{}.txt", day))),
+        (None, None) => Source::File(default),
+    }
+}
+
+/// The options a day's own binary accepts.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct DayArgs<'a> {
+    pub input: Option<&'a str>,
+    pub format: Format,
+    /// The part whose machine to step through, for days that run on one.
+    pub debug: Option<u32>,
+}
+
+/// Parses a day binary's command line: `--input <file>` (or `-` on its own for
+/// stdin), `--format <human|json>` and `--debug <part>`.
+pub fn day_args(args: &[String]) -> Result<DayArgs<'_>, String> {
+    let mut parsed = DayArgs::default();
+    let mut it = args.iter();
+    while let Some(arg) = it.next() {
+        match arg.as_str() {
+            "-" => parsed.input = Some("-"),
+            "--input" => {
+                let value = it.next().ok_or("missing value for --input")?;
+                parsed.input = Some(value.as_str());
+            }
+            "--format" => {
+                let value = it.next().ok_or("missing value for --format")?;
+                parsed.format = value.parse()?;
+            }
+            "--debug" => {
+                let value = it.next().ok_or("missing value for --debug")?;
+                match value.as_str() {
+                    "1" => parsed.debug = Some(1),
+                    "2" => parsed.debug = Some(2),
+                    _ => return Err(format!("--debug takes a part, 1 or 2, not '{}'", value)),
+                }
+            }
+            _ => return Err(format!("unexpected argument '{}'", arg)),
+        }
+    }
+    Ok(parsed)
+}
+
+/// Loads the input for a day's own binary from `input`, `AOC_INPUT_DIR` or
+/// `src/<day>/input.txt`.
+pub fn day_input(year: u32, day: u32, input: Option<&str>) -> Result<String, String> {
+    let default = Path::new("src").join(day.to_string()).join("input.txt");
+    Source::resolve(input, year, day, default).read()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        let default = PathBuf::from("src/1/input.txt");
+        let dir = Path::new("inputs");
+        assert_eq!(
+            Source::Stdin,
+            resolve_with(Some("-"), Some(dir), 2020, 1, default.clone())
+        );
+        assert_eq!(
+            Source::File(PathBuf::from("mine.txt")),
+            resolve_with(Some("mine.txt"), Some(dir), 2020, 1, default.clone())
+        );
+        assert_eq!(
+            Source::File(PathBuf::from("inputs/2020/1.txt")),
+            resolve_with(None, Some(dir), 2020, 1, default.clone())
+        );
+        assert_eq!(
+            Source::File(default.clone()),
+            resolve_with(None, None, 2020, 1, default)
+        );
+    }
+
+    #[test]
+    fn test_day_args() {
+        let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let input = |a: &[&str]| day_args(&args(a)).map(|d| d.input.map(String::from));
+        assert_eq!(Ok(None), input(&[]));
+        assert_eq!(Ok(Some("-".to_string())), input(&["-"]));
+        assert_eq!(Ok(Some("a.txt".to_string())), input(&["--input", "a.txt"]));
+        assert!(input(&["--input"]).is_err());
+        assert!(input(&["--bogus"]).is_err());
+
+        let json = args(&["--format", "json", "-"]);
+        assert_eq!(
+            Ok(DayArgs {
+                input: Some("-"),
+                format: Format::Json,
+                debug: None
+            }),
+            day_args(&json)
+        );
+        assert!(day_args(&args(&["--format", "xml"])).is_err());
+
+        assert_eq!(Ok(Some(2)), day_args(&args(&["--debug", "2"])).map(|d| d.debug));
+        assert!(day_args(&args(&["--debug", "3"])).is_err());
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let err = Source::File(PathBuf::from("does/not/exist.txt"))
+            .read()
+            .unwrap_err();
+        assert!(err.starts_with("failed to read input from does/not/exist.txt"));
+    }
+}
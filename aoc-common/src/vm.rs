@@ -0,0 +1,531 @@
+//! A virtual machine for the puzzles that hand over a program in a made-up
+//! assembly language. A day describes its [`InstructionSet`] (how programs
+//! are stored and decoded, and what each instruction does) and a [`Machine`]
+//! runs it, with stop conditions, an optional trace and loop detection.
+
+use std::error::Error;
+use std::fmt;
+use std::hash::Hash;
+use std::ops::{Index, IndexMut};
+
+use crate::{Cycle, History};
+
+/// An assembly language, plugged into a [`Machine`] to run programs in it.
+pub trait InstructionSet {
+    /// One cell of a stored program, such as a source line or a number.
+    type Code: Clone + fmt::Debug;
+    type Instruction: Clone + fmt::Debug;
+    /// Everything a program can change apart from the instruction pointer:
+    /// registers, memory and output.
+    type State: Clone + fmt::Debug;
+    /// What a trace keeps to take an instruction back. A small state can be
+    /// copied whole, while a big one should keep only what changes.
+    type Undo: Clone + fmt::Debug;
+
+    /// Decodes the instruction at `ip`, which is within `code`, returning it
+    /// with how many cells it takes up.
+    fn decode(&self, code: &[Self::Code], ip: usize)
+        -> Result<(Self::Instruction, usize), String>;
+
+    /// Runs one instruction, returning where to go next.
+    fn execute(
+        &self,
+        instruction: &Self::Instruction,
+        state: &mut Self::State,
+    ) -> Result<Flow, String>;
+
+    /// Saves whatever `instruction` is about to change in `state`.
+    fn save(&self, instruction: &Self::Instruction, state: &Self::State) -> Self::Undo;
+
+    /// Puts back what [`save`](InstructionSet::save) kept, leaving `state` as
+    /// it was before the instruction ran.
+    fn restore(&self, undo: Self::Undo, state: &mut Self::State);
+}
+
+/// Where execution goes after an instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    /// On to the instruction after this one.
+    Next,
+    /// Forwards or back by an offset from this instruction.
+    Jump(i64),
+    /// To an absolute address.
+    Goto(usize),
+    Halt,
+}
+
+/// Why a run stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exit {
+    /// Execution left the program or an instruction halted it.
+    Halted,
+    /// The stop condition held for the instruction about to run.
+    Stopped,
+    /// The instruction about to run has run before.
+    Looped,
+    /// The machine is back in a state it was in before, so will go round the
+    /// same steps forever.
+    Repeated(Cycle),
+}
+
+/// A program that couldn't be decoded or went wrong while running.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VmError {
+    /// The address of the offending instruction.
+    pub ip: usize,
+    /// How many instructions had run before it.
+    pub step: usize,
+    pub message: String,
+}
+
+impl fmt::Display for VmError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "at address {} after {} steps: {}",
+            self.ip, self.step, self.message
+        )
+    }
+}
+
+impl Error for VmError {}
+
+/// A fixed set of named registers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Registers<W, const N: usize> {
+    names: [&'static str; N],
+    values: [W; N],
+}
+
+impl<W: Copy + Default, const N: usize> Registers<W, N> {
+    /// Registers with these names, all zero.
+    pub fn new(names: [&'static str; N]) -> Registers<W, N> {
+        Registers::with_values(names, [W::default(); N])
+    }
+}
+
+impl<W: Copy, const N: usize> Registers<W, N> {
+    pub fn with_values(names: [&'static str; N], values: [W; N]) -> Registers<W, N> {
+        Registers { names, values }
+    }
+
+    pub fn names(&self) -> &[&'static str; N] {
+        &self.names
+    }
+
+    pub fn values(&self) -> &[W; N] {
+        &self.values
+    }
+
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|&n| n == name)
+    }
+
+    pub fn get(&self, name: &str) -> Option<W> {
+        self.index_of(name).map(|i| self.values[i])
+    }
+
+    /// Each register's name and value, in order.
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, W)> + '_ {
+        self.names.iter().copied().zip(self.values.iter().copied())
+    }
+}
+
+impl<W, const N: usize> Index<usize> for Registers<W, N> {
+    type Output = W;
+
+    fn index(&self, i: usize) -> &W {
+        &self.values[i]
+    }
+}
+
+impl<W, const N: usize> IndexMut<usize> for Registers<W, N> {
+    fn index_mut(&mut self, i: usize) -> &mut W {
+        &mut self.values[i]
+    }
+}
+
+impl<W: Copy + fmt::Display, const N: usize> fmt::Display for Registers<W, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, (name, value)) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}={}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// One instruction that ran, with what it takes to undo it.
+#[derive(Debug, Clone)]
+pub struct Traced<I: InstructionSet> {
+    pub step: usize,
+    pub ip: usize,
+    pub instruction: I::Instruction,
+    pub undo: I::Undo,
+}
+
+/// A program loaded into memory, with its state and instruction pointer.
+#[derive(Debug, Clone)]
+pub struct Machine<I: InstructionSet> {
+    isa: I,
+    code: Vec<I::Code>,
+    ip: usize,
+    state: I::State,
+    steps: usize,
+    halted: bool,
+    /// How many times the instruction at each address has run.
+    executions: Vec<usize>,
+    trace: Option<Vec<Traced<I>>>,
+}
+
+impl<I: InstructionSet> Machine<I> {
+    /// A machine about to run `code` from address 0.
+    pub fn new(isa: I, code: Vec<I::Code>, state: I::State) -> Machine<I> {
+        Machine {
+            isa,
+            executions: vec![0; code.len()],
+            code,
+            ip: 0,
+            state,
+            steps: 0,
+            halted: false,
+            trace: None,
+        }
+    }
+
+    /// Records every instruction run from now on in the [`trace`](Machine::trace).
+    pub fn with_trace(mut self) -> Machine<I> {
+        self.trace = Some(Vec::new());
+        self
+    }
+
+    pub fn isa(&self) -> &I {
+        &self.isa
+    }
+
+    pub fn isa_mut(&mut self) -> &mut I {
+        &mut self.isa
+    }
+
+    pub fn code(&self) -> &[I::Code] {
+        &self.code
+    }
+
+    pub fn ip(&self) -> usize {
+        self.ip
+    }
+
+    /// Moves the instruction pointer, resuming a halted machine.
+    pub fn set_ip(&mut self, ip: usize) {
+        self.ip = ip;
+        self.halted = false;
+    }
+
+    pub fn state(&self) -> &I::State {
+        &self.state
+    }
+
+    pub fn state_mut(&mut self) -> &mut I::State {
+        &mut self.state
+    }
+
+    pub fn into_state(self) -> I::State {
+        self.state
+    }
+
+    /// How many instructions have run.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.halted || self.ip >= self.code.len()
+    }
+
+    /// How many times the instruction at `ip` has run.
+    pub fn executions(&self, ip: usize) -> usize {
+        self.executions.get(ip).copied().unwrap_or(0)
+    }
+
+    /// Every address whose instruction has run, in order.
+    pub fn visited(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.code.len()).filter(|&ip| self.executions[ip] > 0)
+    }
+
+    /// The instructions run since tracing was turned on, oldest first.
+    pub fn trace(&self) -> &[Traced<I>] {
+        self.trace.as_deref().unwrap_or(&[])
+    }
+
+    /// The instruction about to run, or `None` if the machine has halted.
+    pub fn next_instruction(&self) -> Result<Option<I::Instruction>, VmError> {
+        Ok(self.fetch()?.map(|(instruction, _)| instruction))
+    }
+
+    /// Runs one instruction, returning false without doing anything if the
+    /// machine has halted.
+    pub fn step(&mut self) -> Result<bool, VmError> {
+        match self.fetch()? {
+            Some((instruction, width)) => {
+                self.execute(instruction, width)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Undoes the last traced instruction, returning false if there's none.
+    pub fn step_back(&mut self) -> bool {
+        let last = match self.trace.as_mut().and_then(|trace| trace.pop()) {
+            Some(last) => last,
+            None => return false,
+        };
+        self.executions[last.ip] -= 1;
+        self.steps = last.step;
+        self.ip = last.ip;
+        self.isa.restore(last.undo, &mut self.state);
+        self.halted = false;
+        true
+    }
+
+    /// Runs until the machine halts.
+    pub fn run(&mut self) -> Result<Exit, VmError> {
+        self.run_until(|_, _| false)
+    }
+
+    /// Runs at most `steps` instructions.
+    pub fn run_for(&mut self, steps: usize) -> Result<Exit, VmError> {
+        let mut left = steps;
+        self.run_until(|_, _| {
+            if left == 0 {
+                return true;
+            }
+            left -= 1;
+            false
+        })
+    }
+
+    /// Runs until the machine halts or `stop` holds for the machine and the
+    /// instruction about to run, which is left unrun.
+    pub fn run_until<F>(&mut self, mut stop: F) -> Result<Exit, VmError>
+    where
+        F: FnMut(&Machine<I>, &I::Instruction) -> bool,
+    {
+        while let Some((instruction, width)) = self.fetch()? {
+            if stop(self, &instruction) {
+                return Ok(Exit::Stopped);
+            }
+            self.execute(instruction, width)?;
+        }
+        Ok(Exit::Halted)
+    }
+
+    /// Runs until an instruction that has already run is about to run again.
+    pub fn run_until_loop(&mut self) -> Result<Exit, VmError> {
+        let exit = self.run_until(|machine, _| machine.executions(machine.ip) > 0)?;
+        Ok(if exit == Exit::Stopped {
+            Exit::Looped
+        } else {
+            exit
+        })
+    }
+
+    fn fetch(&self) -> Result<Option<(I::Instruction, usize)>, VmError> {
+        if self.is_halted() {
+            return Ok(None);
+        }
+        self.isa
+            .decode(&self.code, self.ip)
+            .map(Some)
+            .map_err(|message| self.error(message))
+    }
+
+    fn execute(&mut self, instruction: I::Instruction, width: usize) -> Result<(), VmError> {
+        let undo = self
+            .trace
+            .as_ref()
+            .map(|_| self.isa.save(&instruction, &self.state));
+        let flow = self
+            .isa
+            .execute(&instruction, &mut self.state)
+            .map_err(|message| self.error(message))?;
+        let next = match flow {
+            Flow::Next => Some(self.ip + width),
+            Flow::Jump(offset) => Some(
+                usize::try_from(self.ip as i64 + offset)
+                    .map_err(|_| self.error(format!("jump by {} leaves the program", offset)))?,
+            ),
+            Flow::Goto(ip) => Some(ip),
+            Flow::Halt => None,
+        };
+
+        if let (Some(trace), Some(undo)) = (&mut self.trace, undo) {
+            trace.push(Traced {
+                step: self.steps,
+                ip: self.ip,
+                instruction,
+                undo,
+            });
+        }
+        self.executions[self.ip] += 1;
+        self.steps += 1;
+        match next {
+            Some(ip) => self.ip = ip,
+            None => self.halted = true,
+        }
+        Ok(())
+    }
+
+    fn error(&self, message: impl Into<String>) -> VmError {
+        VmError {
+            ip: self.ip,
+            step: self.steps,
+            message: message.into(),
+        }
+    }
+}
+
+impl<I> Machine<I>
+where
+    I: InstructionSet,
+    I::State: Eq + Hash,
+{
+    /// Runs until the machine is about to run an instruction with the same
+    /// state it had there before. The cycle counts steps from the start of
+    /// this run.
+    pub fn run_until_repeat(&mut self) -> Result<Exit, VmError> {
+        let mut history = History::new();
+        let mut cycle = None;
+        let exit = self.run_until(|machine, _| {
+            cycle = history.record((machine.ip, machine.state.clone()));
+            cycle.is_some()
+        })?;
+        Ok(cycle.map_or(exit, Exit::Repeated))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two registers, counting up and down.
+    #[derive(Debug, Clone)]
+    struct Toy;
+
+    #[derive(Debug, Clone, Copy)]
+    enum Op {
+        Inc(usize),
+        Dec(usize),
+        Mod(usize, i64),
+        Jnz(usize, i64),
+    }
+
+    impl InstructionSet for Toy {
+        type Code = Op;
+        type Instruction = Op;
+        type State = Registers<i64, 2>;
+        type Undo = Registers<i64, 2>;
+
+        fn decode(&self, code: &[Op], ip: usize) -> Result<(Op, usize), String> {
+            Ok((code[ip], 1))
+        }
+
+        fn execute(&self, op: &Op, registers: &mut Registers<i64, 2>) -> Result<Flow, String> {
+            match *op {
+                Op::Inc(r) => registers[r] += 1,
+                Op::Dec(r) => registers[r] -= 1,
+                Op::Mod(r, m) => registers[r] %= m,
+                Op::Jnz(r, offset) => {
+                    if registers[r] != 0 {
+                        return Ok(Flow::Jump(offset));
+                    }
+                }
+            }
+            Ok(Flow::Next)
+        }
+
+        fn save(&self, _op: &Op, registers: &Registers<i64, 2>) -> Registers<i64, 2> {
+            *registers
+        }
+
+        fn restore(&self, undo: Registers<i64, 2>, registers: &mut Registers<i64, 2>) {
+            *registers = undo;
+        }
+    }
+
+    /// Moves `a` into `b`.
+    fn countdown(a: i64) -> Machine<Toy> {
+        let code = vec![Op::Inc(1), Op::Dec(0), Op::Jnz(0, -2)];
+        Machine::new(Toy, code, Registers::with_values(["a", "b"], [a, 0]))
+    }
+
+    #[test]
+    fn test_run() {
+        let mut machine = countdown(3);
+        assert_eq!(Ok(Exit::Halted), machine.run());
+        assert_eq!(Some(3), machine.state().get("b"));
+        assert_eq!("a=0 b=3", machine.state().to_string());
+        assert_eq!(9, machine.steps());
+        assert_eq!(3, machine.executions(2));
+        assert_eq!(Ok(false), machine.step());
+    }
+
+    #[test]
+    fn test_stopping() {
+        let mut machine = countdown(3);
+        assert_eq!(Ok(Exit::Looped), machine.run_until_loop());
+        assert_eq!((0, 3), (machine.ip(), machine.steps()));
+        assert_eq!(vec![0, 1, 2], machine.visited().collect::<Vec<_>>());
+
+        assert_eq!(Ok(Exit::Stopped), machine.run_for(2));
+        assert_eq!(
+            Ok(Exit::Stopped),
+            machine.run_until(|m, op| matches!(op, Op::Jnz(..)) && m.state()[0] == 0)
+        );
+        assert_eq!(8, machine.steps());
+    }
+
+    #[test]
+    fn test_trace() {
+        let mut machine = countdown(2).with_trace();
+        machine.run().unwrap();
+        let trace = machine.trace();
+        assert_eq!(6, trace.len());
+        assert_eq!((3, 0), (trace[3].step, trace[3].ip));
+        assert_eq!([1, 1], *trace[3].undo.values());
+
+        assert!(machine.step_back());
+        assert_eq!((2, 5, 1), (machine.ip(), machine.steps(), machine.executions(2)));
+        assert_eq!([0, 2], *machine.state().values());
+        while machine.step_back() {}
+        assert_eq!((0, 0), (machine.ip(), machine.steps()));
+        assert_eq!([2, 0], *machine.state().values());
+        assert!(!countdown(2).step_back());
+    }
+
+    #[test]
+    fn test_repeat() {
+        // `a` goes round 0, 1, 2 while the loop never ends.
+        let code = vec![Op::Inc(0), Op::Mod(0, 3), Op::Jnz(1, -2)];
+        let registers = Registers::with_values(["a", "b"], [0, 1]);
+        let mut machine = Machine::new(Toy, code, registers);
+        assert_eq!(
+            Ok(Exit::Repeated(Cycle {
+                start: 0,
+                length: 9
+            })),
+            machine.run_until_repeat()
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let registers = Registers::with_values(["a", "b"], [1, 0]);
+        let mut machine = Machine::new(Toy, vec![Op::Jnz(0, -5)], registers);
+        let err = machine.run().unwrap_err();
+        assert_eq!((0, 0), (err.ip, err.step));
+        assert_eq!("jump by -5 leaves the program", err.message);
+    }
+}
@@ -0,0 +1,101 @@
+//! Known answers recorded in `answers.toml`, used to catch regressions.
+//!
+//! The file has one table per day, keyed by year then day:
+//!
+//! ```toml
+//! [2020.15]
+//! part_one = 694
+//! part_two = "21768614"
+//! ```
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use toml::{Table, Value};
+
+const PART_KEYS: [&str; 2] = ["part_one", "part_two"];
+
+#[derive(Debug, Default)]
+pub struct Answers {
+    expected: HashMap<(u32, u32, u32), String>,
+}
+
+impl Answers {
+    pub fn load(path: &Path) -> Result<Answers, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+        Answers::parse(&text).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn parse(text: &str) -> Result<Answers, String> {
+        let table: Table = text.parse().map_err(|e| format!("{}", e))?;
+        let mut expected = HashMap::new();
+
+        for (year, days) in &table {
+            let year = key_number(year)?;
+            let days = days
+                .as_table()
+                .ok_or_else(|| format!("[{}] should be a table of days", year))?;
+
+            for (day, parts) in days {
+                let day = key_number(day)?;
+                let parts = parts
+                    .as_table()
+                    .ok_or_else(|| format!("[{}.{}] should be a table of parts", year, day))?;
+
+                for (key, value) in parts {
+                    let part = PART_KEYS
+                        .iter()
+                        .position(|k| k == key)
+                        .ok_or_else(|| format!("[{}.{}] has unknown key '{}'", year, day, key))?;
+                    let answer = match value {
+                        Value::String(s) => s.clone(),
+                        Value::Integer(i) => i.to_string(),
+                        _ => {
+                            return Err(format!(
+                                "[{}.{}] {} should be a string or integer",
+                                year, day, key
+                            ))
+                        }
+                    };
+                    expected.insert((year, day, part as u32 + 1), answer);
+                }
+            }
+        }
+        Ok(Answers { expected })
+    }
+
+    pub fn get(&self, year: u32, day: u32, part: u32) -> Option<&str> {
+        self.expected.get(&(year, day, part)).map(String::as_str)
+    }
+}
+
+fn key_number(key: &str) -> Result<u32, String> {
+    key.parse()
+        .map_err(|_| format!("expected a number, found '{}'", key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let answers = Answers::parse(
+            "[2020.15]\npart_one = 694\npart_two = \"21768614\"\n\n[2021.13]\npart_two = \"\"\"\n#..\n.#.\"\"\"\n",
+        )
+        .unwrap();
+        assert_eq!(Some("694"), answers.get(2020, 15, 1));
+        assert_eq!(Some("21768614"), answers.get(2020, 15, 2));
+        assert_eq!(Some("#..\n.#."), answers.get(2021, 13, 2));
+        assert_eq!(None, answers.get(2021, 13, 1));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Answers::parse("[2020.1]\npart_three = 1").is_err());
+        assert!(Answers::parse("[twenty.1]\npart_one = 1").is_err());
+        assert!(Answers::parse("[2020.1]\npart_one = 1.5").is_err());
+    }
+}
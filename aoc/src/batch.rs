@@ -0,0 +1,140 @@
+//! Running many days at once on a worker pool, giving up on any day that runs
+//! past its timeout so one hang can't hold up the rest.
+
+use std::cmp::Reverse;
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use aoc_common::Solved;
+use threadpool::ThreadPool;
+
+use crate::registry::Day;
+
+pub enum Outcome {
+    Solved(Solved),
+    /// The input couldn't be read.
+    Missing(String),
+    Failed(String),
+    TimedOut,
+}
+
+pub struct DayRun {
+    pub day: &'static Day,
+    pub outcome: Outcome,
+    /// Wall-clock time including reading the input.
+    pub elapsed: Duration,
+}
+
+/// Runs every part of each day on `jobs` workers. A day still running after
+/// `timeout` is reported as timed out and left to finish in the background.
+/// Results are sorted slowest first.
+pub fn run_days(
+    days: Vec<&'static Day>,
+    jobs: usize,
+    timeout: Duration,
+    load: fn(&Day) -> Result<String, String>,
+) -> Vec<DayRun> {
+    let pool = ThreadPool::new(jobs);
+    let (tx, rx) = channel();
+    let count = days.len();
+
+    for day in days {
+        let tx = tx.clone();
+        pool.execute(move || {
+            let start = Instant::now();
+            let outcome = run_with_timeout(day, timeout, load);
+            let _ = tx.send(DayRun {
+                day,
+                outcome,
+                elapsed: start.elapsed(),
+            });
+        });
+    }
+
+    let mut runs: Vec<DayRun> = rx.iter().take(count).collect();
+    runs.sort_by_key(|run| Reverse(run.elapsed));
+    runs
+}
+
+fn run_with_timeout(
+    day: &'static Day,
+    timeout: Duration,
+    load: fn(&Day) -> Result<String, String>,
+) -> Outcome {
+    let (tx, rx) = channel();
+    thread::spawn(move || {
+        let outcome = match load(day) {
+            Ok(input) => match (day.solve)(&input, None) {
+                Ok(solved) => Outcome::Solved(solved),
+                Err(e) => Outcome::Failed(format!("parse error at {}", e)),
+            },
+            Err(e) => Outcome::Missing(e),
+        };
+        let _ = tx.send(outcome);
+    });
+
+    match rx.recv_timeout(timeout) {
+        Ok(outcome) => outcome,
+        Err(RecvTimeoutError::Timeout) => Outcome::TimedOut,
+        Err(RecvTimeoutError::Disconnected) => Outcome::Failed("panicked".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_common::ParseResult;
+
+    fn quick(_: &str, _: Option<u32>) -> ParseResult<Solved> {
+        Ok(Solved {
+            parse: Duration::ZERO,
+            parts: Vec::new(),
+        })
+    }
+
+    fn hang(_: &str, _: Option<u32>) -> ParseResult<Solved> {
+        loop {
+            thread::sleep(Duration::from_secs(1));
+        }
+    }
+
+    fn boom(_: &str, _: Option<u32>) -> ParseResult<Solved> {
+        panic!("boom");
+    }
+
+    static DAYS: [Day; 3] = [
+        Day {
+            year: 2020,
+            day: 1,
+            parts: 0,
+            solve: quick,
+        },
+        Day {
+            year: 2020,
+            day: 2,
+            parts: 0,
+            solve: hang,
+        },
+        Day {
+            year: 2020,
+            day: 3,
+            parts: 0,
+            solve: boom,
+        },
+    ];
+
+    #[test]
+    fn test_run_days() {
+        let runs = run_days(DAYS.iter().collect(), 2, Duration::from_millis(200), |_| {
+            Ok(String::new())
+        });
+
+        assert_eq!(3, runs.len());
+        assert_eq!(2, runs[0].day.day);
+        assert!(matches!(runs[0].outcome, Outcome::TimedOut));
+        let outcome = |day| &runs.iter().find(|r| r.day.day == day).unwrap().outcome;
+        assert!(matches!(outcome(1), Outcome::Solved(_)));
+        assert!(matches!(outcome(3), Outcome::Failed(_)));
+    }
+}
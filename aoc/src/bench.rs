@@ -0,0 +1,271 @@
+//! Repeated timing of a day's parse and parts, with baselines saved as JSON so
+//! later runs can be checked for regressions.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use aoc_common::ParseResult;
+use serde::{Deserialize, Serialize};
+
+use crate::memory;
+use crate::registry::Day;
+
+/// Summary of one phase's timings over every run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stats {
+    pub mean_ns: u64,
+    pub median_ns: u64,
+    pub min_ns: u64,
+}
+
+impl Stats {
+    pub fn of(samples: &[Duration]) -> Stats {
+        assert!(!samples.is_empty(), "no samples to summarise");
+        let mut nanos: Vec<u64> = samples.iter().map(|d| d.as_nanos() as u64).collect();
+        nanos.sort_unstable();
+
+        let middle = nanos.len() / 2;
+        let median_ns = if nanos.len().is_multiple_of(2) {
+            (nanos[middle - 1] + nanos[middle]) / 2
+        } else {
+            nanos[middle]
+        };
+        Stats {
+            mean_ns: nanos.iter().sum::<u64>() / nanos.len() as u64,
+            median_ns,
+            min_ns: nanos[0],
+        }
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "mean {:>10.2?}  median {:>10.2?}  min {:>10.2?}",
+            Duration::from_nanos(self.mean_ns),
+            Duration::from_nanos(self.median_ns),
+            Duration::from_nanos(self.min_ns)
+        )
+    }
+}
+
+/// The benchmark of a single day. Phases are keyed `parse`, `part_one` and
+/// `part_two`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DayBench {
+    pub runs: u32,
+    pub phases: BTreeMap<String, Stats>,
+    /// The most memory allocated at once during any run, beyond what was
+    /// already in use beforehand.
+    pub peak_bytes: usize,
+}
+
+/// Parses and solves every part `runs` times, timing each phase separately.
+pub fn bench_day(day: &Day, input: &str, runs: u32) -> ParseResult<DayBench> {
+    let mut samples: BTreeMap<String, Vec<Duration>> = BTreeMap::new();
+    let mut peak_bytes = 0;
+
+    for _ in 0..runs {
+        let before = memory::reset_peak();
+        let solved = (day.solve)(input, None)?;
+        peak_bytes = peak_bytes.max(memory::peak().saturating_sub(before));
+
+        samples
+            .entry("parse".to_string())
+            .or_default()
+            .push(solved.parse);
+        for result in solved.parts {
+            samples
+                .entry(phase_name(result.part).to_string())
+                .or_default()
+                .push(result.elapsed);
+        }
+    }
+
+    Ok(DayBench {
+        runs,
+        phases: samples
+            .into_iter()
+            .map(|(phase, durations)| (phase, Stats::of(&durations)))
+            .collect(),
+        peak_bytes,
+    })
+}
+
+pub fn phase_name(part: u32) -> &'static str {
+    match part {
+        1 => "part_one",
+        _ => "part_two",
+    }
+}
+
+/// Saved benchmark results, keyed by `<year>/<day>`.
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub days: BTreeMap<String, DayBench>,
+}
+
+impl Baseline {
+    pub fn key(year: u32, day: u32) -> String {
+        format!("{}/{}", year, day)
+    }
+
+    pub fn load(path: &Path) -> Result<Baseline, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+        serde_json::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(path, json + "\n")
+            .map_err(|e| format!("failed to write {}: {}", path.display(), e))
+    }
+}
+
+/// A phase, or the peak memory, that got worse than the baseline allows.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Regression {
+    pub what: String,
+    pub before: String,
+    pub after: String,
+    pub percent: u64,
+}
+
+impl fmt::Display for Regression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} -> {} (+{}%)",
+            self.what, self.before, self.after, self.percent
+        )
+    }
+}
+
+/// Flags every phase whose median, and the peak memory, grew by more than
+/// `threshold` percent over the baseline.
+pub fn compare(baseline: &DayBench, current: &DayBench, threshold: u32) -> Vec<Regression> {
+    let mut regressions = Vec::new();
+    for (phase, stats) in &current.phases {
+        let Some(before) = baseline.phases.get(phase) else {
+            continue;
+        };
+        if let Some(percent) = growth(before.median_ns, stats.median_ns, threshold) {
+            regressions.push(Regression {
+                what: format!("{} median", phase),
+                before: format!("{:.2?}", Duration::from_nanos(before.median_ns)),
+                after: format!("{:.2?}", Duration::from_nanos(stats.median_ns)),
+                percent,
+            });
+        }
+    }
+
+    if let Some(percent) = growth(
+        baseline.peak_bytes as u64,
+        current.peak_bytes as u64,
+        threshold,
+    ) {
+        regressions.push(Regression {
+            what: "peak memory".to_string(),
+            before: bytes(baseline.peak_bytes),
+            after: bytes(current.peak_bytes),
+            percent,
+        });
+    }
+    regressions
+}
+
+fn growth(before: u64, after: u64, threshold: u32) -> Option<u64> {
+    if before == 0 || after <= before {
+        return None;
+    }
+    let percent = (after - before) * 100 / before;
+    (percent > threshold as u64).then_some(percent)
+}
+
+/// Formats a byte count with a binary unit, e.g. `1.50 MiB`.
+pub fn bytes(n: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut size = n as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", n)
+    } else {
+        format!("{:.2} {}", size, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day_bench(median_ns: u64, peak_bytes: usize) -> DayBench {
+        let stats = Stats {
+            mean_ns: median_ns,
+            median_ns,
+            min_ns: median_ns,
+        };
+        DayBench {
+            runs: 1,
+            phases: [("parse".to_string(), stats)].into_iter().collect(),
+            peak_bytes,
+        }
+    }
+
+    #[test]
+    fn test_stats() {
+        let samples: Vec<Duration> = [40, 10, 30, 20]
+            .iter()
+            .map(|&n| Duration::from_nanos(n))
+            .collect();
+        assert_eq!(
+            Stats {
+                mean_ns: 25,
+                median_ns: 25,
+                min_ns: 10
+            },
+            Stats::of(&samples)
+        );
+        assert_eq!(30, Stats::of(&samples[..3]).median_ns);
+    }
+
+    #[test]
+    fn test_compare() {
+        let baseline = day_bench(1000, 2048);
+        assert!(compare(&baseline, &day_bench(1050, 2048), 10).is_empty());
+        assert!(compare(&baseline, &day_bench(500, 1024), 10).is_empty());
+
+        let regressions = compare(&baseline, &day_bench(1500, 4096), 10);
+        assert_eq!(2, regressions.len());
+        assert_eq!("parse median", regressions[0].what);
+        assert_eq!(50, regressions[0].percent);
+        assert_eq!(
+            "peak memory 2.00 KiB -> 4.00 KiB (+100%)",
+            regressions[1].to_string()
+        );
+    }
+
+    #[test]
+    fn test_baseline_round_trip() {
+        let mut baseline = Baseline::default();
+        baseline
+            .days
+            .insert(Baseline::key(2020, 15), day_bench(1000, 2048));
+        let json = serde_json::to_string(&baseline).unwrap();
+        assert_eq!(baseline, serde_json::from_str(&json).unwrap());
+    }
+
+    #[test]
+    fn test_bytes() {
+        assert_eq!("512 B", bytes(512));
+        assert_eq!("1.50 MiB", bytes(1536 * 1024));
+    }
+}
@@ -0,0 +1,532 @@
+mod answers;
+mod batch;
+mod bench;
+mod memory;
+mod registry;
+mod scaffold;
+
+use std::collections::HashMap;
+use std::env;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use answers::Answers;
+use aoc_common::{Answer, Format, Record, Source};
+use batch::Outcome;
+use bench::Baseline;
+use registry::Day;
+
+const USAGE: &str = "usage:
+    aoc list
+    aoc run --year <year> --day <day> [--part <part>] [--input <file|->]
+            [--format <human|json>]
+    aoc run (--all | --year <year>) [--jobs <n>] [--timeout <seconds>]
+            [--format <human|json>]
+    aoc verify [--year <year>] [--day <day>] [--answers <file>]
+    aoc bench [--year <year>] [--day <day>] [--runs <n>] [--save <file>]
+              [--baseline <file>] [--threshold <percent>]
+    aoc new --year <year> --day <day> [--example <file>]
+
+The input defaults to $AOC_INPUT_DIR/<year>/<day>.txt if set, otherwise
+<year>/src/<day>/input.txt. Expected answers are read from answers.toml.
+run --all (or --year without --day) runs every matching day in parallel, giving
+up on any day that takes longer than the timeout (60 seconds by default).
+bench runs each day 10 times by default and flags any phase whose median, or
+the peak memory, is more than 10% worse than the baseline. new creates a day
+from scripts/template.rs, seeding its tests with the example file if given.";
+
+const DEFAULT_RUNS: u32 = 10;
+const DEFAULT_THRESHOLD: u32 = 10;
+const DEFAULT_TIMEOUT_SECS: u32 = 60;
+
+/// Options that are given on their own, without a value.
+const FLAGS: [&str; 1] = ["all"];
+
+/// The options each command accepts.
+const RUN_OPTIONS: &[&str] = &[
+    "year", "day", "part", "input", "format", "all", "jobs", "timeout",
+];
+const VERIFY_OPTIONS: &[&str] = &["year", "day", "answers"];
+const BENCH_OPTIONS: &[&str] = &["year", "day", "runs", "save", "baseline", "threshold"];
+const NEW_OPTIONS: &[&str] = &["year", "day", "example"];
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("list") => {
+            list();
+            Ok(())
+        }
+        Some("run") => run(&args[1..]),
+        Some("verify") => verify(&args[1..]),
+        Some("bench") => bench(&args[1..]),
+        Some("new") => new(&args[1..]),
+        _ => Err(USAGE.to_string()),
+    };
+
+    if let Err(message) = result {
+        eprintln!("{}", message);
+        process::exit(1);
+    }
+}
+
+fn list() {
+    let mut years: Vec<u32> = registry::DAYS.iter().map(|d| d.year).collect();
+    years.dedup();
+
+    for year in years {
+        let days: Vec<String> = registry::DAYS
+            .iter()
+            .filter(|d| d.year == year)
+            .map(|d| format!("{}{}", d.day, "*".repeat(d.parts as usize)))
+            .collect();
+        println!("{}: {}", year, days.join(" "));
+    }
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    let options = parse_options(args, RUN_OPTIONS)?;
+    if options.contains_key("all") || (options.contains_key("year") && !options.contains_key("day"))
+    {
+        return run_many(&options);
+    }
+    for many in ["jobs", "timeout"] {
+        if options.contains_key(many) {
+            return Err(format!("--{} only applies when running several days", many));
+        }
+    }
+
+    let year = required(&options, "year")?;
+    let day = required(&options, "day")?;
+    let part = number(&options, "part")?;
+    let format = format(&options)?;
+
+    let entry = registry::find(year, day)
+        .ok_or_else(|| format!("no solution registered for {} day {}", year, day))?;
+    if let Some(part) = part {
+        if part == 0 || part > entry.parts {
+            return Err(format!("{} day {} has no part {}", year, day, part));
+        }
+    }
+
+    let source = Source::resolve(
+        options.get("input").map(String::as_str),
+        year,
+        day,
+        input_path(entry),
+    );
+    let input = source.read()?;
+
+    let solved = (entry.solve)(&input, part)
+        .map_err(|e| format!("failed to parse input from {}: {}", source, e))?;
+    if format == Format::Json {
+        for result in &solved.parts {
+            println!("{}", Record::new(year, day, result).to_json());
+        }
+        return Ok(());
+    }
+
+    println!("{} day {}", year, day);
+    println!("  Parse: {:.2?}", solved.parse);
+    for result in solved.parts {
+        println!(
+            "  Part {}: {} ({:.2?})",
+            result.part,
+            display(&result.answer),
+            result.elapsed
+        );
+    }
+    Ok(())
+}
+
+/// Runs every day of one year, or of every year, concurrently and prints a
+/// table of the results slowest first.
+fn run_many(options: &HashMap<String, String>) -> Result<(), String> {
+    for single in ["day", "part", "input"] {
+        if options.contains_key(single) {
+            return Err(format!(
+                "--{} can't be used when running several days",
+                single
+            ));
+        }
+    }
+    if options.contains_key("all") && options.contains_key("year") {
+        return Err("--all runs every year, so can't be given with --year".to_string());
+    }
+    let year = number(options, "year")?;
+    let format = format(options)?;
+    let timeout = match number(options, "timeout")? {
+        Some(0) => return Err("--timeout must be at least 1".to_string()),
+        Some(timeout) => timeout,
+        None => DEFAULT_TIMEOUT_SECS,
+    };
+    let jobs = match number(options, "jobs")? {
+        Some(0) => return Err("--jobs must be at least 1".to_string()),
+        Some(jobs) => jobs as usize,
+        None => thread::available_parallelism().map_or(1, |n| n.get()),
+    };
+
+    let days: Vec<&'static Day> = registry::DAYS
+        .iter()
+        .filter(|d| year.is_none_or(|y| y == d.year))
+        .collect();
+    if days.is_empty() {
+        return Err(format!("no solutions registered for {}", year.unwrap_or(0)));
+    }
+
+    let start = Instant::now();
+    let runs = batch::run_days(days, jobs, Duration::from_secs(timeout as u64), |entry| {
+        Source::resolve(None, entry.year, entry.day, input_path(entry)).read()
+    });
+    let total = start.elapsed();
+
+    let (mut solved, mut failed, mut timed_out, mut missing) = (0, 0, 0, 0);
+    for run in &runs {
+        let (year, day) = (run.day.year, run.day.day);
+        let status = match &run.outcome {
+            Outcome::Solved(result) => {
+                solved += 1;
+                if format == Format::Json {
+                    for part in &result.parts {
+                        println!("{}", Record::new(year, day, part).to_json());
+                    }
+                    continue;
+                }
+                result
+                    .parts
+                    .iter()
+                    .map(|p| format!("Part {}: {}", p.part, inline(&p.answer)))
+                    .collect::<Vec<_>>()
+                    .join("  ")
+            }
+            Outcome::Missing(e) => {
+                missing += 1;
+                format!("missing: {}", e)
+            }
+            Outcome::Failed(e) => {
+                failed += 1;
+                format!("FAIL: {}", e)
+            }
+            Outcome::TimedOut => {
+                timed_out += 1;
+                format!("TIMEOUT after {}s", timeout)
+            }
+        };
+
+        match format {
+            Format::Json => eprintln!("{} day {}: {}", year, day, status),
+            Format::Human => println!(
+                "{} day {:>2}  {:>10}  {}",
+                year,
+                day,
+                format!("{:.2?}", run.elapsed),
+                status
+            ),
+        }
+    }
+
+    if format == Format::Human {
+        println!(
+            "{} solved, {} failed, {} timed out, {} missing in {:.2?}",
+            solved, failed, timed_out, missing, total
+        );
+    }
+    if failed + timed_out > 0 {
+        return Err(format!(
+            "{} day(s) failed and {} timed out",
+            failed, timed_out
+        ));
+    }
+    Ok(())
+}
+
+/// Runs every registered day against its local input and compares the results
+/// with the recorded answers, failing if any part disagrees.
+fn verify(args: &[String]) -> Result<(), String> {
+    let options = parse_options(args, VERIFY_OPTIONS)?;
+    let year = number(&options, "year")?;
+    let day = number(&options, "day")?;
+    let answers = match options.get("answers") {
+        Some(path) => Answers::load(Path::new(path))?,
+        None => Answers::load(&workspace_path().join("answers.toml"))?,
+    };
+
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    for entry in registry::DAYS {
+        if year.is_some_and(|y| y != entry.year) || day.is_some_and(|d| d != entry.day) {
+            continue;
+        }
+
+        println!("{} day {}", entry.year, entry.day);
+        let input = match Source::resolve(None, entry.year, entry.day, input_path(entry)).read() {
+            Ok(input) => input,
+            Err(e) => {
+                println!("  missing: {}", e);
+                missing += entry.parts;
+                continue;
+            }
+        };
+
+        for number in 1..=entry.parts {
+            let start = Instant::now();
+            let answer = panic::catch_unwind(|| {
+                (entry.solve)(&input, Some(number)).map(|s| s.parts[0].answer.to_string())
+            });
+            let elapsed = start.elapsed();
+
+            let status = match (answer, answers.get(entry.year, entry.day, number)) {
+                (Err(_), _) => {
+                    failed += 1;
+                    "FAIL: panicked".to_string()
+                }
+                (Ok(Err(e)), _) => {
+                    failed += 1;
+                    format!("FAIL: parse error at {}", e)
+                }
+                (Ok(Ok(answer)), None) => {
+                    missing += 1;
+                    format!("missing: no recorded answer, got {}", answer)
+                }
+                (Ok(Ok(answer)), Some(expected)) if answer == expected => {
+                    passed += 1;
+                    format!("pass {}", answer)
+                }
+                (Ok(Ok(answer)), Some(expected)) => {
+                    failed += 1;
+                    format!("FAIL: expected {}, got {}", expected, answer)
+                }
+            };
+            println!("  Part {}: {} ({:.2?})", number, status, elapsed);
+        }
+    }
+
+    println!("{} passed, {} failed, {} missing", passed, failed, missing);
+    if failed > 0 {
+        return Err(format!(
+            "{} part(s) did not match their recorded answer",
+            failed
+        ));
+    }
+    Ok(())
+}
+
+/// Times every selected day over several runs, optionally saving the results
+/// as a baseline or comparing them against one.
+fn bench(args: &[String]) -> Result<(), String> {
+    let options = parse_options(args, BENCH_OPTIONS)?;
+    let year = number(&options, "year")?;
+    let day = number(&options, "day")?;
+    let runs = number(&options, "runs")?.unwrap_or(DEFAULT_RUNS);
+    let threshold = number(&options, "threshold")?.unwrap_or(DEFAULT_THRESHOLD);
+    if runs == 0 {
+        return Err("--runs must be at least 1".to_string());
+    }
+    let baseline = options
+        .get("baseline")
+        .map(|path| Baseline::load(Path::new(path)))
+        .transpose()?;
+
+    let mut results = Baseline::default();
+    let mut regressed = 0;
+    for entry in registry::DAYS {
+        if year.is_some_and(|y| y != entry.year) || day.is_some_and(|d| d != entry.day) {
+            continue;
+        }
+
+        println!("{} day {} ({} runs)", entry.year, entry.day, runs);
+        let input = match Source::resolve(None, entry.year, entry.day, input_path(entry)).read() {
+            Ok(input) => input,
+            Err(e) => {
+                println!("  missing: {}", e);
+                continue;
+            }
+        };
+
+        let result = match panic::catch_unwind(|| bench::bench_day(entry, &input, runs)) {
+            Ok(Ok(result)) => result,
+            Ok(Err(e)) => {
+                println!("  FAIL: parse error at {}", e);
+                continue;
+            }
+            Err(_) => {
+                println!("  FAIL: panicked");
+                continue;
+            }
+        };
+        for (phase, stats) in &result.phases {
+            println!("  {:<9} {}", format!("{}:", phase), stats);
+        }
+        println!("  peak memory: {}", bench::bytes(result.peak_bytes));
+
+        let key = Baseline::key(entry.year, entry.day);
+        if let Some(before) = baseline.as_ref().and_then(|b| b.days.get(&key)) {
+            for regression in bench::compare(before, &result, threshold) {
+                println!("  REGRESSION: {}", regression);
+                regressed += 1;
+            }
+        }
+        results.days.insert(key, result);
+    }
+
+    if let Some(path) = options.get("save") {
+        results.save(Path::new(path))?;
+        println!("saved {} day(s) to {}", results.days.len(), path);
+    }
+    if regressed > 0 {
+        return Err(format!(
+            "{} measurement(s) regressed by more than {}%",
+            regressed, threshold
+        ));
+    }
+    Ok(())
+}
+
+fn new(args: &[String]) -> Result<(), String> {
+    let options = parse_options(args, NEW_OPTIONS)?;
+    let year = required(&options, "year")?;
+    let day = required(&options, "day")?;
+    if !(1..=25).contains(&day) {
+        return Err(format!("--day must be between 1 and 25, got {}", day));
+    }
+    let example = options
+        .get("example")
+        .map(|path| {
+            aoc_common::read_input(path).map_err(|e| format!("failed to read {}: {}", path, e))
+        })
+        .transpose()?;
+
+    let root = workspace_path();
+    for path in scaffold::new_day(&root, year, day, example.as_deref())? {
+        let path = path.strip_prefix(&root).unwrap_or(&path);
+        println!("wrote {}", path.display());
+    }
+    Ok(())
+}
+
+/// Puts multi-line answers on their own lines beneath the part label.
+fn display(answer: &Answer) -> String {
+    if answer.is_multiline() {
+        format!("\n{}", answer)
+    } else {
+        answer.to_string()
+    }
+}
+
+/// Keeps table rows on one line by not printing multi-line answers.
+fn inline(answer: &Answer) -> String {
+    if answer.is_multiline() {
+        "<multi-line>".to_string()
+    } else {
+        answer.to_string()
+    }
+}
+
+fn workspace_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("aoc lives inside the workspace")
+        .to_path_buf()
+}
+
+fn input_path(day: &Day) -> PathBuf {
+    workspace_path()
+        .join(day.year.to_string())
+        .join("src")
+        .join(day.day.to_string())
+        .join("input.txt")
+}
+
+/// Reads `--name value` pairs and bare flags, rejecting any option not in
+/// `allowed` or given twice.
+fn parse_options(args: &[String], allowed: &[&str]) -> Result<HashMap<String, String>, String> {
+    let mut options = HashMap::new();
+    let mut it = args.iter();
+    while let Some(arg) = it.next() {
+        let name = arg
+            .strip_prefix("--")
+            .ok_or_else(|| format!("unexpected argument '{}'\n{}", arg, USAGE))?;
+        if !allowed.contains(&name) {
+            return Err(format!("unknown option '{}'\n{}", arg, USAGE));
+        }
+        if options.contains_key(name) {
+            return Err(format!("--{} given more than once", name));
+        }
+        if FLAGS.contains(&name) {
+            options.insert(name.to_string(), String::new());
+            continue;
+        }
+        let value = it
+            .next()
+            .ok_or_else(|| format!("missing value for --{}", name))?;
+        options.insert(name.to_string(), value.clone());
+    }
+    Ok(options)
+}
+
+fn number(options: &HashMap<String, String>, name: &str) -> Result<Option<u32>, String> {
+    options
+        .get(name)
+        .map(|v| {
+            v.parse()
+                .map_err(|_| format!("--{} expects a number, got '{}'", name, v))
+        })
+        .transpose()
+}
+
+fn format(options: &HashMap<String, String>) -> Result<Format, String> {
+    options
+        .get("format")
+        .map_or(Ok(Format::Human), |f| f.parse())
+}
+
+fn required(options: &HashMap<String, String>, name: &str) -> Result<u32, String> {
+    number(options, name)?.ok_or_else(|| format!("missing --{}\n{}", name, USAGE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &str) -> Vec<String> {
+        args.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_parse_options() {
+        let options = parse_options(&args("--all --jobs 4"), RUN_OPTIONS).unwrap();
+        assert_eq!(Some(&String::new()), options.get("all"));
+        assert_eq!(Ok(Some(4)), number(&options, "jobs"));
+
+        let err = parse_options(&args("--yaer 2021"), RUN_OPTIONS).unwrap_err();
+        assert!(err.starts_with("unknown option '--yaer'"));
+        let err = parse_options(&args("--runs 3"), RUN_OPTIONS).unwrap_err();
+        assert!(err.starts_with("unknown option '--runs'"));
+        assert_eq!(
+            Err("--day given more than once".to_string()),
+            parse_options(&args("--day 1 --day 2"), VERIFY_OPTIONS)
+        );
+        assert_eq!(
+            Err("missing value for --year".to_string()),
+            parse_options(&args("--year"), NEW_OPTIONS)
+        );
+    }
+
+    #[test]
+    fn test_run_conflicts() {
+        assert_eq!(
+            Err("--all runs every year, so can't be given with --year".to_string()),
+            run(&args("--all --year 2021"))
+        );
+        assert_eq!(
+            Err("--timeout must be at least 1".to_string()),
+            run(&args("--year 2021 --timeout 0"))
+        );
+        assert_eq!(
+            Err("--jobs only applies when running several days".to_string()),
+            run(&args("--year 2021 --day 1 --jobs 2"))
+        );
+    }
+}
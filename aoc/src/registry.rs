@@ -0,0 +1,112 @@
+//! The table of every solved day the runner knows about.
+
+use aoc_common::{solve, ParseResult, Solution, Solved};
+
+pub struct Day {
+    pub year: u32,
+    pub day: u32,
+    /// How many parts the day has solved.
+    pub parts: u32,
+    /// Parses the input and runs one part, or every part if `None`.
+    pub solve: fn(&str, Option<u32>) -> ParseResult<Solved>,
+}
+
+macro_rules! day {
+    ($year:literal, $day:literal, $krate:ident::$module:ident) => {
+        Day {
+            year: $year,
+            day: $day,
+            parts: <$krate::$module::Day as Solution>::PARTS,
+            solve: solve::<$krate::$module::Day>,
+        }
+    };
+}
+
+pub static DAYS: &[Day] = &[
+    day!(2020, 1, advent_2020::day1),
+    day!(2020, 2, advent_2020::day2),
+    day!(2020, 3, advent_2020::day3),
+    day!(2020, 4, advent_2020::day4),
+    day!(2020, 5, advent_2020::day5),
+    day!(2020, 6, advent_2020::day6),
+    day!(2020, 7, advent_2020::day7),
+    day!(2020, 8, advent_2020::day8),
+    day!(2020, 9, advent_2020::day9),
+    day!(2020, 10, advent_2020::day10),
+    day!(2020, 11, advent_2020::day11),
+    day!(2020, 12, advent_2020::day12),
+    day!(2020, 13, advent_2020::day13),
+    day!(2020, 14, advent_2020::day14),
+    day!(2020, 15, advent_2020::day15),
+    day!(2020, 16, advent_2020::day16),
+    day!(2020, 17, advent_2020::day17),
+    day!(2020, 18, advent_2020::day18),
+    day!(2020, 19, advent_2020::day19),
+    day!(2020, 20, advent_2020::day20),
+    day!(2020, 21, advent_2020::day21),
+    day!(2020, 22, advent_2020::day22),
+    day!(2020, 23, advent_2020::day23),
+    day!(2020, 24, advent_2020::day24),
+    day!(2021, 1, advent_2021::day1),
+    day!(2021, 2, advent_2021::day2),
+    day!(2021, 3, advent_2021::day3),
+    day!(2021, 4, advent_2021::day4),
+    day!(2021, 5, advent_2021::day5),
+    day!(2021, 6, advent_2021::day6),
+    day!(2021, 7, advent_2021::day7),
+    day!(2021, 8, advent_2021::day8),
+    day!(2021, 9, advent_2021::day9),
+    day!(2021, 10, advent_2021::day10),
+    day!(2021, 11, advent_2021::day11),
+    day!(2021, 12, advent_2021::day12),
+    day!(2021, 13, advent_2021::day13),
+    day!(2021, 14, advent_2021::day14),
+    day!(2021, 15, advent_2021::day15),
+    day!(2021, 16, advent_2021::day16),
+    day!(2021, 17, advent_2021::day17),
+    day!(2021, 18, advent_2021::day18),
+    day!(2021, 20, advent_2021::day20),
+    day!(2021, 21, advent_2021::day21),
+    day!(2022, 1, advent_2022::day1),
+    day!(2022, 2, advent_2022::day2),
+    day!(2022, 3, advent_2022::day3),
+    day!(2022, 4, advent_2022::day4),
+    day!(2022, 5, advent_2022::day5),
+    day!(2023, 1, advent_2023::day1),
+    day!(2023, 2, advent_2023::day2),
+    day!(2023, 3, advent_2023::day3),
+    day!(2023, 4, advent_2023::day4),
+    day!(2023, 5, advent_2023::day5),
+    day!(2023, 6, advent_2023::day6),
+    day!(2023, 7, advent_2023::day7),
+    day!(2023, 8, advent_2023::day8),
+    day!(2023, 9, advent_2023::day9),
+    day!(2023, 10, advent_2023::day10),
+    day!(2023, 11, advent_2023::day11),
+    day!(2023, 12, advent_2023::day12),
+    day!(2023, 13, advent_2023::day13),
+    day!(2024, 1, advent_2024::day1),
+    day!(2024, 2, advent_2024::day2),
+    day!(2024, 3, advent_2024::day3),
+    day!(2024, 4, advent_2024::day4),
+    day!(2024, 5, advent_2024::day5),
+    day!(2024, 6, advent_2024::day6),
+    day!(2024, 7, advent_2024::day7),
+    day!(2024, 8, advent_2024::day8),
+    day!(2024, 9, advent_2024::day9),
+    day!(2024, 10, advent_2024::day10),
+    day!(2024, 11, advent_2024::day11),
+    day!(2024, 12, advent_2024::day12),
+    day!(2024, 13, advent_2024::day13),
+    day!(2024, 14, advent_2024::day14),
+    day!(2024, 15, advent_2024::day15),
+    day!(2024, 16, advent_2024::day16),
+    day!(2024, 17, advent_2024::day17),
+    day!(2024, 18, advent_2024::day18),
+    day!(2024, 19, advent_2024::day19),
+    day!(2024, 20, advent_2024::day20),
+];
+
+pub fn find(year: u32, day: u32) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.year == year && d.day == day)
+}
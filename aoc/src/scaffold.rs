@@ -0,0 +1,199 @@
+//! Creating a new day from `scripts/template.rs` and wiring it into its year's
+//! crate and the runner.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Creates `<year>/src/<day>/` with a solution stub and an empty `input.txt`,
+/// then registers the day's binary, module and runner entry. If `example` is
+/// given it seeds the stub's tests. Returns every file written.
+pub fn new_day(
+    root: &Path,
+    year: u32,
+    day: u32,
+    example: Option<&str>,
+) -> Result<Vec<PathBuf>, String> {
+    let krate = root.join(year.to_string());
+    if !krate.join("Cargo.toml").is_file() {
+        return Err(format!(
+            "there is no crate for {}; add one to the workspace first",
+            year
+        ));
+    }
+    let dir = krate.join("src").join(day.to_string());
+    let main = dir.join("main.rs");
+    if main.exists() {
+        return Err(format!("{} already exists", main.display()));
+    }
+
+    let template = read(&root.join("scripts").join("template.rs"))?;
+    let cargo = krate.join("Cargo.toml");
+    let lib = krate.join("src").join("lib.rs");
+    let registry = root.join("aoc").join("src").join("registry.rs");
+
+    // Work out every edit before touching the disk so a failure leaves nothing
+    // half done.
+    let edits = vec![
+        (
+            main,
+            fill_template(&template, year, day, example.unwrap_or("")),
+        ),
+        (dir.join("input.txt"), String::new()),
+        (cargo.clone(), add_bin(&read(&cargo)?, day)),
+        (lib.clone(), add_module(&read(&lib)?, day)),
+        (registry.clone(), add_entry(&read(&registry)?, year, day)?),
+    ];
+
+    fs::create_dir_all(&dir).map_err(|e| format!("failed to create {}: {}", dir.display(), e))?;
+    let mut written = Vec::new();
+    for (path, contents) in edits {
+        fs::write(&path, contents)
+            .map_err(|e| format!("failed to write {}: {}", path.display(), e))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("failed to read {}: {}", path.display(), e))
+}
+
+fn fill_template(template: &str, year: u32, day: u32, example: &str) -> String {
+    let example = example
+        .trim_end_matches('\n')
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"");
+    template
+        .replace("{{year}}", &year.to_string())
+        .replace("{{day}}", &day.to_string())
+        .replace("{{example}}", &example)
+}
+
+/// Finds where an entry for `key` belongs among the existing entries, each
+/// given as its key and the offset it starts at. Returns `None` to append.
+fn insertion_point(entries: &[(u32, usize)], key: u32) -> Option<usize> {
+    entries
+        .iter()
+        .find(|(existing, _)| *existing > key)
+        .map(|(_, offset)| *offset)
+}
+
+/// Every number that directly follows `prefix`, with the offset of `start`
+/// for the match it belongs to.
+fn keyed_offsets(text: &str, start: &str, prefix: &str) -> Vec<(u32, usize)> {
+    text.match_indices(start)
+        .filter_map(|(offset, _)| {
+            let rest = text[offset..]
+                .find(prefix)
+                .map(|i| offset + i + prefix.len())?;
+            let digits: String = text[rest..]
+                .chars()
+                .take_while(|c| c.is_ascii_digit())
+                .collect();
+            digits.parse().ok().map(|key| (key, offset))
+        })
+        .collect()
+}
+
+fn add_bin(cargo: &str, day: u32) -> String {
+    let block = format!(
+        "[[bin]]\nname = \"{}\"\npath = \"src/{}/main.rs\"\n",
+        day, day
+    );
+    let bins = keyed_offsets(cargo, "[[bin]]", "name = \"");
+    insert(cargo, insertion_point(&bins, day), &block)
+}
+
+fn add_module(lib: &str, day: u32) -> String {
+    let block = format!("#[path = \"{}/main.rs\"]\npub mod day{};\n", day, day);
+    let modules = keyed_offsets(lib, "#[path = \"", "#[path = \"");
+    insert(lib, insertion_point(&modules, day), &block)
+}
+
+/// Inserts `block` at `offset` followed by a blank line, or appends it after
+/// one.
+fn insert(text: &str, offset: Option<usize>, block: &str) -> String {
+    match offset {
+        Some(offset) => format!("{}{}\n{}", &text[..offset], block, &text[offset..]),
+        None => format!("{}\n\n{}", text.trim_end_matches('\n'), block),
+    }
+}
+
+fn add_entry(registry: &str, year: u32, day: u32) -> Result<String, String> {
+    let line = format!(
+        "    day!({}, {}, advent_{}::day{}),\n",
+        year, day, year, day
+    );
+    let end = registry
+        .find("\n];")
+        .map(|i| i + 1)
+        .ok_or("could not find the end of DAYS in registry.rs")?;
+
+    let offset = registry[..end]
+        .match_indices("    day!(")
+        .find(|(offset, _)| {
+            let line = registry[*offset..].lines().next().unwrap_or_default();
+            aoc_common::numbers::<u32>(line)
+                .is_ok_and(|key| &key[..key.len().min(2)] > [year, day].as_slice())
+        })
+        .map_or(end, |(offset, _)| offset);
+    Ok(format!(
+        "{}{}{}",
+        &registry[..offset],
+        line,
+        &registry[offset..]
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CARGO: &str = "[package]\nname = \"advent-2021\"\n\n[[bin]]\nname = \"1\"\npath = \"src/1/main.rs\"\n\n[[bin]]\nname = \"20\"\npath = \"src/20/main.rs\"\n";
+
+    #[test]
+    fn test_add_bin() {
+        assert_eq!(
+            "[package]\nname = \"advent-2021\"\n\n[[bin]]\nname = \"1\"\npath = \"src/1/main.rs\"\n\n[[bin]]\nname = \"19\"\npath = \"src/19/main.rs\"\n\n[[bin]]\nname = \"20\"\npath = \"src/20/main.rs\"\n",
+            add_bin(CARGO, 19)
+        );
+        assert!(add_bin(CARGO, 21).ends_with(
+            "path = \"src/20/main.rs\"\n\n[[bin]]\nname = \"21\"\npath = \"src/21/main.rs\"\n"
+        ));
+    }
+
+    #[test]
+    fn test_add_module() {
+        let lib = "#![allow(dead_code)]\n\n#[path = \"2/main.rs\"]\npub mod day2;\n";
+        assert_eq!(
+            "#![allow(dead_code)]\n\n#[path = \"1/main.rs\"]\npub mod day1;\n\n#[path = \"2/main.rs\"]\npub mod day2;\n",
+            add_module(lib, 1)
+        );
+        assert_eq!(
+            "#![allow(dead_code)]\n\n#[path = \"2/main.rs\"]\npub mod day2;\n\n#[path = \"10/main.rs\"]\npub mod day10;\n",
+            add_module(lib, 10)
+        );
+    }
+
+    #[test]
+    fn test_add_entry() {
+        let registry = "pub static DAYS: &[Day] = &[\n    day!(2021, 18, advent_2021::day18),\n    day!(2021, 20, advent_2021::day20),\n    day!(2022, 1, advent_2022::day1),\n];\n";
+        assert_eq!(
+            "pub static DAYS: &[Day] = &[\n    day!(2021, 18, advent_2021::day18),\n    day!(2021, 19, advent_2021::day19),\n    day!(2021, 20, advent_2021::day20),\n    day!(2022, 1, advent_2022::day1),\n];\n",
+            add_entry(registry, 2021, 19).unwrap()
+        );
+        assert!(add_entry(registry, 2022, 2).unwrap().ends_with(
+            "day!(2022, 1, advent_2022::day1),\n    day!(2022, 2, advent_2022::day2),\n];\n"
+        ));
+        assert!(add_entry("", 2022, 2).is_err());
+    }
+
+    #[test]
+    fn test_fill_template() {
+        let template = "run_day::<Day>({{year}}, {{day}});\nlet input = \"{{example}}\";";
+        assert_eq!(
+            "run_day::<Day>(2024, 21);\nlet input = \"a\\\\b\n\\\"c\\\"\";",
+            fill_template(template, 2024, 21, "a\\b\n\"c\"\n")
+        );
+    }
+}
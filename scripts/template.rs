@@ -1,17 +1,27 @@
-use itertools::Itertools;
+use aoc_common::{ParseResult, Solution};
 
 fn main() {
-    let input = include_str!("input.txt");
-    println!("Part one: {}", part_one(input));
-    println!("Part two: {}", part_two(input));
+    aoc_common::run_day::<Day>({{year}}, {{day}});
 }
 
-fn part_one(input: &str) -> usize {
-    0
-}
+pub struct Day;
+
+impl Solution for Day {
+    type Parsed = String;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> ParseResult<String> {
+        Ok(input.to_string())
+    }
 
-fn part_two(input: &str) -> usize {
-    0
+    fn part_one(input: &String) -> usize {
+        0
+    }
+
+    fn part_two(input: &String) -> usize {
+        0
+    }
 }
 
 #[cfg(test)]
@@ -20,13 +30,13 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let input = "";
-        assert_eq!(part_one(input), 0);
+        let input = "{{example}}";
+        assert_eq!(Day::part_one(&Day::parse(input).unwrap()), 0);
     }
 
     #[test]
     fn test_part_two() {
-        let input = "";
-        assert_eq!(part_two(input), 0);
+        let input = "{{example}}";
+        assert_eq!(Day::part_two(&Day::parse(input).unwrap()), 0);
     }
 }